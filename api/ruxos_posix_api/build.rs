@@ -81,6 +81,7 @@ typedef struct {{
             "fd_set",
             "timeval",
             "itimerval",
+            "itimerspec",
             "pthread_t",
             "pthread_attr_t",
             "pthread_mutex_t",
@@ -108,6 +109,7 @@ typedef struct {{
             "ino_t",
             "rusage",
             "dirent",
+            "signalfd_siginfo",
         ];
         let allow_vars = [
             "O_.*",
@@ -116,6 +118,8 @@ typedef struct {{
             "SOCK_.*",
             "SOL_.*",
             "IPPROTO_.*",
+            "IP_.*",
+            "IPV6_.*",
             "FD_.*",
             "F_.*",
             "_SC_.*",
@@ -136,6 +140,11 @@ typedef struct {{
             "GRND_.*",
             "S_IF.+",
             "SCM_.*",
+            "CLOCK_.*",
+            "EFD_.*",
+            "TFD_.*",
+            "SFD_.*",
+            "UTIME_.*",
         ];
 
         #[derive(Debug)]
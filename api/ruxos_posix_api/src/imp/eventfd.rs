@@ -0,0 +1,180 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! `eventfd` implementation.
+//!
+//! An eventfd holds a 64-bit counter. Writing adds to the counter, blocking
+//! if it would overflow, and reading takes the counter and resets it to 0,
+//! or takes 1 from it with `EFD_SEMAPHORE`, blocking while it is 0.
+
+use alloc::sync::Arc;
+use core::ffi::{c_int, c_uint};
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::{PollState, PollWaiters};
+use ruxfdtable::{FileLike, RuxStat};
+use ruxfs::{AbsPath, OpenFlags};
+use ruxtask::fs::add_file_like;
+use spin::RwLock;
+use spinlock::SpinNoIrq;
+
+use crate::{ctypes, utils::block_on};
+
+/// The maximum value of the counter.
+const EVENTFD_MAX: u64 = u64::MAX - 1;
+
+/// An eventfd object.
+pub struct EventFd {
+    value: SpinNoIrq<u64>,
+    semaphore: bool,
+    flags: RwLock<OpenFlags>,
+    waiters: PollWaiters,
+}
+
+impl EventFd {
+    fn new(initval: u64, semaphore: bool, flags: OpenFlags) -> Self {
+        Self {
+            value: SpinNoIrq::new(initval),
+            semaphore,
+            flags: RwLock::new(flags),
+            waiters: PollWaiters::new(),
+        }
+    }
+
+    fn nonblocking(&self) -> bool {
+        self.flags.read().contains(OpenFlags::O_NONBLOCK)
+    }
+
+    fn try_read(&self) -> LinuxResult<u64> {
+        let mut value = self.value.lock();
+        if *value == 0 {
+            return Err(LinuxError::EAGAIN);
+        }
+        let ret = if self.semaphore { 1 } else { *value };
+        *value -= ret;
+        drop(value);
+        self.waiters.notify();
+        Ok(ret)
+    }
+
+    fn try_write(&self, add: u64) -> LinuxResult {
+        let mut value = self.value.lock();
+        if add > EVENTFD_MAX - *value {
+            return Err(LinuxError::EAGAIN);
+        }
+        *value += add;
+        drop(value);
+        self.waiters.notify();
+        Ok(())
+    }
+}
+
+impl FileLike for EventFd {
+    fn path(&self) -> AbsPath {
+        AbsPath::new("/eventfd")
+    }
+
+    /// Reads the counter as a `u64` in native byte order.
+    ///
+    /// Fails with `EINVAL` if `buf` is shorter than 8 bytes.
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < 8 {
+            return Err(LinuxError::EINVAL);
+        }
+        let value = if self.nonblocking() {
+            self.try_read()?
+        } else {
+            block_on(&self.waiters, || self.try_read())?
+        };
+        buf[..8].copy_from_slice(&value.to_ne_bytes());
+        Ok(8)
+    }
+
+    /// Adds a `u64` in native byte order to the counter.
+    ///
+    /// Fails with `EINVAL` if `buf` is shorter than 8 bytes or the value is
+    /// `u64::MAX`.
+    fn write(&self, buf: &[u8]) -> LinuxResult<usize> {
+        let Some(bytes) = buf.get(..8) else {
+            return Err(LinuxError::EINVAL);
+        };
+        let add = u64::from_ne_bytes(bytes.try_into().unwrap());
+        if add == u64::MAX {
+            return Err(LinuxError::EINVAL);
+        }
+        if self.nonblocking() {
+            self.try_write(add)?;
+        } else {
+            block_on(&self.waiters, || self.try_write(add))?;
+        }
+        Ok(8)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        let value = *self.value.lock();
+        Ok(PollState {
+            readable: value > 0,
+            writable: value < EVENTFD_MAX,
+            pollhup: false,
+        })
+    }
+
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        Some(&self.waiters)
+    }
+
+    fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
+        *self.flags.write() = flags;
+        Ok(())
+    }
+
+    fn flags(&self) -> OpenFlags {
+        *self.flags.read() | OpenFlags::O_RDWR
+    }
+}
+
+/// Creates an eventfd object with the counter initialized to `initval`.
+///
+/// `flags` can contain `EFD_SEMAPHORE`, `EFD_NONBLOCK` and `EFD_CLOEXEC`.
+pub fn sys_eventfd2(initval: c_uint, flags: c_int) -> c_int {
+    debug!("sys_eventfd2 <= initval: {initval}, flags: {flags:#x}");
+    syscall_body!(sys_eventfd2, {
+        let valid = (ctypes::EFD_SEMAPHORE | ctypes::EFD_NONBLOCK | ctypes::EFD_CLOEXEC) as c_int;
+        if flags & !valid != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let semaphore = flags & ctypes::EFD_SEMAPHORE as c_int != 0;
+        let flags = OpenFlags::from_bits_truncate(flags & !(ctypes::EFD_SEMAPHORE as c_int));
+        let eventfd = EventFd::new(initval as u64, semaphore, flags & OpenFlags::O_NONBLOCK);
+        add_file_like(Arc::new(eventfd), flags)
+    })
+}
+
+/// Creates an eventfd object, same as [`sys_eventfd2`] without flags.
+pub fn sys_eventfd(initval: c_uint) -> c_int {
+    sys_eventfd2(initval, 0)
+}
@@ -7,14 +7,14 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::string::String;
 use alloc::sync::Arc;
 use core::{
     ffi::{c_char, c_int, c_long, c_ulong, c_void, CStr},
     str,
+    time::Duration,
 };
 
-use ruxfs::{api::FileType, fops::lookup, FilePerm, MountPoint};
+use ruxfs::{api::FileType, fops::lookup, FilePerm};
 
 use axerrno::{LinuxError, LinuxResult};
 use axio::{Error, SeekFrom};
@@ -131,18 +131,40 @@ pub unsafe fn sys_ftruncate(fd: c_int, length: ctypes::off_t) -> c_int {
 
 /// Synchronize a file's in-core state with storage device
 ///
-/// TODO
+/// The modified pages of a regular file in the page cache are written back.
 pub unsafe fn sys_fsync(fd: c_int) -> c_int {
     debug!("sys_fsync <= fd: {fd}");
-    syscall_body!(sys_fsync, Ok(0))
+    syscall_body!(sys_fsync, {
+        get_file_like(fd)?;
+        if let Ok(file) = file_from_fd(fd) {
+            file.sync()?;
+        }
+        Ok(0)
+    })
 }
 
 /// Synchronize a file's in-core state with storage device
 ///
-/// TODO
+/// Same as [`sys_fsync`], the metadata are always written back with the data.
 pub unsafe fn sys_fdatasync(fd: c_int) -> c_int {
     debug!("sys_fdatasync <= fd: {fd}");
-    syscall_body!(sys_fdatasync, Ok(0))
+    syscall_body!(sys_fdatasync, {
+        get_file_like(fd)?;
+        if let Ok(file) = file_from_fd(fd) {
+            file.sync()?;
+        }
+        Ok(0)
+    })
+}
+
+/// Write back the modified pages of all files in the page cache, and the
+/// modified buffers of all disks.
+pub fn sys_sync() -> c_int {
+    debug!("sys_sync");
+    syscall_body!(sys_sync, {
+        ruxfs::fops::sync()?;
+        Ok(0)
+    })
 }
 
 /// Get the file metadata by `path` and write into `buf`.
@@ -156,32 +178,41 @@ pub unsafe fn sys_stat(path: *const c_char, buf: *mut core::ffi::c_void) -> c_in
             return Err(LinuxError::EFAULT);
         }
         let node = fops::lookup(&path)?;
-        let st = RuxStat::from(node.get_attr()?);
+        unsafe { write_stat(RuxStat::from(node.get_attr()?), buf) };
+        Ok(0)
+    })
+}
 
-        #[cfg(not(feature = "musl"))]
-        {
-            let buf = buf as *mut ctypes::stat;
-            unsafe { *buf = st };
-            Ok(0)
-        }
+/// Write `st` into `buf`, which is a `kstat` with musl, or a `stat` otherwise.
+unsafe fn write_stat(st: RuxStat, buf: *mut core::ffi::c_void) {
+    #[cfg(not(feature = "musl"))]
+    {
+        let buf = buf as *mut ctypes::stat;
+        unsafe { *buf = st };
+    }
 
-        #[cfg(feature = "musl")]
-        {
-            let kst = buf as *mut ctypes::kstat;
-            unsafe {
-                (*kst).st_dev = st.st_dev;
-                (*kst).st_ino = st.st_ino;
-                (*kst).st_mode = st.st_mode;
-                (*kst).st_nlink = st.st_nlink;
-                (*kst).st_uid = st.st_uid;
-                (*kst).st_gid = st.st_gid;
-                (*kst).st_size = st.st_size;
-                (*kst).st_blocks = st.st_blocks;
-                (*kst).st_blksize = st.st_blksize;
-            }
-            Ok(0)
+    #[cfg(feature = "musl")]
+    {
+        let kst = buf as *mut ctypes::kstat;
+        unsafe {
+            (*kst).st_dev = st.st_dev;
+            (*kst).st_ino = st.st_ino;
+            (*kst).st_mode = st.st_mode;
+            (*kst).st_nlink = st.st_nlink;
+            (*kst).st_uid = st.st_uid;
+            (*kst).st_gid = st.st_gid;
+            (*kst).st_size = st.st_size;
+            (*kst).st_blocks = st.st_blocks;
+            (*kst).st_blksize = st.st_blksize;
+            (*kst).st_atime_sec = st.st_atime.tv_sec;
+            (*kst).st_atime_nsec = st.st_atime.tv_nsec;
+            (*kst).st_mtime_sec = st.st_mtime.tv_sec;
+            (*kst).st_mtime_nsec = st.st_mtime.tv_nsec;
+            (*kst).st_ctime_sec = st.st_ctime.tv_sec;
+            (*kst).st_ctime_nsec = st.st_ctime.tv_nsec;
+            (*kst).st_rdev = st.st_rdev;
         }
-    })
+    }
 }
 
 /// retrieve information about the file pointed by `fd`
@@ -199,26 +230,7 @@ pub fn sys_fstat(fd: c_int, kst: *mut core::ffi::c_void) -> c_int {
         }
         #[cfg(feature = "musl")]
         {
-            let st = get_file_like(fd)?.stat()?;
-            let kst = kst as *mut ctypes::kstat;
-            unsafe {
-                (*kst).st_dev = st.st_dev;
-                (*kst).st_ino = st.st_ino;
-                (*kst).st_mode = st.st_mode;
-                (*kst).st_nlink = st.st_nlink;
-                (*kst).st_uid = st.st_uid;
-                (*kst).st_gid = st.st_gid;
-                (*kst).st_size = st.st_size;
-                (*kst).st_blocks = st.st_blocks;
-                (*kst).st_blksize = st.st_blksize;
-                (*kst).st_atime_sec = st.st_atime.tv_sec;
-                (*kst).st_atime_nsec = st.st_atime.tv_nsec;
-                (*kst).st_mtime_sec = st.st_mtime.tv_sec;
-                (*kst).st_mtime_nsec = st.st_mtime.tv_nsec;
-                (*kst).st_ctime_sec = st.st_ctime.tv_sec;
-                (*kst).st_ctime_nsec = st.st_ctime.tv_nsec;
-                (*kst).st_rdev = st.st_rdev;
-            }
+            unsafe { write_stat(get_file_like(fd)?.stat()?, kst) };
             Ok(0)
         }
     })
@@ -226,15 +238,19 @@ pub fn sys_fstat(fd: c_int, kst: *mut core::ffi::c_void) -> c_int {
 
 /// Get the metadata of the symbolic link and write into `buf`.
 ///
+/// Same as [`sys_stat`], except that if `path` is a symbolic link, the
+/// metadata of the link itself is returned.
+///
 /// Return 0 if success.
-pub unsafe fn sys_lstat(path: *const c_char, buf: *mut ctypes::stat) -> ctypes::ssize_t {
+pub unsafe fn sys_lstat(path: *const c_char, buf: *mut core::ffi::c_void) -> c_int {
     syscall_body!(sys_lstat, {
         let path = parse_path(path)?;
         debug!("sys_lstat <= {:?} {:#x}", path, buf as usize);
         if buf.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        unsafe { *buf = Default::default() }; // TODO
+        let node = fops::lookup_nofollow(&path)?;
+        unsafe { write_stat(RuxStat::from(node.get_attr()?), buf) };
         Ok(0)
     })
 }
@@ -252,7 +268,11 @@ pub unsafe fn sys_newfstatat(
         if kst.is_null() {
             return Err(LinuxError::EFAULT);
         }
-        let node = fops::lookup(&path)?;
+        let node = if flag & ctypes::AT_SYMLINK_NOFOLLOW as c_int != 0 {
+            fops::lookup_nofollow(&path)?
+        } else {
+            fops::lookup(&path)?
+        };
         let st = RuxStat::from(node.get_attr()?);
 
         // TODO: remove this initialization when fields are fully implemented
@@ -260,17 +280,7 @@ pub unsafe fn sys_newfstatat(
             core::ptr::write_bytes(kst, 0, 1);
         }
 
-        unsafe {
-            (*kst).st_dev = st.st_dev;
-            (*kst).st_ino = st.st_ino;
-            (*kst).st_mode = st.st_mode;
-            (*kst).st_nlink = st.st_nlink;
-            (*kst).st_uid = st.st_uid;
-            (*kst).st_gid = st.st_gid;
-            (*kst).st_size = st.st_size;
-            (*kst).st_blocks = st.st_blocks;
-            (*kst).st_blksize = st.st_blksize;
-        }
+        unsafe { write_stat(st, kst as _) };
         Ok(0)
     })
 }
@@ -307,11 +317,11 @@ pub fn sys_rename(old: *const c_char, new: *const c_char) -> c_int {
         if old == new {
             return Ok(0);
         }
-        match fops::lookup(&old) {
+        match fops::lookup_nofollow(&old) {
             Ok(_) => {}
             Err(e) => return Err(e.into()),
         }
-        match fops::lookup(&new) {
+        match fops::lookup_nofollow(&new) {
             Ok(_) => return Err(LinuxError::EEXIST),
             Err(Error::NotFound) => {}
             Err(e) => return Err(e.into()),
@@ -339,7 +349,7 @@ pub fn sys_rmdir(pathname: *const c_char) -> c_int {
     syscall_body!(sys_rmdir, {
         let path = parse_path(pathname)?;
         debug!("sys_rmdir <= path: {path:?}");
-        match fops::lookup(&path) {
+        match fops::lookup_nofollow(&path) {
             Ok(node) => {
                 let attr = node.get_attr()?;
                 if !attr.is_dir() {
@@ -367,7 +377,7 @@ pub fn sys_unlink(pathname: *const c_char) -> c_int {
     syscall_body!(sys_unlink, {
         let path = parse_path(pathname)?;
         debug!("sys_unlink <= path: {path:?}");
-        match fops::lookup(&path) {
+        match fops::lookup_nofollow(&path) {
             Ok(node) => {
                 let attr = node.get_attr()?;
                 if attr.is_dir() {
@@ -390,7 +400,7 @@ pub fn sys_unlinkat(fd: c_int, pathname: *const c_char, flags: c_int) -> c_int {
         let path = parse_path_at(fd, pathname)?;
         let rmdir = flags as u32 & ctypes::AT_REMOVEDIR != 0;
         debug!("sys_unlinkat <= fd: {fd}, pathname: {path:?}, flags: {flags}");
-        match fops::lookup(&path) {
+        match fops::lookup_nofollow(&path) {
             Ok(node) => {
                 let attr = node.get_attr()?;
                 if rmdir {
@@ -434,6 +444,19 @@ pub fn sys_fchmodat(dirfd: c_int, pathname: *const c_char, mode: ctypes::mode_t)
     })
 }
 
+/// Change permissions of the file opened as `fd`
+pub fn sys_fchmod(fd: c_int, mode: ctypes::mode_t) -> c_int {
+    syscall_body!(sys_fchmod, {
+        debug!("sys_fchmod <= fd: {fd}, mode: {mode:#o}");
+        let mode = FilePerm::from_bits_truncate(mode as u16);
+        match file_from_fd(fd) {
+            Ok(file) => file.set_mode(mode)?,
+            Err(_) => dir_from_fd(fd)?.set_mode(mode)?,
+        }
+        Ok(0)
+    })
+}
+
 /// Creates a new, empty file at the provided path.
 pub fn sys_mknodat(
     fd: c_int,
@@ -467,7 +490,7 @@ pub fn sys_mkdirat(fd: c_int, pathname: *const c_char, mode: ctypes::mode_t) ->
         let path = parse_path_at(fd, pathname)?;
         debug!("sys_mkdirat <= fd: {fd}, pathname: {path:?}, mode: {mode:#o}",);
         let mode = FilePerm::from_bits_truncate(mode as u16 & !get_umask());
-        match fops::lookup(&path) {
+        match fops::lookup_nofollow(&path) {
             Ok(_) => return Err(LinuxError::EEXIST),
             Err(Error::NotFound) => fops::create(&path, FileType::Dir, mode)?,
             Err(e) => return Err(e.into()),
@@ -476,7 +499,8 @@ pub fn sys_mkdirat(fd: c_int, pathname: *const c_char, mode: ctypes::mode_t) ->
     })
 }
 
-/// Changes the ownership of the file referred to by the open file descriptor fd
+/// Changes the ownership of the file at `path` relative to `fd`, an id of -1
+/// is unchanged.
 pub fn sys_fchownat(
     fd: c_int,
     path: *const c_char,
@@ -484,15 +508,138 @@ pub fn sys_fchownat(
     gid: ctypes::gid_t,
     flag: c_int,
 ) -> c_int {
+    if flag & ctypes::AT_EMPTY_PATH as c_int != 0 && is_empty_path(path) {
+        return sys_fchown(fd, uid, gid);
+    }
     syscall_body!(sys_fchownat, {
         let path = parse_path_at(fd, path)?;
         debug!("sys_fchownat <= fd: {fd}, path: {path:?}, uid: {uid}, gid: {gid}, flag: {flag}");
+        let node = if flag & ctypes::AT_SYMLINK_NOFOLLOW as c_int != 0 {
+            fops::lookup_nofollow(&path)?
+        } else {
+            lookup(&path)?
+        };
+        node.set_owner(owner_id(uid), owner_id(gid))?;
+        Ok(0)
+    })
+}
+
+/// Changes the ownership of the file opened as `fd`, an id of -1 is unchanged.
+pub fn sys_fchown(fd: c_int, uid: ctypes::uid_t, gid: ctypes::gid_t) -> c_int {
+    syscall_body!(sys_fchown, {
+        debug!("sys_fchown <= fd: {fd}, uid: {uid}, gid: {gid}");
+        let (uid, gid) = (owner_id(uid), owner_id(gid));
+        match file_from_fd(fd) {
+            Ok(file) => file.set_owner(uid, gid)?,
+            Err(_) => dir_from_fd(fd)?.set_owner(uid, gid)?,
+        }
+        Ok(0)
+    })
+}
+
+/// Changes the access and modification times of the file at `path`, with
+/// nanosecond precision.
+///
+/// Both times are set to the current time if `times` is null, otherwise a
+/// time of `UTIME_NOW` is the current time and `UTIME_OMIT` is unchanged, and
+/// nothing changes if both are `UTIME_OMIT`. The file opened as `dirfd` is
+/// changed if `path` is null, as musl implements `futimens`, or if `path` is
+/// empty and `flags` contains `AT_EMPTY_PATH`.
+pub unsafe fn sys_utimensat(
+    dirfd: c_int,
+    path: *const c_char,
+    times: *const ctypes::timespec,
+    flags: c_int,
+) -> c_int {
+    if path.is_null() || (flags & ctypes::AT_EMPTY_PATH as c_int != 0 && is_empty_path(path)) {
+        return unsafe { sys_futimens(dirfd, times) };
+    }
+    syscall_body!(sys_utimensat, {
+        let path = parse_path_at(dirfd, path)?;
+        let (atime, mtime) = unsafe { parse_utimes(times)? };
+        debug!("sys_utimensat <= fd: {dirfd}, path: {path:?}, atime: {atime:?}, mtime: {mtime:?}, flags: {flags}");
+        let node = if flags & ctypes::AT_SYMLINK_NOFOLLOW as c_int != 0 {
+            fops::lookup_nofollow(&path)?
+        } else {
+            lookup(&path)?
+        };
+        if atime.is_some() || mtime.is_some() {
+            node.set_times(atime, mtime)?;
+        }
+        Ok(0)
+    })
+}
+
+/// Changes the access and modification times of the file opened as `fd`, see
+/// [`sys_utimensat`].
+pub unsafe fn sys_futimens(fd: c_int, times: *const ctypes::timespec) -> c_int {
+    syscall_body!(sys_futimens, {
+        let (atime, mtime) = unsafe { parse_utimes(times)? };
+        debug!("sys_futimens <= fd: {fd}, atime: {atime:?}, mtime: {mtime:?}");
+        if atime.is_none() && mtime.is_none() {
+            get_file_like(fd)?;
+            return Ok(0);
+        }
+        match file_from_fd(fd) {
+            Ok(file) => file.set_times(atime, mtime)?,
+            Err(_) => dir_from_fd(fd)?.set_times(atime, mtime)?,
+        }
+        Ok(0)
+    })
+}
+
+/// Creates a symbolic link `linkpath` relative to the directory `newdirfd`,
+/// which contains the string `target`.
+pub fn sys_symlinkat(target: *const c_char, newdirfd: c_int, linkpath: *const c_char) -> c_int {
+    syscall_body!(sys_symlinkat, {
+        let target = char_ptr_to_path_str(target)?;
+        let path = parse_path_at(newdirfd, linkpath)?;
+        debug!("sys_symlinkat <= target: {target:?}, newdirfd: {newdirfd}, linkpath: {path:?}");
+        fops::create_symlink(target, &path)?;
+        Ok(0)
+    })
+}
+
+/// Creates a symbolic link `linkpath` which contains the string `target`.
+pub fn sys_symlink(target: *const c_char, linkpath: *const c_char) -> c_int {
+    sys_symlinkat(target, ctypes::AT_FDCWD, linkpath)
+}
+
+/// Creates a hard link `newpath` relative to the directory `newdirfd`, which
+/// refers to the file `oldpath` relative to the directory `olddirfd`.
+///
+/// A symbolic link at `oldpath` is followed only if `flags` contains
+/// `AT_SYMLINK_FOLLOW`.
+pub fn sys_linkat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+    flags: c_int,
+) -> c_int {
+    syscall_body!(sys_linkat, {
+        let old = parse_path_at(olddirfd, oldpath)?;
+        let new = parse_path_at(newdirfd, newpath)?;
+        debug!(
+            "sys_linkat <= olddirfd: {olddirfd}, oldpath: {old:?}, newdirfd: {newdirfd}, newpath: {new:?}, flags: {flags:#x}"
+        );
+        if flags & !(ctypes::AT_SYMLINK_FOLLOW as c_int) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        fops::link(&old, &new, flags & ctypes::AT_SYMLINK_FOLLOW as c_int != 0)?;
         Ok(0)
     })
 }
 
+/// Creates a hard link `newpath` which refers to the file `oldpath`.
+pub fn sys_link(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    sys_linkat(ctypes::AT_FDCWD, oldpath, ctypes::AT_FDCWD, newpath, 0)
+}
+
 /// read value of a symbolic link relative to directory file descriptor
-/// TODO: currently only support symlink, so return EINVAL anyway
+///
+/// The target is truncated if `buf` is too small, and it is not terminated
+/// by a null byte. Fails with `EINVAL` if `pathname` is not a symbolic link.
 pub fn sys_readlinkat(
     fd: c_int,
     pathname: *const c_char,
@@ -504,7 +651,14 @@ pub fn sys_readlinkat(
         debug!(
             "sys_readlinkat <= path = {path:?}, fd = {fd:}, buf = {buf:p}, bufsize = {bufsize:}"
         );
-        Err::<usize, LinuxError>(LinuxError::EINVAL)
+        if buf.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if bufsize == 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let buf = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, bufsize) };
+        Ok(fops::read_link(&path, buf)?)
     })
 }
 
@@ -619,8 +773,12 @@ pub fn sys_chdir(path: *const c_char) -> c_int {
     })
 }
 
-pub const MS_NODEV: u32 = 2;
-pub const MS_NOSUID: u32 = 4;
+pub const MS_RDONLY: u32 = 1;
+pub const MS_NOSUID: u32 = 2;
+pub const MS_NODEV: u32 = 4;
+pub const MS_REMOUNT: u32 = 32;
+pub const MS_BIND: u32 = 4096;
+pub const MS_REC: u32 = 16384;
 
 /// umount a filesystem at a specific location in the filesystem tree
 pub fn sys_umount2(target: *const c_char, flags: c_int) -> c_int {
@@ -630,20 +788,17 @@ pub fn sys_umount2(target: *const c_char, flags: c_int) -> c_int {
         flags
     );
     syscall_body!(sys_umount2, {
-        let target = char_ptr_to_str(target)?;
-        let dir = ruxtask::current()
-            .fs
-            .lock()
-            .as_mut()
-            .unwrap()
-            .root_dir
-            .clone();
-        dir.umount(&AbsPath::new(target));
+        let target = parse_path(target)?;
+        fops::umount(&target)?;
         Ok(0)
     })
 }
 
 /// mount a filesystem at a specific location in the filesystem tree
+///
+/// The filesystem types registered in [`ruxfs::fstype`] can be mounted, and
+/// `fuse` for the filesystems in user space. `MS_RDONLY`, `MS_BIND` and
+/// `MS_REMOUNT` are supported, other flags are ignored.
 pub fn sys_mount(
     source: *const c_char,
     raw_target: *const c_char,
@@ -660,34 +815,44 @@ pub fn sys_mount(
         data
     );
     syscall_body!(sys_mount, {
-        let f1 = MS_NODEV; //ctypes::MS_NODEV;
-        let f2 = MS_NOSUID; //ctypes::MS_NOSUID;
-        info!(
-            "mount flags: {:#x}, f1: {:#}, f2: {:#}, flag: {:#}",
-            mountflags,
-            f1,
-            f2,
-            f1 | f2
-        );
-        if mountflags != (f1 | f2).into() {
-            warn!("mount flags not supported: {mountflags:#x}");
-        }
-
-        let target = char_ptr_to_str(raw_target)?;
-        let target = String::from(target);
-        let dir = ruxtask::current()
-            .fs
-            .lock()
-            .as_mut()
-            .unwrap()
-            .root_dir
-            .clone();
-        let vfsops = ruxfuse::fuse::fusefs();
-        info!("mounting filesystem at {target}");
-        dir.mount(MountPoint {
-            path: target,
-            fs: vfsops,
-        })?;
+        let flags = mountflags as u32;
+        let read_only = flags & MS_RDONLY != 0;
+        let unsupported =
+            flags & !(MS_RDONLY | MS_NOSUID | MS_NODEV | MS_REMOUNT | MS_BIND | MS_REC);
+        if unsupported != 0 {
+            warn!("mount flags not supported: {unsupported:#x}");
+        }
+
+        let target = parse_path(raw_target)?;
+        if flags & MS_REMOUNT != 0 {
+            fops::remount(&target, read_only)?;
+            return Ok(0);
+        }
+        if flags & MS_BIND != 0 {
+            fops::bind_mount(&parse_path(source)?, &target, read_only)?;
+            return Ok(0);
+        }
+
+        let source = if source.is_null() {
+            "none"
+        } else {
+            char_ptr_to_str(source)?
+        };
+        let fstype = char_ptr_to_str(filesystemtype)?;
+        let data = if data.is_null() {
+            ""
+        } else {
+            char_ptr_to_str(data as *const c_char)?
+        };
+        info!("mounting {fstype} filesystem at {target}");
+        if fstype == "fuse" || fstype.starts_with("fuse.") {
+            let vfsops = ruxfuse::fuse::fusefs();
+            fops::mount_fs(vfsops, source, &target, fstype, read_only)?;
+        } else if ruxfs::fstype::is_registered(fstype) {
+            fops::mount(source, &target, fstype, data, read_only)?;
+        } else {
+            return Err(LinuxError::ENODEV);
+        }
         Ok(0)
     })
 }
@@ -742,6 +907,40 @@ pub fn parse_path_at(dirfd: c_int, path: *const c_char) -> LinuxResult<AbsPath<'
     }
 }
 
+/// Converts an id of `chown`, where -1 leaves the id unchanged.
+fn owner_id(id: u32) -> Option<u32> {
+    (id != u32::MAX).then_some(id)
+}
+
+/// Converts the `times` of `utimensat` into the access and modification
+/// times, the `None` ones are unchanged.
+unsafe fn parse_utimes(
+    times: *const ctypes::timespec,
+) -> LinuxResult<(Option<Duration>, Option<Duration>)> {
+    let now = ruxhal::time::current_time();
+    if times.is_null() {
+        return Ok((Some(now), Some(now)));
+    }
+    let parse = |ts: &ctypes::timespec| {
+        if ts.tv_nsec == ctypes::UTIME_NOW as c_long {
+            Ok(Some(now))
+        } else if ts.tv_nsec == ctypes::UTIME_OMIT as c_long {
+            Ok(None)
+        } else if ts.tv_sec < 0 || !(0..1_000_000_000).contains(&ts.tv_nsec) {
+            Err(LinuxError::EINVAL)
+        } else {
+            Ok(Some(Duration::from(*ts)))
+        }
+    };
+    let times = unsafe { core::slice::from_raw_parts(times, 2) };
+    Ok((parse(&times[0])?, parse(&times[1])?))
+}
+
+/// Whether `path` is null or an empty string.
+fn is_empty_path(path: *const c_char) -> bool {
+    path.is_null() || unsafe { *path == 0 }
+}
+
 fn file_from_fd(fd: i32) -> LinuxResult<Arc<File>> {
     get_file_like(fd)?
         .into_any()
@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use crate::ctypes;
+use crate::{ctypes, utils::restartable};
 use axerrno::LinuxError;
 use core::ffi::{c_int, c_void};
 
@@ -31,11 +31,12 @@ pub fn sys_read(fd: c_int, buf: *mut c_void, count: usize) -> ctypes::ssize_t {
         let dst = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, count) };
         #[cfg(feature = "fd")]
         {
-            Ok(get_file_like(fd as _)?.read(dst)? as ctypes::ssize_t)
+            let file = get_file_like(fd as _)?;
+            Ok(restartable(true, || file.read(dst))? as ctypes::ssize_t)
         }
         #[cfg(not(feature = "fd"))]
         match fd {
-            0 => Ok(Stdin::default().read(dst)? as ctypes::ssize_t),
+            0 => Ok(restartable(true, || Ok(Stdin::default().read(dst)?))? as ctypes::ssize_t),
             1 | 2 => Err(LinuxError::EPERM),
             _ => Err(LinuxError::EBADF),
         }
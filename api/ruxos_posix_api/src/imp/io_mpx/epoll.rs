@@ -9,24 +9,170 @@
 
 //! `epoll` implementation.
 //!
-//! TODO: do not support `EPOLLET` flag
+//! Files with [`PollWaiters`] wake up the epoll instances watching them when
+//! their readiness may have changed, and `epoll_wait` sleeps until then.
+//! Files without them, such as inet sockets without the `irq` feature, are
+//! polled every [`POLL_INTERVAL`] instead.
+//!
+//! With `EPOLLET`, a file is only reported after it has notified the
+//! instance, or, if it has to be polled, for the events which were not ready
+//! when it was last polled.
+//!
+//! [`PollWaiters`]: axio::PollWaiters
 
 use alloc::collections::btree_map::Entry;
 use alloc::collections::BTreeMap;
-use alloc::sync::Arc;
+use alloc::sync::{Arc, Weak};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use core::{ffi::c_int, time::Duration};
 
 use axerrno::{LinuxError, LinuxResult};
+use axio::{PollState, PollWaker};
 use axsync::Mutex;
 use ruxfdtable::{FileLike, RuxStat};
 use ruxhal::time::current_time;
+#[cfg(feature = "multitask")]
+use ruxtask::WaitQueue;
 
-use crate::ctypes;
+use crate::{ctypes, utils::restartable};
 use ruxfs::{AbsPath, OpenFlags};
 use ruxtask::fs::{add_file_like, get_file_like};
 
+/// Interval to poll the files which do not notify their readiness.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Events reported even if they are not requested.
+const EPOLL_ALWAYS: u32 = ctypes::EPOLLERR | ctypes::EPOLLHUP;
+
+/// Wakes up the tasks waiting on an epoll instance.
+struct EpollNotifier {
+    /// Incremented on every wake-up, so that those during polling are not missed.
+    seq: AtomicUsize,
+    #[cfg(feature = "multitask")]
+    wq: WaitQueue,
+}
+
+impl EpollNotifier {
+    fn notify(&self) {
+        self.seq.fetch_add(1, Ordering::AcqRel);
+        #[cfg(feature = "multitask")]
+        self.wq.notify_all(false);
+    }
+}
+
+/// Registered on the [`PollWaiters`] of a watched file.
+///
+/// [`PollWaiters`]: axio::PollWaiters
+struct EpollWaker {
+    /// Whether the file has notified since it was last polled.
+    notified: AtomicBool,
+    notifier: Arc<EpollNotifier>,
+}
+
+impl PollWaker for EpollWaker {
+    fn wake(&self) {
+        self.notified.store(true, Ordering::Release);
+        self.notifier.notify();
+    }
+}
+
+/// A file watched by an epoll instance.
+struct EpollItem {
+    event: ctypes::epoll_event,
+    file: Weak<dyn FileLike>,
+    waker: Arc<EpollWaker>,
+    /// Whether the file does not notify and has to be polled.
+    polled: bool,
+    /// Events ready when the file was last polled.
+    ready: u32,
+    /// Whether the item is disabled by `EPOLLONESHOT` after being reported.
+    disabled: bool,
+}
+
+impl EpollItem {
+    fn new(
+        file: &Arc<dyn FileLike>,
+        event: ctypes::epoll_event,
+        notifier: Arc<EpollNotifier>,
+    ) -> Self {
+        let waker = Arc::new(EpollWaker {
+            // report the events already ready
+            notified: AtomicBool::new(true),
+            notifier,
+        });
+        let polled = match file.poll_waiters() {
+            Some(waiters) => {
+                let exclusive = event.events & ctypes::EPOLLEXCLUSIVE != 0;
+                waiters.register(&(waker.clone() as Arc<dyn PollWaker>), exclusive);
+                false
+            }
+            None => true,
+        };
+        Self {
+            event,
+            file: Arc::downgrade(file),
+            waker,
+            polled,
+            ready: 0,
+            disabled: false,
+        }
+    }
+
+    /// Polls the file for the events to be reported.
+    fn poll(&mut self) -> u32 {
+        let notified = self.waker.notified.swap(false, Ordering::AcqRel);
+        let edge_triggered = self.event.events & ctypes::EPOLLET != 0;
+        if self.disabled || (edge_triggered && !notified && !self.polled) {
+            return 0;
+        }
+        let Some(file) = self.file.upgrade() else {
+            return 0;
+        };
+        let ready = match file.poll() {
+            Ok(state) => poll_events(state),
+            Err(_) => ctypes::EPOLLERR,
+        };
+        let mut events = if edge_triggered && !notified {
+            ready & !self.ready
+        } else {
+            ready
+        };
+        self.ready = ready;
+        events &= self.event.events | EPOLL_ALWAYS;
+        if events != 0 && self.event.events & ctypes::EPOLLONESHOT != 0 {
+            self.disabled = true;
+        }
+        events
+    }
+}
+
+impl Drop for EpollItem {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.upgrade() {
+            if let Some(waiters) = file.poll_waiters() {
+                waiters.unregister(&(self.waker.clone() as Arc<dyn PollWaker>));
+            }
+        }
+    }
+}
+
+fn poll_events(state: PollState) -> u32 {
+    let mut events = 0;
+    if state.readable {
+        events |= ctypes::EPOLLIN;
+    }
+    if state.writable {
+        events |= ctypes::EPOLLOUT;
+    }
+    if state.pollhup {
+        events |= ctypes::EPOLLHUP;
+    }
+    events
+}
+
 pub struct EpollInstance {
-    events: Mutex<BTreeMap<usize, ctypes::epoll_event>>,
+    items: Mutex<BTreeMap<usize, EpollItem>>,
+    notifier: Arc<EpollNotifier>,
 }
 
 unsafe impl Send for ctypes::epoll_event {}
@@ -36,7 +182,12 @@ impl EpollInstance {
     // TODO: parse flags
     pub fn new(_flags: usize) -> Self {
         Self {
-            events: Mutex::new(BTreeMap::new()),
+            items: Mutex::new(BTreeMap::new()),
+            notifier: Arc::new(EpollNotifier {
+                seq: AtomicUsize::new(0),
+                #[cfg(feature = "multitask")]
+                wq: WaitQueue::new(),
+            }),
         }
     }
 
@@ -48,77 +199,116 @@ impl EpollInstance {
     }
 
     fn control(&self, op: usize, fd: usize, event: &ctypes::epoll_event) -> LinuxResult<usize> {
-        match get_file_like(fd as c_int) {
-            Ok(_) => {}
-            Err(e) => return Err(e),
-        }
+        let file = get_file_like(fd as c_int)?;
+        let exclusive = event.events & ctypes::EPOLLEXCLUSIVE != 0;
 
         match op as u32 {
             ctypes::EPOLL_CTL_ADD => {
-                if let Entry::Vacant(e) = self.events.lock().entry(fd) {
-                    e.insert(*event);
-                } else {
-                    return Err(LinuxError::EEXIST);
+                if exclusive && event.events & ctypes::EPOLLONESHOT != 0 {
+                    return Err(LinuxError::EINVAL);
+                }
+                let item = EpollItem::new(&file, *event, self.notifier.clone());
+                match self.items.lock().entry(fd) {
+                    Entry::Vacant(e) => {
+                        e.insert(item);
+                    }
+                    // the file watched before has been closed
+                    Entry::Occupied(mut ocp) if ocp.get().file.strong_count() == 0 => {
+                        ocp.insert(item);
+                    }
+                    Entry::Occupied(_) => return Err(LinuxError::EEXIST),
                 }
             }
             ctypes::EPOLL_CTL_MOD => {
-                let mut events = self.events.lock();
-                if let Entry::Occupied(mut ocp) = events.entry(fd) {
-                    ocp.insert(*event);
-                } else {
-                    return Err(LinuxError::ENOENT);
+                let mut items = self.items.lock();
+                let item = items.get_mut(&fd).ok_or(LinuxError::ENOENT)?;
+                // `EPOLLEXCLUSIVE` can only be set by `EPOLL_CTL_ADD`
+                if exclusive || item.event.events & ctypes::EPOLLEXCLUSIVE != 0 {
+                    return Err(LinuxError::EINVAL);
                 }
+                item.event = *event;
+                item.ready = 0;
+                item.disabled = false;
+                item.waker.notified.store(true, Ordering::Release);
             }
             ctypes::EPOLL_CTL_DEL => {
-                let mut events = self.events.lock();
-                if let Entry::Occupied(ocp) = events.entry(fd) {
-                    ocp.remove_entry();
-                } else {
-                    return Err(LinuxError::ENOENT);
-                }
+                self.items.lock().remove(&fd).ok_or(LinuxError::ENOENT)?;
             }
             _ => {
                 return Err(LinuxError::EINVAL);
             }
         }
+        // let the waiting tasks poll the file
+        self.notifier.notify();
         Ok(0)
     }
 
-    fn poll_all(&self, events: &mut [ctypes::epoll_event]) -> LinuxResult<usize> {
-        let ready_list = self.events.lock();
-        let mut events_num = 0;
+    fn poll_all(&self, events: &mut [ctypes::epoll_event]) -> usize {
+        let mut items = self.items.lock();
+        // forget the files which have been closed
+        items.retain(|_, item| item.file.strong_count() > 0);
 
-        for (infd, ev) in ready_list.iter() {
-            match get_file_like(*infd as c_int)?.poll() {
-                Err(_) => {
-                    if (ev.events & ctypes::EPOLLERR) != 0 {
-                        events[events_num].events = ctypes::EPOLLERR;
-                        events[events_num].data = ev.data;
-                        events_num += 1;
-                    }
-                }
-                Ok(state) => {
-                    if state.readable && (ev.events & ctypes::EPOLLIN != 0) {
-                        events[events_num].events = ctypes::EPOLLIN;
-                        events[events_num].data = ev.data;
-                        events_num += 1;
-                    }
+        let mut events_num = 0;
+        for item in items.values_mut() {
+            if events_num == events.len() {
+                break;
+            }
+            let ready = item.poll();
+            if ready != 0 {
+                events[events_num].events = ready;
+                events[events_num].data = item.event.data;
+                events_num += 1;
+            }
+        }
+        events_num
+    }
 
-                    if state.writable && (ev.events & ctypes::EPOLLOUT != 0) {
-                        events[events_num].events = ctypes::EPOLLOUT;
-                        events[events_num].data = ev.data;
-                        events_num += 1;
-                    }
+    /// Whether some of the files have to be polled.
+    fn has_polled(&self) -> bool {
+        self.items
+            .lock()
+            .values()
+            .any(|item| item.polled && !item.disabled)
+    }
 
-                    if state.pollhup {
-                        events[events_num].events = ctypes::EPOLLHUP;
-                        events[events_num].data = ev.data;
-                        events_num += 1;
-                    }
+    /// Sleeps until notified after `seq` was read, or until `deadline`.
+    fn wait(&self, seq: usize, deadline: Option<Duration>) {
+        #[cfg(feature = "multitask")]
+        {
+            let wq = &self.notifier.wq;
+            let condition = || {
+                #[cfg(feature = "signal")]
+                if ruxtask::Signal::has_pending() {
+                    return Err(());
                 }
-            }
+                if self.notifier.seq.load(Ordering::Acquire) != seq {
+                    return Err(());
+                }
+                Ok(())
+            };
+            let wait = || match deadline {
+                #[cfg(feature = "irq")]
+                Some(deadline) => wq
+                    .wait_timeout_absolutely_meta_if(deadline, (), condition)
+                    .map(|_| ()),
+                // no timer to wake up the task
+                #[cfg(not(feature = "irq"))]
+                Some(_) => {
+                    ruxtask::yield_now();
+                    Ok(())
+                }
+                None => wq.wait_meta_if((), condition).map(|_| ()),
+            };
+            #[cfg(feature = "signal")]
+            let _ = ruxtask::Signal::interruptible(wait);
+            #[cfg(not(feature = "signal"))]
+            let _ = wait();
+        }
+        #[cfg(not(feature = "multitask"))]
+        {
+            let _ = (seq, deadline);
+            crate::sys_sched_yield();
         }
-        Ok(events_num)
     }
 }
 
@@ -228,39 +418,32 @@ pub unsafe fn sys_epoll_wait(
         let deadline = (!timeout.is_negative())
             .then(|| current_time() + Duration::from_millis(timeout as u64));
         let epoll_instance = EpollInstance::from_fd(epfd)?;
-        loop {
+        // never restarted after a signal handler, see signal(7)
+        restartable(false, || loop {
             #[cfg(feature = "net")]
             ruxnet::poll_interfaces();
-            let poll_all_res = epoll_instance.poll_all(events);
-            let mut events_num = 0;
-            match poll_all_res {
-                Ok(num) => events_num = num,
-                Err(LinuxError::EBADF) => {
-                    error!("sys_epoll_wait a non-exist fd");
-                    let mut events = epoll_instance.events.lock();
-                    let del_fds = events
-                        .iter()
-                        .filter(|(&fd, _)| get_file_like(fd as _).is_err())
-                        .map(|(&fd, _)| fd)
-                        .collect::<alloc::vec::Vec<_>>();
-                    del_fds.iter().for_each(|&fd| {
-                        if let Entry::Occupied(ocp) = events.entry(fd) {
-                            ocp.remove_entry();
-                        }
-                    });
-                    return Ok(0);
-                }
-                Err(_) => {}
-            }
+            let seq = epoll_instance.notifier.seq.load(Ordering::Acquire);
+            let events_num = epoll_instance.poll_all(events);
             if events_num > 0 {
                 return Ok(events_num as c_int);
             }
 
-            if deadline.is_some_and(|ddl| current_time() >= ddl) {
+            let now = current_time();
+            if deadline.is_some_and(|ddl| now >= ddl) {
                 debug!("    timeout!");
                 return Ok(0);
             }
-            crate::sys_sched_yield();
-        }
+            #[cfg(feature = "signal")]
+            if ruxtask::Signal::interrupted() {
+                return Err(LinuxError::EINTR);
+            }
+            let wake_at = if epoll_instance.has_polled() {
+                let next_poll = now + POLL_INTERVAL;
+                Some(deadline.map_or(next_poll, |ddl| ddl.min(next_poll)))
+            } else {
+                deadline
+            };
+            epoll_instance.wait(seq, wake_at);
+        })
     })
 }
@@ -14,6 +14,21 @@ use ruxtask::fs::get_file_like;
 pub const FIONBIO: usize = 0x5421;
 pub const FIOCLEX: usize = 0x5451;
 
+// ioctls of the routing table and the interfaces on sockets
+pub const SIOCADDRT: usize = 0x890B;
+pub const SIOCDELRT: usize = 0x890C;
+pub const SIOCGIFCONF: usize = 0x8912;
+pub const SIOCGIFFLAGS: usize = 0x8913;
+pub const SIOCSIFFLAGS: usize = 0x8914;
+pub const SIOCGIFADDR: usize = 0x8915;
+pub const SIOCSIFADDR: usize = 0x8916;
+pub const SIOCGIFBRDADDR: usize = 0x8919;
+pub const SIOCGIFNETMASK: usize = 0x891B;
+pub const SIOCSIFNETMASK: usize = 0x891C;
+pub const SIOCGIFMTU: usize = 0x8921;
+pub const SIOCGIFHWADDR: usize = 0x8927;
+pub const SIOCGIFINDEX: usize = 0x8933;
+
 /// ioctl implementation
 pub fn sys_ioctl(fd: c_int, request: usize, data: usize) -> c_int {
     debug!("sys_ioctl <= fd: {fd}, request: {request}");
@@ -30,6 +45,10 @@ pub fn sys_ioctl(fd: c_int, request: usize, data: usize) -> c_int {
                 Ok(0)
             }
             FIOCLEX => Ok(0),
+            #[cfg(feature = "net")]
+            SIOCADDRT | SIOCDELRT | SIOCGIFCONF | SIOCGIFFLAGS | SIOCSIFFLAGS | SIOCGIFADDR
+            | SIOCSIFADDR | SIOCGIFBRDADDR | SIOCGIFNETMASK | SIOCSIFNETMASK | SIOCGIFMTU
+            | SIOCGIFHWADDR | SIOCGIFINDEX => crate::imp::net::socket_ioctl(fd, request, data),
             _ => {
                 get_file_like(fd)?.ioctl(request, data)?;
                 Ok(0)
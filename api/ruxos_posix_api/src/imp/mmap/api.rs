@@ -27,7 +27,7 @@ use ruxtask::vma::Vma;
 
 #[cfg(feature = "fs")]
 use {
-    super::utils::{release_pages_swaped, write_into},
+    super::utils::{release_pages_swaped, write_back},
     alloc::sync::Arc,
     ruxtask::vma::FileInfo,
 };
@@ -299,7 +299,7 @@ pub fn sys_msync(start: *mut c_void, len: ctypes::size_t, flags: c_int) -> c_int
             for (&vaddr, page_info) in current().mm.mem_map.lock().range(start..end) {
                 if let Some(FileInfo { file, offset, size }) = &page_info.mapping_file {
                     let src = vaddr as *mut u8;
-                    write_into(file, src, *offset as u64, *size);
+                    write_back(file, src, *offset as u64, *size);
                 }
             }
         }
@@ -97,8 +97,8 @@ impl ruxhal::trap::TrapHandler for TrapHandlerImpl {
                 assert!(mapping_flags.contains(MappingFlags::READ));
                 assert!(!mapping_flags.contains(MappingFlags::WRITE));
                 let mem_arc = mem_item.unwrap();
-                if Arc::strong_count(mem_arc).eq(&1) {
-                    // the last owner of the page, we can safely map it.
+                if vma.is_shared() || mem_arc.ref_count() == 1 {
+                    // the last owner of the page (or the page is shared on purpose), we can safely map it.
                     pte_update_page(vaddr.into(), None, Some(map_flag))
                         .expect("failed to update page table entry");
                     return true;
@@ -121,8 +121,28 @@ impl ruxhal::trap::TrapHandler for TrapHandlerImpl {
             // fake_vaddr = preload() => do_pte_map(vaddr... fake_vaddr ...)
             #[cfg(not(feature = "fs"))]
             let fake_vaddr = alloc_page_preload().expect("alloc memory for new page failed");
+            //
+            // The pages of shared file mappings are the pages in the page
+            // cache if the file is cached, which are mapped directly.
             #[cfg(feature = "fs")]
-            let fake_vaddr = preload_page_with_swap(memory_map, &mut swaped_map, &mut off_pool);
+            let cached_page = match &vma.file {
+                Some(file) if !is_cow && vma.is_shared() => {
+                    let off = (vma.offset + (vaddr - vma.start_addr)) as u64;
+                    match file.map_page(off, map_flag.contains(MappingFlags::WRITE)) {
+                        Ok(page) => page.map(VirtAddr::from),
+                        Err(err) => {
+                            error!("Page Fault: failed to map the cached page, vaddr:0x{vaddr:x?}, err:{err:?}");
+                            return false;
+                        }
+                    }
+                }
+                _ => None,
+            };
+            #[cfg(feature = "fs")]
+            let fake_vaddr = match cached_page {
+                Some(page) => page,
+                None => preload_page_with_swap(memory_map, &mut swaped_map, &mut off_pool),
+            };
 
             // Fill target data to assigned physical addresses, from file or zero according to mapping type
             let dst: *mut u8 = fake_vaddr.as_mut_ptr();
@@ -133,6 +153,8 @@ impl ruxhal::trap::TrapHandler for TrapHandlerImpl {
                 {
                     if let Some(swap_info) = swaped_map.remove(&vaddr) {
                         read_from(&SWAP_FILE, dst, swap_info.offset as u64, size);
+                    } else if cached_page.is_some() {
+                        // the cached page is filled by the page cache
                     } else if let Some(file) = &vma.file {
                         let off = (vma.offset + (vaddr - vma.start_addr)) as u64;
                         read_from(file, dst, off, size);
@@ -199,7 +221,8 @@ impl ruxhal::trap::TrapHandler for TrapHandlerImpl {
                     Err(_) => false,
                 }
             } else {
-                // get here if the page is belong to current process and is in Copy-on-Write mode.
+                // get here if the page is shared with other processes and is in Copy-on-Write mode,
+                // the reference count of the original page is decreased when its `PageInfo` is removed.
                 unsafe {
                     dst.copy_from(vaddr as *mut u8, size);
                 }
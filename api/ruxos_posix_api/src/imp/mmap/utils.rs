@@ -59,6 +59,17 @@ pub(crate) fn write_into(file: &Arc<File>, buf: *mut u8, offset: u64, len: usize
     }
 }
 
+/// write back the page of a shared file mapping at `buf` into target file
+#[cfg(feature = "fs")]
+pub(crate) fn write_back(file: &Arc<File>, buf: *mut u8, offset: u64, len: usize) {
+    if file.is_cached() {
+        // the page is the cached page itself
+        file.sync_page(offset).expect("write_back failed");
+    } else {
+        write_into(file, buf, offset, len);
+    }
+}
+
 /// transform usize-like mmap flags to MappingFlags
 pub(crate) fn get_mflags_from_usize(prot: u32) -> MappingFlags {
     let mut mmap_prot = MappingFlags::empty();
@@ -224,7 +235,7 @@ pub(crate) fn release_pages_mapped(start: usize, end: usize) {
         #[cfg(feature = "fs")]
         if let Some(FileInfo { file, offset, size }) = &page_info.mapping_file {
             let src = vaddr as *mut u8;
-            write_into(file, src, *offset as u64, *size);
+            write_back(file, src, *offset as u64, *size);
         }
         if pte_unmap_page(VirtAddr::from(vaddr)).is_err() {
             panic!("Release page failed when munmapping!");
@@ -341,14 +352,20 @@ pub(crate) fn preload_page_with_swap(
         Ok(vaddr) => vaddr,
         // Try to swap the mapped memory into Disk and use this segment of physical memory
         #[cfg(feature = "fs")]
-        Err(PagingError::NoMemory) => match memory_map.pop_first() {
+        // Pages shared with other processes (Copy-on-Write after fork) can't be reused here.
+        Err(PagingError::NoMemory) => match memory_map
+            .iter()
+            .find(|(_, page_info)| page_info.ref_count() == 1)
+            .map(|(&vaddr, _)| vaddr)
+            .and_then(|vaddr| memory_map.remove_entry(&vaddr))
+        {
             // Some((vaddr_swapped, PageInfo{paddr:_, mapping_file:Some(FileInfo{file, offset, size})})) => {
             Some((vaddr_swapped, page_info)) => {
                 match &page_info.mapping_file {
                     // For file mapping, the mapped content will be written directly to the original file.
                     Some(FileInfo { file, offset, size }) => {
                         let offset = *offset as u64;
-                        write_into(file, vaddr_swapped as *mut u8, offset, *size);
+                        write_back(file, vaddr_swapped as *mut u8, offset, *size);
                         pte_swap_preload(VirtAddr::from(vaddr_swapped)).unwrap()
                     }
                     // For anonymous mapping, you need to save the mapped memory to the prepared swap file,
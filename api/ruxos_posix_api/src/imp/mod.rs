@@ -19,6 +19,8 @@ pub mod sys;
 pub mod task;
 pub mod time;
 
+#[cfg(feature = "fd")]
+pub mod eventfd;
 #[cfg(feature = "fs")]
 pub mod execve;
 #[cfg(feature = "fd")]
@@ -41,6 +43,10 @@ pub mod pthread;
 pub mod rt_sig;
 #[cfg(feature = "signal")]
 pub mod signal;
+#[cfg(all(feature = "fd", feature = "signal"))]
+pub mod signalfd;
+#[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+pub mod timerfd;
 
 /// Invalid syscall
 pub fn sys_invalid(id: core::ffi::c_int) -> core::ffi::c_int {
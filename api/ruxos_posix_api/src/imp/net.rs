@@ -7,11 +7,10 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::{sync::Arc, vec, vec::Vec};
-use axsync::Mutex;
+use alloc::{string::String, sync::Arc, vec, vec::Vec};
 use core::ffi::{c_char, c_int, c_void};
 use core::mem::size_of;
-use core::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 use iovec::{read_iovecs_ptr, IoVecsInput, IoVecsOutput};
 use ruxnet::address::{SocketAddress, UnixSocketAddr};
 use ruxnet::message::{ControlMessageData, MessageFlags};
@@ -21,10 +20,14 @@ use ruxtask::fs::{add_file_like, get_file_like};
 
 use axerrno::{LinuxError, LinuxResult};
 use ruxfdtable::OpenFlags;
-use ruxnet::{ShutdownFlags, TcpSocket, UdpSocket};
+use ruxnet::ShutdownFlags;
 
 use crate::ctypes::{self};
 use crate::imp::fs::parse_path;
+use crate::imp::ioctl::{
+    SIOCADDRT, SIOCDELRT, SIOCGIFADDR, SIOCGIFBRDADDR, SIOCGIFCONF, SIOCGIFFLAGS, SIOCGIFHWADDR,
+    SIOCGIFINDEX, SIOCGIFMTU, SIOCGIFNETMASK, SIOCSIFADDR, SIOCSIFFLAGS, SIOCSIFNETMASK,
+};
 use crate::utils::char_ptr_to_str;
 
 const SA_FAMILY_SIZE: usize = size_of::<ctypes::sa_family_t>();
@@ -68,7 +71,13 @@ fn parse_socket_address(
                 Ok(SocketAddress::Unix(UnixSocketAddr::PathName(abs_path)))
             }
         }
-        SocketDomain::Inet6 => Err(LinuxError::EAFNOSUPPORT),
+        SocketDomain::Inet6 => {
+            if addrlen < (size_of::<ctypes::sockaddr_in6>() as u32) {
+                return Err(LinuxError::EINVAL);
+            }
+            let addr = unsafe { *(addr as *const ctypes::sockaddr_in6) };
+            Ok(SocketAddress::Inet6(addr.into()))
+        }
     }
 }
 
@@ -121,7 +130,19 @@ fn write_sockaddr_with_max_len(
             }
             actual_len
         }
-        SocketAddress::Inet6(_) => todo!(),
+        SocketAddress::Inet6(ipv6_addr) => {
+            let actual_len = size_of::<ctypes::sockaddr_in6>();
+            let write_len = core::cmp::min(actual_len, max_len as usize);
+            let sockaddr_in6 = ctypes::sockaddr_in6::from(ipv6_addr);
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &sockaddr_in6 as *const ctypes::sockaddr_in6 as *const u8,
+                    addr_ptr as *mut u8,
+                    write_len,
+                );
+            }
+            actual_len
+        }
     };
     Ok(actual_len as u32)
 }
@@ -164,6 +185,33 @@ impl From<SocketAddrV4> for ctypes::sockaddr_in {
     }
 }
 
+impl From<ctypes::sockaddr_in6> for SocketAddrV6 {
+    fn from(addr: ctypes::sockaddr_in6) -> SocketAddrV6 {
+        SocketAddrV6::new(
+            Ipv6Addr::from(unsafe { addr.sin6_addr.__in6_union.__s6_addr }),
+            u16::from_be(addr.sin6_port),
+            u32::from_be(addr.sin6_flowinfo),
+            addr.sin6_scope_id,
+        )
+    }
+}
+
+impl From<SocketAddrV6> for ctypes::sockaddr_in6 {
+    fn from(addr: SocketAddrV6) -> ctypes::sockaddr_in6 {
+        ctypes::sockaddr_in6 {
+            sin6_family: ctypes::AF_INET6 as u16,
+            sin6_port: addr.port().to_be(),
+            sin6_flowinfo: addr.flowinfo().to_be(),
+            sin6_addr: ctypes::in6_addr {
+                __in6_union: ctypes::in6_addr__bindgen_ty_1 {
+                    __s6_addr: addr.ip().octets(),
+                },
+            },
+            sin6_scope_id: addr.scope_id(),
+        }
+    }
+}
+
 /// Create an socket for communication.
 ///
 /// Return the socket file descriptor.
@@ -180,31 +228,89 @@ pub fn sys_socket(domain: c_int, socktype: c_int, protocol: c_int) -> c_int {
         debug!("sys_socket <= {domain:?} {socktype:?} {protocol}");
         debug!("nonblock: {nonblock}, cloexec: {flags:?}");
         let f = match domain {
-            SocketDomain::Inet => match socktype {
-                SocketType::Stream => Arc::new(Socket::Tcp(Mutex::new(TcpSocket::new(nonblock)))),
-                SocketType::Datagram => Arc::new(Socket::Udp(Mutex::new(UdpSocket::new()))),
-            },
+            SocketDomain::Inet | SocketDomain::Inet6 => Arc::new(Socket::new_inet(
+                domain,
+                socktype,
+                protocol as u32,
+                nonblock,
+            )?),
             SocketDomain::Unix => UnixSocket::create_socket(socktype, nonblock),
-            SocketDomain::Inet6 => return Err(LinuxError::EAFNOSUPPORT),
         };
         add_file_like(f, flags)
     })
 }
 
-/// `setsockopt`, currently ignored
+/// `setsockopt`, only `SO_BINDTODEVICE`, `IP_HDRINCL`, `IPV6_HDRINCL` and
+/// `IPV6_V6ONLY` are supported and others are ignored
 ///
-/// TODO: implement this
+/// TODO: implement others
 pub fn sys_setsockopt(
     fd: c_int,
     level: c_int,
     optname: c_int,
-    _optval: *const c_void,
+    optval: *const c_void,
     optlen: ctypes::socklen_t,
 ) -> c_int {
-    debug!(
-        "sys_setsockopt <= fd: {fd}, level: {level}, optname: {optname}, optlen: {optlen}, IGNORED"
-    );
-    syscall_body!(sys_setsockopt, Ok(0))
+    debug!("sys_setsockopt <= fd: {fd}, level: {level}, optname: {optname}, optlen: {optlen}");
+    syscall_body!(sys_setsockopt, {
+        if (level as u32, optname as u32) == (ctypes::SOL_SOCKET, ctypes::SO_BINDTODEVICE) {
+            // an empty name unbinds the socket
+            let name = if optval.is_null() || optlen == 0 {
+                ""
+            } else {
+                let bytes =
+                    unsafe { core::slice::from_raw_parts(optval as *const u8, optlen as _) };
+                let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+                core::str::from_utf8(&bytes[..len]).map_err(|_| LinuxError::EINVAL)?
+            };
+            let dev = (!name.is_empty()).then_some(name);
+            match &*socket_from_fd(fd)? {
+                Socket::Tcp(tcpsocket) => tcpsocket.lock().bind_to_device(dev)?,
+                Socket::Udp(udpsocket) => udpsocket.lock().bind_to_device(dev)?,
+                Socket::Raw(rawsocket) => rawsocket.lock().bind_to_device(dev)?,
+                Socket::Icmp(icmpsocket) => icmpsocket.lock().bind_to_device(dev)?,
+                Socket::Unix(_) => {}
+            }
+            return Ok(0);
+        }
+        if is_hdrincl_opt(level, optname) {
+            if optval.is_null() {
+                return Err(LinuxError::EFAULT);
+            }
+            if (optlen as usize) < size_of::<c_int>() {
+                return Err(LinuxError::EINVAL);
+            }
+            let hdrincl = unsafe { *(optval as *const c_int) } != 0;
+            match &*socket_from_fd(fd)? {
+                Socket::Raw(rawsocket) => rawsocket.lock().set_hdrincl(hdrincl),
+                _ => return Err(LinuxError::ENOPROTOOPT),
+            }
+            return Ok(0);
+        }
+        if (level as u32, optname as u32) != (ctypes::IPPROTO_IPV6, ctypes::IPV6_V6ONLY) {
+            debug!("sys_setsockopt IGNORED");
+            return Ok(0);
+        }
+        if optval.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        if (optlen as usize) < size_of::<c_int>() {
+            return Err(LinuxError::EINVAL);
+        }
+        let socket = socket_from_fd(fd)?;
+        if socket.domain() != SocketDomain::Inet6 {
+            return Err(LinuxError::ENOPROTOOPT);
+        }
+        let v6only = unsafe { *(optval as *const c_int) } != 0;
+        match &*socket {
+            Socket::Tcp(tcpsocket) => tcpsocket.lock().set_v6only(v6only)?,
+            Socket::Udp(udpsocket) => udpsocket.lock().set_v6only(v6only)?,
+            // only talk to IPv6 peers anyway
+            Socket::Raw(_) | Socket::Icmp(_) => {}
+            Socket::Unix(_) => unreachable!(),
+        }
+        Ok(0)
+    })
 }
 
 /// Bind a address to a socket.
@@ -401,7 +507,7 @@ pub fn sys_shutdown(socket_fd: c_int, how: c_int) -> c_int {
 
 /// Query addresses for a domain name.
 ///
-/// Only IPv4. Ports are always 0. Ignore servname and hint.
+/// Ignore hint, and the servname must be a port number.
 /// Results' ai_flags and ai_canonname are 0 or NULL.
 ///
 /// Return address number if success.
@@ -440,27 +546,38 @@ pub unsafe fn sys_getaddrinfo(
 
         let mut out: Vec<ctypes::aibuf> = Vec::with_capacity(len);
         for (i, &ip) in ip_addrs.iter().enumerate().take(len) {
-            let buf = match ip {
-                IpAddr::V4(ip) => ctypes::aibuf {
-                    ai: ctypes::addrinfo {
-                        ai_family: ctypes::AF_INET as _,
-                        // TODO: This is a hard-code part, only return TCP parameters
-                        ai_socktype: ctypes::SOCK_STREAM as _,
-                        ai_protocol: ctypes::IPPROTO_TCP as _,
-                        ai_addrlen: size_of::<ctypes::sockaddr_in>() as _,
-                        ai_addr: core::ptr::null_mut(),
-                        ai_canonname: core::ptr::null_mut(),
-                        ai_next: core::ptr::null_mut(),
-                        ai_flags: 0,
-                    },
-                    sa: ctypes::aibuf_sa {
+            let (family, addrlen, sa) = match ip {
+                IpAddr::V4(ip) => (
+                    ctypes::AF_INET,
+                    size_of::<ctypes::sockaddr_in>(),
+                    ctypes::aibuf_sa {
                         sin: SocketAddrV4::new(ip, port).into(),
                     },
-                    slot: i as i16,
-                    lock: [0],
-                    ref_: 0,
+                ),
+                IpAddr::V6(ip) => (
+                    ctypes::AF_INET6,
+                    size_of::<ctypes::sockaddr_in6>(),
+                    ctypes::aibuf_sa {
+                        sin6: SocketAddrV6::new(ip, port, 0, 0).into(),
+                    },
+                ),
+            };
+            let buf = ctypes::aibuf {
+                ai: ctypes::addrinfo {
+                    ai_family: family as _,
+                    // TODO: This is a hard-code part, only return TCP parameters
+                    ai_socktype: ctypes::SOCK_STREAM as _,
+                    ai_protocol: ctypes::IPPROTO_TCP as _,
+                    ai_addrlen: addrlen as _,
+                    ai_addr: core::ptr::null_mut(),
+                    ai_canonname: core::ptr::null_mut(),
+                    ai_next: core::ptr::null_mut(),
+                    ai_flags: 0,
                 },
-                _ => panic!("IPv6 is not supported"),
+                sa,
+                slot: i as i16,
+                lock: [0],
+                ref_: 0,
             };
             out.push(buf);
             out[i].ai.ai_addr =
@@ -533,18 +650,62 @@ pub fn sys_getsockopt(
         }
         let socket = socket_from_fd(socket_fd)?;
         match level as u32 {
+            ctypes::IPPROTO_IP | ctypes::IPPROTO_IPV6 if is_hdrincl_opt(level, optname) => {
+                let Socket::Raw(rawsocket) = &*socket else {
+                    return Err(LinuxError::ENOPROTOOPT);
+                };
+                let val = rawsocket.lock().hdrincl();
+                unsafe {
+                    core::ptr::write(optlen, size_of::<c_int>() as _);
+                    core::ptr::write(optval as *mut c_int, val as c_int);
+                }
+                Ok(0)
+            }
+            ctypes::IPPROTO_IPV6 => {
+                if optname as u32 != ctypes::IPV6_V6ONLY || socket.domain() != SocketDomain::Inet6 {
+                    return Err(LinuxError::ENOPROTOOPT);
+                }
+                let val = match &*socket {
+                    Socket::Tcp(tcpsocket) => tcpsocket.lock().is_v6only(),
+                    Socket::Udp(udpsocket) => udpsocket.lock().is_v6only(),
+                    Socket::Raw(_) | Socket::Icmp(_) => true,
+                    Socket::Unix(_) => unreachable!(),
+                };
+                unsafe {
+                    core::ptr::write(optlen, size_of::<c_int>() as _);
+                    core::ptr::write(optval as *mut c_int, val as c_int);
+                }
+                Ok(0)
+            }
+            ctypes::SOL_SOCKET if optname as u32 == ctypes::SO_BINDTODEVICE => {
+                let dev = match &*socket {
+                    Socket::Tcp(tcpsocket) => tcpsocket.lock().bound_device(),
+                    Socket::Udp(udpsocket) => udpsocket.lock().bound_device(),
+                    Socket::Raw(rawsocket) => rawsocket.lock().bound_device(),
+                    Socket::Icmp(icmpsocket) => icmpsocket.lock().bound_device(),
+                    Socket::Unix(_) => None,
+                };
+                // the name with a NUL, or nothing if not bound
+                let len = dev.as_ref().map_or(0, |dev| dev.len() + 1);
+                if (unsafe { *optlen } as usize) < len {
+                    return Err(LinuxError::EINVAL);
+                }
+                if let Some(dev) = dev {
+                    let buf = unsafe { core::slice::from_raw_parts_mut(optval as *mut u8, len) };
+                    buf[..dev.len()].copy_from_slice(dev.as_bytes());
+                    buf[dev.len()] = 0;
+                }
+                unsafe { *optlen = len as _ };
+                Ok(0)
+            }
             ctypes::SOL_SOCKET => {
                 let val = match optname as u32 {
                     ctypes::SO_ACCEPTCONN => match &*socket {
-                        Socket::Udp(_) => 0,
+                        Socket::Udp(_) | Socket::Raw(_) | Socket::Icmp(_) => 0,
                         Socket::Tcp(tcpsocket) => tcpsocket.lock().is_listening() as u32,
                         Socket::Unix(unixsocket) => unixsocket.is_listening() as u32,
                     },
-                    ctypes::SO_TYPE => match &*socket {
-                        Socket::Udp(_) => ctypes::SOCK_DGRAM,
-                        Socket::Tcp(_) => ctypes::SOCK_STREAM,
-                        Socket::Unix(unixsocket) => unixsocket.socket_type().into(),
-                    },
+                    ctypes::SO_TYPE => socket.socket_type().into(),
                     ctypes::SO_RCVLOWAT | ctypes::SO_SNDLOWAT | ctypes::SO_BROADCAST => 1,
                     ctypes::SO_ERROR
                     | ctypes::SO_DONTROUTE
@@ -555,8 +716,7 @@ pub fn sys_getsockopt(
                     | ctypes::SO_RCVTIMEO
                     | ctypes::SO_REUSEADDR
                     | ctypes::SO_SNDBUF
-                    | ctypes::SO_SNDTIMEO
-                    | ctypes::SO_BINDTODEVICE => 0,
+                    | ctypes::SO_SNDTIMEO => 0,
                     _ => return Err(LinuxError::ENOPROTOOPT),
                 };
 
@@ -793,6 +953,256 @@ fn socket_from_fd(fd: i32) -> LinuxResult<Arc<Socket>> {
         .map_err(|_| LinuxError::ENOTSOCK)
 }
 
+/// Whether the option is `IP_HDRINCL` or `IPV6_HDRINCL` of raw sockets.
+fn is_hdrincl_opt(level: c_int, optname: c_int) -> bool {
+    matches!(
+        (level as u32, optname as u32),
+        (ctypes::IPPROTO_IP, ctypes::IP_HDRINCL) | (ctypes::IPPROTO_IPV6, ctypes::IPV6_HDRINCL)
+    )
+}
+
+/*
+* the ioctls of the interfaces and the routing table, for `ifconfig` and `route`
+* see https://man7.org/linux/man-pages/man7/netdevice.7.html
+*/
+
+const IFNAMSIZ: usize = 16;
+
+const IFF_UP: i16 = 0x1;
+const IFF_BROADCAST: i16 = 0x2;
+const IFF_LOOPBACK: i16 = 0x8;
+const IFF_RUNNING: i16 = 0x40;
+const IFF_MULTICAST: i16 = 0x1000;
+
+const ARPHRD_ETHER: u16 = 1;
+const ARPHRD_LOOPBACK: u16 = 772;
+
+const RTF_GATEWAY: u16 = 0x2;
+const RTF_HOST: u16 = 0x4;
+
+/// `struct ifreq` in `<net/if.h>`.
+#[repr(C)]
+struct IfReq {
+    name: [c_char; IFNAMSIZ],
+    data: IfReqData,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+union IfReqData {
+    addr: ctypes::sockaddr_in,
+    hwaddr: ctypes::sockaddr,
+    flags: i16,
+    ivalue: c_int,
+    _map: [usize; 3],
+}
+
+/// `struct ifconf` in `<net/if.h>`.
+#[repr(C)]
+struct IfConf {
+    len: c_int,
+    req: *mut IfReq,
+}
+
+/// `struct rtentry` in `<net/route.h>`.
+#[repr(C)]
+struct RtEntry {
+    _pad1: usize,
+    dst: ctypes::sockaddr_in,
+    gateway: ctypes::sockaddr_in,
+    genmask: ctypes::sockaddr_in,
+    flags: u16,
+    _pad2: i16,
+    _pad3: usize,
+    _pad4: *mut c_void,
+    _metric: i16,
+    dev: *const c_char,
+    _mtu: usize,
+    _window: usize,
+    _irtt: u16,
+}
+
+fn ifreq_name(req: &IfReq) -> LinuxResult<String> {
+    let len = req.name.iter().position(|&c| c == 0).unwrap_or(IFNAMSIZ);
+    let bytes = unsafe { core::slice::from_raw_parts(req.name.as_ptr() as *const u8, len) };
+    core::str::from_utf8(bytes)
+        .map(String::from)
+        .map_err(|_| LinuxError::ENODEV)
+}
+
+fn ifreq_of(name: &str, data: IfReqData) -> IfReq {
+    let mut req = IfReq {
+        name: [0; IFNAMSIZ],
+        data,
+    };
+    for (c, &b) in req
+        .name
+        .iter_mut()
+        .zip(&name.as_bytes()[..name.len().min(IFNAMSIZ - 1)])
+    {
+        *c = b as c_char;
+    }
+    req
+}
+
+fn sockaddr_ipv4(addr: &ctypes::sockaddr_in) -> Ipv4Addr {
+    *SocketAddrV4::from(*addr).ip()
+}
+
+fn ipv4_sockaddr(addr: Ipv4Addr) -> ctypes::sockaddr_in {
+    SocketAddrV4::new(addr, 0).into()
+}
+
+fn prefix_netmask(prefix_len: u8) -> Ipv4Addr {
+    Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0))
+}
+
+/// Returns the prefix length of the netmask, which must be contiguous.
+fn netmask_prefix(netmask: Ipv4Addr) -> LinuxResult<u8> {
+    let netmask = u32::from(netmask);
+    let prefix_len = netmask.leading_ones();
+    if netmask.checked_shl(prefix_len).unwrap_or(0) != 0 {
+        return Err(LinuxError::EINVAL);
+    }
+    Ok(prefix_len as u8)
+}
+
+/// Returns the prefix length of the class of the address, which is given to
+/// an interface without an address yet like Linux.
+fn classful_prefix(addr: Ipv4Addr) -> u8 {
+    match addr.octets()[0] {
+        0..=127 => 8,
+        128..=191 => 16,
+        192..=223 => 24,
+        _ => 32,
+    }
+}
+
+/// Lists the interfaces with IPv4 addresses, or returns the length needed if
+/// the buffer is null.
+fn get_ifconf(conf: &mut IfConf) -> LinuxResult<c_int> {
+    let ifaces: Vec<_> = ruxnet::interfaces()
+        .into_iter()
+        .filter_map(|iface| iface.ipv4.map(|(addr, _)| (iface.name, addr)))
+        .collect();
+    if conf.req.is_null() {
+        conf.len = (ifaces.len() * size_of::<IfReq>()) as c_int;
+        return Ok(0);
+    }
+    let capacity = conf.len.max(0) as usize / size_of::<IfReq>();
+    let reqs = unsafe { core::slice::from_raw_parts_mut(conf.req, capacity) };
+    let count = reqs.len().min(ifaces.len());
+    for (req, (name, addr)) in reqs.iter_mut().zip(ifaces) {
+        *req = ifreq_of(
+            &name,
+            IfReqData {
+                addr: ipv4_sockaddr(addr),
+            },
+        );
+    }
+    conf.len = (count * size_of::<IfReq>()) as c_int;
+    Ok(0)
+}
+
+fn update_route(request: usize, rt: &RtEntry) -> LinuxResult<c_int> {
+    if rt.dst.sin_family as u32 != ctypes::AF_INET {
+        return Err(LinuxError::EAFNOSUPPORT);
+    }
+    let dest = IpAddr::V4(sockaddr_ipv4(&rt.dst));
+    let prefix_len = if rt.flags & RTF_HOST != 0 {
+        32
+    } else {
+        netmask_prefix(sockaddr_ipv4(&rt.genmask))?
+    };
+    let gateway = (rt.flags & RTF_GATEWAY != 0).then(|| IpAddr::V4(sockaddr_ipv4(&rt.gateway)));
+    let dev = if rt.dev.is_null() {
+        None
+    } else {
+        Some(char_ptr_to_str(rt.dev)?)
+    };
+    if request == SIOCADDRT {
+        ruxnet::add_route(dest, prefix_len, gateway, dev)?;
+    } else {
+        ruxnet::del_route(dest, prefix_len, gateway, dev)?;
+    }
+    Ok(0)
+}
+
+/// Handles the ioctls of the interfaces and the routing table, which can be
+/// called on any socket.
+pub(crate) fn socket_ioctl(fd: c_int, request: usize, data: usize) -> LinuxResult<c_int> {
+    socket_from_fd(fd)?;
+    if data == 0 {
+        return Err(LinuxError::EFAULT);
+    }
+    match request {
+        SIOCGIFCONF => return get_ifconf(unsafe { &mut *(data as *mut IfConf) }),
+        SIOCADDRT | SIOCDELRT => {
+            return update_route(request, unsafe { &*(data as *const RtEntry) })
+        }
+        _ => {}
+    }
+
+    let req = unsafe { &mut *(data as *mut IfReq) };
+    let name = ifreq_name(req)?;
+    let iface = ruxnet::interfaces()
+        .into_iter()
+        .find(|iface| iface.name == name)
+        .ok_or(LinuxError::ENODEV)?;
+    match request {
+        SIOCGIFFLAGS => {
+            let kind = if iface.loopback {
+                IFF_LOOPBACK
+            } else {
+                IFF_BROADCAST | IFF_MULTICAST
+            };
+            req.data.flags = IFF_UP | IFF_RUNNING | kind;
+        }
+        // the interfaces are always up
+        SIOCSIFFLAGS => {}
+        SIOCGIFADDR | SIOCGIFNETMASK | SIOCGIFBRDADDR => {
+            let (addr, prefix_len) = iface.ipv4.ok_or(LinuxError::EADDRNOTAVAIL)?;
+            let netmask = prefix_netmask(prefix_len);
+            let addr = match request {
+                SIOCGIFADDR => addr,
+                SIOCGIFNETMASK => netmask,
+                _ => Ipv4Addr::from(u32::from(addr) | !u32::from(netmask)),
+            };
+            req.data.addr = ipv4_sockaddr(addr);
+        }
+        SIOCSIFADDR => {
+            let addr = sockaddr_ipv4(unsafe { &req.data.addr });
+            let prefix_len = iface
+                .ipv4
+                .map_or_else(|| classful_prefix(addr), |(_, prefix_len)| prefix_len);
+            ruxnet::set_ipv4_addr(&name, addr, prefix_len)?;
+        }
+        SIOCSIFNETMASK => {
+            let (addr, _) = iface.ipv4.ok_or(LinuxError::EADDRNOTAVAIL)?;
+            let prefix_len = netmask_prefix(sockaddr_ipv4(unsafe { &req.data.addr }))?;
+            ruxnet::set_ipv4_addr(&name, addr, prefix_len)?;
+        }
+        SIOCGIFMTU => req.data.ivalue = iface.mtu as c_int,
+        SIOCGIFHWADDR => {
+            let mut hwaddr = ctypes::sockaddr {
+                sa_family: if iface.loopback {
+                    ARPHRD_LOOPBACK
+                } else {
+                    ARPHRD_ETHER
+                },
+                sa_data: [0; 14],
+            };
+            for (c, &b) in hwaddr.sa_data.iter_mut().zip(iface.mac.iter()) {
+                *c = b as c_char;
+            }
+            req.data.hwaddr = hwaddr;
+        }
+        SIOCGIFINDEX => req.data.ivalue = iface.index as c_int,
+        _ => return Err(LinuxError::ENOTTY),
+    }
+    Ok(0)
+}
+
 /*
 * the following functions refers to macros in musl
 * see https://www.man7.org/linux/man-pages/man3/cmsg.3.html
@@ -14,9 +14,12 @@ use core::{
 
 use axerrno::{ax_err, ax_err_type, AxResult, LinuxError};
 use bitflags::bitflags;
-use ruxfutex::{futex_wait, futex_wait_bitset, futex_wake, futex_wake_bitset};
+use ruxfutex::{
+    futex_lock_pi, futex_requeue, futex_trylock_pi, futex_unlock_pi, futex_wait, futex_wait_bitset,
+    futex_wake, futex_wake_bitset, futex_wake_op, RobustListHead,
+};
 
-use crate::ctypes;
+use crate::{ctypes, imp::pthread::find_thread, utils::restartable};
 
 const FUTEX_OP_MASK: u32 = 0x0000_000F;
 const FUTEX_FLAGS_MASK: u32 = u32::MAX ^ FUTEX_OP_MASK;
@@ -88,24 +91,29 @@ pub fn sys_futex(
     uaddr: usize,
     op: c_uint,
     val: c_int,
-    // timeout value, should be struct timespec pointer
+    // timeout value, should be struct timespec pointer,
+    // or the max count of the second futex for requeue and wake op
     to: usize,
-    // used by Requeue, unused for now
-    #[allow(unused_variables)] uaddr2: c_int,
-    // bitset
+    // the second futex, used by requeue and wake op
+    uaddr2: usize,
+    // bitset, the expected value for cmp requeue, or the operation for wake op
     val3: c_int,
 ) -> c_int {
     let futex_addr = uaddr as *const i32;
+    let futex_addr2 = uaddr2 as *const i32;
     let bitset = val3 as _;
     let max_count = val as _;
+    let max_count2 = to as u32 as _;
     let futex_val = val as _;
 
     syscall_body!(sys_futex, {
         let (op, _flag) = futex_op_and_flags_from_u32(op).map_err(LinuxError::from)?;
         let timeout = to as *const ctypes::timespec;
         let timeout = if !timeout.is_null()
-            && matches!(op, FutexOp::FUTEX_WAIT | FutexOp::FUTEX_WAIT_BITSET)
-        {
+            && matches!(
+                op,
+                FutexOp::FUTEX_WAIT | FutexOp::FUTEX_WAIT_BITSET | FutexOp::FUTEX_LOCK_PI
+            ) {
             let dur = unsafe { Duration::from(*timeout) };
             Some(dur)
         } else {
@@ -114,14 +122,78 @@ pub fn sys_futex(
         debug!("sys_futex <= addr: {uaddr:#x}, op: {op:?}, val: {val}, to: {timeout:?}",);
 
         let ret = match op {
-            FutexOp::FUTEX_WAIT => futex_wait(futex_addr, futex_val, timeout).map(|_| 0),
+            FutexOp::FUTEX_WAIT => {
+                // a relative timeout can not be restarted with its full duration
+                return restartable(timeout.is_none(), || {
+                    futex_wait(futex_addr, futex_val, timeout).map_err(LinuxError::from)
+                })
+                .map(|_| 0);
+            }
             FutexOp::FUTEX_WAIT_BITSET => {
-                futex_wait_bitset(futex_addr, futex_val, timeout, bitset).map(|_| 0)
+                return restartable(true, || {
+                    futex_wait_bitset(futex_addr, futex_val, timeout, bitset)
+                        .map_err(LinuxError::from)
+                })
+                .map(|_| 0);
+            }
+            FutexOp::FUTEX_LOCK_PI => {
+                return restartable(true, || {
+                    futex_lock_pi(futex_addr, timeout, |tid| find_thread(tid as u64))
+                        .map_err(LinuxError::from)
+                })
+                .map(|_| 0);
             }
+            FutexOp::FUTEX_TRYLOCK_PI => futex_trylock_pi(futex_addr).map(|_| 0),
+            FutexOp::FUTEX_UNLOCK_PI => futex_unlock_pi(futex_addr).map(|_| 0),
             FutexOp::FUTEX_WAKE => futex_wake(futex_addr, max_count),
             FutexOp::FUTEX_WAKE_BITSET => futex_wake_bitset(futex_addr, max_count, bitset),
+            FutexOp::FUTEX_REQUEUE => {
+                futex_requeue(futex_addr, max_count, futex_addr2, max_count2, None)
+            }
+            FutexOp::FUTEX_CMP_REQUEUE => {
+                futex_requeue(futex_addr, max_count, futex_addr2, max_count2, Some(val3))
+            }
+            FutexOp::FUTEX_WAKE_OP => {
+                futex_wake_op(futex_addr, max_count, futex_addr2, max_count2, val3 as u32)
+            }
             _ => ax_err!(Unsupported, "unsupported futex option: {:?}", op),
         };
         ret.map_err(LinuxError::from)
     })
 }
+
+/// Sets the head of the robust futex list of the current thread, whose locks
+/// are released on exit.
+pub fn sys_set_robust_list(head: usize, len: usize) -> c_int {
+    debug!("sys_set_robust_list <= head: {head:#x}, len: {len}");
+    syscall_body!(sys_set_robust_list, {
+        if len != core::mem::size_of::<RobustListHead>() {
+            return Err(LinuxError::EINVAL);
+        }
+        ruxtask::current().set_robust_list(head);
+        Ok(0)
+    })
+}
+
+/// Gets the head of the robust futex list of the thread `tid`, or the current
+/// thread if `tid` is 0.
+pub unsafe fn sys_get_robust_list(tid: c_int, head: *mut usize, len: *mut usize) -> c_int {
+    debug!("sys_get_robust_list <= tid: {tid}");
+    syscall_body!(sys_get_robust_list, {
+        if head.is_null() || len.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let list = if tid == 0 {
+            ruxtask::current().robust_list()
+        } else {
+            find_thread(tid as u64)
+                .ok_or(LinuxError::ESRCH)?
+                .robust_list()
+        };
+        unsafe {
+            *head = list;
+            *len = core::mem::size_of::<RobustListHead>();
+        }
+        Ok(0)
+    })
+}
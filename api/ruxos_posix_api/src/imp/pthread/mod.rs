@@ -178,6 +178,15 @@ impl Pthread {
     }
 }
 
+/// Find the task of the thread `tid`, which may also be the main thread of a process.
+pub(crate) fn find_thread(tid: u64) -> Option<AxTaskRef> {
+    if let Some(ptr) = TID_TO_PTHREAD.read().get(&tid) {
+        let thread = unsafe { &*(ptr.0 as *const Pthread) };
+        return Some(thread.inner.clone());
+    }
+    ruxtask::task::PROCESS_MAP.lock().get(&tid).cloned()
+}
+
 /// Returns the `pthread` struct of current thread.
 pub fn sys_pthread_self() -> ctypes::pthread_t {
     Pthread::current().expect("fail to get current thread") as *const Pthread as _
@@ -207,6 +216,7 @@ pub unsafe fn sys_pthread_create(
 /// Exits the current thread. The value `retval` will be returned to the joiner.
 pub fn sys_pthread_exit(retval: *mut c_void) -> ! {
     debug!("sys_pthread_exit <= {:#x}", retval as usize);
+    ruxfutex::futex_exit_robust_list(ruxtask::current().robust_list());
     #[cfg(feature = "musl")]
     {
         use core::sync::atomic::Ordering;
@@ -415,41 +425,59 @@ pub unsafe fn sys_clone(
     );
 
     syscall_body!(sys_clone, {
-        if (flags as u32 & ctypes::CLONE_THREAD) == 0 {
-            debug!("ONLY support thread");
-            return Err(LinuxError::EINVAL);
-        }
+        if (flags as u32 & ctypes::CLONE_THREAD) != 0 {
+            let func = unsafe {
+                core::mem::transmute::<*const (), extern "C" fn(arg: *mut c_void) -> *mut c_void>(
+                    func as usize as *const (),
+                )
+            };
+            let args = unsafe { *((stack as usize) as *mut usize) } as *mut c_void;
 
-        let func = unsafe {
-            core::mem::transmute::<*const (), extern "C" fn(arg: *mut c_void) -> *mut c_void>(
-                func as usize as *const (),
-            )
-        };
-        let args = unsafe { *((stack as usize) as *mut usize) } as *mut c_void;
+            let set_tid = if (flags as u32 & ctypes::CLONE_CHILD_SETTID) != 0 {
+                core::sync::atomic::AtomicU64::new(ctid as _)
+            } else {
+                core::sync::atomic::AtomicU64::new(0)
+            };
 
-        let set_tid = if (flags as u32 & ctypes::CLONE_CHILD_SETTID) != 0 {
-            core::sync::atomic::AtomicU64::new(ctid as _)
-        } else {
-            core::sync::atomic::AtomicU64::new(0)
-        };
+            let (tid, task_inner) = Pthread::pcreate(
+                core::ptr::null(),
+                func,
+                args,
+                tls,
+                set_tid,
+                core::sync::atomic::AtomicU64::from(ctid as u64),
+            )?;
 
-        let (tid, task_inner) = Pthread::pcreate(
-            core::ptr::null(),
-            func,
-            args,
-            tls,
-            set_tid,
-            core::sync::atomic::AtomicU64::from(ctid as u64),
-        )?;
-
-        // write tid to ptid
-        if (flags as u32 & ctypes::CLONE_PARENT_SETTID) != 0 {
-            unsafe { *ptid = tid as c_int };
-        }
+            // write tid to ptid
+            if (flags as u32 & ctypes::CLONE_PARENT_SETTID) != 0 {
+                unsafe { *ptid = tid as c_int };
+            }
 
-        ruxtask::put_task(task_inner);
+            ruxtask::put_task(task_inner);
 
-        Ok(tid)
+            return Ok(tid);
+        } else if (flags as u32 & ctypes::SIGCHLD) != 0 {
+            let pid = if let Some(task_ref) = ruxtask::fork_task() {
+                task_ref.id().as_u64()
+            } else {
+                let children_ref = ruxtask::current();
+                let tid = children_ref.id().as_u64();
+                let thread = Pthread {
+                    inner: children_ref.clone_as_taskref(),
+                    retval: Arc::new(Packet {
+                        result: UnsafeCell::new(core::ptr::null_mut()),
+                    }),
+                };
+                let ptr = Box::into_raw(Box::new(thread)) as *mut c_void;
+                TID_TO_PTHREAD.write().insert(tid, ForceSendSync(ptr));
+                0
+            };
+            debug!("will sys_clone <= pid: {pid}");
+            return Ok(pid);
+        } else {
+            debug!("ONLY support CLONE_THREAD and SIGCHLD");
+            return Err(LinuxError::EINVAL);
+        }
     })
 }
 
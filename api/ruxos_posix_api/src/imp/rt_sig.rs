@@ -11,14 +11,9 @@
 
 use axerrno::LinuxError;
 
-use crate::{
-    ctypes::{self, k_sigaction},
-    sys_sigaction,
-};
-use core::{
-    ffi::c_int,
-    sync::atomic::{AtomicUsize, Ordering},
-};
+use crate::ctypes::{self, k_sigaction};
+use core::ffi::c_int;
+use ruxtask::{rx_sigaction, Signal};
 
 enum RTSigprocmaskHow {
     Block = 0,
@@ -38,102 +33,101 @@ impl TryFrom<c_int> for RTSigprocmaskHow {
     }
 }
 
-static mut MASK_TMP: AtomicUsize = AtomicUsize::new(0);
-
-fn set_mask(old: *mut usize, new: usize) {
-    unsafe {
-        *old = new;
-    }
-}
-
-fn get_mask(mask: *const usize) -> usize {
-    unsafe { *mask }
-}
-
-/// Set mask for given thread
-#[allow(static_mut_refs)]
+/// Examine and change the blocked mask of the current thread
 pub fn sys_rt_sigprocmask(
     how: c_int,
-    _new_mask: *const usize,
-    _old_mask: *mut usize,
+    new_mask: *const usize,
+    old_mask: *mut usize,
     sigsetsize: usize,
 ) -> c_int {
     debug!("sys_rt_sigprocmask <= flag: {how}, sigsetsize: {sigsetsize}");
 
     syscall_body!(sys_rt_sigprocmask, {
-        if !_old_mask.is_null() {
-            unsafe {
-                let new = MASK_TMP.load(Ordering::Relaxed);
-                set_mask(_old_mask, new);
-            }
+        if sigsetsize != core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
         }
-
-        if !_new_mask.is_null() {
-            unsafe {
-                let set = get_mask(_new_mask);
-                match how.try_into() {
-                    Ok(RTSigprocmaskHow::Block) => MASK_TMP.fetch_or(set, Ordering::Relaxed),
-                    Ok(RTSigprocmaskHow::UnBlock) => MASK_TMP.fetch_and(!set, Ordering::Relaxed),
-                    Ok(RTSigprocmaskHow::SetMask) => MASK_TMP.swap(set, Ordering::Relaxed),
-                    _ => return Err(LinuxError::EINVAL),
-                };
-            }
+        let old = Signal::sigmask(None);
+        if !new_mask.is_null() {
+            let set = unsafe { *new_mask } as u64;
+            let new = match how.try_into() {
+                Ok(RTSigprocmaskHow::Block) => old | set,
+                Ok(RTSigprocmaskHow::UnBlock) => old & !set,
+                Ok(RTSigprocmaskHow::SetMask) => set,
+                _ => return Err(LinuxError::EINVAL),
+            };
+            Signal::sigmask(Some(new));
+        }
+        if !old_mask.is_null() {
+            unsafe { *old_mask = old as usize };
         }
-
         Ok(0)
     })
 }
 
-/// sigaction syscall for A64 musl
+/// `struct k_sigaction` of musl on RISC-V, which has no `restorer`.
+#[cfg(target_arch = "riscv64")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct KSigaction {
+    handler: Option<unsafe extern "C" fn(c_int)>,
+    flags: core::ffi::c_ulong,
+    mask: [core::ffi::c_uint; 2],
+    unused: usize,
+}
+
+/// `struct k_sigaction` of musl.
+#[cfg(not(target_arch = "riscv64"))]
+pub type KSigaction = k_sigaction;
+
+#[cfg(target_arch = "riscv64")]
+impl From<KSigaction> for k_sigaction {
+    fn from(sa: KSigaction) -> Self {
+        Self {
+            handler: sa.handler,
+            flags: sa.flags,
+            restorer: None,
+            mask: sa.mask,
+        }
+    }
+}
+
+#[cfg(target_arch = "riscv64")]
+impl From<k_sigaction> for KSigaction {
+    fn from(sa: k_sigaction) -> Self {
+        Self {
+            handler: sa.handler,
+            flags: sa.flags,
+            mask: sa.mask,
+            unused: 0,
+        }
+    }
+}
+
+/// sigaction syscall for musl
 ///
-/// TODO: if sa is 0, return now action
+/// `act` and `oldact` point to the `struct k_sigaction` of musl, either of
+/// them can be null.
 pub unsafe fn sys_rt_sigaction(
     sig: c_int,
-    sa: *const ctypes::sigaction,
-    old: *mut ctypes::sigaction,
+    act: *const KSigaction,
+    oldact: *mut KSigaction,
     _sigsetsize: ctypes::size_t,
 ) -> c_int {
-    debug!(
-        "sys_rt_sigaction <= sig: {} sa {:x} old {:x}",
-        sig, sa as u64, old as u64
-    );
+    debug!("sys_rt_sigaction <= sig: {sig} act {act:p} oldact {oldact:p}");
     syscall_body!(sys_rt_sigaction, {
-        if sa as u64 == 0 && old as u64 == 0 {
-            sys_sigaction(sig as _, None, None);
-            Ok(0)
-        } else if sa as u64 != 0 && old as u64 == 0 {
-            let sa = unsafe { *sa };
-            let sa = k_sigaction::from(sa);
-            sys_sigaction(sig as _, Some(&sa), None);
-            Ok(0)
-        } else if sa as u64 == 0 && old as u64 != 0 {
-            let old = unsafe { *old };
-            let mut old_sa = k_sigaction::from(old);
-            sys_sigaction(sig as _, None, Some(&mut old_sa));
-            Ok(0)
-        } else {
-            let sa = unsafe { *sa };
-            let old = unsafe { *old };
-            let sa = k_sigaction::from(sa);
-            let mut old_sa = k_sigaction::from(old);
-            sys_sigaction(sig as _, Some(&sa), Some(&mut old_sa));
-            Ok(0)
+        let act = (!act.is_null()).then(|| k_sigaction::from(unsafe { *act }));
+        let mut old = k_sigaction::default();
+        if !Signal::sigaction(
+            sig as usize,
+            act.as_ref()
+                .map(|act| act as *const k_sigaction as *const rx_sigaction),
+            (!oldact.is_null()).then_some(&mut old as *mut k_sigaction as *mut rx_sigaction),
+        ) {
+            return Err(LinuxError::EINVAL);
         }
+        if !oldact.is_null() {
+            unsafe { *oldact = old.into() };
+        }
+        Ok(0)
     })
 }
-
-impl From<ctypes::sigaction> for k_sigaction {
-    fn from(sa: ctypes::sigaction) -> Self {
-        let mut ret = Self {
-            ..Default::default()
-        };
-        ret.flags = sa.sa_flags as _;
-        let mask = sa.sa_mask.__bits[0]; // only get the first 64 signals
-        ret.mask[0] = mask as _;
-        ret.mask[1] = (mask >> 32) as _;
-
-        ret.handler = unsafe { sa.__sa_handler.sa_handler };
-        ret.restorer = sa.sa_restorer;
-        ret
-    }
-}
@@ -14,7 +14,9 @@ use crate::ctypes::k_sigaction;
 use crate::ctypes::{self, pid_t};
 
 use axerrno::LinuxError;
-use ruxtask::{rx_sigaction, Signal};
+use ruxhal::trap::SignalStack;
+use ruxtask::signal::{SI_TKILL, SI_USER};
+use ruxtask::{rx_sigaction, SigInfo, Signal};
 
 /// Set signal handler
 pub fn sys_sigaction(
@@ -24,11 +26,13 @@ pub fn sys_sigaction(
 ) -> c_int {
     debug!("sys_sigaction <= signum: {signum}",);
     syscall_body!(sys_sigaction, {
-        Signal::sigaction(
-            signum,
+        if !Signal::sigaction(
+            signum as usize,
             sigaction.map(|act| act as *const k_sigaction as *const rx_sigaction),
             oldact.map(|old| old as *mut k_sigaction as *mut rx_sigaction),
-        );
+        ) {
+            return Err(LinuxError::EINVAL);
+        }
         Ok(0)
     })
 }
@@ -72,31 +76,101 @@ pub unsafe fn sys_getitimer(which: c_int, curr_value: *mut ctypes::itimerval) ->
     })
 }
 
-/// Sigal stack
+/// Get and set the alternate signal stack of the current thread
+pub unsafe fn sys_sigaltstack(ss: *const ctypes::stack_t, old_ss: *mut ctypes::stack_t) -> c_int {
+    debug!("sys_sigaltstack <= ss: {ss:p}, old_ss: {old_ss:p}");
+    syscall_body!(sys_sigaltstack, {
+        let new = (!ss.is_null()).then(|| {
+            let ss = unsafe { &*ss };
+            SignalStack {
+                sp: ss.ss_sp as usize,
+                flags: ss.ss_flags,
+                size: ss.ss_size as usize,
+            }
+        });
+        let old = Signal::sigaltstack(new)?;
+        if !old_ss.is_null() {
+            unsafe {
+                (*old_ss).ss_sp = old.sp as *mut core::ffi::c_void;
+                (*old_ss).ss_flags = old.flags;
+                (*old_ss).ss_size = old.size as _;
+            }
+        }
+        Ok(0)
+    })
+}
+
+/// Return from a signal handler and restore the interrupted context
 ///
-/// TODO: implement this && the parameter type should be ctypes::stack_t
-pub unsafe fn sys_sigaltstack(
-    _ss: *const core::ffi::c_void,
-    _old_ss: *mut core::ffi::c_void,
-) -> c_int {
-    debug!("sys_sigaltstack <= ss: {_ss:p}, old_ss: {_old_ss:p}");
-    syscall_body!(sys_sigaltstack, Ok(0))
+/// It only returns, with `EINVAL`, if no signal handler is running.
+pub fn sys_rt_sigreturn() -> c_int {
+    debug!("sys_rt_sigreturn");
+    syscall_body!(sys_rt_sigreturn, {
+        Signal::sigreturn();
+        Err::<c_int, _>(LinuxError::EINVAL)
+    })
 }
 
 /// send a signal to a process
+///
+/// `pid` 0 refers to the current process, since process groups are not
+/// supported, while -1 refers to every process except the init process and
+/// the current one. If `sig` is 0, only the existence of the process is checked.
 pub fn sys_kill(pid: pid_t, sig: c_int) -> c_int {
     debug!("sys_kill <= pid {pid} sig {sig}");
     syscall_body!(sys_kill, {
-        match Signal::signal(sig as _, true) {
-            None => Err(LinuxError::EINVAL),
-            Some(_) => Ok(0),
+        let send = |task: &ruxtask::AxTaskRef| {
+            if sig != 0 && !Signal::send_process(task, SigInfo::new(sig as _, SI_USER)) {
+                return Err(LinuxError::EINVAL);
+            }
+            Ok(0)
+        };
+        match pid {
+            0 => send(&ruxtask::current().process_task()),
+            -1 => {
+                let curr_pid = ruxtask::current().process_id().as_u64();
+                let process_map = ruxtask::task::PROCESS_MAP.lock();
+                // The first process is the init process.
+                let mut targets = process_map
+                    .iter()
+                    .skip(1)
+                    .filter(|(&pid, _)| pid != curr_pid)
+                    .peekable();
+                if targets.peek().is_none() {
+                    return Err(LinuxError::ESRCH);
+                }
+                targets.try_for_each(|(_, task)| send(task).map(|_| ()))?;
+                Ok(0)
+            }
+            pid if pid > 0 => {
+                let task = ruxtask::task::PROCESS_MAP
+                    .lock()
+                    .get(&(pid as u64))
+                    .cloned()
+                    .ok_or(LinuxError::ESRCH)?;
+                send(&task)
+            }
+            _ => Err(LinuxError::ESRCH),
         }
     })
 }
 
 /// send a signal to a thread
-/// TODO: send to the specified thread.
+///
+/// If `sig` is 0, only the existence of the thread is checked.
 pub fn sys_tkill(tid: pid_t, sig: c_int) -> c_int {
     debug!("sys_tkill <= tid {tid} sig {sig}");
-    sys_kill(tid, sig)
+    syscall_body!(sys_tkill, {
+        if tid <= 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let task = crate::imp::pthread::find_thread(tid as u64).ok_or(LinuxError::ESRCH)?;
+        if sig == 0 {
+            return Ok(0);
+        }
+        if !Signal::send_thread(&task, SigInfo::new(sig as _, SI_TKILL)) {
+            return Err(LinuxError::EINVAL);
+        }
+        Ok(0)
+    })
 }
@@ -0,0 +1,183 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! `signalfd` implementation.
+//!
+//! Reading a signalfd takes the signals in its mask out of the pending sets
+//! of the reading thread and its process, in the same order as they would be
+//! delivered. The signals should be blocked, otherwise they may be delivered
+//! to the handlers before being read.
+
+use alloc::sync::Arc;
+use core::ffi::c_int;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::{PollState, PollWaiters};
+use ruxfdtable::{FileLike, RuxStat};
+use ruxfs::{AbsPath, OpenFlags};
+use ruxtask::fs::{add_file_like, get_file_like};
+use ruxtask::signal::{sigbit, SIGKILL, SIGSTOP};
+use ruxtask::{SigInfo, Signal};
+use spin::RwLock;
+
+use crate::{ctypes, utils::block_on};
+
+/// Signals that can not be read by a signalfd, `SIGKILL` and `SIGSTOP`.
+const UNREADABLE: u64 = sigbit(SIGKILL) | sigbit(SIGSTOP);
+
+const SIGINFO_SIZE: usize = core::mem::size_of::<ctypes::signalfd_siginfo>();
+
+impl From<SigInfo> for ctypes::signalfd_siginfo {
+    fn from(info: SigInfo) -> Self {
+        Self {
+            ssi_signo: info.signo as _,
+            ssi_code: info.code,
+            ssi_pid: info.pid as _,
+            ssi_int: info.value as _,
+            ssi_ptr: info.value as _,
+            ..Default::default()
+        }
+    }
+}
+
+/// A signalfd object.
+pub struct SignalFd {
+    mask: AtomicU64,
+    flags: RwLock<OpenFlags>,
+    /// Waiters of the process which created the signalfd.
+    waiters: Arc<PollWaiters>,
+}
+
+impl SignalFd {
+    fn new(mask: u64, flags: OpenFlags) -> Self {
+        Self {
+            mask: AtomicU64::new(mask),
+            flags: RwLock::new(flags),
+            waiters: Signal::poll_waiters(),
+        }
+    }
+
+    fn try_read(&self) -> LinuxResult<SigInfo> {
+        Signal::dequeue_masked(self.mask.load(Ordering::Acquire)).ok_or(LinuxError::EAGAIN)
+    }
+}
+
+impl FileLike for SignalFd {
+    fn path(&self) -> AbsPath {
+        AbsPath::new("/signalfd")
+    }
+
+    /// Reads as many pending signals as fit in `buf`, each as a
+    /// `signalfd_siginfo`.
+    ///
+    /// Fails with `EINVAL` if `buf` can not hold a `signalfd_siginfo`.
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < SIGINFO_SIZE {
+            return Err(LinuxError::EINVAL);
+        }
+        let first = if self.flags.read().contains(OpenFlags::O_NONBLOCK) {
+            self.try_read()?
+        } else {
+            block_on(&self.waiters, || self.try_read())?
+        };
+        let mut len = 0;
+        let mut next = Some(first);
+        while let Some(info) = next {
+            let siginfo = ctypes::signalfd_siginfo::from(info);
+            let bytes = unsafe {
+                core::slice::from_raw_parts(&siginfo as *const _ as *const u8, SIGINFO_SIZE)
+            };
+            buf[len..len + SIGINFO_SIZE].copy_from_slice(bytes);
+            len += SIGINFO_SIZE;
+            if buf.len() - len < SIGINFO_SIZE {
+                break;
+            }
+            next = self.try_read().ok();
+        }
+        Ok(len)
+    }
+
+    fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
+        Err(LinuxError::EINVAL)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        Ok(PollState {
+            readable: Signal::sigpending() & self.mask.load(Ordering::Acquire) != 0,
+            writable: false,
+            pollhup: false,
+        })
+    }
+
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        Some(&self.waiters)
+    }
+
+    fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
+        *self.flags.write() = flags;
+        Ok(())
+    }
+
+    fn flags(&self) -> OpenFlags {
+        *self.flags.read() | OpenFlags::O_RDONLY
+    }
+}
+
+/// Creates a signalfd object reading the signals in `mask` if `fd` is -1,
+/// otherwise replaces the mask of the signalfd `fd`.
+///
+/// `flags` can contain `SFD_NONBLOCK` and `SFD_CLOEXEC`. `SIGKILL` and
+/// `SIGSTOP` in `mask` are ignored.
+pub unsafe fn sys_signalfd4(fd: c_int, mask: *const usize, sizemask: usize, flags: c_int) -> c_int {
+    debug!("sys_signalfd4 <= fd: {fd}, sizemask: {sizemask}, flags: {flags:#x}");
+    syscall_body!(sys_signalfd4, {
+        if sizemask != core::mem::size_of::<u64>() {
+            return Err(LinuxError::EINVAL);
+        }
+        if flags & !((ctypes::SFD_NONBLOCK | ctypes::SFD_CLOEXEC) as c_int) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        if mask.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let mask = unsafe { *mask } as u64 & !UNREADABLE;
+        if fd != -1 {
+            let signalfd = get_file_like(fd)?
+                .into_any()
+                .downcast::<SignalFd>()
+                .map_err(|_| LinuxError::EINVAL)?;
+            signalfd.mask.store(mask, Ordering::Release);
+            // signals already pending may become readable
+            signalfd.waiters.notify();
+            return Ok(fd);
+        }
+        let flags = OpenFlags::from_bits_truncate(flags);
+        let signalfd = SignalFd::new(mask, flags & OpenFlags::O_NONBLOCK);
+        add_file_like(Arc::new(signalfd), flags)
+    })
+}
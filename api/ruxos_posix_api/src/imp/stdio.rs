@@ -14,7 +14,7 @@ extern crate alloc;
 use {
     alloc::sync::Arc,
     axerrno::{LinuxError, LinuxResult},
-    axio::PollState,
+    axio::{PollState, PollWaiters},
     core::sync::atomic::{AtomicBool, Ordering},
     ruxfdtable::OpenFlags,
     ruxfs::AbsPath,
@@ -51,6 +51,10 @@ impl Stdin {
                     if self.nonblocking.load(Ordering::Relaxed) {
                         return Err(AxError::WouldBlock);
                     }
+                    #[cfg(feature = "signal")]
+                    if ruxtask::Signal::interrupted() {
+                        return Err(AxError::Interrupted);
+                    }
                     crate::sys_sched_yield();
                 }
                 Err(_) => unreachable!(),
@@ -82,7 +86,7 @@ impl ruxfdtable::FileLike for Stdin {
     }
 
     fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
-        self.read_inner(buf).map_err(|_| LinuxError::EAGAIN)
+        self.read_inner(buf).map_err(LinuxError::from)
     }
 
     fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
@@ -117,6 +121,16 @@ impl ruxfdtable::FileLike for Stdin {
         Ok(tty::tty_poll())
     }
 
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        // only the UART IRQ handler of aarch64 pushes input, otherwise it is
+        // fetched by `poll`
+        if cfg!(all(feature = "irq", target_arch = "aarch64")) {
+            Some(tty::tty_poll_waiters())
+        } else {
+            None
+        }
+    }
+
     fn ioctl(&self, cmd: usize, arg: usize) -> LinuxResult<usize> {
         tty::tty_ioctl(cmd, arg).map_err(LinuxError::from)
     }
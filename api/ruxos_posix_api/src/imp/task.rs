@@ -98,8 +98,7 @@ pub unsafe fn sys_wait4(
                         unsafe {
                             // lower 8 bits of exit_code is the signal number, while upper 8 bits of exit_code is the exit status
                             // according to "bits/waitstatus.h" in glibc source code.
-                            // TODO: add signal number to wstatus
-                            wstatus.write(task.exit_code() << 8);
+                            wstatus.write(task.wait_status());
                         }
                     }
                     process_map.remove(&(pid as u64));
@@ -132,8 +131,7 @@ pub unsafe fn sys_wait4(
                         unsafe {
                             // lower 8 bits of exit_code is the signal number, while upper 8 bits of exit_code is the exit status
                             // according to "bits/waitstatus.h" in glibc source code.
-                            // TODO: add signal number to wstatus
-                            wstatus.write(task.exit_code() << 8);
+                            wstatus.write(task.wait_status());
                         }
                     }
                     let _ = to_remove.insert(*child_pid);
@@ -0,0 +1,319 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! `timerfd` implementation.
+//!
+//! A timerfd is armed with a timer in the timer list of `ruxtask`, which
+//! counts the expirations and wakes up the waiters of the timerfd. Reading
+//! takes the number of expirations since the last read.
+//!
+//! All clocks are the same clock of `ruxhal`.
+
+use alloc::sync::{Arc, Weak};
+use core::ffi::c_int;
+use core::time::Duration;
+
+use axerrno::{LinuxError, LinuxResult};
+use axio::{PollState, PollWaiters};
+use ruxfdtable::{FileLike, RuxStat};
+use ruxfs::{AbsPath, OpenFlags};
+use ruxhal::time::current_time;
+use ruxtask::fs::{add_file_like, get_file_like};
+use spin::RwLock;
+use spinlock::SpinNoIrq;
+
+use crate::{ctypes, utils::block_on};
+
+#[derive(Default)]
+struct TimerState {
+    /// The next expiration, or [`None`] if the timer is disarmed.
+    deadline: Option<Duration>,
+    /// Period of the timer, zero if it expires only once.
+    interval: Duration,
+    /// Expirations not read yet.
+    ticks: u64,
+    /// Incremented each time the timer is set, to ignore the stale timers.
+    generation: usize,
+    /// ID of the timer in `ruxtask`, 0 if there is none.
+    timer_id: usize,
+}
+
+impl TimerState {
+    /// Counts the expirations up to `now`.
+    fn update(&mut self, now: Duration) {
+        let Some(deadline) = self.deadline else {
+            return;
+        };
+        if deadline > now {
+            return;
+        }
+        if self.interval.is_zero() {
+            self.ticks += 1;
+            self.deadline = None;
+        } else {
+            let interval = self.interval.as_nanos();
+            let n = (now - deadline).as_nanos() / interval + 1;
+            self.ticks = self.ticks.saturating_add(n as u64);
+            self.deadline = Some(deadline + Duration::from_nanos((n * interval) as u64));
+        }
+    }
+
+    /// Time left until the next expiration, and the period.
+    fn get(&self, now: Duration) -> ctypes::itimerspec {
+        ctypes::itimerspec {
+            it_interval: self.interval.into(),
+            it_value: self
+                .deadline
+                .map_or(Duration::ZERO, |deadline| deadline.saturating_sub(now))
+                .into(),
+        }
+    }
+}
+
+/// A timerfd object.
+pub struct TimerFd {
+    state: SpinNoIrq<TimerState>,
+    flags: RwLock<OpenFlags>,
+    waiters: PollWaiters,
+}
+
+impl TimerFd {
+    fn new(flags: OpenFlags) -> Self {
+        Self {
+            state: SpinNoIrq::new(TimerState::default()),
+            flags: RwLock::new(flags),
+            waiters: PollWaiters::new(),
+        }
+    }
+
+    /// Sets a timer in `ruxtask` for the next expiration, if any.
+    fn arm(self: &Arc<Self>, state: &mut TimerState) {
+        let Some(deadline) = state.deadline else {
+            state.timer_id = 0;
+            return;
+        };
+        let timerfd = Arc::downgrade(self);
+        let generation = state.generation;
+        state.timer_id = ruxtask::set_timer(deadline, move |now| {
+            if let Some(timerfd) = Weak::upgrade(&timerfd) {
+                timerfd.expire(generation, now);
+            }
+        });
+    }
+
+    /// Called by the timer set by [`TimerFd::arm`].
+    fn expire(self: &Arc<Self>, generation: usize, now: Duration) {
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.update(now);
+        self.arm(&mut state);
+        drop(state);
+        self.waiters.notify();
+    }
+
+    fn settime(
+        self: &Arc<Self>,
+        flags: c_int,
+        new_value: &ctypes::itimerspec,
+    ) -> LinuxResult<ctypes::itimerspec> {
+        let valid_timespec =
+            |ts: &ctypes::timespec| ts.tv_sec >= 0 && (0..1_000_000_000).contains(&ts.tv_nsec);
+        if !valid_timespec(&new_value.it_value) || !valid_timespec(&new_value.it_interval) {
+            return Err(LinuxError::EINVAL);
+        }
+        let value = Duration::from(new_value.it_value);
+        let interval = Duration::from(new_value.it_interval);
+
+        let now = current_time();
+        let mut state = self.state.lock();
+        state.update(now);
+        let old_value = state.get(now);
+        if state.timer_id != 0 {
+            ruxtask::cancel_timer(state.timer_id);
+        }
+        state.generation += 1;
+        state.ticks = 0;
+        state.interval = interval;
+        state.deadline = if value.is_zero() {
+            None
+        } else if flags & ctypes::TFD_TIMER_ABSTIME as c_int != 0 {
+            Some(value)
+        } else {
+            Some(now + value)
+        };
+        self.arm(&mut state);
+        drop(state);
+        // an absolute time in the past expires right away
+        self.waiters.notify();
+        Ok(old_value)
+    }
+
+    fn gettime(&self) -> ctypes::itimerspec {
+        let now = current_time();
+        let mut state = self.state.lock();
+        state.update(now);
+        state.get(now)
+    }
+
+    fn try_read(&self) -> LinuxResult<u64> {
+        let mut state = self.state.lock();
+        state.update(current_time());
+        if state.ticks == 0 {
+            return Err(LinuxError::EAGAIN);
+        }
+        Ok(core::mem::take(&mut state.ticks))
+    }
+
+    fn from_fd(fd: c_int) -> LinuxResult<Arc<Self>> {
+        get_file_like(fd)?
+            .into_any()
+            .downcast::<Self>()
+            .map_err(|_| LinuxError::EINVAL)
+    }
+}
+
+impl Drop for TimerFd {
+    fn drop(&mut self) {
+        let timer_id = self.state.get_mut().timer_id;
+        if timer_id != 0 {
+            ruxtask::cancel_timer(timer_id);
+        }
+    }
+}
+
+impl FileLike for TimerFd {
+    fn path(&self) -> AbsPath {
+        AbsPath::new("/timerfd")
+    }
+
+    /// Reads the number of expirations as a `u64` in native byte order.
+    ///
+    /// Fails with `EINVAL` if `buf` is shorter than 8 bytes.
+    fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
+        if buf.len() < 8 {
+            return Err(LinuxError::EINVAL);
+        }
+        let ticks = if self.flags.read().contains(OpenFlags::O_NONBLOCK) {
+            self.try_read()?
+        } else {
+            block_on(&self.waiters, || self.try_read())?
+        };
+        buf[..8].copy_from_slice(&ticks.to_ne_bytes());
+        Ok(8)
+    }
+
+    fn write(&self, _buf: &[u8]) -> LinuxResult<usize> {
+        Err(LinuxError::EINVAL)
+    }
+
+    fn flush(&self) -> LinuxResult {
+        Ok(())
+    }
+
+    fn stat(&self) -> LinuxResult<RuxStat> {
+        let st_mode = 0o600u32; // rw-------
+        Ok(RuxStat::from(ctypes::stat {
+            st_ino: 1,
+            st_nlink: 1,
+            st_mode,
+            ..Default::default()
+        }))
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn core::any::Any + Send + Sync> {
+        self
+    }
+
+    fn poll(&self) -> LinuxResult<PollState> {
+        let mut state = self.state.lock();
+        state.update(current_time());
+        Ok(PollState {
+            readable: state.ticks > 0,
+            writable: false,
+            pollhup: false,
+        })
+    }
+
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        Some(&self.waiters)
+    }
+
+    fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
+        *self.flags.write() = flags;
+        Ok(())
+    }
+
+    fn flags(&self) -> OpenFlags {
+        *self.flags.read() | OpenFlags::O_RDWR
+    }
+}
+
+/// Creates a disarmed timerfd object on the clock `clockid`.
+///
+/// `clockid` can be `CLOCK_REALTIME`, `CLOCK_MONOTONIC` or `CLOCK_BOOTTIME`,
+/// and `flags` can contain `TFD_NONBLOCK` and `TFD_CLOEXEC`.
+pub fn sys_timerfd_create(clockid: ctypes::clockid_t, flags: c_int) -> c_int {
+    debug!("sys_timerfd_create <= clockid: {clockid}, flags: {flags:#x}");
+    syscall_body!(sys_timerfd_create, {
+        match clockid as u32 {
+            ctypes::CLOCK_REALTIME | ctypes::CLOCK_MONOTONIC | ctypes::CLOCK_BOOTTIME => {}
+            _ => return Err(LinuxError::EINVAL),
+        }
+        if flags & !((ctypes::TFD_NONBLOCK | ctypes::TFD_CLOEXEC) as c_int) != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        let flags = OpenFlags::from_bits_truncate(flags);
+        let timerfd = TimerFd::new(flags & OpenFlags::O_NONBLOCK);
+        add_file_like(Arc::new(timerfd), flags)
+    })
+}
+
+/// Arms or disarms the timerfd `fd` with `new_value`, and returns the old
+/// setting in `old_value` if it is not null.
+///
+/// The timer is disarmed if `it_value` is zero. `it_value` is an absolute
+/// time if `flags` contains `TFD_TIMER_ABSTIME`.
+pub unsafe fn sys_timerfd_settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const ctypes::itimerspec,
+    old_value: *mut ctypes::itimerspec,
+) -> c_int {
+    debug!("sys_timerfd_settime <= fd: {fd}, flags: {flags:#x}");
+    syscall_body!(sys_timerfd_settime, {
+        let valid = (ctypes::TFD_TIMER_ABSTIME | ctypes::TFD_TIMER_CANCEL_ON_SET) as c_int;
+        if flags & !valid != 0 {
+            return Err(LinuxError::EINVAL);
+        }
+        if new_value.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let old = TimerFd::from_fd(fd)?.settime(flags, unsafe { &*new_value })?;
+        if !old_value.is_null() {
+            unsafe { *old_value = old };
+        }
+        Ok(0)
+    })
+}
+
+/// Gets the time left until the next expiration of the timerfd `fd`, and
+/// its period.
+pub unsafe fn sys_timerfd_gettime(fd: c_int, curr_value: *mut ctypes::itimerspec) -> c_int {
+    debug!("sys_timerfd_gettime <= fd: {fd}");
+    syscall_body!(sys_timerfd_gettime, {
+        if curr_value.is_null() {
+            return Err(LinuxError::EFAULT);
+        }
+        let curr = TimerFd::from_fd(fd)?.gettime();
+        unsafe { *curr_value = curr };
+        Ok(0)
+    })
+}
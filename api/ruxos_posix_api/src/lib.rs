@@ -63,17 +63,20 @@ pub use imp::time::{
     sys_nanosleep, sys_times,
 };
 
+#[cfg(feature = "fd")]
+pub use imp::eventfd::{sys_eventfd, sys_eventfd2};
 #[cfg(all(feature = "fd", feature = "musl"))]
 pub use imp::fd_ops::sys_dup3;
 #[cfg(feature = "fd")]
 pub use imp::fd_ops::{sys_close, sys_dup, sys_dup2, sys_fcntl};
 #[cfg(feature = "fs")]
 pub use imp::fs::{
-    sys_chdir, sys_faccessat, sys_fchmodat, sys_fchownat, sys_fdatasync, sys_fstat, sys_fsync,
-    sys_ftruncate, sys_getcwd, sys_getdents64, sys_lseek, sys_lstat, sys_membarrier, sys_mkdir,
-    sys_mkdirat, sys_mknodat, sys_mount, sys_newfstatat, sys_open, sys_openat, sys_pread64,
-    sys_preadv, sys_pwrite64, sys_readlinkat, sys_rename, sys_renameat, sys_rmdir, sys_stat,
-    sys_umount2, sys_unlink, sys_unlinkat,
+    sys_chdir, sys_faccessat, sys_fchmod, sys_fchmodat, sys_fchown, sys_fchownat, sys_fdatasync,
+    sys_fstat, sys_fsync, sys_ftruncate, sys_futimens, sys_getcwd, sys_getdents64, sys_link,
+    sys_linkat, sys_lseek, sys_lstat, sys_membarrier, sys_mkdir, sys_mkdirat, sys_mknodat,
+    sys_mount, sys_newfstatat, sys_open, sys_openat, sys_pread64, sys_preadv, sys_pwrite64,
+    sys_readlinkat, sys_rename, sys_renameat, sys_rmdir, sys_stat, sys_symlink, sys_symlinkat,
+    sys_sync, sys_umount2, sys_unlink, sys_unlinkat, sys_utimensat,
 };
 #[cfg(feature = "epoll")]
 pub use imp::io_mpx::{sys_epoll_create1, sys_epoll_ctl, sys_epoll_pwait, sys_epoll_wait};
@@ -109,14 +112,19 @@ pub use imp::pthread::{
     sys_pthread_setspecific,
 };
 #[cfg(feature = "signal")]
-pub use imp::rt_sig::{sys_rt_sigaction, sys_rt_sigprocmask};
+pub use imp::rt_sig::{sys_rt_sigaction, sys_rt_sigprocmask, KSigaction};
 #[cfg(feature = "signal")]
 pub use imp::signal::{
-    sys_getitimer, sys_kill, sys_setitimer, sys_sigaction, sys_sigaltstack, sys_tkill,
+    sys_getitimer, sys_kill, sys_rt_sigreturn, sys_setitimer, sys_sigaction, sys_sigaltstack,
+    sys_tkill,
 };
+#[cfg(all(feature = "fd", feature = "signal"))]
+pub use imp::signalfd::sys_signalfd4;
+#[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+pub use imp::timerfd::{sys_timerfd_create, sys_timerfd_gettime, sys_timerfd_settime};
 
 #[cfg(feature = "multitask")]
-pub use imp::pthread::futex::sys_futex;
+pub use imp::pthread::futex::{sys_futex, sys_get_robust_list, sys_set_robust_list};
 #[cfg(all(feature = "multitask", feature = "musl"))]
 pub use imp::pthread::sys_clone;
 #[cfg(all(feature = "multitask", feature = "musl"))]
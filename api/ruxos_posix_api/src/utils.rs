@@ -39,6 +39,106 @@ pub fn check_null_mut_ptr<T>(ptr: *mut T) -> LinuxResult {
     }
 }
 
+/// Runs a blocking operation `f`, which fails with `EINTR` when it is
+/// interrupted by a signal.
+///
+/// The interrupting signals are handled by [`ruxtask::Signal::handle_interrupt`]
+/// before returning. If `restart` is true and all of the handlers have
+/// `SA_RESTART`, `f` is called again instead of failing with `EINTR`.
+pub fn restartable<T>(restart: bool, mut f: impl FnMut() -> LinuxResult<T>) -> LinuxResult<T> {
+    #[cfg(feature = "signal")]
+    loop {
+        ruxtask::Signal::clear_interrupted();
+        match f() {
+            Err(LinuxError::EINTR) => {
+                if !(ruxtask::Signal::handle_interrupt() && restart) {
+                    return Err(LinuxError::EINTR);
+                }
+            }
+            res => return res,
+        }
+    }
+    #[cfg(not(feature = "signal"))]
+    {
+        let _ = restart;
+        f()
+    }
+}
+
+/// Runs a nonblocking operation `f` until it does not fail with `EAGAIN`,
+/// sleeping until `waiters` are notified in between.
+///
+/// Fails with `EINTR` if a signal is pending while sleeping.
+#[cfg(feature = "fd")]
+pub fn block_on<T>(
+    waiters: &axio::PollWaiters,
+    mut f: impl FnMut() -> LinuxResult<T>,
+) -> LinuxResult<T> {
+    #[cfg(feature = "multitask")]
+    {
+        use alloc::sync::Arc;
+        use axio::PollWaker;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        struct TaskWaker {
+            seq: AtomicUsize,
+            wq: ruxtask::WaitQueue,
+        }
+
+        impl PollWaker for TaskWaker {
+            fn wake(&self) {
+                self.seq.fetch_add(1, Ordering::AcqRel);
+                self.wq.notify_all(false);
+            }
+        }
+
+        let waker = Arc::new(TaskWaker {
+            seq: AtomicUsize::new(0),
+            wq: ruxtask::WaitQueue::new(),
+        });
+        let dyn_waker: Arc<dyn PollWaker> = waker.clone();
+        waiters.register(&dyn_waker, false);
+        let res = loop {
+            // notifications after this are not missed
+            let seq = waker.seq.load(Ordering::Acquire);
+            match f() {
+                Err(LinuxError::EAGAIN) => {}
+                res => break res,
+            }
+            let condition = || {
+                #[cfg(feature = "signal")]
+                if ruxtask::Signal::has_pending() {
+                    return Err(LinuxError::EINTR);
+                }
+                if waker.seq.load(Ordering::Acquire) != seq {
+                    return Err(LinuxError::EAGAIN);
+                }
+                Ok(())
+            };
+            let wait = || waker.wq.wait_meta_if((), condition);
+            #[cfg(feature = "signal")]
+            let wait_res = ruxtask::Signal::interruptible(wait);
+            #[cfg(not(feature = "signal"))]
+            let wait_res = wait();
+            if let Err(LinuxError::EINTR) = wait_res {
+                break Err(LinuxError::EINTR);
+            }
+        };
+        waiters.unregister(&dyn_waker);
+        res
+    }
+    #[cfg(not(feature = "multitask"))]
+    {
+        let _ = waiters;
+        loop {
+            match f() {
+                Err(LinuxError::EAGAIN) => crate::sys_sched_yield(),
+                res => return res,
+            }
+        }
+    }
+}
+
 macro_rules! syscall_body {
     ($fn: ident, $($stmt: tt)*) => {{
         #[allow(clippy::redundant_closure_call)]
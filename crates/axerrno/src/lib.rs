@@ -101,6 +101,26 @@ pub enum AxError {
     FunctionNotImplemented,
     /// Not a tty device
     NoTty,
+    /// The operation was interrupted by a signal.
+    Interrupted,
+    /// The operation timed out.
+    TimedOut,
+    /// The operation would cause a deadlock.
+    Deadlock,
+    /// The operation is not permitted for the caller.
+    NotPermitted,
+    /// The target process or thread does not exist.
+    NoSuchProcess,
+    /// Too many symbolic links were encountered in resolving a path.
+    FilesystemLoop,
+    /// The operation would link or rename across filesystems.
+    CrossesDevices,
+    /// The filesystem is mounted read-only.
+    ReadOnlyFilesystem,
+    /// No route to the network of the destination.
+    NetworkUnreachable,
+    /// The named device, e.g. a network interface, does not exist.
+    NoSuchDevice,
 }
 
 /// A specialized [`Result`] type with [`AxError`] as the error type.
@@ -253,6 +273,16 @@ impl AxError {
             InProgress => "non_blocking operation is not completed",
             FunctionNotImplemented => "Function not implemented",
             NoTty => "not a tty device",
+            Interrupted => "Interrupted by a signal",
+            TimedOut => "Timed out",
+            Deadlock => "Resource deadlock would occur",
+            NotPermitted => "Operation not permitted",
+            NoSuchProcess => "No such process",
+            FilesystemLoop => "Too many levels of symbolic links",
+            CrossesDevices => "Cross-device link",
+            ReadOnlyFilesystem => "Read-only file system",
+            NetworkUnreachable => "Network is unreachable",
+            NoSuchDevice => "No such device",
         }
     }
 
@@ -307,6 +337,16 @@ impl From<AxError> for LinuxError {
             InProgress => LinuxError::EINPROGRESS,
             FunctionNotImplemented => LinuxError::ENOSYS,
             NoTty => LinuxError::ENOTTY,
+            Interrupted => LinuxError::EINTR,
+            TimedOut => LinuxError::ETIMEDOUT,
+            Deadlock => LinuxError::EDEADLK,
+            NotPermitted => LinuxError::EPERM,
+            NoSuchProcess => LinuxError::ESRCH,
+            FilesystemLoop => LinuxError::ELOOP,
+            CrossesDevices => LinuxError::EXDEV,
+            ReadOnlyFilesystem => LinuxError::EROFS,
+            NetworkUnreachable => LinuxError::ENETUNREACH,
+            NoSuchDevice => LinuxError::ENODEV,
         }
     }
 }
@@ -329,13 +369,13 @@ mod tests {
     #[test]
     fn test_try_from() {
         let max_code = core::mem::variant_count::<AxError>() as i32;
-        assert_eq!(max_code, 25);
-        assert_eq!(max_code, AxError::NoTty.code());
+        assert_eq!(max_code, 35);
+        assert_eq!(max_code, AxError::NoSuchDevice.code());
 
         assert_eq!(AxError::AddrInUse.code(), 1);
         assert_eq!(Ok(AxError::AddrInUse), AxError::try_from(1));
         assert_eq!(Ok(AxError::AlreadyExists), AxError::try_from(2));
-        assert_eq!(Ok(AxError::NoTty), AxError::try_from(max_code));
+        assert_eq!(Ok(AxError::NoSuchDevice), AxError::try_from(max_code));
         assert_eq!(Err(max_code + 1), AxError::try_from(max_code + 1));
         assert_eq!(Err(0), AxError::try_from(0));
         assert_eq!(Err(-1), AxError::try_from(-1));
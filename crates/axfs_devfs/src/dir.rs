@@ -10,9 +10,11 @@
 use alloc::collections::BTreeMap;
 use alloc::sync::{Arc, Weak};
 use axfs_vfs::{
-    RelPath, VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
+    current_time, RelPath, VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef,
+    VfsNodeType,
 };
 use axfs_vfs::{VfsError, VfsResult};
+use core::time::Duration;
 use spin::RwLock;
 
 use crate::pts::PTS_FS;
@@ -36,8 +38,11 @@ impl DirNode {
         ialloc: Weak<InoAllocator>,
     ) -> Arc<Self> {
         let parent = parent.map_or(Weak::<Self>::new() as _, Arc::downgrade);
+        let mut attr = VfsNodeAttr::new(ino, mode, VfsNodeType::Dir, 4096, 0);
+        let now = current_time();
+        attr.set_times(now, now, now);
         Arc::new(Self {
-            attr: RwLock::new(VfsNodeAttr::new(ino, mode, VfsNodeType::Dir, 4096, 0)),
+            attr: RwLock::new(attr),
             parent: RwLock::new(parent),
             children: RwLock::new(BTreeMap::new()),
             ialloc,
@@ -54,12 +59,22 @@ impl DirNode {
         let ino = self.ialloc.upgrade().unwrap().alloc();
         let node = Self::new(ino, mode, Some(&parent), self.ialloc.clone());
         self.children.write().insert(name, node.clone());
+        self.touch();
         node
     }
 
     /// Add a node to this directory.
     pub fn add(&self, name: &'static str, node: VfsNodeRef) {
         self.children.write().insert(name, node);
+        self.touch();
+    }
+
+    /// Updates the modification and change times after the entries changed.
+    fn touch(&self) {
+        let now = current_time();
+        let mut attr = self.attr.write();
+        let atime = attr.atime();
+        attr.set_times(atime, now, now);
     }
 }
 
@@ -69,7 +84,27 @@ impl VfsNodeOps for DirNode {
     }
 
     fn set_mode(&self, mode: VfsNodePerm) -> VfsResult {
-        self.attr.write().set_perm(mode);
+        let mut attr = self.attr.write();
+        attr.set_perm(mode);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        let mut attr = self.attr.write();
+        let (uid, gid) = (uid.unwrap_or(attr.uid()), gid.unwrap_or(attr.gid()));
+        attr.set_owner(uid, gid);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        let mut attr = self.attr.write();
+        let atime = atime.unwrap_or(attr.atime());
+        let mtime = mtime.unwrap_or(attr.mtime());
+        attr.set_times(atime, mtime, current_time());
         Ok(())
     }
 
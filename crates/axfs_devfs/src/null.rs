@@ -8,6 +8,7 @@
  */
 
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
+use core::time::Duration;
 
 /// A null device behaves like `/dev/null`.
 ///
@@ -27,6 +28,14 @@ impl VfsNodeOps for NullDev {
     fn set_mode(&self, _mode: VfsNodePerm) -> VfsResult {
         Ok(())
     }
+
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
     fn read_at(&self, _offset: u64, _buf: &mut [u8]) -> VfsResult<usize> {
         Ok(0)
     }
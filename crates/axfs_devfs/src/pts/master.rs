@@ -7,6 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
 
 use alloc::sync::Arc;
 use axerrno::{AxError, AxResult};
@@ -81,6 +82,14 @@ impl VfsNodeOps for PtyMaster {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn release(&self) -> VfsResult {
         self.ptmx.ptsfs().remove_pty(self.idx);
         Ok(())
@@ -12,6 +12,7 @@ use axfs_vfs::{
     impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
     VfsResult,
 };
+use core::time::Duration;
 
 /// Pseudo-Terminal Master Multiplexer
 pub struct Ptmx {
@@ -48,5 +49,13 @@ impl VfsNodeOps for Ptmx {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     impl_vfs_non_dir_default!();
 }
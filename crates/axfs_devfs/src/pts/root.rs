@@ -13,6 +13,7 @@ use axfs_vfs::{
     impl_vfs_dir_default, RelPath, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm,
     VfsNodeRef, VfsNodeType, VfsResult,
 };
+use core::time::Duration;
 use spin::once::Once;
 use spin::rwlock::RwLock;
 
@@ -76,6 +77,14 @@ impl VfsNodeOps for PtsRootInode {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn parent(&self) -> Option<VfsNodeRef> {
         self.parent.get().unwrap().upgrade()
     }
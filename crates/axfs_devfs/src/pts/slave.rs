@@ -7,6 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
 
 use alloc::sync::{Arc, Weak};
 use axerrno::AxResult;
@@ -53,6 +54,14 @@ impl VfsNodeOps for PtySlaveInode {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
         self.slave.read_at(offset, buf)
     }
@@ -108,6 +117,14 @@ impl VfsNodeOps for PtySlave {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn read_at(&self, _offset: u64, dst: &mut [u8]) -> VfsResult<usize> {
         self.master().output.read(dst)
     }
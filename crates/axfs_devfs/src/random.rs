@@ -9,6 +9,7 @@
 
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
 use core::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use core::time::Duration;
 
 static SEED: AtomicU64 = AtomicU64::new(0xae_f3);
 
@@ -42,6 +43,14 @@ impl VfsNodeOps for RandomDev {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
         let len = buf.len() >> 2;
         let remainder = buf.len() & 0x3;
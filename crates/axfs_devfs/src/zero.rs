@@ -8,6 +8,7 @@
  */
 
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
+use core::time::Duration;
 
 /// A zero device behaves like `/dev/zero`.
 ///
@@ -29,6 +30,14 @@ impl VfsNodeOps for ZeroDev {
         Ok(())
     }
 
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Ok(())
+    }
+
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Ok(())
+    }
+
     fn read_at(&self, _offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
         buf.fill(0);
         Ok(buf.len())
@@ -10,16 +10,20 @@
 use alloc::collections::BTreeMap;
 use alloc::sync::{Arc, Weak};
 use alloc::{string::String, vec::Vec};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
 
 use axfs_vfs::{
-    RelPath, VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
+    current_time, RelPath, VfsDirEntry, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef,
+    VfsNodeType,
 };
 use axfs_vfs::{VfsError, VfsResult};
 use ruxfifo::FifoNode;
 use spin::rwlock::RwLock;
 
 use crate::file::FileNode;
-use crate::InoAllocator;
+use crate::symlink::SymlinkNode;
+use crate::{new_attr, set_attr_owner, set_attr_times, InoAllocator};
 
 /// The directory node in the RAM filesystem.
 ///
@@ -39,8 +43,10 @@ impl DirNode {
         parent: Option<Weak<dyn VfsNodeOps>>,
         ialloc: Weak<InoAllocator>,
     ) -> Arc<Self> {
+        let mut attr = new_attr(ino, mode, VfsNodeType::Dir);
+        attr.set_size(4096);
         Arc::new_cyclic(|this| Self {
-            attr: RwLock::new(VfsNodeAttr::new(ino, mode, VfsNodeType::Dir, 4096, 0)),
+            attr: RwLock::new(attr),
             this: this.clone(),
             parent: RwLock::new(parent.unwrap_or_else(|| Weak::<Self>::new())),
             children: RwLock::new(BTreeMap::new()),
@@ -52,6 +58,14 @@ impl DirNode {
         *self.parent.write() = parent.map_or(Weak::<Self>::new() as _, Arc::downgrade);
     }
 
+    /// Updates the modification and change times after the entries changed.
+    fn touch(&self) {
+        let now = current_time();
+        let mut attr = self.attr.write();
+        let atime = attr.atime();
+        attr.set_times(atime, now, now);
+    }
+
     /// Returns a string list of all entries in this directory.
     pub fn get_entries(&self) -> Vec<String> {
         self.children.read().keys().cloned().collect()
@@ -76,10 +90,63 @@ impl DirNode {
             _ => return Err(VfsError::Unsupported),
         };
         self.children.write().insert(name.into(), node);
+        self.touch();
         Ok(())
     }
 
+    /// Creates a new symbolic link with the given name and target in this
+    /// directory.
+    pub fn create_symlink(&self, name: &str, target: &str) -> VfsResult {
+        if self.exist(name) {
+            log::error!("AlreadyExists {name}");
+            return Err(VfsError::AlreadyExists);
+        }
+        let ino = self.ialloc.upgrade().unwrap().alloc();
+        let node = Arc::new(SymlinkNode::new(ino, target));
+        self.children.write().insert(name.into(), node);
+        self.touch();
+        Ok(())
+    }
+
+    /// Adds a node implemented outside of ramfs with the given name in this
+    /// directory, e.g. a file whose content is generated when read.
+    pub fn add_node(&self, name: &str, node: VfsNodeRef) -> VfsResult {
+        let mut children = self.children.write();
+        if children.contains_key(name) {
+            log::error!("AlreadyExists {name}");
+            return Err(VfsError::AlreadyExists);
+        }
+        children.insert(name.into(), node);
+        drop(children);
+        self.touch();
+        Ok(())
+    }
+
+    /// Creates a new hard link with the given name in this directory, which
+    /// refers to the existing node `src`.
+    ///
+    /// Directories can not be linked.
+    pub fn link_node(&self, name: &str, src: VfsNodeRef) -> VfsResult<VfsNodeRef> {
+        if src.as_any().is::<DirNode>() {
+            return Err(VfsError::NotPermitted);
+        }
+        let nlink = nlink_of(&src).ok_or(VfsError::Unsupported)?;
+        let mut children = self.children.write();
+        if children.contains_key(name) {
+            log::error!("AlreadyExists {name}");
+            return Err(VfsError::AlreadyExists);
+        }
+        nlink.fetch_add(1, Ordering::AcqRel);
+        children.insert(name.into(), src.clone());
+        drop(children);
+        self.touch();
+        Ok(src)
+    }
+
     /// Removes a node by the given name in this directory.
+    ///
+    /// The node itself is freed when the last link to it is removed and it is
+    /// no longer opened.
     pub fn remove_node(&self, name: &str) -> VfsResult {
         let mut children = self.children.write();
         let node = children.get(name).ok_or(VfsError::NotFound)?;
@@ -88,17 +155,43 @@ impl DirNode {
                 return Err(VfsError::DirectoryNotEmpty);
             }
         }
+        if let Some(nlink) = nlink_of(node) {
+            nlink.fetch_sub(1, Ordering::AcqRel);
+        }
         children.remove(name);
+        drop(children);
+        self.touch();
         Ok(())
     }
 }
 
 impl VfsNodeOps for DirNode {
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        Ok(*self.attr.read())
+        let mut attr = *self.attr.read();
+        // the entry in the parent, "." and ".." of each subdirectory
+        let subdirs = self
+            .children
+            .read()
+            .values()
+            .filter(|node| node.as_any().is::<DirNode>())
+            .count();
+        attr.set_nlink(2 + subdirs as u64);
+        Ok(attr)
     }
     fn set_mode(&self, mode: VfsNodePerm) -> VfsResult {
-        self.attr.write().set_perm(mode);
+        let mut attr = self.attr.write();
+        attr.set_perm(mode);
+        set_attr_times(&mut attr, None, None);
+        Ok(())
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        set_attr_owner(&mut self.attr.write(), uid, gid);
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        set_attr_times(&mut self.attr.write(), atime, mtime);
         Ok(())
     }
 
@@ -179,9 +272,51 @@ impl VfsNodeOps for DirNode {
             return Err(VfsError::AlreadyExists);
         }
         self.children.write().insert(name.as_str().into(), node);
+        self.touch();
         Ok(())
     }
 
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        let (name, rest) = split_path(path);
+        if let Some(rest) = rest {
+            match name {
+                ".." => self
+                    .parent()
+                    .ok_or(VfsError::NotFound)?
+                    .symlink(&rest, target),
+                _ => self
+                    .children
+                    .read()
+                    .get(name)
+                    .ok_or(VfsError::NotFound)?
+                    .symlink(&rest, target),
+            }
+        } else if name.is_empty() || name == ".." {
+            Err(VfsError::AlreadyExists)
+        } else {
+            self.create_symlink(name, target)
+        }
+    }
+
+    fn link(&self, path: &RelPath, src: VfsNodeRef) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        if let Some(rest) = rest {
+            match name {
+                ".." => self.parent().ok_or(VfsError::NotFound)?.link(&rest, src),
+                _ => self
+                    .children
+                    .read()
+                    .get(name)
+                    .ok_or(VfsError::NotFound)?
+                    .link(&rest, src),
+            }
+        } else if name.is_empty() || name == ".." {
+            Err(VfsError::AlreadyExists)
+        } else {
+            self.link_node(name, src)
+        }
+    }
+
     fn unlink(&self, path: &RelPath) -> VfsResult {
         let (name, rest) = split_path(path);
         if let Some(rest) = rest {
@@ -204,6 +339,16 @@ impl VfsNodeOps for DirNode {
     axfs_vfs::impl_vfs_dir_default! {}
 }
 
+/// Returns the link count of `node`, if it is a node that can be linked.
+fn nlink_of(node: &VfsNodeRef) -> Option<&AtomicU64> {
+    let node = node.as_any();
+    if let Some(file) = node.downcast_ref::<FileNode>() {
+        Some(file.nlink())
+    } else {
+        node.downcast_ref::<SymlinkNode>().map(SymlinkNode::nlink)
+    }
+}
+
 fn split_path<'a>(path: &'a RelPath) -> (&'a str, Option<RelPath<'a>>) {
     path.find('/').map_or((path, None), |n| {
         (&path[..n], Some(RelPath::new(&path[n + 1..])))
@@ -8,43 +8,71 @@
  */
 
 use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
 use axfs_vfs::{
-    impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult,
+    current_time, impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType,
+    VfsResult,
 };
 use spin::rwlock::RwLock;
 
+use crate::{new_attr, set_attr_owner, set_attr_times};
+
 /// The file node in the RAM filesystem.
 ///
 /// It implements [`axfs_vfs::VfsNodeOps`].
 pub struct FileNode {
-    ino: u64,
-    mode: RwLock<VfsNodePerm>,
+    attr: RwLock<VfsNodeAttr>,
     content: RwLock<Vec<u8>>,
+    nlink: AtomicU64,
 }
 
 impl FileNode {
-    pub(super) const fn new(ino: u64, mode: VfsNodePerm) -> Self {
+    pub(super) fn new(ino: u64, mode: VfsNodePerm) -> Self {
         Self {
-            ino,
-            mode: RwLock::new(mode),
+            attr: RwLock::new(new_attr(ino, mode, VfsNodeType::File)),
             content: RwLock::new(Vec::new()),
+            nlink: AtomicU64::new(1),
         }
     }
+
+    /// Returns the number of directory entries referring to the node.
+    pub(super) fn nlink(&self) -> &AtomicU64 {
+        &self.nlink
+    }
+
+    /// Updates the modification and change times after the content changed.
+    fn touch(&self) {
+        let now = current_time();
+        let mut attr = self.attr.write();
+        let atime = attr.atime();
+        attr.set_times(atime, now, now);
+    }
 }
 
 impl VfsNodeOps for FileNode {
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        Ok(VfsNodeAttr::new(
-            self.ino,
-            *self.mode.read(),
-            VfsNodeType::File,
-            self.content.read().len() as _,
-            0,
-        ))
+        let mut attr = *self.attr.read();
+        attr.set_size(self.content.read().len() as _);
+        attr.set_nlink(self.nlink.load(Ordering::Acquire));
+        Ok(attr)
     }
 
     fn set_mode(&self, mode: VfsNodePerm) -> VfsResult {
-        *self.mode.write() = mode;
+        let mut attr = self.attr.write();
+        attr.set_perm(mode);
+        set_attr_times(&mut attr, None, None);
+        Ok(())
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        set_attr_owner(&mut self.attr.write(), uid, gid);
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        set_attr_times(&mut self.attr.write(), atime, mtime);
         Ok(())
     }
 
@@ -55,6 +83,7 @@ impl VfsNodeOps for FileNode {
         } else {
             content.resize(size as _, 0);
         }
+        self.touch();
         Ok(())
     }
 
@@ -75,6 +104,7 @@ impl VfsNodeOps for FileNode {
         }
         let dst = &mut content[offset..offset + buf.len()];
         dst.copy_from_slice(&buf[..dst.len()]);
+        self.touch();
         Ok(buf.len())
     }
 
@@ -17,16 +17,21 @@ extern crate alloc;
 
 mod dir;
 mod file;
+mod symlink;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::dir::DirNode;
 pub use self::file::FileNode;
+pub use self::symlink::SymlinkNode;
 
 use alloc::sync::Arc;
-use axfs_vfs::{VfsNodePerm, VfsNodeRef, VfsOps, VfsResult};
+use axfs_vfs::{
+    current_time, VfsNodeAttr, VfsNodePerm, VfsNodeRef, VfsNodeType, VfsOps, VfsResult,
+};
 use core::sync::atomic::AtomicU64;
+use core::time::Duration;
 use spin::once::Once;
 
 /// An auto-increasing inode number allocator.
@@ -89,6 +94,33 @@ impl VfsOps for RamFileSystem {
     }
 }
 
+/// Returns the attributes of a node created now, owned by root.
+fn new_attr(ino: u64, mode: VfsNodePerm, ty: VfsNodeType) -> VfsNodeAttr {
+    let mut attr = VfsNodeAttr::new(ino, mode, ty, 0, 0);
+    let now = current_time();
+    attr.set_times(now, now, now);
+    attr
+}
+
+/// Changes the owner in `attr` as [`VfsNodeOps::set_owner`](axfs_vfs::VfsNodeOps::set_owner).
+fn set_attr_owner(attr: &mut VfsNodeAttr, uid: Option<u32>, gid: Option<u32>) {
+    let (uid, gid) = (uid.unwrap_or(attr.uid()), gid.unwrap_or(attr.gid()));
+    attr.set_owner(uid, gid);
+    attr.set_times(attr.atime(), attr.mtime(), current_time());
+}
+
+/// Changes the times in `attr` as [`VfsNodeOps::set_times`](axfs_vfs::VfsNodeOps::set_times).
+///
+/// Nothing changes, not even the status change time, if both times are omitted.
+fn set_attr_times(attr: &mut VfsNodeAttr, atime: Option<Duration>, mtime: Option<Duration>) {
+    if atime.is_none() && mtime.is_none() {
+        return;
+    }
+    let atime = atime.unwrap_or(attr.atime());
+    let mtime = mtime.unwrap_or(attr.mtime());
+    attr.set_times(atime, mtime, current_time());
+}
+
 impl Default for RamFileSystem {
     fn default() -> Self {
         Self::new()
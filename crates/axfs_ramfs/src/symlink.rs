@@ -0,0 +1,71 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+use alloc::string::String;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use axfs_vfs::{
+    impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult,
+};
+use spin::rwlock::RwLock;
+
+use crate::{new_attr, set_attr_owner, set_attr_times};
+
+/// The symbolic link node in the RAM filesystem.
+///
+/// It implements [`axfs_vfs::VfsNodeOps`].
+pub struct SymlinkNode {
+    attr: RwLock<VfsNodeAttr>,
+    target: String,
+    nlink: AtomicU64,
+}
+
+impl SymlinkNode {
+    pub(super) fn new(ino: u64, target: &str) -> Self {
+        let mut attr = new_attr(ino, VfsNodePerm::default_symlink(), VfsNodeType::SymLink);
+        attr.set_size(target.len() as _);
+        Self {
+            attr: RwLock::new(attr),
+            target: String::from(target),
+            nlink: AtomicU64::new(1),
+        }
+    }
+
+    /// Returns the number of directory entries referring to the node.
+    pub(super) fn nlink(&self) -> &AtomicU64 {
+        &self.nlink
+    }
+}
+
+impl VfsNodeOps for SymlinkNode {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let mut attr = *self.attr.read();
+        attr.set_nlink(self.nlink.load(Ordering::Acquire));
+        Ok(attr)
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        set_attr_owner(&mut self.attr.write(), uid, gid);
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        set_attr_times(&mut self.attr.write(), atime, mtime);
+        Ok(())
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let len = self.target.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.target.as_bytes()[..len]);
+        Ok(len)
+    }
+
+    impl_vfs_non_dir_default! {}
+}
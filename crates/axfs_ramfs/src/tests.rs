@@ -7,9 +7,11 @@
  *   See the Mulan PSL v2 for more details.
  */
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use axfs_vfs::{RelPath, VfsError, VfsNodePerm, VfsNodeType, VfsResult};
+use axfs_vfs::{RelPath, VfsError, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
 
 use crate::*;
 
@@ -219,3 +221,200 @@ fn test_ramfs() {
     assert_eq!(root.unlink(&RelPath::new_canonicalized("./foo")), Ok(()));
     assert!(ramfs.root_dir_node().get_entries().is_empty());
 }
+
+#[test]
+fn test_symlink() {
+    let ramfs = RamFileSystem::new();
+    let root = ramfs.root_dir();
+    root.create(
+        &RelPath::new_canonicalized("foo"),
+        VfsNodeType::Dir,
+        VfsNodePerm::default_dir(),
+    )
+    .unwrap();
+    root.symlink(&RelPath::new_canonicalized("foo/ln"), "../bar/baz")
+        .unwrap();
+    assert_eq!(
+        root.symlink(&RelPath::new_canonicalized("foo/ln"), "x")
+            .err(),
+        Some(VfsError::AlreadyExists)
+    );
+
+    let link = root
+        .clone()
+        .lookup(&RelPath::new_canonicalized("foo/ln"))
+        .unwrap();
+    let attr = link.get_attr().unwrap();
+    assert!(attr.is_symlink());
+    assert_eq!(attr.size(), 10);
+
+    let mut buf = [0; 16];
+    assert_eq!(link.readlink(&mut buf), Ok(10));
+    assert_eq!(&buf[..10], b"../bar/baz");
+    assert_eq!(link.readlink(&mut buf[..3]), Ok(3));
+    assert_eq!(&buf[..3], b"../");
+    assert_eq!(
+        link.symlink(&RelPath::new_canonicalized("x"), "y").err(),
+        Some(VfsError::NotADirectory)
+    );
+    assert_eq!(root.readlink(&mut buf).err(), Some(VfsError::InvalidInput));
+
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("foo/ln")), Ok(()));
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("foo")), Ok(()));
+}
+
+#[test]
+fn test_link() {
+    let ramfs = RamFileSystem::new();
+    let root = ramfs.root_dir();
+    root.create(
+        &RelPath::new_canonicalized("foo"),
+        VfsNodeType::Dir,
+        VfsNodePerm::default_dir(),
+    )
+    .unwrap();
+    root.create(
+        &RelPath::new_canonicalized("f1"),
+        VfsNodeType::File,
+        VfsNodePerm::default_file(),
+    )
+    .unwrap();
+    assert_eq!(root.get_attr().unwrap().nlink(), 3);
+
+    let file = root
+        .clone()
+        .lookup(&RelPath::new_canonicalized("f1"))
+        .unwrap();
+    assert_eq!(file.get_attr().unwrap().nlink(), 1);
+    let linked = root
+        .link(&RelPath::new_canonicalized("foo/f2"), file.clone())
+        .unwrap();
+    assert!(Arc::ptr_eq(&linked, &file));
+    assert!(Arc::ptr_eq(
+        &root
+            .clone()
+            .lookup(&RelPath::new_canonicalized("foo/f2"))
+            .unwrap(),
+        &file
+    ));
+    assert_eq!(file.get_attr().unwrap().nlink(), 2);
+    assert_eq!(
+        root.link(&RelPath::new_canonicalized("foo/f2"), file.clone())
+            .err(),
+        Some(VfsError::AlreadyExists)
+    );
+    let dir = root
+        .clone()
+        .lookup(&RelPath::new_canonicalized("foo"))
+        .unwrap();
+    assert_eq!(
+        root.link(&RelPath::new_canonicalized("bar"), dir).err(),
+        Some(VfsError::NotPermitted)
+    );
+
+    // the content is shared, and kept after all links are removed
+    assert_eq!(file.write_at(0, b"hello"), Ok(5));
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("f1")), Ok(()));
+    assert_eq!(file.get_attr().unwrap().nlink(), 1);
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("foo/f2")), Ok(()));
+    assert_eq!(file.get_attr().unwrap().nlink(), 0);
+    let mut buf = [0; 8];
+    assert_eq!(file.read_at(0, &mut buf), Ok(5));
+    assert_eq!(&buf[..5], b"hello");
+
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("foo")), Ok(()));
+    assert_eq!(root.get_attr().unwrap().nlink(), 2);
+}
+
+#[test]
+fn test_add_node() {
+    struct HelloNode;
+
+    impl axfs_vfs::VfsNodeOps for HelloNode {
+        fn get_attr(&self) -> VfsResult<axfs_vfs::VfsNodeAttr> {
+            Ok(axfs_vfs::VfsNodeAttr::new_file(0, 5, 0))
+        }
+
+        fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+            let data = &b"hello"[(offset as usize).min(5)..];
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            Ok(len)
+        }
+
+        axfs_vfs::impl_vfs_non_dir_default! {}
+    }
+
+    let ramfs = RamFileSystem::new();
+    let root = ramfs.root_dir_node();
+    assert_eq!(root.add_node("hello", Arc::new(HelloNode)), Ok(()));
+    assert_eq!(
+        root.add_node("hello", Arc::new(HelloNode)),
+        Err(VfsError::AlreadyExists)
+    );
+
+    let node = root
+        .clone()
+        .lookup(&RelPath::new_canonicalized("hello"))
+        .unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(node.read_at(0, &mut buf), Ok(5));
+    assert_eq!(&buf[..5], b"hello");
+
+    // nodes without a link count can be removed as well
+    assert_eq!(root.unlink(&RelPath::new_canonicalized("hello")), Ok(()));
+    assert!(!root.exist("hello"));
+}
+
+static TICKS: AtomicU64 = AtomicU64::new(1);
+
+/// A clock that advances one second each time it is read.
+fn ticking_clock() -> Duration {
+    Duration::from_secs(TICKS.fetch_add(1, Ordering::SeqCst))
+}
+
+#[test]
+fn test_times_and_owner() {
+    axfs_vfs::set_clock(ticking_clock);
+    let ramfs = RamFileSystem::new();
+    let root = ramfs.root_dir();
+    let path = RelPath::new_canonicalized("f1");
+    root.create(&path, VfsNodeType::File, VfsNodePerm::default_file())
+        .unwrap();
+    let file = root.clone().lookup(&path).unwrap();
+
+    let created = file.get_attr().unwrap();
+    assert_eq!((created.uid(), created.gid()), (0, 0));
+    assert!(created.mtime() > Duration::ZERO);
+    assert_eq!(created.atime(), created.mtime());
+    assert_eq!(created.ctime(), created.mtime());
+    assert!(root.get_attr().unwrap().mtime() > created.mtime());
+
+    // writes update the modification time only
+    assert_eq!(file.write_at(0, b"hello"), Ok(5));
+    let written = file.get_attr().unwrap();
+    assert!(written.mtime() > created.mtime());
+    assert_eq!(written.ctime(), written.mtime());
+    assert_eq!(written.atime(), created.atime());
+
+    let atime = Duration::new(1_000_000_000, 5);
+    assert_eq!(file.set_times(Some(atime), None), Ok(()));
+    let touched = file.get_attr().unwrap();
+    assert_eq!(touched.atime(), atime);
+    assert_eq!(touched.mtime(), written.mtime());
+    assert!(touched.ctime() > written.ctime());
+
+    // omitting both times changes nothing
+    assert_eq!(file.set_times(None, None), Ok(()));
+    assert_eq!(file.get_attr().unwrap().ctime(), touched.ctime());
+
+    assert_eq!(file.set_owner(Some(1000), None), Ok(()));
+    assert_eq!(file.set_owner(None, Some(100)), Ok(()));
+    let owned = file.get_attr().unwrap();
+    assert_eq!((owned.uid(), owned.gid()), (1000, 100));
+    assert!(owned.ctime() > touched.ctime());
+
+    let before = root.get_attr().unwrap().mtime();
+    assert_eq!(root.unlink(&path), Ok(()));
+    assert!(root.get_attr().unwrap().mtime() > before);
+}
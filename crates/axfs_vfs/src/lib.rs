@@ -9,9 +9,9 @@
 
 //! Virtual filesystem interfaces used by [ArceOS](https://github.com/rcore-os/arceos).
 //!
-//! A filesystem is a set of files and directories (symbol links are not
-//! supported currently), collectively referred to as **nodes**, which are
-//! conceptually similar to [inodes] in Linux. A file system needs to implement
+//! A filesystem is a set of files, directories and symbolic links,
+//! collectively referred to as **nodes**, which are conceptually similar to
+//! [inodes] in Linux. A file system needs to implement
 //! the [`VfsOps`] trait, its files and directories need to implement the
 //! [`VfsNodeOps`] trait.
 //!
@@ -22,6 +22,8 @@
 //! - [`format()`](VfsOps::format): Format the filesystem.
 //! - [`statfs()`](VfsOps::statfs): Get the attributes of the filesystem.
 //! - [`root_dir()`](VfsOps::root_dir): Get root directory of the filesystem.
+//! - [`use_page_cache()`](VfsOps::use_page_cache): Whether the files should be
+//!   cached in the page cache.
 //!
 //! The [`VfsNodeOps`] trait provides the following operations on a file or a
 //! directory:
@@ -35,10 +37,12 @@
 //! | [`write_at()`](VfsNodeOps::write_at) | Write data to the file | file |
 //! | [`fsync()`](VfsNodeOps::fsync) | Synchronize the file data to disk | file |
 //! | [`truncate()`](VfsNodeOps::truncate) | Truncate the file | file |
+//! | [`readlink()`](VfsNodeOps::readlink) | Read the target of the symbolic link | symlink |
 //! | [`parent()`](VfsNodeOps::parent) | Get the parent directory | directory |
 //! | [`lookup()`](VfsNodeOps::lookup) | Lookup the node with the given path | directory |
 //! | [`create()`](VfsNodeOps::create) | Create a new node with the given path | directory |
 //! | [`link()`](VfsNodeOps::link) | Create a hard link with the given path | directory |
+//! | [`symlink()`](VfsNodeOps::symlink) | Create a symbolic link with the given path | directory |
 //! | [`unlink()`](VfsNodeOps::unlink) | Remove the node with the given path | directory |
 //! | [`read_dir()`](VfsNodeOps::read_dir) | Read directory entries | directory |
 //! | [`is_empty()`](VfsNodeOps::is_empty) | Check if the directory is empty | directory |
@@ -53,11 +57,12 @@ mod macros;
 mod path;
 mod structs;
 
-use core::any::Any;
+use core::{any::Any, time::Duration};
 
 use alloc::sync::Arc;
 use axerrno::{ax_err, AxError, AxResult};
 use axio::PollState;
+use spin::Once;
 
 pub use self::path::{AbsPath, RelPath};
 pub use self::structs::{FileSystemInfo, VfsDirEntry, VfsNodeAttr, VfsNodePerm, VfsNodeType};
@@ -71,6 +76,22 @@ pub type VfsError = AxError;
 /// Alias of [`AxResult`].
 pub type VfsResult<T = ()> = AxResult<T>;
 
+static CLOCK: Once<fn() -> Duration> = Once::new();
+
+/// Sets the clock that returns the wall-clock time since the Unix epoch, used
+/// by the in-memory filesystems to stamp their nodes.
+///
+/// Only the first call takes effect.
+pub fn set_clock(clock: fn() -> Duration) {
+    CLOCK.call_once(|| clock);
+}
+
+/// Returns the current time of the clock set by [`set_clock`], or the Unix
+/// epoch if no clock is set.
+pub fn current_time() -> Duration {
+    CLOCK.get().map_or(Duration::ZERO, |clock| clock())
+}
+
 /// Filesystem operations.
 pub trait VfsOps: Send + Sync {
     /// Do something when the filesystem is mounted.
@@ -95,6 +116,14 @@ pub trait VfsOps: Send + Sync {
 
     /// Get the root directory of the filesystem.
     fn root_dir(&self) -> VfsNodeRef;
+
+    /// Whether the regular files of the filesystem should be cached in the
+    /// page cache of the kernel, which is worth it for filesystems on block
+    /// devices. The files are identified by [`VfsNodeOps::get_inode`], those
+    /// without inode numbers are not cached.
+    fn use_page_cache(&self) -> bool {
+        false
+    }
 }
 
 /// Node (file/directory/lib) operations.
@@ -120,6 +149,17 @@ pub trait VfsNodeOps: Send + Sync {
         ax_err!(Unsupported, "set_attr method is unsupported")
     }
 
+    /// Set the user and group owning the node, the `None` ones are unchanged.
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        ax_err!(Unsupported, "set_owner method is unsupported")
+    }
+
+    /// Set the access and modification times of the node, the `None` ones are
+    /// unchanged. The change time is updated to the current time.
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        ax_err!(Unsupported, "set_times method is unsupported")
+    }
+
     /// Get the inode number of the node.
     fn get_inode(&self) -> Option<u64> {
         None
@@ -147,6 +187,17 @@ pub trait VfsNodeOps: Send + Sync {
         ax_err!(InvalidInput)
     }
 
+    // symbolic link operations:
+
+    /// Read the target of the symbolic link into `buf`.
+    ///
+    /// Return the number of bytes read, the target is truncated if `buf` is
+    /// too small. Fails with [`InvalidInput`](AxError::InvalidInput) if the
+    /// node is not a symbolic link.
+    fn readlink(&self, _buf: &mut [u8]) -> VfsResult<usize> {
+        ax_err!(InvalidInput)
+    }
+
     // directory operations:
 
     /// Get the parent directory of this directory.
@@ -186,10 +237,26 @@ pub trait VfsNodeOps: Send + Sync {
     }
 
     /// Create a new hard link to the src dentry
+    ///
+    /// `name` is a path relative to the directory, and `src` must be a node
+    /// of the same filesystem. Returns the node linked.
     fn link(&self, name: &RelPath, _src: Arc<dyn VfsNodeOps>) -> VfsResult<Arc<dyn VfsNodeOps>> {
         ax_err!(Unsupported, "link method is unsupported in path {}", name)
     }
 
+    /// Create a symbolic link with the given `path` in the directory, which
+    /// points to `target`.
+    ///
+    /// `target` is stored as is, it is not required to exist.
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        ax_err!(
+            Unsupported,
+            "symlink method is unsupported in path {}, target {}",
+            path,
+            target
+        )
+    }
+
     /// Remove (the hard link of) the node with the given `path` in the directory.
     fn unlink(&self, path: &RelPath) -> VfsResult {
         ax_err!(Unsupported, "unlink method is unsupported in path {}", path)
@@ -75,6 +75,10 @@ macro_rules! impl_vfs_non_dir_default {
             $crate::__priv::ax_err!(NotADirectory)
         }
 
+        fn symlink(&self, _path: &$crate::RelPath, _target: &str) -> $crate::VfsResult {
+            $crate::__priv::ax_err!(NotADirectory)
+        }
+
         fn unlink(&self, _path: &$crate::RelPath) -> $crate::VfsResult {
             $crate::__priv::ax_err!(NotADirectory)
         }
@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::str;
+use core::{str, time::Duration};
 
 use alloc::{borrow::ToOwned, string::String};
 
@@ -31,6 +31,18 @@ pub struct VfsNodeAttr {
     size: u64,
     /// Number of 512B blocks allocated.
     blocks: u64,
+    /// Number of hard links.
+    nlink: u64,
+    /// User ID of the owner.
+    uid: u32,
+    /// Group ID of the owner.
+    gid: u32,
+    /// Time of the last access, since the Unix epoch.
+    atime: Duration,
+    /// Time of the last modification of the content.
+    mtime: Duration,
+    /// Time of the last change of the content or the attributes.
+    ctime: Duration,
 }
 
 bitflags::bitflags! {
@@ -123,6 +135,13 @@ impl VfsNodePerm {
         Self::from_bits_truncate(0o777)
     }
 
+    /// Returns the default permission for a symbolic link.
+    ///
+    /// The default permission is `0o777` (owner/group/others can read, write and execute).
+    pub const fn default_symlink() -> Self {
+        Self::from_bits_truncate(0o777)
+    }
+
     /// Returns the underlying raw `st_mode` bits that contain the standard
     /// Unix permissions for this file.
     pub const fn mode(&self) -> u32 {
@@ -242,6 +261,12 @@ impl VfsNodeAttr {
             ty,
             size,
             blocks,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            atime: Duration::ZERO,
+            mtime: Duration::ZERO,
+            ctime: Duration::ZERO,
         }
     }
 
@@ -253,6 +278,12 @@ impl VfsNodeAttr {
             ty: VfsNodeType::File,
             size,
             blocks,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            atime: Duration::ZERO,
+            mtime: Duration::ZERO,
+            ctime: Duration::ZERO,
         }
     }
 
@@ -264,6 +295,12 @@ impl VfsNodeAttr {
             ty: VfsNodeType::Dir,
             size,
             blocks,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            atime: Duration::ZERO,
+            mtime: Duration::ZERO,
+            ctime: Duration::ZERO,
         }
     }
 
@@ -276,11 +313,26 @@ impl VfsNodeAttr {
         self.size
     }
 
+    /// Sets the size of the node.
+    pub fn set_size(&mut self, size: u64) {
+        self.size = size
+    }
+
     /// Returns the number of blocks the node occupies on the disk.
     pub const fn blocks(&self) -> u64 {
         self.blocks
     }
 
+    /// Returns the number of hard links to the node.
+    pub const fn nlink(&self) -> u64 {
+        self.nlink
+    }
+
+    /// Sets the number of hard links to the node, which is 1 by default.
+    pub fn set_nlink(&mut self, nlink: u64) {
+        self.nlink = nlink
+    }
+
     /// Returns the permission of the node.
     pub const fn perm(&self) -> VfsNodePerm {
         self.mode
@@ -291,6 +343,46 @@ impl VfsNodeAttr {
         self.mode = perm
     }
 
+    /// Returns the user ID of the owner of the node.
+    pub const fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// Returns the group ID of the owner of the node.
+    pub const fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    /// Sets the user and group IDs of the owner of the node, which are 0 by
+    /// default.
+    pub fn set_owner(&mut self, uid: u32, gid: u32) {
+        self.uid = uid;
+        self.gid = gid;
+    }
+
+    /// Returns the time of the last access of the node.
+    pub const fn atime(&self) -> Duration {
+        self.atime
+    }
+
+    /// Returns the time of the last modification of the node content.
+    pub const fn mtime(&self) -> Duration {
+        self.mtime
+    }
+
+    /// Returns the time of the last change of the node content or attributes.
+    pub const fn ctime(&self) -> Duration {
+        self.ctime
+    }
+
+    /// Sets the access, modification and change times of the node, which are
+    /// the Unix epoch by default.
+    pub fn set_times(&mut self, atime: Duration, mtime: Duration, ctime: Duration) {
+        self.atime = atime;
+        self.mtime = mtime;
+        self.ctime = ctime;
+    }
+
     /// Returns the type of the node.
     pub const fn file_type(&self) -> VfsNodeType {
         self.ty
@@ -306,6 +398,11 @@ impl VfsNodeAttr {
         self.ty.is_dir()
     }
 
+    /// Whether the node is a symbolic link.
+    pub const fn is_symlink(&self) -> bool {
+        self.ty.is_symlink()
+    }
+
     /// Whether the node is a fifo.
     pub const fn is_fifo(&self) -> bool {
         self.ty.is_fifo()
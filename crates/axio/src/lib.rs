@@ -20,11 +20,15 @@ use core::fmt;
 mod buffered;
 mod error;
 mod impls;
+#[cfg(feature = "alloc")]
+mod poll;
 
 pub mod prelude;
 
 pub use self::buffered::BufReader;
 pub use self::error::{Error, Result};
+#[cfg(feature = "alloc")]
+pub use self::poll::{PollWaiters, PollWaker};
 
 #[cfg(feature = "alloc")]
 use alloc::{string::String, vec::Vec};
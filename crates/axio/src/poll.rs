@@ -0,0 +1,110 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Notifications of readiness changes of I/O objects.
+
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use spinlock::SpinNoIrq;
+
+/// A waiter woken up when the [`PollState`] of an I/O object may have
+/// changed, such as an epoll instance watching the object.
+///
+/// [`PollState`]: crate::PollState
+pub trait PollWaker: Send + Sync {
+    /// Wakes up the waiter, which polls the object again to find out what
+    /// has changed.
+    fn wake(&self);
+}
+
+struct PollWaiter {
+    waker: Weak<dyn PollWaker>,
+    exclusive: bool,
+}
+
+/// Waiters registered on an I/O object, notified by the object whenever its
+/// [`PollState`] may have changed.
+///
+/// All non-exclusive waiters are woken up by each notification, while the
+/// exclusive ones take turns to be woken up, one per notification.
+///
+/// [`PollState`]: crate::PollState
+pub struct PollWaiters {
+    waiters: SpinNoIrq<Vec<PollWaiter>>,
+}
+
+impl PollWaiters {
+    /// Creates an empty set of waiters.
+    pub const fn new() -> Self {
+        Self {
+            waiters: SpinNoIrq::new(Vec::new()),
+        }
+    }
+
+    /// Registers `waker` to be woken up by [`PollWaiters::notify`].
+    ///
+    /// Only a weak reference to `waker` is kept, it is unregistered
+    /// automatically when dropped.
+    pub fn register(&self, waker: &Arc<dyn PollWaker>, exclusive: bool) {
+        self.waiters.lock().push(PollWaiter {
+            waker: Arc::downgrade(waker),
+            exclusive,
+        });
+    }
+
+    /// Unregisters `waker`, once for each [`PollWaiters::register`].
+    pub fn unregister(&self, waker: &Arc<dyn PollWaker>) {
+        let mut waiters = self.waiters.lock();
+        if let Some(idx) = waiters
+            .iter()
+            .position(|waiter| core::ptr::addr_eq(waiter.waker.as_ptr(), Arc::as_ptr(waker)))
+        {
+            waiters.remove(idx);
+        }
+    }
+
+    /// Wakes up the registered waiters.
+    ///
+    /// The wakers are called without the lock of the waiters held. The caller
+    /// should not hold the locks taken to poll the object either, as the
+    /// woken waiters may poll it right away.
+    pub fn notify(&self) {
+        let mut woken = Vec::new();
+        {
+            let mut waiters = self.waiters.lock();
+            if waiters.is_empty() {
+                return;
+            }
+            waiters.retain(|waiter| waiter.waker.strong_count() > 0);
+            let mut exclusive = None;
+            for (idx, waiter) in waiters.iter().enumerate() {
+                if !waiter.exclusive {
+                    woken.extend(waiter.waker.upgrade());
+                } else if exclusive.is_none() {
+                    exclusive = Some(idx);
+                }
+            }
+            if let Some(idx) = exclusive {
+                // move it to the end, so that the next one is woken up next time
+                let waiter = waiters.remove(idx);
+                woken.extend(waiter.waker.upgrade());
+                waiters.push(waiter);
+            }
+        }
+        for waker in woken {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for PollWaiters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
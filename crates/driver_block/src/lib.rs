@@ -43,6 +43,38 @@ pub trait BlockDriverOps: BaseDriverOps {
     /// contiguous blocks will be written.
     fn write_block(&mut self, block_id: u64, buf: &[u8]) -> DevResult;
 
+    /// Reads contiguous blocks starting from the given block, the size of the
+    /// buffer must be a multiple of the block size.
+    ///
+    /// Drivers supporting multi-block requests read all the blocks in one
+    /// request, the default implementation reads them one by one.
+    fn read_blocks(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        let block_size = self.block_size();
+        if block_size == 0 || buf.len() % block_size != 0 {
+            return Err(DevError::InvalidParam);
+        }
+        for (i, block) in buf.chunks_exact_mut(block_size).enumerate() {
+            self.read_block(block_id + i as u64, block)?;
+        }
+        Ok(())
+    }
+
+    /// Writes contiguous blocks starting from the given block, the size of the
+    /// buffer must be a multiple of the block size.
+    ///
+    /// Drivers supporting multi-block requests write all the blocks in one
+    /// request, the default implementation writes them one by one.
+    fn write_blocks(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        let block_size = self.block_size();
+        if block_size == 0 || buf.len() % block_size != 0 {
+            return Err(DevError::InvalidParam);
+        }
+        for (i, block) in buf.chunks_exact(block_size).enumerate() {
+            self.write_block(block_id + i as u64, block)?;
+        }
+        Ok(())
+    }
+
     /// Flushes the device to write all pending data to the storage.
     fn flush(&mut self) -> DevResult;
 }
@@ -99,6 +99,16 @@ impl BlockDriverOps for RamDisk {
         Ok(())
     }
 
+    #[inline]
+    fn read_blocks(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        self.read_block(block_id, buf)
+    }
+
+    #[inline]
+    fn write_blocks(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        self.write_block(block_id, buf)
+    }
+
     fn flush(&mut self) -> DevResult {
         Ok(())
     }
@@ -24,6 +24,15 @@ const RX_BUFFER_SIZE: usize = 1024;
 const MEM_POOL: usize = 4096;
 const MEM_POOL_ENTRY_SIZE: usize = 2048;
 
+/// Extended interrupt cause read register, which is cleared on read.
+const IXGBE_EICR: usize = 0x00800;
+/// Extended interrupt mask set register.
+const IXGBE_EIMS: usize = 0x00880;
+/// Interrupt vector allocation register of the queues 0 and 1.
+const IXGBE_IVAR0: usize = 0x00900;
+/// The valid bit of an entry of `IVAR`.
+const IXGBE_IVAR_ALLOC_VAL: u32 = 0x80;
+
 /// The ixgbe NIC device driver.
 ///
 /// `QS` is the ixgbe queue size, `QN` is the ixgbe queue num.
@@ -31,6 +40,8 @@ pub struct IxgbeNic<H: IxgbeHal, const QS: usize, const QN: u16> {
     inner: IxgbeDevice<H, QS>,
     mem_pool: Arc<MemPool>,
     rx_buffer_queue: VecDeque<NetBufPtr>,
+    base: usize,
+    irq_num: Option<usize>,
 }
 
 unsafe impl<H: IxgbeHal, const QS: usize, const QN: u16> Sync for IxgbeNic<H, QS, QN> {}
@@ -39,7 +50,10 @@ unsafe impl<H: IxgbeHal, const QS: usize, const QN: u16> Send for IxgbeNic<H, QS
 impl<H: IxgbeHal, const QS: usize, const QN: u16> IxgbeNic<H, QS, QN> {
     /// Creates a net ixgbe NIC instance and initialize, or returns a error if
     /// any step fails.
-    pub fn init(base: usize, len: usize) -> DevResult<Self> {
+    ///
+    /// The interrupt of receiving is enabled if `irq_num` is given, which is
+    /// the legacy interrupt of the PCI device.
+    pub fn init(base: usize, len: usize, irq_num: Option<usize>) -> DevResult<Self> {
         let mem_pool = MemPool::allocate::<H>(MEM_POOL, MEM_POOL_ENTRY_SIZE)
             .map_err(|_| DevError::NoMemory)?;
         let inner = IxgbeDevice::<H, QS>::init(base, len, QN, QN, &mem_pool).map_err(|err| {
@@ -48,11 +62,31 @@ impl<H: IxgbeHal, const QS: usize, const QN: u16> IxgbeNic<H, QS, QN> {
         })?;
 
         let rx_buffer_queue = VecDeque::with_capacity(RX_BUFFER_SIZE);
-        Ok(Self {
+        let nic = Self {
             inner,
             mem_pool,
             rx_buffer_queue,
-        })
+            base,
+            irq_num,
+        };
+        if irq_num.is_some() {
+            nic.enable_rx_interrupt();
+        }
+        Ok(nic)
+    }
+
+    /// Maps the receive queue 0 to the interrupt cause 0 and unmasks it.
+    fn enable_rx_interrupt(&self) {
+        self.write_reg(IXGBE_IVAR0, IXGBE_IVAR_ALLOC_VAL);
+        self.write_reg(IXGBE_EIMS, 1);
+    }
+
+    fn read_reg(&self, reg: usize) -> u32 {
+        unsafe { core::ptr::read_volatile((self.base + reg) as *const u32) }
+    }
+
+    fn write_reg(&self, reg: usize, value: u32) {
+        unsafe { core::ptr::write_volatile((self.base + reg) as *mut u32, value) }
     }
 }
 
@@ -155,6 +189,14 @@ impl<H: IxgbeHal, const QS: usize, const QN: u16> NetDriverOps for IxgbeNic<H, Q
         let tx_buf = IxgbeNetBuf::alloc(&self.mem_pool, size).map_err(|_| DevError::NoMemory)?;
         Ok(NetBufPtr::from(tx_buf))
     }
+
+    fn irq_num(&self) -> Option<usize> {
+        self.irq_num
+    }
+
+    fn ack_interrupt(&mut self) {
+        self.read_reg(IXGBE_EICR);
+    }
 }
 
 impl From<IxgbeNetBuf> for NetBufPtr {
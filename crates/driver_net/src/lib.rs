@@ -87,6 +87,18 @@ pub trait NetDriverOps: BaseDriverOps {
     /// Allocate a memory buffer of a specified size for network transmission,
     /// returns [`DevResult`]
     fn alloc_tx_buffer(&mut self, size: usize) -> DevResult<NetBufPtr>;
+
+    /// The IRQ number of the NIC, or `None` if it has no interrupt available,
+    /// so that it can only be polled.
+    fn irq_num(&self) -> Option<usize> {
+        None
+    }
+
+    /// Acknowledges the interrupt of the NIC.
+    ///
+    /// It should be called before receiving the pending packets, so that the
+    /// packets arriving later raise the interrupt again.
+    fn ack_interrupt(&mut self) {}
 }
 
 /// A raw buffer struct for network device.
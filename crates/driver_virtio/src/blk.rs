@@ -63,6 +63,18 @@ impl<H: Hal, T: Transport> BlockDriverOps for VirtIoBlkDev<H, T> {
             .map_err(as_dev_err)
     }
 
+    /// Reads all the blocks in one request.
+    #[inline]
+    fn read_blocks(&mut self, block_id: u64, buf: &mut [u8]) -> DevResult {
+        self.read_block(block_id, buf)
+    }
+
+    /// Writes all the blocks in one request.
+    #[inline]
+    fn write_blocks(&mut self, block_id: u64, buf: &[u8]) -> DevResult {
+        self.write_block(block_id, buf)
+    }
+
     fn flush(&mut self) -> DevResult {
         Ok(())
     }
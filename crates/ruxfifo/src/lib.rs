@@ -12,10 +12,11 @@
 extern crate alloc;
 use alloc::sync::Arc;
 use axerrno::AxError;
-use axfs_vfs::{impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsResult};
+use axfs_vfs::{current_time, impl_vfs_non_dir_default, VfsNodeAttr, VfsNodeOps, VfsResult};
 use axfs_vfs::{VfsNodePerm, VfsNodeType};
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
 use ringbuffer::RingBuffer;
 use spin::mutex::Mutex;
 
@@ -35,16 +36,19 @@ pub struct FifoNode {
     readers: AtomicUsize,
     /// Active writers counter (atomic for lock-free access)
     writers: AtomicUsize,
+    /// Waiters notified when the buffer or the peers change
+    waiters: PollWaiters,
 }
 
 impl FifoNode {
     /// create a new fifo
     pub fn new(ino: u64, mode: VfsNodePerm) -> Self {
         Self {
-            attr: Mutex::new(VfsNodeAttr::new(ino, mode, VfsNodeType::Fifo, 0, 0)),
+            attr: Mutex::new(new_attr(ino, mode)),
             buffer: Mutex::new(RingBuffer::new(FIFO_SIZE)),
             readers: AtomicUsize::new(0),
             writers: AtomicUsize::new(0),
+            waiters: PollWaiters::new(),
         }
     }
 
@@ -54,16 +58,11 @@ impl FifoNode {
     /// typically used for creating pipe reader/writer endpoints.
     pub fn new_pair() -> (Arc<Self>, Arc<Self>) {
         let node = Arc::new(Self {
-            attr: Mutex::new(VfsNodeAttr::new(
-                1,
-                VfsNodePerm::default_fifo(),
-                VfsNodeType::Fifo,
-                0,
-                0,
-            )),
+            attr: Mutex::new(new_attr(1, VfsNodePerm::default_fifo())),
             buffer: Mutex::new(RingBuffer::new(FIFO_SIZE)),
             readers: AtomicUsize::new(1),
             writers: AtomicUsize::new(1),
+            waiters: PollWaiters::new(),
         });
         (node.clone(), node)
     }
@@ -81,12 +80,14 @@ impl FifoNode {
     /// - Release: Make buffer writes visible to others
     pub fn acquire_reader(&self) {
         self.readers.fetch_add(1, Ordering::AcqRel);
+        self.waiters.notify();
     }
 
     /// Unregisters a reader and checks for underflow
     pub fn release_reader(&self) {
         let cnt_before = self.readers.fetch_sub(1, Ordering::AcqRel);
-        debug_assert!(cnt_before != 0)
+        debug_assert!(cnt_before != 0);
+        self.waiters.notify();
     }
 
     /// Returns current number of active writers
@@ -97,12 +98,19 @@ impl FifoNode {
     /// Registers a new writer with atomic reference counting
     pub fn acquire_writer(&self) {
         self.writers.fetch_add(1, Ordering::AcqRel);
+        self.waiters.notify();
     }
 
     /// Unregisters a writer and checks for underflow
     pub fn release_writer(&self) {
         let cnt_before = self.writers.fetch_sub(1, Ordering::AcqRel);
-        debug_assert!(cnt_before != 0)
+        debug_assert!(cnt_before != 0);
+        self.waiters.notify();
+    }
+
+    /// Waiters notified when the fifo becomes readable, writable or hung up
+    pub fn poll_waiters(&self) -> &PollWaiters {
+        &self.waiters
     }
 
     /// Checks readable status and peer existence for readers
@@ -132,7 +140,27 @@ impl VfsNodeOps for FifoNode {
     }
 
     fn set_mode(&self, mode: VfsNodePerm) -> VfsResult {
-        self.attr.lock().set_perm(mode);
+        let mut attr = self.attr.lock();
+        attr.set_perm(mode);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        let mut attr = self.attr.lock();
+        let (uid, gid) = (uid.unwrap_or(attr.uid()), gid.unwrap_or(attr.gid()));
+        attr.set_owner(uid, gid);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        let mut attr = self.attr.lock();
+        let atime = atime.unwrap_or(attr.atime());
+        let mtime = mtime.unwrap_or(attr.mtime());
+        attr.set_times(atime, mtime, current_time());
         Ok(())
     }
 
@@ -142,7 +170,10 @@ impl VfsNodeOps for FifoNode {
         if buffer.is_empty() {
             return Err(AxError::WouldBlock);
         }
-        Ok(buffer.read(buf))
+        let len = buffer.read(buf);
+        drop(buffer);
+        self.waiters.notify();
+        Ok(len)
     }
 
     // for fifo, offset is useless and ignored
@@ -151,7 +182,10 @@ impl VfsNodeOps for FifoNode {
         if buffer.is_full() {
             return Err(AxError::WouldBlock);
         }
-        Ok(buffer.write(buf))
+        let len = buffer.write(buf);
+        drop(buffer);
+        self.waiters.notify();
+        Ok(len)
     }
 
     // fifo does not support truncate
@@ -166,3 +200,11 @@ impl VfsNodeOps for FifoNode {
 
     impl_vfs_non_dir_default! {}
 }
+
+/// Returns the attributes of a fifo created now.
+fn new_attr(ino: u64, mode: VfsNodePerm) -> VfsNodeAttr {
+    let mut attr = VfsNodeAttr::new(ino, mode, VfsNodeType::Fifo, 0, 0);
+    let now = current_time();
+    attr.set_times(now, now, now);
+    attr
+}
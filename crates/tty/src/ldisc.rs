@@ -14,7 +14,7 @@
 use crate::termios::{Termios, CC_C_CHAR};
 use alloc::{format, sync::Arc};
 use axerrno::AxResult;
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use ringbuffer::RingBuffer;
 use spinlock::SpinNoIrq;
 
@@ -32,6 +32,8 @@ pub struct Ldisc {
     termios: SpinNoIrq<Termios>,
     /// Terminal window dimensions
     winsize: SpinNoIrq<WinSize>,
+    /// Waiters notified when input arrives
+    waiters: PollWaiters,
 }
 
 impl Ldisc {
@@ -42,6 +44,7 @@ impl Ldisc {
             read_buffer: SpinNoIrq::new(RingBuffer::new(BUFFER_CAPACITY)),
             termios: SpinNoIrq::new(Termios::default()),
             winsize: SpinNoIrq::new(WinSize::default()),
+            waiters: PollWaiters::new(),
         })
     }
 
@@ -190,7 +193,12 @@ impl Ldisc {
     ///
     /// Applies termios settings for character conversion, echo handling, and
     /// buffering in either raw or canonical mode.
-    pub fn push_char<F: FnMut(&str)>(&self, mut ch: u8, mut echo: F) {
+    pub fn push_char<F: FnMut(&str)>(&self, ch: u8, echo: F) {
+        self.input_char(ch, echo);
+        self.waiters.notify();
+    }
+
+    fn input_char<F: FnMut(&str)>(&self, mut ch: u8, mut echo: F) {
         let termios = self.termios.lock();
 
         // Convert CR to LF if ICRNL is enabled
@@ -263,6 +271,11 @@ impl Ldisc {
         self.read_buffer.lock().available_read()
     }
 
+    /// Waiters notified when input arrives
+    pub fn poll_waiters(&self) -> &PollWaiters {
+        &self.waiters
+    }
+
     /// poll Ldisc
     pub fn poll(&self) -> PollState {
         let readable = !self.read_buffer.lock().is_empty();
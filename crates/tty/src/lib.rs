@@ -20,7 +20,7 @@ mod tty;
 
 use alloc::{ffi::CString, sync::Arc};
 use axerrno::AxResult;
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use axlog::{ax_print, ax_println};
 use driver::TtyDriver;
 use lazy_init::LazyInit;
@@ -66,6 +66,11 @@ pub fn tty_poll() -> PollState {
     N_TTY.get().unwrap().ldisc.poll()
 }
 
+/// Waiters notified when the TTY receives input
+pub fn tty_poll_waiters() -> &'static PollWaiters {
+    N_TTY.get().unwrap().ldisc.poll_waiters()
+}
+
 /// Writes data to TTY output, handles UTF-8 and binary content
 pub fn tty_write(src: &[u8]) -> AxResult<usize> {
     if let Ok(content) = alloc::str::from_utf8(src) {
@@ -25,6 +25,8 @@ mod page;
 use allocator::{AllocResult, BaseAllocator, BitmapPageAllocator, ByteAllocator, PageAllocator};
 use core::alloc::{GlobalAlloc, Layout};
 use core::ptr::NonNull;
+use core::sync::atomic::{AtomicU32, Ordering};
+use lazy_init::LazyInit;
 use spinlock::SpinNoIrq;
 
 const PAGE_SIZE: usize = 0x1000;
@@ -32,6 +34,14 @@ const MIN_HEAP_SIZE: usize = 0x8000; // 32 K
 
 pub use page::GlobalPage;
 
+/// A function called when the page allocator runs out of memory, which tries
+/// to release at least `num_pages` pages, such as the clean pages of the page
+/// cache. Returns the number of pages released.
+///
+/// It must not allocate memory, and should not wait for the locks which may
+/// be held by the allocating task.
+pub type ReclaimFn = fn(num_pages: usize) -> usize;
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "slab")] {
         use allocator::SlabByteAllocator as DefaultByteAllocator;
@@ -42,6 +52,21 @@ cfg_if::cfg_if! {
     }
 }
 
+/// Reference counts of the pages in the page allocator.
+struct PageRefs {
+    /// Start address of the region of the page allocator.
+    base: usize,
+    /// Number of owners of each page minus one, so that a page allocated by
+    /// [`GlobalAllocator::alloc_pages`] counts zero.
+    counts: &'static [AtomicU32],
+}
+
+impl PageRefs {
+    fn count(&self, pos: usize) -> &AtomicU32 {
+        &self.counts[(pos - self.base) / PAGE_SIZE]
+    }
+}
+
 /// The global allocator used by Ruxos.
 ///
 /// It combines a [`ByteAllocator`] and a [`PageAllocator`] into a simple
@@ -56,6 +81,12 @@ cfg_if::cfg_if! {
 pub struct GlobalAllocator {
     balloc: SpinNoIrq<DefaultByteAllocator>,
     palloc: SpinNoIrq<BitmapPageAllocator<PAGE_SIZE>>,
+    /// Reference counts of pages shared by more than one owner, such as
+    /// Copy-on-Write pages after `fork`. Counted per page rather than in a
+    /// map, so that it neither allocates memory nor takes a lock, which may
+    /// be needed by the reclaimer.
+    page_refs: LazyInit<PageRefs>,
+    reclaim: SpinNoIrq<Option<ReclaimFn>>,
 }
 
 impl Default for GlobalAllocator {
@@ -70,6 +101,8 @@ impl GlobalAllocator {
         Self {
             balloc: SpinNoIrq::new(DefaultByteAllocator::new()),
             palloc: SpinNoIrq::new(BitmapPageAllocator::new()),
+            page_refs: LazyInit::new(),
+            reclaim: SpinNoIrq::new(None),
         }
     }
 
@@ -89,12 +122,24 @@ impl GlobalAllocator {
     /// Initializes the allocator with the given region.
     ///
     /// It firstly adds the whole region to the page allocator, then allocates
-    /// a small region (32 KB) to initialize the byte allocator. Therefore,
-    /// the given region must be larger than 32 KB.
+    /// the reference counts of its pages, and a small region (32 KB) to
+    /// initialize the byte allocator. Therefore, the given region must be
+    /// larger than 32 KB.
     pub fn init(&self, start_vaddr: usize, size: usize) {
         assert!(size > MIN_HEAP_SIZE);
         let init_heap_size = MIN_HEAP_SIZE;
         self.palloc.lock().init(start_vaddr, size);
+        let base = memory_addr::align_up_4k(start_vaddr);
+        let num_pages = (memory_addr::align_down_4k(start_vaddr + size) - base) / PAGE_SIZE;
+        let refs_size = num_pages * core::mem::size_of::<AtomicU32>();
+        let refs_ptr = self
+            .alloc_pages(refs_size.div_ceil(PAGE_SIZE), PAGE_SIZE)
+            .unwrap();
+        let counts = unsafe {
+            core::ptr::write_bytes(refs_ptr as *mut u8, 0, refs_size);
+            core::slice::from_raw_parts(refs_ptr as *const AtomicU32, num_pages)
+        };
+        self.page_refs.init_by(PageRefs { base, counts });
         let heap_ptr = self
             .alloc_pages(init_heap_size / PAGE_SIZE, PAGE_SIZE)
             .unwrap();
@@ -129,7 +174,20 @@ impl GlobalAllocator {
                     .max(layout.size())
                     .next_power_of_two()
                     .max(PAGE_SIZE);
-                let heap_ptr = self.alloc_pages(expand_size / PAGE_SIZE, PAGE_SIZE)?;
+                let num_pages = expand_size / PAGE_SIZE;
+                let res = self.palloc.lock().alloc_pages(num_pages, PAGE_SIZE);
+                let heap_ptr = match res {
+                    Ok(ptr) => ptr,
+                    Err(e) => {
+                        // the reclaimer may free memory to both allocators
+                        drop(balloc);
+                        if self.reclaim(num_pages) == 0 {
+                            return Err(e);
+                        }
+                        balloc = self.balloc.lock();
+                        continue;
+                    }
+                };
                 debug!(
                     "expand heap memory: [{:#x}, {:#x})",
                     heap_ptr,
@@ -157,8 +215,17 @@ impl GlobalAllocator {
     ///
     /// `align_pow2` must be a power of 2, and the returned region bound will be
     /// aligned to it.
+    ///
+    /// If there is no memory, it asks the reclaimer set by [`set_reclaim`]
+    /// to release some pages and tries again.
+    ///
+    /// [`set_reclaim`]: GlobalAllocator::set_reclaim
     pub fn alloc_pages(&self, num_pages: usize, align_pow2: usize) -> AllocResult<usize> {
-        self.palloc.lock().alloc_pages(num_pages, align_pow2)
+        let res = self.palloc.lock().alloc_pages(num_pages, align_pow2);
+        if res.is_err() && self.reclaim(num_pages) > 0 {
+            return self.palloc.lock().alloc_pages(num_pages, align_pow2);
+        }
+        res
     }
 
     /// Gives back the allocated pages starts from `pos` to the page allocator.
@@ -172,6 +239,52 @@ impl GlobalAllocator {
         self.palloc.lock().dealloc_pages(pos, num_pages)
     }
 
+    /// Increases the reference count of the page starts from `pos`.
+    ///
+    /// A page allocated by [`alloc_pages`] has one reference. After sharing it
+    /// with this function, it should be released by [`page_ref_dec`] instead
+    /// of [`dealloc_pages`].
+    ///
+    /// [`alloc_pages`]: GlobalAllocator::alloc_pages
+    /// [`page_ref_dec`]: GlobalAllocator::page_ref_dec
+    /// [`dealloc_pages`]: GlobalAllocator::dealloc_pages
+    pub fn page_ref_inc(&self, pos: usize) {
+        self.page_refs.count(pos).fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Decreases the reference count of the page starts from `pos`, the page
+    /// is given back to the page allocator when the last reference is dropped.
+    pub fn page_ref_dec(&self, pos: usize) {
+        let last = self
+            .page_refs
+            .count(pos)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_err();
+        if last {
+            self.dealloc_pages(pos, 1);
+        }
+    }
+
+    /// Returns the reference count of the page starts from `pos`.
+    pub fn page_ref_count(&self, pos: usize) -> usize {
+        self.page_refs.count(pos).load(Ordering::Acquire) as usize + 1
+    }
+
+    /// Sets the function to release memory when the page allocator runs out
+    /// of memory.
+    pub fn set_reclaim(&self, f: ReclaimFn) {
+        *self.reclaim.lock() = Some(f);
+    }
+
+    /// Asks the reclaimer set by [`set_reclaim`] to release at least
+    /// `num_pages` pages. Returns the number of pages released.
+    ///
+    /// [`set_reclaim`]: GlobalAllocator::set_reclaim
+    pub fn reclaim(&self, num_pages: usize) -> usize {
+        let reclaim = *self.reclaim.lock();
+        reclaim.map_or(0, |f| f(num_pages))
+    }
+
     /// Returns the number of allocated bytes in the byte allocator.
     pub fn used_bytes(&self) -> usize {
         self.balloc.lock().used_bytes()
@@ -10,6 +10,7 @@
 #![allow(dead_code)]
 
 use alloc::{string::String, string::ToString, sync::Arc, vec, vec::Vec};
+use core::time::Duration;
 use log::*;
 use ruxdriver::prelude::*;
 use spin::RwLock;
@@ -932,6 +933,48 @@ impl FileAttr {
     pub fn get_blk_num(&self) -> u64 {
         self.n_blk
     }
+
+    pub fn get_uid(&self) -> u32 {
+        self.uid
+    }
+
+    pub fn set_uid(&mut self, uid: u32) {
+        self.vaild |= _9P_SETATTR_UID;
+        self.uid = uid;
+    }
+
+    pub fn get_gid(&self) -> u32 {
+        self.gid
+    }
+
+    pub fn set_gid(&mut self, gid: u32) {
+        self.vaild |= _9P_SETATTR_GID;
+        self.gid = gid;
+    }
+
+    pub fn get_atime(&self) -> Duration {
+        Duration::new(self.atime_sec, self.atime_ns as u32)
+    }
+
+    pub fn set_atime(&mut self, atime: Duration) {
+        self.vaild |= _9P_SETATTR_ATIME | _9P_SETATTR_ATIME_SET;
+        self.atime_sec = atime.as_secs();
+        self.atime_ns = atime.subsec_nanos() as u64;
+    }
+
+    pub fn get_mtime(&self) -> Duration {
+        Duration::new(self.mtime_sec, self.mtime_ns as u32)
+    }
+
+    pub fn set_mtime(&mut self, mtime: Duration) {
+        self.vaild |= _9P_SETATTR_MTIME | _9P_SETATTR_MTIME_SET;
+        self.mtime_sec = mtime.as_secs();
+        self.mtime_ns = mtime.subsec_nanos() as u64;
+    }
+
+    pub fn get_ctime(&self) -> Duration {
+        Duration::new(self.ctime_sec, self.ctime_ns as u32)
+    }
 }
 
 pub struct LStatFs {
@@ -1043,6 +1086,38 @@ impl UStatFs {
         self.mode = perm;
     }
 
+    pub fn get_uid(&self) -> u32 {
+        self.n_uid
+    }
+
+    pub fn set_uid(&mut self, uid: u32) {
+        self.n_uid = uid;
+    }
+
+    pub fn get_gid(&self) -> u32 {
+        self.n_gid
+    }
+
+    pub fn set_gid(&mut self, gid: u32) {
+        self.n_gid = gid;
+    }
+
+    pub fn get_atime(&self) -> Duration {
+        Duration::from_secs(self.atime as u64)
+    }
+
+    pub fn set_atime(&mut self, atime: Duration) {
+        self.atime = atime.as_secs() as u32;
+    }
+
+    pub fn get_mtime(&self) -> Duration {
+        Duration::from_secs(self.mtime as u64)
+    }
+
+    pub fn set_mtime(&mut self, mtime: Duration) {
+        self.mtime = mtime.as_secs() as u32;
+    }
+
     pub fn get_ftype(&self) -> u8 {
         match self.qid.ftype {
             0x00 => 0o10,
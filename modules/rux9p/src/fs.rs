@@ -16,6 +16,7 @@ use axfs_vfs::{
     RelPath, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
     VfsOps, VfsResult,
 };
+use core::time::Duration;
 use log::*;
 use spin::{once::Once, RwLock};
 
@@ -137,24 +138,9 @@ impl CommonNode {
                 dev.write().topen(fid, O_RDONLY),
                 "9pfs topen failed! error code: {}"
             ),
-            Err(ELOOP) if *protocol == "9P2000.L" => {
-                let try_readlink = dev.write().treadlink(fid);
-                if let Ok(path) = try_readlink {
-                    debug!("read link path ==> {path:}");
-                    let mut splited: Vec<&str> = path
-                        .split('/')
-                        .filter(|&x| !x.is_empty() && (x != "."))
-                        .collect();
-                    splited.insert(0, "..");
-                    let try_walk = dev.write().twalk(fid, fid, splited.len() as u16, &splited);
-                    match try_walk {
-                        Ok(_) => return Self::new(fid, parent, dev, protocol),
-                        Err(ecode) => error!("9pfs twalk failed! error code: {ecode}"),
-                    }
-                } else {
-                    error!("9pfs treadlink failed! error code: {try_readlink:?}");
-                }
-            }
+            // a symbolic link can not be opened, the node is the link itself
+            // and the link is followed by the VFS.
+            Err(ELOOP) => debug!("9pfs fid {fid} is a symbolic link"),
             Err(ecode) => error!("9pfs topen failed! error code: {ecode}"),
             _ => {}
         };
@@ -412,15 +398,19 @@ impl VfsNodeOps for CommonNode {
                     let ty = match stat.get_ftype() {
                         0o4 => VfsNodeType::Dir,
                         0o10 => VfsNodeType::File,
+                        0o12 => VfsNodeType::SymLink,
                         _ => return Err(VfsError::BadState),
                     };
-                    Ok(VfsNodeAttr::new(
+                    let mut attr = VfsNodeAttr::new(
                         stat.get_qid().path(),
                         VfsNodePerm::from_bits_truncate(stat.get_perm() as u16 & 0o777),
                         ty,
                         stat.get_size(),
                         stat.get_blk_num(),
-                    ))
+                    );
+                    attr.set_owner(stat.get_uid(), stat.get_gid());
+                    attr.set_times(stat.get_atime(), stat.get_mtime(), stat.get_ctime());
+                    Ok(attr)
                 } else {
                     Err(VfsError::BadState)
                 }
@@ -431,15 +421,20 @@ impl VfsNodeOps for CommonNode {
                     let ty = match stat.get_ftype() {
                         0o4 => VfsNodeType::Dir,
                         0o10 => VfsNodeType::File,
+                        0o12 => VfsNodeType::SymLink,
                         _ => return Err(VfsError::BadState),
                     };
-                    Ok(VfsNodeAttr::new(
+                    let mut attr = VfsNodeAttr::new(
                         stat.get_qid().path(),
                         VfsNodePerm::from_bits_truncate(stat.get_perm() as u16 & 0o777),
                         ty,
                         stat.get_length(),
                         stat.get_blk_num(),
-                    ))
+                    );
+                    // 9P2000.u has no change time
+                    attr.set_owner(stat.get_uid(), stat.get_gid());
+                    attr.set_times(stat.get_atime(), stat.get_mtime(), stat.get_mtime());
+                    Ok(attr)
                 } else {
                     Err(VfsError::BadState)
                 }
@@ -475,6 +470,74 @@ impl VfsNodeOps for CommonNode {
         Ok(())
     }
 
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        debug!("9pfs set owner, uid:{uid:?}, gid:{gid:?}");
+        let mut dev = self.inner.write();
+        match self.protocol.as_str() {
+            "9P2000.L" => {
+                let mut attr = drv::FileAttr::new();
+                if let Some(uid) = uid {
+                    attr.set_uid(uid);
+                }
+                if let Some(gid) = gid {
+                    attr.set_gid(gid);
+                }
+                dev.tsetattr(*self.fid, attr)
+                    .map_err(|_| VfsError::BadState)?;
+            }
+            "9P2000.u" => {
+                let mut stat = dev.tstat(*self.fid).map_err(|_| VfsError::BadState)?;
+                if let Some(uid) = uid {
+                    stat.set_uid(uid);
+                }
+                if let Some(gid) = gid {
+                    stat.set_gid(gid);
+                }
+                dev.twstat(*self.fid, stat)
+                    .map_err(|_| VfsError::BadState)?;
+            }
+            _ => {
+                error!("{} is not supported", self.protocol);
+                return Err(VfsError::Unsupported);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        debug!("9pfs set times, atime:{atime:?}, mtime:{mtime:?}");
+        let mut dev = self.inner.write();
+        match self.protocol.as_str() {
+            "9P2000.L" => {
+                let mut attr = drv::FileAttr::new();
+                if let Some(atime) = atime {
+                    attr.set_atime(atime);
+                }
+                if let Some(mtime) = mtime {
+                    attr.set_mtime(mtime);
+                }
+                dev.tsetattr(*self.fid, attr)
+                    .map_err(|_| VfsError::BadState)?;
+            }
+            "9P2000.u" => {
+                let mut stat = dev.tstat(*self.fid).map_err(|_| VfsError::BadState)?;
+                if let Some(atime) = atime {
+                    stat.set_atime(atime);
+                }
+                if let Some(mtime) = mtime {
+                    stat.set_mtime(mtime);
+                }
+                dev.twstat(*self.fid, stat)
+                    .map_err(|_| VfsError::BadState)?;
+            }
+            _ => {
+                error!("{} is not supported", self.protocol);
+                return Err(VfsError::Unsupported);
+            }
+        }
+        Ok(())
+    }
+
     fn parent(&self) -> Option<VfsNodeRef> {
         self.parent.read().upgrade()
     }
@@ -551,6 +614,28 @@ impl VfsNodeOps for CommonNode {
         }
     }
 
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        debug!("symlink at 9pfs: {path} -> {target}");
+        let (name, rest) = split_path(path);
+        if let Some(rpath) = rest {
+            return self.try_get(&RelPath::new(name))?.symlink(&rpath, target);
+        }
+        if *self.protocol != "9P2000.L" {
+            error!("9pfs symlink is not supported by {}", self.protocol);
+            return Err(VfsError::Unsupported);
+        }
+        if self.exist(&RelPath::new(name)) {
+            return Err(VfsError::AlreadyExists);
+        }
+        self.inner
+            .write()
+            .tsymlink(*self.fid, name, target, 500)
+            .map_err(|ecode| {
+                error!("9pfs tsymlink failed! error code: {ecode}");
+                VfsError::BadState
+            })
+    }
+
     fn unlink(&self, path: &RelPath) -> VfsResult {
         debug!("unlink at 9pfs: {path}");
         match split_path(path) {
@@ -637,6 +722,21 @@ impl VfsNodeOps for CommonNode {
         Ok(buf.len())
     }
 
+    /// Read the target of the symbolic link.
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        if *self.protocol != "9P2000.L" {
+            error!("9pfs readlink is not supported by {}", self.protocol);
+            return Err(VfsError::Unsupported);
+        }
+        let target = self.inner.write().treadlink(*self.fid).map_err(|ecode| {
+            debug!("9pfs treadlink failed! error code: {ecode}");
+            VfsError::InvalidInput
+        })?;
+        let len = target.len().min(buf.len());
+        buf[..len].copy_from_slice(&target.as_bytes()[..len]);
+        Ok(len)
+    }
+
     /// Flush the file, synchronize the data to disk.
     fn fsync(&self) -> VfsResult {
         let mut dev = self.inner.write();
@@ -55,7 +55,7 @@ pub fn init_virtio_9pfs(
     let v9p_driver = self::drv::Drv9pOps::new(v9p);
     let v9p_fs = self::fs::_9pFileSystem::new(Arc::new(RwLock::new(v9p_driver)), aname, protocol);
 
-    MountPoint::new(String::from("/v9fs"), Arc::new(v9p_fs))
+    MountPoint::new(String::from("/v9fs"), Arc::new(v9p_fs)).with_source(aname, "9p")
 }
 
 #[cfg(feature = "net-9p")]
@@ -74,7 +74,7 @@ pub fn init_net_9pfs(ip_port: &str, aname: &str, protocol: &str) -> MountPoint {
     let net9p_driver = self::drv::Drv9pOps::new(Box::new(net9p));
     let n9p_fs = self::fs::_9pFileSystem::new(Arc::new(RwLock::new(net9p_driver)), aname, protocol);
 
-    MountPoint::new(String::from("/n9fs"), Arc::new(n9p_fs))
+    MountPoint::new(String::from("/n9fs"), Arc::new(n9p_fs)).with_source(ip_port, "9p")
 }
 
 #[cfg(feature = "net-9p")]
@@ -12,6 +12,18 @@ use crate::{prelude::*, AllDevices};
 #[cfg(all(feature = "virtio_console", feature = "virtio"))]
 use ruxhal::virtio::virtio_console;
 
+/// Returns the IRQ of the VirtIO MMIO device at `mmio_base`, as the regions
+/// are wired to the IRQs from `VIRTIO_MMIO_IRQ_BASE` in the order of their
+/// addresses, or `None` if they are not wired.
+#[cfg(feature = "virtio")]
+pub(crate) fn mmio_irq_num(mmio_base: usize) -> Option<usize> {
+    let (first_base, size) = *ruxconfig::VIRTIO_MMIO_REGIONS.first()?;
+    if ruxconfig::VIRTIO_MMIO_IRQ_BASE == 0 || mmio_base < first_base {
+        return None;
+    }
+    Some(ruxconfig::VIRTIO_MMIO_IRQ_BASE + (mmio_base - first_base) / size)
+}
+
 impl AllDevices {
     pub(crate) fn probe_bus_devices(&mut self) {
         // TODO: parse device tree
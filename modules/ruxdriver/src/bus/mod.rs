@@ -11,3 +11,8 @@
 mod mmio;
 #[cfg(bus = "pci")]
 mod pci;
+
+#[cfg(all(bus = "mmio", feature = "virtio"))]
+pub(crate) use self::mmio::mmio_irq_num;
+#[cfg(bus = "pci")]
+pub(crate) use self::pci::pci_irq_num;
@@ -94,6 +94,25 @@ fn config_pci_device(
     Ok(())
 }
 
+/// Returns the IRQ of the legacy interrupt (INTx) of the PCI device, or
+/// `None` if the interrupts are not wired.
+///
+/// The 4 interrupt pins are rotated by the slot among the IRQs from
+/// `PCI_IRQ_BASE`, as the host bridge of QEMU does for the devices on bus 0.
+pub(crate) fn pci_irq_num(bdf: DeviceFunction) -> Option<usize> {
+    if ruxconfig::PCI_IRQ_BASE == 0 {
+        return None;
+    }
+    let base_vaddr = phys_to_virt(ruxconfig::PCI_ECAM_BASE.into());
+    let cam = unsafe { MmioCam::new(base_vaddr.as_mut_ptr(), Cam::Ecam) };
+    // the interrupt pin is in bits 8..16 of the register at 0x3c, 1 for INTA#
+    let pin = (cam.read_word(bdf, 0x3c) >> 8) & 0xff;
+    if pin == 0 || pin > 4 {
+        return None;
+    }
+    Some(ruxconfig::PCI_IRQ_BASE + (bdf.device as usize + pin as usize - 1) % 4)
+}
+
 impl AllDevices {
     pub(crate) fn probe_bus_devices(&mut self) {
         let base_vaddr = phys_to_virt(ruxconfig::PCI_ECAM_BASE.into());
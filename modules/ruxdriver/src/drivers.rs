@@ -144,7 +144,8 @@ cfg_if::cfg_if! {
                             } => {
                                 let ixgbe_nic = IxgbeNic::<IxgbeHalImpl, QS, QN>::init(
                                     phys_to_virt((address as usize).into()).into(),
-                                    size as usize
+                                    size as usize,
+                                    crate::bus::pci_irq_num(bdf),
                                 )
                                 .expect("failed to initialize ixgbe device");
                                 return Some(AxDeviceEnum::from_net(ixgbe_nic));
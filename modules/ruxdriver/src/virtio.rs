@@ -43,7 +43,12 @@ pub trait VirtIoDevMeta {
     type Driver = VirtIoDriver<Self>;
 
     /// Try to create a new instance of the VirtIO device.Z
-    fn try_new(transport: VirtIoTransport<'static>) -> DevResult<AxDeviceEnum>;
+    ///
+    /// `irq_num` is the IRQ of the device, or `None` if it is not wired.
+    fn try_new(
+        transport: VirtIoTransport<'static>,
+        irq_num: Option<usize>,
+    ) -> DevResult<AxDeviceEnum>;
 }
 
 cfg_if! {
@@ -55,8 +60,11 @@ cfg_if! {
             const DEVICE_TYPE: DeviceType = DeviceType::Net;
             type Device<'a> = driver_virtio::VirtIoNetDev<VirtIoHalImpl, VirtIoTransport<'static>, 64>;
 
-            fn try_new(transport: VirtIoTransport<'static>) -> DevResult<AxDeviceEnum> {
-                Ok(AxDeviceEnum::from_net(Self::Device::try_new(transport)?))
+            fn try_new(
+                transport: VirtIoTransport<'static>,
+                irq_num: Option<usize>,
+            ) -> DevResult<AxDeviceEnum> {
+                Ok(AxDeviceEnum::from_net(Self::Device::try_new(transport, irq_num)?))
             }
         }
     }
@@ -71,7 +79,10 @@ cfg_if! {
             const DEVICE_TYPE: DeviceType = DeviceType::Block;
             type Device<'a> = driver_virtio::VirtIoBlkDev<VirtIoHalImpl, VirtIoTransport<'static>>;
 
-            fn try_new(transport: VirtIoTransport<'static>) -> DevResult<AxDeviceEnum> {
+            fn try_new(
+                transport: VirtIoTransport<'static>,
+                _irq_num: Option<usize>,
+            ) -> DevResult<AxDeviceEnum> {
                 Ok(AxDeviceEnum::from_block(Self::Device::try_new(transport)?))
             }
         }
@@ -87,7 +98,10 @@ cfg_if! {
             const DEVICE_TYPE: DeviceType = DeviceType::Display;
             type Device<'a> = driver_virtio::VirtIoGpuDev<VirtIoHalImpl, VirtIoTransport<'static>>;
 
-            fn try_new(transport: VirtIoTransport<'static>) -> DevResult<AxDeviceEnum> {
+            fn try_new(
+                transport: VirtIoTransport<'static>,
+                _irq_num: Option<usize>,
+            ) -> DevResult<AxDeviceEnum> {
                 Ok(AxDeviceEnum::from_display(Self::Device::try_new(transport)?))
             }
         }
@@ -103,7 +117,10 @@ cfg_if! {
             const DEVICE_TYPE: DeviceType = DeviceType::_9P;
             type Device<'a> = driver_virtio::VirtIo9pDev<VirtIoHalImpl, VirtIoTransport<'static>>;
 
-            fn try_new(transport: VirtIoTransport<'static>) -> DevResult<AxDeviceEnum> {
+            fn try_new(
+                transport: VirtIoTransport<'static>,
+                _irq_num: Option<usize>,
+            ) -> DevResult<AxDeviceEnum> {
                 Ok(AxDeviceEnum::from_9p(Self::Device::try_new(transport)?))
             }
         }
@@ -121,7 +138,7 @@ impl<D: VirtIoDevMeta> DriverProbe for VirtIoDriver<D> {
             driver_virtio::probe_mmio_device(base_vaddr.as_mut_ptr(), mmio_size)
         {
             if ty == D::DEVICE_TYPE {
-                match D::try_new(transport) {
+                match D::try_new(transport, crate::bus::mmio_irq_num(mmio_base)) {
                     Ok(dev) => return Some(dev),
                     Err(e) => {
                         warn!(
@@ -159,7 +176,7 @@ impl<D: VirtIoDevMeta> DriverProbe for VirtIoDriver<D> {
             driver_virtio::probe_pci_device::<VirtIoHalImpl, MmioCam>(root, bdf, dev_info)
         {
             if ty == D::DEVICE_TYPE {
-                match D::try_new(transport) {
+                match D::try_new(transport, crate::bus::pci_irq_num(bdf)) {
                     Ok(dev) => return Some(dev),
                     Err(e) => {
                         warn!("failed to initialize PCI device at {bdf}({dev_info}): {e:?}");
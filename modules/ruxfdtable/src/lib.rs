@@ -18,11 +18,12 @@ use bitmaps::Bitmap;
 use capability::Cap;
 use core::marker::Send;
 use core::marker::Sync;
+use core::time::Duration;
 use flatten_objects::FlattenObjects;
 
 use axerrno::LinuxResult;
 use axfs_vfs::AbsPath;
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 
 /// Maximum number of files per process
 pub const RUX_FILE_LIMIT: usize = 1024;
@@ -70,6 +71,12 @@ impl FdTable {
         self.files.get(fd)
     }
 
+    /// Returns an iterator over the opened file descriptors and their file
+    /// objects, in ascending order of the file descriptors.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Arc<dyn FileLike>)> {
+        (0..self.files.capacity()).filter_map(|fd| self.files.get(fd).map(|f| (fd, f)))
+    }
+
     /// Adds a new file object to the table and associates it with a file descriptor.
     ///
     /// Also sets the `FD_CLOEXEC` flag for the file descriptor based on the `flags` argument.
@@ -177,6 +184,15 @@ pub struct RuxTimeSpec {
     pub tv_nsec: core::ffi::c_long,
 }
 
+impl From<Duration> for RuxTimeSpec {
+    fn from(time: Duration) -> Self {
+        Self {
+            tv_sec: time.as_secs() as _,
+            tv_nsec: time.subsec_nanos() as _,
+        }
+    }
+}
+
 ///Rust version for struct stat in ctypes. Represents file status information.
 #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
 #[derive(Default)]
@@ -257,28 +273,19 @@ impl From<VfsNodeAttr> for RuxStat {
         Self {
             st_dev: 0,
             st_ino: attr.ino(),
-            st_nlink: 1,
+            st_nlink: attr.nlink() as _,
             st_mode: ((attr.file_type() as u32) << 12) | attr.perm().bits() as u32,
-            st_uid: 1000,
-            st_gid: 1000,
+            st_uid: attr.uid(),
+            st_gid: attr.gid(),
             st_rdev: 0,
             __pad: 0,
             st_size: attr.size() as _,
             st_blksize: 512,
             __pad2: 0,
             st_blocks: attr.blocks() as _,
-            st_atime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
-            st_mtime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
-            st_ctime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
+            st_atime: attr.atime().into(),
+            st_mtime: attr.mtime().into(),
+            st_ctime: attr.ctime().into(),
             __unused: [0; 2],
         }
     }
@@ -290,27 +297,18 @@ impl From<VfsNodeAttr> for RuxStat {
         Self {
             st_dev: 0,
             st_ino: attr.ino(),
-            st_nlink: 1,
+            st_nlink: attr.nlink() as _,
             st_mode: ((attr.file_type() as u32) << 12) | attr.perm().bits() as u32,
-            st_uid: 1000,
-            st_gid: 1000,
+            st_uid: attr.uid(),
+            st_gid: attr.gid(),
             __pad0: 0,
             st_rdev: 0,
             st_size: attr.size() as _,
             st_blksize: 512,
             st_blocks: attr.blocks() as _,
-            st_atime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
-            st_mtime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
-            st_ctime: RuxTimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            },
+            st_atime: attr.atime().into(),
+            st_mtime: attr.mtime().into(),
+            st_ctime: attr.ctime().into(),
             __unused: [0; 3],
         }
     }
@@ -343,6 +341,13 @@ pub trait FileLike: Send + Sync {
     /// Polls the file-like object for readiness events.
     fn poll(&self) -> LinuxResult<PollState>;
 
+    /// Returns the waiters notified whenever the result of [`FileLike::poll`]
+    /// may have changed, or [`None`] if the object has to be polled to find
+    /// out about changes.
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        None
+    }
+
     /// Sets the flags such as `nonblocking` for the file-like object.
     /// Only File Status Flags can be changed once a file is opened. Other flags will be ignored.
     fn set_flags(&self, _flags: OpenFlags) -> LinuxResult;
@@ -21,17 +21,17 @@ use axfs_vfs::{VfsError, VfsNodePerm, VfsNodeType};
 use axio::{self as io, prelude::*, Error, Result};
 /// Opens a regular file at given path. Fails if path points to a directory.
 pub fn open_file(path: &AbsPath, flags: OpenFlags) -> Result<File> {
-    let node = fops::open_abspath(path, flags, VfsNodePerm::default_file())?;
+    let (real_path, node) = fops::open_abspath(path, flags, VfsNodePerm::default_file())?;
     if node.get_attr()?.is_dir() {
         Err(Error::IsADirectory)
     } else {
-        Ok(File::new(path.to_owned(), node, flags))
+        Ok(File::new_cached(path.to_owned(), &real_path, node, flags))
     }
 }
 
 /// Opens a directory at given path. Fails if path points to non-directory.
 pub fn open_dir(path: &AbsPath, flags: OpenFlags) -> Result<Directory> {
-    let node = fops::open_abspath(path, flags, VfsNodePerm::default_dir())?;
+    let (_, node) = fops::open_abspath(path, flags, VfsNodePerm::default_dir())?;
     if node.get_attr()?.is_dir() {
         Ok(Directory::new(path.to_owned(), node, flags))
     } else {
@@ -88,7 +88,7 @@ pub fn write<C: AsRef<[u8]>>(path: &AbsPath, contents: C) -> io::Result<()> {
 
 /// Creates a new, empty directory at the provided path.
 pub fn create_dir(path: &AbsPath) -> io::Result<()> {
-    match fops::lookup(path) {
+    match fops::lookup_nofollow(path) {
         Ok(_) => return ax_err!(AlreadyExists),
         Err(VfsError::NotFound) => {}
         Err(e) => return ax_err!(e),
@@ -109,7 +109,7 @@ pub fn create_dir_all(path: &AbsPath) -> io::Result<()> {
 
 /// Removes an empty directory.
 pub fn remove_dir(path: &AbsPath) -> io::Result<()> {
-    let node = fops::lookup(path)?;
+    let node = fops::lookup_nofollow(path)?;
     let attr = node.get_attr()?;
     if !attr.is_dir() {
         return ax_err!(NotADirectory);
@@ -128,7 +128,7 @@ pub fn remove_dir(path: &AbsPath) -> io::Result<()> {
 
 /// Removes a file from the filesystem.
 pub fn remove_file(path: &AbsPath) -> io::Result<()> {
-    let node = fops::lookup(path)?;
+    let node = fops::lookup_nofollow(path)?;
     let attr = node.get_attr()?;
     if attr.is_dir() {
         return ax_err!(IsADirectory);
@@ -144,8 +144,8 @@ pub fn remove_file(path: &AbsPath) -> io::Result<()> {
 ///
 /// This only works then the new path is in the same mounted fs.
 pub fn rename(old: &AbsPath, new: &AbsPath) -> io::Result<()> {
-    fops::lookup(old)?;
-    match fops::lookup(new) {
+    fops::lookup_nofollow(old)?;
+    match fops::lookup_nofollow(new) {
         Ok(_) => ax_err!(AlreadyExists),
         Err(VfsError::NotFound) => fops::rename(old, new),
         Err(e) => ax_err!(e),
@@ -28,20 +28,32 @@ pub fn get_cpuinfo() -> String {
     }
 }
 
+/// Returns the content of `/proc/meminfo`, from the usage of the global
+/// allocator. The sizes are in kB.
 pub fn get_meminfo() -> String {
     #[cfg(feature = "alloc")]
     {
-        use core::ffi::c_ulong;
+        use memory_addr::PAGE_SIZE_4K;
         let allocator = axalloc::global_allocator();
-        let freeram = (allocator.available_bytes()
-            + allocator.available_pages() * memory_addr::PAGE_SIZE_4K)
-            as c_ulong;
-        let totalram = freeram + allocator.used_bytes() as c_ulong;
+        let free = allocator.available_bytes() + allocator.available_pages() * PAGE_SIZE_4K;
+        // the heap of the byte allocator is allocated from the page allocator
+        let total = (allocator.used_pages() + allocator.available_pages()) * PAGE_SIZE_4K;
+        // clean pages of the page cache can be evicted when out of memory
+        let cached = crate::page_cache::cached_pages() * PAGE_SIZE_4K;
 
         let mut meminfo = String::new();
-        meminfo.push_str(format!("MemTotal:       {totalram:8}\n").as_ref());
-        meminfo.push_str(format!("MemFree:        {freeram:8}\n").as_ref());
-
+        for (key, bytes) in [
+            ("MemTotal", total),
+            ("MemFree", free),
+            ("MemAvailable", free + cached),
+            ("Buffers", 0),
+            ("Cached", cached),
+            ("SwapTotal", 0),
+            ("SwapFree", 0),
+        ] {
+            let key = format!("{key}:");
+            meminfo.push_str(&format!("{key:<16}{:>8} kB\n", bytes / 1024));
+        }
         meminfo
     }
     #[cfg(not(feature = "alloc"))]
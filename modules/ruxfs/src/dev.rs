@@ -8,107 +8,355 @@
  */
 
 //! Block device abstraction.
+//!
+//! The data of a disk are cached in buffers of [`BUFFER_SIZE`] bytes. Missing
+//! buffers are read with multi-block requests, and the following buffers are
+//! read ahead on sequential reads. Modified buffers are written back when
+//! they are evicted, the disk is flushed or dropped, or [`sync_all`] is called.
+//!
+//! The disks and partitions are registered by name with [`register`], to be
+//! opened by [`open`] when mounted at runtime, and to be accessed as the
+//! block device files in `/dev` by [`BlockDevNode`].
 
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::{Arc, Weak};
+use alloc::vec;
+use alloc::vec::Vec;
+use axfs_vfs::{VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType, VfsResult};
 use ruxdriver::prelude::*;
+use spin::Mutex;
 
-const BLOCK_SIZE: usize = 512;
+use crate::partition::Partition;
 
-/// A disk device with a cursor.
-pub struct Disk {
-    block_id: u64,
-    offset: usize,
+pub(crate) const BLOCK_SIZE: usize = 512;
+
+/// Size of the buffers in the block cache.
+pub const BUFFER_SIZE: usize = 4096;
+
+const BLOCKS_PER_BUFFER: u64 = (BUFFER_SIZE / BLOCK_SIZE) as u64;
+
+/// The maximum number of buffers cached for each disk.
+const CACHE_CAPACITY: usize = 256;
+
+/// The number of buffers read ahead on sequential reads.
+pub(crate) const READ_AHEAD: u64 = 8;
+
+/// The maximum number of buffers in one request.
+pub(crate) const MAX_REQUEST: u64 = 32;
+
+/// Block caches of all disks, to be written back by [`sync_all`].
+static CACHES: Mutex<Vec<Weak<Mutex<BlockCache>>>> = Mutex::new(Vec::new());
+
+/// Disks and partitions registered by name.
+static DISKS: Mutex<BTreeMap<String, Disk>> = Mutex::new(BTreeMap::new());
+
+struct Buffer {
+    data: Box<[u8]>,
+    dirty: bool,
+    /// Time of the last access, to find the least recently used one.
+    accessed: u64,
+}
+
+/// Cached buffers of a block device.
+struct BlockCache {
     dev: AxBlockDevice,
+    buffers: BTreeMap<u64, Buffer>,
+    clock: u64,
+    /// The buffer following the last one read from the device, a read of it
+    /// is considered sequential.
+    next_read: u64,
+}
+
+impl BlockCache {
+    fn new(dev: AxBlockDevice) -> Self {
+        Self {
+            dev,
+            buffers: BTreeMap::new(),
+            clock: 0,
+            next_read: u64::MAX,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        self.dev.num_blocks() * BLOCK_SIZE as u64
+    }
+
+    fn num_buffers(&self) -> u64 {
+        self.dev.num_blocks().div_ceil(BLOCKS_PER_BUFFER)
+    }
+
+    /// Size of the buffer `idx`, the last one may be smaller than
+    /// [`BUFFER_SIZE`].
+    fn buffer_len(&self, idx: u64) -> usize {
+        (self.size() - idx * BUFFER_SIZE as u64).min(BUFFER_SIZE as u64) as usize
+    }
+
+    /// Writes back the buffers in `[start, end)`, which are all dirty, in one
+    /// request.
+    fn write_back(&mut self, start: u64, end: u64) -> DevResult {
+        let mut data = Vec::with_capacity((end - start) as usize * BUFFER_SIZE);
+        for idx in start..end {
+            data.extend_from_slice(&self.buffers[&idx].data);
+        }
+        self.dev.write_blocks(start * BLOCKS_PER_BUFFER, &data)?;
+        for idx in start..end {
+            self.buffers.get_mut(&idx).unwrap().dirty = false;
+        }
+        Ok(())
+    }
+
+    /// Evicts the least recently used buffers until `count` new buffers can
+    /// be cached.
+    fn make_room(&mut self, count: usize) -> DevResult {
+        while !self.buffers.is_empty() && self.buffers.len() + count > CACHE_CAPACITY {
+            let (&idx, _) = self
+                .buffers
+                .iter()
+                .min_by_key(|(_, buffer)| buffer.accessed)
+                .unwrap();
+            if self.buffers[&idx].dirty {
+                self.write_back(idx, idx + 1)?;
+            }
+            self.buffers.remove(&idx);
+        }
+        Ok(())
+    }
+
+    /// Reads the buffers from `idx` in one request, at most `count` buffers
+    /// up to the next one cached.
+    fn load(&mut self, idx: u64, count: u64) -> DevResult {
+        let end = (idx + count.clamp(1, MAX_REQUEST)).min(self.num_buffers());
+        let end = self
+            .buffers
+            .range(idx..end)
+            .next()
+            .map_or(end, |(&cached, _)| cached);
+        let lens = (idx..end).map(|i| self.buffer_len(i)).collect::<Vec<_>>();
+        let mut data = vec![0; lens.iter().sum()];
+        self.dev.read_blocks(idx * BLOCKS_PER_BUFFER, &mut data)?;
+        self.make_room(lens.len())?;
+        let mut pos = 0;
+        for (i, len) in (idx..end).zip(lens) {
+            let buffer = Buffer {
+                data: data[pos..pos + len].into(),
+                dirty: false,
+                accessed: self.clock,
+            };
+            self.buffers.insert(i, buffer);
+            pos += len;
+        }
+        self.next_read = end;
+        Ok(())
+    }
+
+    /// Gets the buffer `idx`, reads it with at most `count - 1` following
+    /// buffers if not cached.
+    ///
+    /// If `fill` is not set, the buffer is going to be overwritten, and it is
+    /// not read from the device.
+    fn buffer(&mut self, idx: u64, count: u64, fill: bool) -> DevResult<&mut Buffer> {
+        if !self.buffers.contains_key(&idx) {
+            if fill {
+                let count = if idx == self.next_read {
+                    count.max(READ_AHEAD)
+                } else {
+                    count
+                };
+                self.load(idx, count)?;
+            } else {
+                self.make_room(1)?;
+                let buffer = Buffer {
+                    data: vec![0; self.buffer_len(idx)].into(),
+                    dirty: false,
+                    accessed: self.clock,
+                };
+                self.buffers.insert(idx, buffer);
+            }
+        }
+        self.clock += 1;
+        let buffer = self.buffers.get_mut(&idx).unwrap();
+        buffer.accessed = self.clock;
+        Ok(buffer)
+    }
+
+    /// Reads the data at `pos`, which must be within the device.
+    fn read(&mut self, pos: u64, buf: &mut [u8]) -> DevResult {
+        let end = pos + buf.len() as u64;
+        let last = end.div_ceil(BUFFER_SIZE as u64);
+        let mut read = 0;
+        while read < buf.len() {
+            let pos = pos + read as u64;
+            let idx = pos / BUFFER_SIZE as u64;
+            let start = (pos % BUFFER_SIZE as u64) as usize;
+            let buffer = self.buffer(idx, last - idx, true)?;
+            let n = (buf.len() - read).min(buffer.data.len() - start);
+            buf[read..read + n].copy_from_slice(&buffer.data[start..start + n]);
+            read += n;
+        }
+        Ok(())
+    }
+
+    /// Writes the data at `pos`, which must be within the device.
+    fn write(&mut self, pos: u64, buf: &[u8]) -> DevResult {
+        let end = pos + buf.len() as u64;
+        let last = end.div_ceil(BUFFER_SIZE as u64);
+        let mut written = 0;
+        while written < buf.len() {
+            let pos = pos + written as u64;
+            let idx = pos / BUFFER_SIZE as u64;
+            let start = (pos % BUFFER_SIZE as u64) as usize;
+            let n = (buf.len() - written).min(self.buffer_len(idx) - start);
+            let whole = start == 0 && n == self.buffer_len(idx);
+            let buffer = self.buffer(idx, last - idx, !whole)?;
+            buffer.data[start..start + n].copy_from_slice(&buf[written..written + n]);
+            buffer.dirty = true;
+            written += n;
+        }
+        Ok(())
+    }
+
+    /// Writes back all the modified buffers, the contiguous ones are written
+    /// in one request.
+    fn flush(&mut self) -> DevResult {
+        let dirty = self
+            .buffers
+            .iter()
+            .filter(|(_, buffer)| buffer.dirty)
+            .map(|(&idx, _)| idx)
+            .collect::<Vec<_>>();
+        let mut i = 0;
+        while i < dirty.len() {
+            let start = dirty[i];
+            let mut end = start + 1;
+            while i + 1 < dirty.len() && dirty[i + 1] == end && end - start < MAX_REQUEST {
+                end += 1;
+                i += 1;
+            }
+            self.write_back(start, end)?;
+            i += 1;
+        }
+        self.dev.flush()
+    }
+}
+
+impl Drop for BlockCache {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("failed to write back the block cache: {e:?}");
+        }
+    }
+}
+
+/// A disk device with a cursor, or a partition of it.
+///
+/// The clones share the block cache, each with its own cursor.
+#[derive(Clone)]
+pub struct Disk {
+    /// Offset of the partition on the device.
+    start: u64,
+    size: u64,
+    pos: u64,
+    cache: Arc<Mutex<BlockCache>>,
 }
 
 impl Disk {
     /// Create a new disk.
     pub fn new(dev: AxBlockDevice) -> Self {
         assert_eq!(BLOCK_SIZE, dev.block_size());
+        let cache = BlockCache::new(dev);
+        let size = cache.size();
+        let cache = Arc::new(Mutex::new(cache));
+        let mut caches = CACHES.lock();
+        caches.retain(|cache| cache.strong_count() > 0);
+        caches.push(Arc::downgrade(&cache));
         Self {
-            block_id: 0,
-            offset: 0,
-            dev,
+            start: 0,
+            size,
+            pos: 0,
+            cache,
+        }
+    }
+
+    /// Open a partition of the disk, which shares the block cache with it.
+    pub fn partition(&self, part: &Partition) -> Self {
+        assert!(part.start + part.size <= self.size);
+        Self {
+            start: self.start + part.start,
+            size: part.size,
+            pos: 0,
+            cache: self.cache.clone(),
         }
     }
 
     /// Get the size of the disk.
     pub fn size(&self) -> u64 {
-        self.dev.num_blocks() * BLOCK_SIZE as u64
+        self.size
     }
 
     /// Get the position of the cursor.
     pub fn position(&self) -> u64 {
-        self.block_id * BLOCK_SIZE as u64 + self.offset as u64
+        self.pos
     }
 
     /// Set the position of the cursor.
     pub fn set_position(&mut self, pos: u64) {
-        self.block_id = pos / BLOCK_SIZE as u64;
-        self.offset = pos as usize % BLOCK_SIZE;
+        self.pos = pos;
     }
 
-    /// Read within one block, returns the number of bytes read.
+    /// Read within one buffer, returns the number of bytes read.
     pub fn read_one(&mut self, buf: &mut [u8]) -> DevResult<usize> {
-        let read_size = if self.offset == 0 && buf.len() >= BLOCK_SIZE {
-            // whole block
-            self.dev
-                .read_block(self.block_id, &mut buf[0..BLOCK_SIZE])?;
-            self.block_id += 1;
-            BLOCK_SIZE
-        } else {
-            // partial block
-            let mut data = [0u8; BLOCK_SIZE];
-            let start = self.offset;
-            let count = buf.len().min(BLOCK_SIZE - self.offset);
-
-            self.dev.read_block(self.block_id, &mut data)?;
-            buf[..count].copy_from_slice(&data[start..start + count]);
-
-            self.offset += count;
-            if self.offset >= BLOCK_SIZE {
-                self.block_id += 1;
-                self.offset -= BLOCK_SIZE;
-            }
-            count
-        };
-        Ok(read_size)
+        if self.pos >= self.size {
+            return Ok(0);
+        }
+        let pos = self.start + self.pos;
+        let count = buf
+            .len()
+            .min(BUFFER_SIZE - pos as usize % BUFFER_SIZE)
+            .min((self.size - self.pos) as usize);
+        self.cache.lock().read(pos, &mut buf[..count])?;
+        self.pos += count as u64;
+        Ok(count)
     }
 
-    /// Write within one block, returns the number of bytes written.
+    /// Write within one buffer, returns the number of bytes written.
     pub fn write_one(&mut self, buf: &[u8]) -> DevResult<usize> {
-        let write_size = if self.offset == 0 && buf.len() >= BLOCK_SIZE {
-            // whole block
-            self.dev.write_block(self.block_id, &buf[0..BLOCK_SIZE])?;
-            self.block_id += 1;
-            BLOCK_SIZE
-        } else {
-            // partial block
-            let mut data = [0u8; BLOCK_SIZE];
-            let start = self.offset;
-            let count = buf.len().min(BLOCK_SIZE - self.offset);
-
-            self.dev.read_block(self.block_id, &mut data)?;
-            data[start..start + count].copy_from_slice(&buf[..count]);
-            self.dev.write_block(self.block_id, &data)?;
-
-            self.offset += count;
-            if self.offset >= BLOCK_SIZE {
-                self.block_id += 1;
-                self.offset -= BLOCK_SIZE;
-            }
-            count
-        };
-        Ok(write_size)
+        if self.pos >= self.size {
+            return Ok(0);
+        }
+        let pos = self.start + self.pos;
+        let count = buf
+            .len()
+            .min(BUFFER_SIZE - pos as usize % BUFFER_SIZE)
+            .min((self.size - self.pos) as usize);
+        self.cache.lock().write(pos, &buf[..count])?;
+        self.pos += count as u64;
+        Ok(count)
+    }
+
+    /// Read the data at the specified offset, without moving the cursor.
+    pub fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> DevResult {
+        if offset + buf.len() as u64 > self.size {
+            return Err(DevError::Io);
+        }
+        self.cache.lock().read(self.start + offset, buf)
+    }
+
+    /// Write the data at the specified offset, without moving the cursor.
+    pub fn write_at(&mut self, offset: u64, buf: &[u8]) -> DevResult {
+        if offset + buf.len() as u64 > self.size {
+            return Err(DevError::Io);
+        }
+        self.cache.lock().write(self.start + offset, buf)
     }
 
     /// Read a single block starting from the specified offset.
     #[allow(unused)]
     pub fn read_offset(&mut self, offset: usize) -> [u8; BLOCK_SIZE] {
-        let block_id = offset / BLOCK_SIZE;
+        let offset = offset / BLOCK_SIZE * BLOCK_SIZE;
         let mut block_data = [0u8; BLOCK_SIZE];
-        self.dev
-            .read_block(block_id as u64, &mut block_data)
-            .unwrap();
+        self.read_at(offset as u64, &mut block_data).unwrap();
         block_data
     }
 
@@ -120,13 +368,98 @@ impl Disk {
             "Buffer length must be equal to BLOCK_SIZE"
         );
         assert!(offset % BLOCK_SIZE == 0);
-        let block_id = offset / BLOCK_SIZE;
-        self.dev.write_block(block_id as u64, buf).unwrap();
+        self.write_at(offset as u64, buf).unwrap();
         Ok(buf.len())
     }
 
     ///flush device cache
     pub fn do_flush(&mut self) -> DevResult {
-        self.dev.flush()
+        self.cache.lock().flush()
+    }
+}
+
+/// Write back the modified buffers of all disks.
+pub fn sync_all() -> DevResult {
+    let caches = CACHES
+        .lock()
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect::<Vec<_>>();
+    for cache in caches {
+        cache.lock().flush()?;
     }
+    Ok(())
+}
+
+/// Register a disk or partition with the name, e.g. `vda1`.
+pub fn register(name: &str, disk: &Disk) {
+    let mut disk = disk.clone();
+    disk.set_position(0);
+    DISKS.lock().insert(String::from(name), disk);
+}
+
+/// Open the disk or partition registered with the name.
+pub fn open(name: &str) -> Option<Disk> {
+    DISKS.lock().get(name).cloned()
+}
+
+/// Names of all the registered disks and partitions.
+pub fn names() -> Vec<String> {
+    DISKS.lock().keys().cloned().collect()
+}
+
+/// The block device file of a disk or partition.
+pub struct BlockDevNode {
+    disk: Mutex<Disk>,
+}
+
+impl BlockDevNode {
+    /// Create a block device file of the disk.
+    pub fn new(disk: Disk) -> Self {
+        Self {
+            disk: Mutex::new(disk),
+        }
+    }
+}
+
+impl VfsNodeOps for BlockDevNode {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        let size = self.disk.lock().size();
+        Ok(VfsNodeAttr::new(
+            0,
+            VfsNodePerm::from_bits_truncate(0o660),
+            VfsNodeType::BlockDevice,
+            size,
+            size / BLOCK_SIZE as u64,
+        ))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        let mut disk = self.disk.lock();
+        let len = disk.size().saturating_sub(offset).min(buf.len() as u64) as usize;
+        disk.read_at(offset, &mut buf[..len])
+            .map_err(|_| VfsError::Io)?;
+        Ok(len)
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> VfsResult<usize> {
+        let mut disk = self.disk.lock();
+        let len = disk.size().saturating_sub(offset).min(buf.len() as u64) as usize;
+        if len == 0 && !buf.is_empty() {
+            return Err(VfsError::StorageFull);
+        }
+        disk.write_at(offset, &buf[..len])
+            .map_err(|_| VfsError::Io)?;
+        Ok(len)
+    }
+
+    fn fsync(&self) -> VfsResult {
+        self.disk.lock().do_flush().map_err(|_| VfsError::Io)
+    }
+
+    fn truncate(&self, _size: u64) -> VfsResult {
+        Ok(())
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
 }
@@ -8,12 +8,13 @@
  */
 use core::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::{AbsPath, DirEntry, FileAttr};
+use crate::{AbsPath, DirEntry, FileAttr, FilePerm};
 use alloc::sync::Arc;
 use axerrno::{AxResult, LinuxError, LinuxResult};
 use axfs_vfs::VfsNodeRef;
 use axio::PollState;
 use capability::{Cap, WithCap};
+use core::time::Duration;
 use ruxfdtable::{FileLike, OpenFlags, RuxStat};
 use spin::rwlock::RwLock;
 /// An opened directory object, with open permissions and a cursor for entry reading.
@@ -55,6 +56,22 @@ impl Directory {
         self.node.access(Cap::empty())?.get_attr()
     }
 
+    /// Sets the permission of the directory.
+    pub fn set_mode(&self, mode: FilePerm) -> AxResult {
+        self.node.access(Cap::empty())?.set_mode(mode)
+    }
+
+    /// Sets the user and group owning the directory, the `None` ones are unchanged.
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> AxResult {
+        self.node.access(Cap::empty())?.set_owner(uid, gid)
+    }
+
+    /// Sets the access and modification times of the directory, the `None` ones
+    /// are unchanged.
+    pub fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> AxResult {
+        self.node.access(Cap::empty())?.set_times(atime, mtime)
+    }
+
     /// Reads directory entries starts from the current position into the
     /// given buffer. Returns the number of entries read.
     ///
@@ -12,7 +12,7 @@ use crate::AbsPath;
 use alloc::sync::Arc;
 use axerrno::{AxError, LinuxError, LinuxResult};
 use axfs_vfs::VfsNodeOps;
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use crate_interface::call_interface;
 use ruxfdtable::{FileLike, OpenFlags, RuxStat};
 use ruxfifo::FifoNode;
@@ -66,6 +66,7 @@ impl FileLike for FifoReader {
     ///
     /// - Returns 0 when all writers closed and buffer empty
     /// - EAGAIN if non-blocking and no data available
+    /// - EINTR if interrupted by a signal while blocking
     fn read(&self, buf: &mut [u8]) -> LinuxResult<usize> {
         loop {
             match self.node.read_at(0, buf) {
@@ -78,6 +79,9 @@ impl FileLike for FifoReader {
                     if self.flags.read().contains(OpenFlags::O_NONBLOCK) {
                         return Err(LinuxError::EAGAIN);
                     }
+                    if crate_interface::call_interface!(SchedYieldIf::interrupted) {
+                        return Err(LinuxError::EINTR);
+                    }
                     crate_interface::call_interface!(SchedYieldIf::yield_now);
                 }
                 err => return err.map_err(LinuxError::from),
@@ -107,6 +111,10 @@ impl FileLike for FifoReader {
         self.node.reader_poll().map_err(LinuxError::from)
     }
 
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        Some(self.node.poll_waiters())
+    }
+
     fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
         *self.flags.write() = flags;
         Ok(())
@@ -206,6 +214,10 @@ impl FileLike for FifoWriter {
         self.node.writer_poll().map_err(LinuxError::from)
     }
 
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        Some(self.node.poll_waiters())
+    }
+
     fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
         *self.flags.write() = flags;
         Ok(())
@@ -240,4 +252,7 @@ pub fn new_pipe_pair(flags: OpenFlags) -> (Arc<FifoReader>, Arc<FifoWriter>) {
 pub trait SchedYieldIf {
     /// Yields CPU using appropriate scheduling strategy
     fn yield_now();
+    /// Whether the current task is interrupted by a signal, so that a
+    /// blocking operation should return `EINTR`.
+    fn interrupted() -> bool;
 }
@@ -11,11 +11,14 @@ use axerrno::{ax_err_type, AxResult, LinuxError, LinuxResult};
 use axfs_vfs::VfsNodeRef;
 use axio::{PollState, Read, SeekFrom, Write};
 use capability::{Cap, WithCap};
+use core::time::Duration;
 
 use ruxfdtable::{FileLike, OpenFlags, RuxStat};
 use spin::{mutex::Mutex, RwLock};
 
-use crate::{AbsPath, FileAttr};
+#[cfg(feature = "alloc")]
+use crate::page_cache::{self, PageCache};
+use crate::{AbsPath, FileAttr, FilePerm};
 
 /// An opened file with permissions and a cursor for I/O operations.
 pub struct File {
@@ -31,6 +34,9 @@ pub struct File {
     offset: Mutex<u64>,
     /// File mode flags
     flags: RwLock<OpenFlags>,
+    /// Page cache shared by the files opened with the same inode, if used.
+    #[cfg(feature = "alloc")]
+    cache: Option<Arc<PageCache>>,
 }
 
 impl File {
@@ -41,9 +47,36 @@ impl File {
             node: WithCap::new(node, Cap::from(flags)),
             offset: Mutex::new(0),
             flags: RwLock::new(flags),
+            #[cfg(feature = "alloc")]
+            cache: None,
         }
     }
 
+    /// Create an opened file which is read and written through the page cache,
+    /// if the filesystem uses it and `O_DIRECT` is not set.
+    ///
+    /// `real_path` is the path of the file with symbolic links resolved.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_mut, unused_variables))]
+    pub(crate) fn new_cached(
+        path: AbsPath<'static>,
+        real_path: &AbsPath,
+        node: VfsNodeRef,
+        flags: OpenFlags,
+    ) -> Self {
+        #[cfg(feature = "alloc")]
+        let cache = if flags.contains(OpenFlags::O_DIRECT) {
+            None
+        } else {
+            page_cache::open(real_path, &node)
+        };
+        let mut file = Self::new(path, node, flags);
+        #[cfg(feature = "alloc")]
+        {
+            file.cache = cache;
+        }
+        file
+    }
+
     /// Reads data into `dst` from current offset. Atomically updates the offset  
     /// after reading. Locking ensures synchronization with underlying node operations.
     fn read(&self, dst: &mut [u8]) -> AxResult<usize> {
@@ -60,8 +93,7 @@ impl File {
         if self.flags.read().contains(OpenFlags::O_APPEND) {
             *offset = self.get_attr()?.size();
         };
-        let node = self.node.access(Cap::WRITE)?;
-        let write_len = node.write_at(*offset, src)?;
+        let write_len = self.write_at(*offset, src)?;
         *offset += write_len as u64;
         Ok(write_len)
     }
@@ -76,16 +108,42 @@ impl File {
         self.node.access(Cap::empty())?.get_attr()
     }
 
+    /// Sets the permission of the file.
+    pub fn set_mode(&self, mode: FilePerm) -> AxResult {
+        self.node.access(Cap::empty())?.set_mode(mode)
+    }
+
+    /// Sets the user and group owning the file, the `None` ones are unchanged.
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> AxResult {
+        self.node.access(Cap::empty())?.set_owner(uid, gid)
+    }
+
+    /// Sets the access and modification times of the file, the `None` ones
+    /// are unchanged.
+    pub fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> AxResult {
+        self.node.access(Cap::empty())?.set_times(atime, mtime)
+    }
+
     /// Truncates the file to the specified size.
     pub fn truncate(&self, size: u64) -> AxResult {
-        self.node.access(Cap::WRITE)?.truncate(size)
+        let node = self.node.access(Cap::WRITE)?;
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            cache.truncate(size);
+        }
+        node.truncate(size)
     }
 
     /// Reads the file at the given position. Returns the number of bytes read.
     ///
     /// It does not update the file cursor.
     pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> AxResult<usize> {
-        self.node.access(Cap::READ)?.read_at(offset, buf)
+        let node = self.node.access(Cap::READ)?;
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            return cache.read_at(node, offset, buf);
+        }
+        node.read_at(offset, buf)
     }
 
     /// Writes the file at the given position. Returns the number of bytes
@@ -93,12 +151,67 @@ impl File {
     ///
     /// It does not update the file cursor.
     pub fn write_at(&self, offset: u64, buf: &[u8]) -> AxResult<usize> {
-        self.node.access(Cap::WRITE)?.write_at(offset, buf)
+        let node = self.node.access(Cap::WRITE)?;
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            return cache.write_at(node, offset, buf);
+        }
+        node.write_at(offset, buf)
     }
 
     /// Flushes the file, writes all buffered data to the underlying device.
     pub fn flush(&self) -> AxResult {
-        self.node.access(Cap::WRITE)?.fsync()
+        let node = self.node.access(Cap::WRITE)?;
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            cache.sync()?;
+        }
+        node.fsync()
+    }
+
+    /// Writes back the modified pages of the file, for `fsync`. Unlike
+    /// [`File::flush`], it does not require the file to be writable.
+    pub fn sync(&self) -> AxResult {
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            cache.sync()?;
+            return unsafe { self.node.access_unchecked() }.fsync();
+        }
+        Ok(())
+    }
+
+    /// Whether the file is read and written through the page cache.
+    pub fn is_cached(&self) -> bool {
+        #[cfg(feature = "alloc")]
+        return self.cache.is_some();
+        #[cfg(not(feature = "alloc"))]
+        false
+    }
+
+    /// Gets the address of the cached page at `offset` to be mapped by a
+    /// shared mapping, or [`None`] if the file is not cached.
+    ///
+    /// The page holds a reference for the mapping, which is dropped when it
+    /// is unmapped. It is written back while mapped if `writable` is set.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+    pub fn map_page(&self, offset: u64, writable: bool) -> AxResult<Option<usize>> {
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            let node = self.node.access(Cap::READ)?;
+            return cache.map_page(node, offset, writable).map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Writes back the cached page at `offset` if it is modified, for `msync`
+    /// and unmapping.
+    #[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+    pub fn sync_page(&self, offset: u64) -> AxResult {
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            return cache.sync_page(unsafe { self.node.access_unchecked() }, offset);
+        }
+        Ok(())
     }
 
     /// Sets the cursor of the file to the specified offset. Returns the new
@@ -119,9 +232,14 @@ impl File {
 
 impl Drop for File {
     fn drop(&mut self) {
-        unsafe {
-            self.node.access_unchecked().release().ok();
+        let node = unsafe { self.node.access_unchecked() };
+        #[cfg(feature = "alloc")]
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.release(node) {
+                warn!("failed to write back {}: {e:?}", self.path);
+            }
         }
+        node.release().ok();
     }
 }
 
@@ -195,6 +313,6 @@ impl Write for File {
     }
 
     fn flush(&mut self) -> AxResult<()> {
-        self.node.access(Cap::WRITE)?.fsync()
+        File::flush(self)
     }
 }
@@ -11,11 +11,12 @@
 //!
 //! - File: open, read, write, seek, truncate
 //! - Directory: open, read, create, remove
+//! - Filesystem: mount, umount, sync
 //!
 //! The interface is designed with low coupling to avoid repetitive error handling.
-use alloc::sync::Arc;
+use alloc::{format, string::String, sync::Arc, vec};
 use axerrno::{AxError, AxResult, LinuxResult};
-use axfs_vfs::{AbsPath, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType};
+use axfs_vfs::{AbsPath, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType, VfsOps};
 use capability::Cap;
 use ruxfdtable::{FileLike, OpenFlags};
 use ruxfifo::FifoNode;
@@ -24,7 +25,7 @@ use crate::{
     directory::Directory,
     fifo::{FifoReader, FifoWriter},
     file::File,
-    root::RootDirectory,
+    root::{BindFs, MountPoint, RootDirectory},
     FileAttr, FilePerm,
 };
 
@@ -62,11 +63,104 @@ pub(crate) fn root_dir() -> Arc<RootDirectory> {
 
 /* File operations with absolute path. */
 
-/// Look up a file given an absolute path.
-pub fn lookup(path: &AbsPath) -> AxResult<VfsNodeRef> {
+/// Maximum number of symbolic links followed in resolving a path.
+const MAX_SYMLINKS: usize = 40;
+
+/// Maximum length of the target of a symbolic link.
+const PATH_MAX: usize = 4096;
+
+/// Look up a node in the mounted filesystems, without following any link.
+fn lookup_raw(path: &AbsPath) -> AxResult<VfsNodeRef> {
     root_dir().clone().lookup(&path.to_rel())
 }
 
+/// Read the target of the symbolic link `node`.
+fn read_link_node(node: &VfsNodeRef) -> AxResult<String> {
+    let mut buf = vec![0; PATH_MAX];
+    let len = node.readlink(&mut buf)?;
+    buf.truncate(len);
+    String::from_utf8(buf).map_err(|_| AxError::InvalidData)
+}
+
+/// Find the first symbolic link in `path` to be followed, and replace the
+/// components up to it with its target.
+///
+/// Return `None` if there is no such link, or the path can not be resolved
+/// any further.
+fn expand_first_link(path: &AbsPath, follow: bool) -> AxResult<Option<AbsPath<'static>>> {
+    let ends = path
+        .match_indices('/')
+        .skip(1)
+        .map(|(i, _)| i)
+        .chain(core::iter::once(path.len()));
+    for end in ends {
+        let Ok(node) = lookup_raw(&AbsPath::new(&path[..end])) else {
+            return Ok(None);
+        };
+        if !node.get_attr()?.is_symlink() || (end == path.len() && !follow) {
+            continue;
+        }
+        let target = read_link_node(&node)?;
+        let rest = &path[end..];
+        let expanded = if target.starts_with('/') {
+            format!("{target}{rest}")
+        } else {
+            let parent = &path[..path[..end].rfind('/').unwrap()];
+            format!("{parent}/{target}{rest}")
+        };
+        return Ok(Some(AbsPath::new_canonicalized(&expanded)));
+    }
+    Ok(None)
+}
+
+/// Resolve the symbolic links in `path`, returns the resolved path and the
+/// result of looking it up.
+///
+/// The last component is followed only if `follow` is set.
+fn resolve(path: &AbsPath, follow: bool) -> AxResult<(AbsPath<'static>, AxResult<VfsNodeRef>)> {
+    let mut path = path.to_owned();
+    let mut links = 0;
+    loop {
+        // fast path: no links in the path
+        let node = lookup_raw(&path);
+        match &node {
+            Ok(node) if follow && node.get_attr()?.is_symlink() => {}
+            Err(AxError::NotFound) | Err(AxError::NotADirectory) => {}
+            _ => return Ok((path, node)),
+        }
+        match expand_first_link(&path, follow)? {
+            Some(expanded) => {
+                links += 1;
+                if links > MAX_SYMLINKS {
+                    return Err(AxError::FilesystemLoop);
+                }
+                path = expanded;
+            }
+            None => return Ok((path, node)),
+        }
+    }
+}
+
+/// Resolve the symbolic links in an absolute path.
+///
+/// The last component is followed only if `follow` is set. The components
+/// which do not exist are kept as is, so that the returned path can be used
+/// to create a new node.
+pub fn resolve_path(path: &AbsPath, follow: bool) -> AxResult<AbsPath<'static>> {
+    resolve(path, follow).map(|(path, _)| path)
+}
+
+/// Look up a file given an absolute path, following symbolic links.
+pub fn lookup(path: &AbsPath) -> AxResult<VfsNodeRef> {
+    resolve(path, true)?.1
+}
+
+/// Look up a file given an absolute path, without following the last
+/// component if it is a symbolic link.
+pub fn lookup_nofollow(path: &AbsPath) -> AxResult<VfsNodeRef> {
+    resolve(path, false)?.1
+}
+
 /// Get the file attributes given an absolute path.
 pub fn get_attr(path: &AbsPath) -> AxResult<FileAttr> {
     lookup(path)?.get_attr()
@@ -79,17 +173,34 @@ pub(crate) fn open_abspath(
     path: &AbsPath,
     flags: OpenFlags,
     mode: FilePerm,
-) -> AxResult<VfsNodeRef> {
-    let node = match lookup(path) {
+) -> AxResult<(AbsPath<'static>, VfsNodeRef)> {
+    // `O_CREAT | O_EXCL` does not follow the link either, it just fails
+    let follow = !flags.contains(OpenFlags::O_NOFOLLOW)
+        && !flags.contains(OpenFlags::O_EXCL | OpenFlags::O_CREAT);
+    let (path, node) = resolve(path, follow)?;
+    let node = match node {
         Ok(node) => {
             if flags.contains(OpenFlags::O_EXCL | OpenFlags::O_CREAT) {
                 return Err(AxError::AlreadyExists);
             }
             let attr = node.get_attr()?;
+            if attr.is_symlink() {
+                return Err(AxError::FilesystemLoop);
+            }
             if !attr.is_dir() && flags.contains(OpenFlags::O_DIRECTORY) {
                 return Err(AxError::NotADirectory);
             }
+            if attr.is_file()
+                && Cap::from(flags).contains(Cap::WRITE)
+                && root_dir().is_read_only(&path.to_rel())
+            {
+                return Err(AxError::ReadOnlyFilesystem);
+            }
             if attr.is_file() && flags.contains(OpenFlags::O_TRUNC) {
+                #[cfg(feature = "alloc")]
+                if let Some(cache) = crate::page_cache::get(&path, &node) {
+                    cache.truncate(0);
+                }
                 node.truncate(0)?;
             }
             node
@@ -98,8 +209,8 @@ pub(crate) fn open_abspath(
             if !flags.contains(OpenFlags::O_CREAT) || flags.contains(OpenFlags::O_DIRECTORY) {
                 return Err(AxError::NotFound);
             }
-            create(path, VfsNodeType::File, mode)?;
-            lookup(path)?
+            root_dir().create(&path.to_rel(), VfsNodeType::File, mode)?;
+            lookup_raw(&path)?
         }
         Err(e) => return Err(e),
     };
@@ -108,9 +219,9 @@ pub(crate) fn open_abspath(
         return Err(AxError::PermissionDenied);
     }
     if let Some(new_node) = node.open()? {
-        return Ok(new_node);
+        return Ok((path, new_node));
     }
-    Ok(node)
+    Ok((path, node))
 }
 
 /// Opens a file-like object (file or directory) at given path with flags.
@@ -119,10 +230,15 @@ pub fn open_file_like(
     flags: OpenFlags,
     mode: FilePerm,
 ) -> LinuxResult<Arc<dyn FileLike>> {
-    let node = open_abspath(path, flags, mode)?;
+    let (real_path, node) = open_abspath(path, flags, mode)?;
     match node.get_attr()?.file_type() {
         VfsNodeType::Dir => Ok(Arc::new(Directory::new(path.to_owned(), node, flags))),
-        VfsNodeType::File => Ok(Arc::new(File::new(path.to_owned(), node, flags))),
+        VfsNodeType::File => Ok(Arc::new(File::new_cached(
+            path.to_owned(),
+            &real_path,
+            node,
+            flags,
+        ))),
         VfsNodeType::Fifo => {
             let node = Arc::downcast::<FifoNode>(node.as_any_arc()).unwrap();
             if flags.contains(OpenFlags::O_WRONLY) {
@@ -138,21 +254,65 @@ pub fn open_file_like(
 ///
 /// This function will not check if the node exists, check it with [`lookup`] first.
 pub fn create(path: &AbsPath, ty: VfsNodeType, mode: VfsNodePerm) -> AxResult {
-    root_dir().create(&path.to_rel(), ty, mode)
+    root_dir().create(&resolve_path(path, false)?.to_rel(), ty, mode)
 }
 
 /// Create a directory recursively given an absolute path.
 ///
 /// This function will not check if the directory exists, check it with [`lookup`] first.
 pub fn create_dir_all(path: &AbsPath) -> AxResult {
-    root_dir().create_recursive(&path.to_rel(), VfsNodeType::Dir, VfsNodePerm::default_dir())
+    root_dir().create_recursive(
+        &resolve_path(path, false)?.to_rel(),
+        VfsNodeType::Dir,
+        VfsNodePerm::default_dir(),
+    )
+}
+
+/// Create a symbolic link at the given absolute path, which points to `target`.
+///
+/// `target` is not required to exist.
+pub fn create_symlink(target: &str, path: &AbsPath) -> AxResult {
+    if target.is_empty() {
+        return Err(AxError::NotFound);
+    }
+    root_dir().symlink(&resolve_path(path, false)?.to_rel(), target)
+}
+
+/// Read the target of the symbolic link at the given absolute path into `buf`.
+///
+/// Return the number of bytes read, the target is truncated if `buf` is too small.
+pub fn read_link(path: &AbsPath, buf: &mut [u8]) -> AxResult<usize> {
+    lookup_nofollow(path)?.readlink(buf)
+}
+
+/// Create a hard link at the new absolute path, which refers to the node at
+/// the old absolute path.
+///
+/// A symbolic link at the old path is followed only if `follow` is set. Both
+/// paths must be in the same filesystem.
+pub fn link(old: &AbsPath, new: &AbsPath, follow: bool) -> AxResult {
+    let (old, node) = resolve(old, follow)?;
+    let node = node?;
+    let new = resolve_path(new, false)?;
+    if !root_dir().same_fs(&old.to_rel(), &new.to_rel()) {
+        return Err(AxError::CrossesDevices);
+    }
+    root_dir().link(&new.to_rel(), node)?;
+    Ok(())
 }
 
 /// Remove a file given an absolute path.
 ///
+/// A symbolic link is removed itself, rather than its target.
+///
 /// This function will not check if the file exits or removeable,
-/// check it with [`lookup`] first.
+/// check it with [`lookup_nofollow`] first.
 pub fn remove_file(path: &AbsPath) -> AxResult {
+    let path = resolve_path(path, false)?;
+    #[cfg(feature = "alloc")]
+    if let Ok(node) = lookup_raw(&path) {
+        crate::page_cache::unlink(&path, &node);
+    }
     root_dir().unlink(&path.to_rel())
 }
 
@@ -161,7 +321,7 @@ pub fn remove_file(path: &AbsPath) -> AxResult {
 /// This function will not check if the directory exists or is empty,
 /// check it with [`lookup`] first.
 pub fn remove_dir(path: &AbsPath) -> AxResult {
-    root_dir().unlink(&path.to_rel())
+    root_dir().unlink(&resolve_path(path, false)?.to_rel())
 }
 
 /// Check if a directory is a mount point.
@@ -171,8 +331,106 @@ pub fn is_mount_point(path: &AbsPath) -> bool {
 
 /// Rename a file given an old and a new absolute path.
 ///
+/// Symbolic links at the old or new path are renamed or replaced themselves.
+///
 /// This function will not check if the old path or new path exists, check it with
-/// [`lookup`] first.
+/// [`lookup_nofollow`] first.
 pub fn rename(old: &AbsPath, new: &AbsPath) -> AxResult {
-    root_dir().rename(&old.to_rel(), &new.to_rel())
+    let old = resolve_path(old, false)?;
+    let new = resolve_path(new, false)?;
+    #[cfg(feature = "alloc")]
+    let old_node = lookup_raw(&old);
+    #[cfg(feature = "alloc")]
+    if let Ok(node) = lookup_raw(&new) {
+        // the file replaced is unlinked
+        crate::page_cache::unlink(&new, &node);
+    }
+    root_dir().rename(&old.to_rel(), &new.to_rel())?;
+    #[cfg(feature = "alloc")]
+    if let (Ok(old_node), Ok(new_node)) = (old_node, lookup_raw(&new)) {
+        crate::page_cache::rename(&old, &old_node, &new, &new_node);
+    }
+    Ok(())
+}
+
+/// Write back the modified pages of all files in the page cache, and the
+/// modified buffers of all disks.
+pub fn sync() -> AxResult {
+    #[cfg(feature = "alloc")]
+    crate::page_cache::sync_all()?;
+    #[cfg(feature = "blkfs")]
+    crate::dev::sync_all().map_err(|_| AxError::Io)?;
+    Ok(())
+}
+
+/// Mount a filesystem of the registered type `fstype` from `source` at
+/// `target`, see [`crate::fstype`].
+///
+/// A block device can not be mounted more than once, however its path is
+/// spelled.
+pub fn mount(
+    source: &str,
+    target: &AbsPath,
+    fstype: &str,
+    data: &str,
+    read_only: bool,
+) -> AxResult {
+    if !crate::fstype::requires_dev(fstype) {
+        let fs = crate::fstype::new_fs(fstype, source, data)?;
+        return mount_fs(fs, source, target, fstype, read_only);
+    }
+    let source = dev_source(source)?;
+    if root_dir().is_source_mounted(&source) {
+        return Err(AxError::ResourceBusy);
+    }
+    let fs = crate::fstype::new_fs(fstype, &source, data)?;
+    mount_fs(fs, &source, target, fstype, read_only)
+}
+
+/// Canonicalize the path of a block device, e.g. `vdb` or `/dev/./vdb` is
+/// `/dev/vdb`, with the symbolic links in it resolved.
+fn dev_source(source: &str) -> AxResult<String> {
+    let path = if source.starts_with('/') {
+        AbsPath::new_canonicalized(source)
+    } else {
+        AbsPath::new_canonicalized(&format!("/dev/{source}"))
+    };
+    Ok(resolve_path(&path, true)?.to_string())
+}
+
+/// Mount a created filesystem at `target`.
+pub fn mount_fs(
+    fs: Arc<dyn VfsOps>,
+    source: &str,
+    target: &AbsPath,
+    fstype: &str,
+    read_only: bool,
+) -> AxResult {
+    let target = resolve_path(target, true)?;
+    let mut mp = MountPoint::new(target.to_string(), fs).with_source(source, fstype);
+    mp.read_only = read_only;
+    root_dir().mount(mp)
+}
+
+/// Mount the directory at `source` at `target` as well.
+///
+/// The filesystems mounted under `source` are not visible at `target`.
+pub fn bind_mount(source: &AbsPath, target: &AbsPath, read_only: bool) -> AxResult {
+    let source = resolve_path(source, true)?;
+    let dir = lookup_raw(&source)?;
+    if !dir.get_attr()?.is_dir() {
+        return Err(AxError::NotADirectory);
+    }
+    let fs = Arc::new(BindFs::new(dir));
+    mount_fs(fs, &source, target, "none", read_only)
+}
+
+/// Change whether the filesystem mounted at `target` is read-only.
+pub fn remount(target: &AbsPath, read_only: bool) -> AxResult {
+    root_dir().remount(&resolve_path(target, true)?, read_only)
+}
+
+/// Unmount the filesystem at `target`.
+pub fn umount(target: &AbsPath) -> AxResult {
+    root_dir().umount(&resolve_path(target, true)?)
 }
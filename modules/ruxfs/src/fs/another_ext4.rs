@@ -20,39 +20,31 @@ use another_ext4::{
 use axfs_vfs::{RelPath, VfsDirEntry, VfsError, VfsNodePerm, VfsResult};
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType, VfsOps};
 use axsync::Mutex;
+use core::time::Duration;
 
 pub struct DiskAdapter(Arc<Mutex<Disk>>);
 
 unsafe impl Send for DiskAdapter {}
 unsafe impl Sync for DiskAdapter {}
 
-// The io block size of the disk layer
-const DISK_BLOCK_SIZE: usize = 512;
-
 // The block size of the file system
 pub const BLOCK_SIZE: usize = EXT4_BLOCK_SIZE;
 
 impl BlockDevice for DiskAdapter {
     fn read_block(&self, block_id: u64) -> Block {
-        let mut disk = self.0.lock();
-        let base = block_id as usize * EXT4_BLOCK_SIZE;
         let mut data = [0u8; EXT4_BLOCK_SIZE];
-        // Per-disk-block read
-        for i in 0..(EXT4_BLOCK_SIZE / DISK_BLOCK_SIZE) {
-            let dblock = disk.read_offset(base + i * DISK_BLOCK_SIZE);
-            data[i * DISK_BLOCK_SIZE..(i + 1) * DISK_BLOCK_SIZE].copy_from_slice(&dblock);
-        }
+        self.0
+            .lock()
+            .read_at(block_id * EXT4_BLOCK_SIZE as u64, &mut data)
+            .expect("failed to read ext4 block");
         Block::new(block_id, data)
     }
 
     fn write_block(&self, block: &Block) {
-        let mut disk = self.0.lock();
-        let base = block.id as usize * EXT4_BLOCK_SIZE;
-        // Per-disk-block write
-        for i in 0..(EXT4_BLOCK_SIZE / DISK_BLOCK_SIZE) {
-            let dblock = &block.data[i * DISK_BLOCK_SIZE..(i + 1) * DISK_BLOCK_SIZE];
-            let _ = disk.write_offset(base + i * DISK_BLOCK_SIZE, dblock);
-        }
+        self.0
+            .lock()
+            .write_at(block.id * EXT4_BLOCK_SIZE as u64, &block.data)
+            .expect("failed to write ext4 block");
     }
 }
 
@@ -73,7 +65,11 @@ impl VfsOps for Ext4FileSystem {
     }
     fn umount(&self) -> VfsResult {
         self.0.flush_all();
-        Ok(())
+        crate::dev::sync_all().map_err(|_| VfsError::Io)
+    }
+
+    fn use_page_cache(&self) -> bool {
+        true
     }
 }
 
@@ -115,6 +111,31 @@ impl VfsNodeOps for Ext4VirtInode {
             .map_err(map_error)
     }
 
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        let ctime = ruxhal::time::current_time().as_secs() as u32;
+        self.fs
+            .setattr(self.id, None, uid, gid, None, None, None, Some(ctime), None)
+            .map_err(map_error)
+    }
+
+    /// The times are stored in seconds, the nanoseconds are dropped.
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        let ctime = ruxhal::time::current_time().as_secs() as u32;
+        self.fs
+            .setattr(
+                self.id,
+                None,
+                None,
+                None,
+                None,
+                atime.map(|t| t.as_secs() as u32),
+                mtime.map(|t| t.as_secs() as u32),
+                Some(ctime),
+                None,
+            )
+            .map_err(map_error)
+    }
+
     // file operations:
 
     fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
@@ -130,7 +151,8 @@ impl VfsNodeOps for Ext4VirtInode {
     }
 
     fn fsync(&self) -> VfsResult {
-        Ok(())
+        self.fs.flush_all();
+        crate::dev::sync_all().map_err(|_| VfsError::Io)
     }
 
     fn truncate(&self, size: u64) -> VfsResult {
@@ -151,6 +173,19 @@ impl VfsNodeOps for Ext4VirtInode {
             .map_err(map_error)
     }
 
+    // symbolic link operations:
+
+    /// The target is stored in the data blocks of the link, links with the
+    /// target inlined in the inode are not supported yet.
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let attr = self.fs.getattr(self.id).map_err(map_error)?;
+        if !matches!(attr.ftype, EXt4FileType::SymLink) {
+            return Err(VfsError::InvalidInput);
+        }
+        let len = buf.len().min(attr.size as usize);
+        self.fs.read(self.id, 0, &mut buf[..len]).map_err(map_error)
+    }
+
     // directory operations:
 
     fn parent(&self) -> Option<VfsNodeRef> {
@@ -177,6 +212,21 @@ impl VfsNodeOps for Ext4VirtInode {
             .map_err(map_error)
     }
 
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        if self.fs.generic_lookup(self.id, path).is_ok() {
+            return Err(VfsError::AlreadyExists);
+        }
+        let mode = Ext4InodeMode::from_type_and_perm(EXt4FileType::SymLink, Ext4InodeMode::ALL_RWX);
+        self.fs
+            .generic_create(self.id, path, mode)
+            .map_err(map_error)?;
+        let id = self.fs.generic_lookup(self.id, path).map_err(map_error)?;
+        self.fs
+            .write(id, 0, target.as_bytes())
+            .map(|_| ())
+            .map_err(map_error)
+    }
+
     fn unlink(&self, path: &RelPath) -> VfsResult {
         self.fs.unlink(self.id, path).map_err(map_error)
     }
@@ -19,6 +19,7 @@ use axfs_vfs::{RelPath, VfsDirEntry, VfsError, VfsNodePerm, VfsResult};
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType, VfsOps};
 use axsync::Mutex;
 use core::cell::RefCell;
+use core::time::Duration;
 use ext4_rs::*;
 
 pub struct DiskAdapter {
@@ -28,63 +29,24 @@ pub struct DiskAdapter {
 unsafe impl Send for DiskAdapter {}
 unsafe impl Sync for DiskAdapter {}
 
-// The io block size of the disk layer
-const DISK_BLOCK_SIZE: usize = 512;
-
 // The block size of the file system
 pub const BLOCK_SIZE: usize = 4096;
 
 impl BlockDevice for DiskAdapter {
     fn read_offset(&self, offset: usize) -> Vec<u8> {
-        let mut disk = self.inner.borrow_mut();
         let mut buf = vec![0u8; BLOCK_SIZE];
-
-        let start_block_id = offset / DISK_BLOCK_SIZE;
-        let mut offset_in_block = offset % DISK_BLOCK_SIZE;
-        let mut total_bytes_read = 0;
-
-        while total_bytes_read < buf.len() {
-            let current_block_id = start_block_id + (total_bytes_read / DISK_BLOCK_SIZE);
-            let bytes_to_copy =
-                (buf.len() - total_bytes_read).min(DISK_BLOCK_SIZE - offset_in_block);
-
-            let block_data = disk.read_offset(current_block_id * DISK_BLOCK_SIZE + offset_in_block);
-
-            buf[total_bytes_read..total_bytes_read + bytes_to_copy]
-                .copy_from_slice(&block_data[offset_in_block..offset_in_block + bytes_to_copy]);
-
-            total_bytes_read += bytes_to_copy;
-            offset_in_block = 0; // After the first block, subsequent blocks read from the beginning
-        }
-
+        self.inner
+            .borrow_mut()
+            .read_at(offset as u64, &mut buf)
+            .expect("failed to read ext4 block");
         buf
     }
 
     fn write_offset(&self, offset: usize, buf: &[u8]) {
-        let mut disk = self.inner.borrow_mut();
-
-        let start_block_id = offset / DISK_BLOCK_SIZE;
-        let mut offset_in_block = offset % DISK_BLOCK_SIZE;
-
-        let bytes_to_write = buf.len();
-        let mut total_bytes_written = 0;
-
-        while total_bytes_written < bytes_to_write {
-            let current_block_id = start_block_id + (total_bytes_written / DISK_BLOCK_SIZE);
-            let bytes_to_copy =
-                (bytes_to_write - total_bytes_written).min(DISK_BLOCK_SIZE - offset_in_block);
-
-            let mut block_data = disk.read_offset(current_block_id * DISK_BLOCK_SIZE);
-
-            block_data[offset_in_block..offset_in_block + bytes_to_copy]
-                .copy_from_slice(&buf[total_bytes_written..total_bytes_written + bytes_to_copy]);
-
-            disk.write_offset(current_block_id * DISK_BLOCK_SIZE, &block_data)
-                .unwrap();
-
-            total_bytes_written += bytes_to_copy;
-            offset_in_block = 0; // After the first block, subsequent blocks start at the beginning
-        }
+        self.inner
+            .borrow_mut()
+            .write_at(offset as u64, buf)
+            .expect("failed to write ext4 block");
     }
 }
 
@@ -115,8 +77,11 @@ impl VfsOps for Ext4FileSystem {
 
     fn umount(&self) -> VfsResult {
         log::info!("umount:");
-        // todo!()
-        Ok(())
+        crate::dev::sync_all().map_err(|_| VfsError::Io)
+    }
+
+    fn use_page_cache(&self) -> bool {
+        true
     }
 }
 
@@ -165,6 +130,17 @@ impl VfsNodeOps for Ext4FileWrapper {
         Ok(VfsNodeAttr::new(perm, ty, size as _, blocks as _))
     }
 
+    /// Set the owner of the node, not supported by `ext4_rs`.
+    fn set_owner(&self, _uid: Option<u32>, _gid: Option<u32>) -> VfsResult {
+        Err(VfsError::Unsupported)
+    }
+
+    /// Set the access and modification times of the node, not supported by
+    /// `ext4_rs`.
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        Err(VfsError::Unsupported)
+    }
+
     // file operations:
 
     /// Read data from the file at the given offset.
@@ -209,13 +185,43 @@ impl VfsNodeOps for Ext4FileWrapper {
     }
 
     /// Flush the file, synchronize the data to disk.
+    ///
+    /// The data written by `write_at` are in the block cache, which is
+    /// written back here.
     fn fsync(&self) -> VfsResult {
-        todo!()
+        crate::dev::sync_all().map_err(|_| VfsError::Io)
     }
 
-    /// Truncate the file to the given size.
+    /// Truncate the file to the given size, not supported by `ext4_rs`.
     fn truncate(&self, _size: u64) -> VfsResult {
-        todo!()
+        Err(VfsError::Unsupported)
+    }
+
+    // symbolic link operations:
+
+    /// Read the target of the symbolic link, which is stored in its data
+    /// blocks.
+    ///
+    /// Creating symbolic links is not supported by `ext4_rs` yet.
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let mut ext4_file = self.ext4_file.lock();
+        let inode_ref = Ext4InodeRef::get_inode_ref(Arc::downgrade(&self.ext4), ext4_file.inode);
+        let (ty, _) = map_imode(inode_ref.inner.inode.mode as u16);
+        if ty != VfsNodeType::SymLink {
+            return Err(VfsError::InvalidInput);
+        }
+        ext4_file.fpos = 0;
+        let read_len = buf.len().min(ext4_file.fsize as usize);
+        let mut read_cnt = 0;
+        self.ext4
+            .ext4_file_read(
+                &mut ext4_file,
+                &mut buf[..read_len],
+                read_len,
+                &mut read_cnt,
+            )
+            .map_err(|_| VfsError::Io)?;
+        Ok(read_len)
     }
 
     // directory operations:
@@ -276,9 +282,10 @@ impl VfsNodeOps for Ext4FileWrapper {
         Ok(())
     }
 
-    /// Remove the node with the given `path` in the directory.
+    /// Remove the node with the given `path` in the directory, not supported
+    /// by `ext4_rs`.
     fn unlink(&self, _path: &RelPath) -> VfsResult {
-        todo!()
+        Err(VfsError::Unsupported)
     }
 
     /// Read directory entries into `dirents`, starting from `start_idx`.
@@ -307,9 +314,10 @@ impl VfsNodeOps for Ext4FileWrapper {
         Ok(dirents.len())
     }
 
-    /// Renames or moves existing file or directory.
+    /// Renames or moves existing file or directory, not supported by
+    /// `ext4_rs`.
     fn rename(&self, _src_path: &RelPath, _dst_path: &RelPath) -> VfsResult {
-        todo!()
+        Err(VfsError::Unsupported)
     }
 
     fn as_any(&self) -> &dyn core::any::Any {
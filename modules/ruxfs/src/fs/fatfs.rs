@@ -7,26 +7,88 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::sync::Arc;
+//! FAT filesystem on a disk.
+//!
+//! FAT has no inode, the inode numbers are derived from the directory entries
+//! instead: the root is 1, and other nodes get a hash of the inode number of
+//! the parent and the short name, which is unique in the directory. They are
+//! stable as long as the node is not renamed.
+
+use alloc::sync::{Arc, Weak};
 use axerrno::ax_err;
 use axfs_vfs::RelPath;
-use core::cell::UnsafeCell;
+use core::time::Duration;
 
 use crate::dev::Disk;
 use axfs_vfs::{VfsDirEntry, VfsError, VfsNodePerm, VfsResult};
 use axfs_vfs::{VfsNodeAttr, VfsNodeOps, VfsNodeRef, VfsNodeType, VfsOps};
-use fatfs::{Dir, File, LossyOemCpConverter, NullTimeProvider, Read, Seek, SeekFrom, Write};
+use fatfs::{Date, DateTime, FileAttributes, LossyOemCpConverter, Time, TimeProvider};
+use fatfs::{Read, Seek, SeekFrom, Write};
 use spin::RwLock;
 
 const BLOCK_SIZE: usize = 512;
+const ROOT_INO: u64 = 1;
 
+type Dir<'a> = fatfs::Dir<'a, Disk, RuxTimeProvider, LossyOemCpConverter>;
+type DirEntry<'a> = fatfs::DirEntry<'a, Disk, RuxTimeProvider, LossyOemCpConverter>;
+type File<'a> = fatfs::File<'a, Disk, RuxTimeProvider, LossyOemCpConverter>;
+
+/// Timestamps of the new and modified files, from the wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuxTimeProvider;
+
+impl TimeProvider for RuxTimeProvider {
+    fn get_current_date(&self) -> Date {
+        self.get_current_date_time().date
+    }
+
+    fn get_current_date_time(&self) -> DateTime {
+        to_fat_time(ruxhal::time::current_time())
+    }
+}
+
+/// The nodes borrow the filesystem, and keep it alive by a reference count
+/// until all of them are dropped.
 pub struct FatFileSystem {
-    inner: fatfs::FileSystem<Disk, NullTimeProvider, LossyOemCpConverter>,
-    root_dir: UnsafeCell<Option<VfsNodeRef>>,
+    inner: fatfs::FileSystem<Disk, RuxTimeProvider, LossyOemCpConverter>,
+    this: Weak<FatFileSystem>,
+    cluster_size: u64,
+}
+
+/// What the directory entry of a node tells.
+#[derive(Debug, Clone, Copy)]
+struct EntryInfo {
+    ino: u64,
+    attrs: FileAttributes,
+    accessed: Duration,
+    modified: Duration,
+    created: Duration,
+}
+
+/// An opened file, with its own cursor so that sequential reads and writes
+/// don't seek through the cluster chain again.
+struct OpenFile<'a> {
+    file: File<'a>,
+    pos: u64,
+    size: u64,
+    accessed: Duration,
+    modified: Duration,
+}
+
+pub struct FileWrapper<'a> {
+    inner: RwLock<OpenFile<'a>>,
+    info: EntryInfo,
+    // dropped after the file borrowing it
+    fs: Arc<FatFileSystem>,
 }
 
-pub struct FileWrapper<'a>(RwLock<File<'a, Disk, NullTimeProvider, LossyOemCpConverter>>);
-pub struct DirWrapper<'a>(Dir<'a, Disk, NullTimeProvider, LossyOemCpConverter>);
+pub struct DirWrapper<'a> {
+    dir: Dir<'a>,
+    info: EntryInfo,
+    parent: Option<Arc<DirWrapper<'a>>>,
+    // dropped after the directory borrowing it
+    fs: Arc<FatFileSystem>,
+}
 
 unsafe impl Sync for FatFileSystem {}
 unsafe impl Send for FatFileSystem {}
@@ -37,38 +99,110 @@ unsafe impl<'a> Sync for DirWrapper<'a> {}
 
 impl FatFileSystem {
     #[cfg(feature = "use-ramdisk")]
-    pub fn new(mut disk: Disk) -> Self {
+    pub fn new(mut disk: Disk) -> Arc<Self> {
         let opts = fatfs::FormatVolumeOptions::new();
         fatfs::format_volume(&mut disk, opts).expect("failed to format volume");
-        let inner = fatfs::FileSystem::new(disk, fatfs::FsOptions::new())
-            .expect("failed to initialize FAT filesystem");
-        Self {
-            inner,
-            root_dir: UnsafeCell::new(None),
-        }
+        Self::mount(disk)
     }
 
     #[cfg(not(feature = "use-ramdisk"))]
-    pub fn new(disk: Disk) -> Self {
-        let inner = fatfs::FileSystem::new(disk, fatfs::FsOptions::new())
-            .expect("failed to initialize FAT filesystem");
-        Self {
+    pub fn new(disk: Disk) -> Arc<Self> {
+        Self::mount(disk)
+    }
+
+    fn mount(mut disk: Disk) -> Arc<Self> {
+        // bytes per sector and sectors per cluster in the boot sector
+        let mut bpb = [0; 14];
+        disk.read_at(0, &mut bpb)
+            .expect("failed to read the boot sector");
+        let cluster_size = u16::from_le_bytes([bpb[11], bpb[12]]) as u64 * bpb[13] as u64;
+        let opts = fatfs::FsOptions::new().time_provider(RuxTimeProvider);
+        let inner =
+            fatfs::FileSystem::new(disk, opts).expect("failed to initialize FAT filesystem");
+        Arc::new_cyclic(|this| Self {
             inner,
-            root_dir: UnsafeCell::new(None),
+            this: this.clone(),
+            cluster_size: cluster_size.max(BLOCK_SIZE as u64),
+        })
+    }
+
+    /// The number of 512-byte blocks taken by `size` bytes of clusters.
+    fn blocks(&self, size: u64) -> u64 {
+        size.div_ceil(self.cluster_size) * (self.cluster_size / BLOCK_SIZE as u64)
+    }
+}
+
+impl EntryInfo {
+    fn new(parent: u64, entry: &DirEntry) -> Self {
+        // FNV-1a
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for &b in parent
+            .to_le_bytes()
+            .iter()
+            .chain(entry.short_file_name_as_bytes())
+        {
+            hash = (hash ^ b as u64).wrapping_mul(0x100_0000_01b3);
+        }
+        Self {
+            ino: if hash <= ROOT_INO { hash + 2 } else { hash },
+            attrs: entry.attributes(),
+            accessed: from_fat_date(entry.accessed()),
+            modified: from_fat_time(entry.modified()),
+            created: from_fat_time(entry.created()),
+        }
+    }
+
+    /// FAT has only the read-only attribute, files are executable so that
+    /// programs can run from it.
+    fn perm(&self) -> VfsNodePerm {
+        let perm = VfsNodePerm::from_bits_truncate(0o755);
+        if self.attrs.contains(FileAttributes::READ_ONLY) {
+            perm - VfsNodePerm::OWNER_WRITE
+        } else {
+            perm
         }
     }
+}
 
-    pub fn init(&'static self) {
-        // must be called before later operations
-        unsafe { *self.root_dir.get() = Some(Self::new_dir(self.inner.root_dir())) }
+impl OpenFile<'_> {
+    /// Moves the cursor to `pos`, which is not past the end of the file.
+    fn seek(&mut self, pos: u64) -> VfsResult {
+        if self.pos != pos {
+            self.file.seek(SeekFrom::Start(pos)).map_err(as_vfs_err)?;
+            self.pos = pos;
+        }
+        Ok(())
     }
 
-    fn new_file(file: File<'_, Disk, NullTimeProvider, LossyOemCpConverter>) -> Arc<FileWrapper> {
-        Arc::new(FileWrapper(RwLock::new(file)))
+    fn write(&mut self, offset: u64, buf: &[u8]) -> VfsResult<usize> {
+        if offset > self.size {
+            // FAT has no hole, fill the gap with zero
+            let size = self.size;
+            self.seek(size)?;
+            let zeros = [0; BLOCK_SIZE];
+            while self.pos < offset {
+                let len = (offset - self.pos).min(BLOCK_SIZE as u64) as usize;
+                if self.write_all(&zeros[..len])? == 0 {
+                    return Err(VfsError::StorageFull);
+                }
+            }
+        }
+        self.seek(offset)?;
+        self.write_all(buf)
     }
 
-    fn new_dir(dir: Dir<'_, Disk, NullTimeProvider, LossyOemCpConverter>) -> Arc<DirWrapper> {
-        Arc::new(DirWrapper(dir))
+    fn write_all(&mut self, buf: &[u8]) -> VfsResult<usize> {
+        let mut total_write = 0;
+        while total_write < buf.len() {
+            let write_len = self.file.write(&buf[total_write..]).map_err(as_vfs_err)?;
+            if write_len == 0 {
+                break;
+            }
+            total_write += write_len;
+            self.pos += write_len as u64;
+        }
+        self.size = self.size.max(self.pos);
+        Ok(total_write)
     }
 }
 
@@ -76,58 +210,92 @@ impl VfsNodeOps for FileWrapper<'static> {
     axfs_vfs::impl_vfs_non_dir_default! {}
 
     fn fsync(&self) -> VfsResult {
-        self.0.write().flush().map_err(as_vfs_err)
+        self.inner.write().file.flush().map_err(as_vfs_err)
     }
 
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        let size = self.0.write().seek(SeekFrom::End(0)).map_err(as_vfs_err)?;
-        let blocks = (size + BLOCK_SIZE as u64 - 1) / BLOCK_SIZE as u64;
-        // FAT fs doesn't support permissions, we just set everything to 755
-        let perm = VfsNodePerm::from_bits_truncate(0o755);
+        let file = self.inner.read();
+        let mut attr = VfsNodeAttr::new(
+            self.info.ino,
+            self.info.perm(),
+            VfsNodeType::File,
+            file.size,
+            self.fs.blocks(file.size),
+        );
+        // FAT has no change time, take the modification time like Linux
+        attr.set_times(file.accessed, file.modified, file.modified);
+        Ok(attr)
+    }
+
+    #[allow(deprecated)]
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        let mut file = self.inner.write();
+        if let Some(atime) = atime {
+            let date = to_fat_time(atime).date;
+            file.file.set_accessed(date);
+            file.accessed = from_fat_date(date);
+        }
+        if let Some(mtime) = mtime {
+            let time = to_fat_time(mtime);
+            file.file.set_modified(time);
+            file.modified = from_fat_time(time);
+        }
+        file.file.flush().map_err(as_vfs_err)
+    }
 
-        // WARN: Inode of files, for musl dynamic linker.
-        // WARN: there will be collision for files with the same size.
-        // TODO: implement real inode.
-        let ino = size + VfsNodeType::File as u64 | perm.bits() as u64;
-        Ok(VfsNodeAttr::new(ino, perm, VfsNodeType::File, size, blocks))
+    fn get_inode(&self) -> Option<u64> {
+        Some(self.info.ino)
     }
 
     fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
-        let mut file = self.0.write();
-        file.seek(SeekFrom::Start(offset)).map_err(as_vfs_err)?;
+        let mut file = self.inner.write();
+        if offset >= file.size {
+            return Ok(0);
+        }
+        file.seek(offset)?;
 
         let mut total_read = 0;
         while total_read < buf.len() {
-            let read_len = file.read(&mut buf[total_read..]).map_err(as_vfs_err)?;
+            let read_len = file.file.read(&mut buf[total_read..]).map_err(as_vfs_err)?;
             if read_len == 0 {
                 break;
             }
             total_read += read_len;
+            file.pos += read_len as u64;
         }
 
         Ok(total_read)
     }
 
     fn write_at(&self, offset: u64, buf: &[u8]) -> VfsResult<usize> {
-        let mut file = self.0.write();
-        file.seek(SeekFrom::Start(offset)).map_err(as_vfs_err)?; // TODO: more efficient
+        let mut file = self.inner.write();
+        file.modified = ruxhal::time::current_time();
+        file.write(offset, buf)
+    }
 
-        let mut total_write = 0;
-        while total_write < buf.len() {
-            let write_len = file.write(&buf[total_write..]).map_err(as_vfs_err)?;
-            if write_len == 0 {
-                break;
-            }
-            total_write += write_len;
+    fn truncate(&self, size: u64) -> VfsResult {
+        let mut file = self.inner.write();
+        file.modified = ruxhal::time::current_time();
+        if size > file.size {
+            return file.write(size, &[]).map(|_| ());
         }
-
-        Ok(total_write)
+        file.seek(size)?;
+        file.file.truncate().map_err(as_vfs_err)?;
+        file.size = size;
+        Ok(())
     }
+}
 
-    fn truncate(&self, size: u64) -> VfsResult {
-        let mut file = self.0.write();
-        file.seek(SeekFrom::Start(size)).map_err(as_vfs_err)?; // TODO: more efficient
-        file.truncate().map_err(as_vfs_err)
+impl<'a> DirWrapper<'a> {
+    /// Finds the entry by the long or the short name, ignoring the case.
+    fn find(&self, name: &str) -> VfsResult<DirEntry<'a>> {
+        for entry in self.dir.iter() {
+            let entry = entry.map_err(as_vfs_err)?;
+            if eq_name(&entry.file_name(), name) || eq_name(&entry.short_file_name(), name) {
+                return Ok(entry);
+            }
+        }
+        Err(VfsError::NotFound)
     }
 }
 
@@ -135,47 +303,74 @@ impl VfsNodeOps for DirWrapper<'static> {
     axfs_vfs::impl_vfs_dir_default! {}
 
     fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
-        Ok(VfsNodeAttr::new(
-            1,
-            VfsNodePerm::from_bits_truncate(0o755),
+        let size = self.fs.cluster_size;
+        let mut attr = VfsNodeAttr::new(
+            self.info.ino,
+            self.info.perm(),
             VfsNodeType::Dir,
-            BLOCK_SIZE as u64,
-            1,
-        ))
+            size,
+            self.fs.blocks(size),
+        );
+        attr.set_times(self.info.accessed, self.info.modified, self.info.modified);
+        Ok(attr)
+    }
+
+    fn get_inode(&self) -> Option<u64> {
+        Some(self.info.ino)
     }
 
     fn set_mode(&self, _mode: VfsNodePerm) -> VfsResult {
         Ok(())
     }
 
+    fn set_times(&self, _atime: Option<Duration>, _mtime: Option<Duration>) -> VfsResult {
+        // the entries of directories can not be edited by `fatfs`
+        Ok(())
+    }
+
     fn parent(&self) -> Option<VfsNodeRef> {
-        self.0
-            .open_dir("..")
-            .map_or(None, |dir| Some(FatFileSystem::new_dir(dir)))
+        self.parent.clone().map(|dir| dir as VfsNodeRef)
     }
 
     fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
         debug!("lookup at fatfs: {}", path);
-        if path.is_empty() {
-            return Ok(self.clone());
-        }
-        if let Ok(Some(is_dir)) = self.0.check_path_type(path) {
-            if is_dir {
-                if let Ok(dir) = self.0.open_dir(path) {
-                    Ok(FatFileSystem::new_dir(dir))
-                } else {
-                    Err(VfsError::NotADirectory)
-                }
+        let mut dir = self;
+        let mut names = path
+            .split('/')
+            .filter(|name| !name.is_empty() && *name != ".")
+            .peekable();
+        while let Some(name) = names.next() {
+            if name == ".." {
+                dir = dir.parent.clone().unwrap_or(dir);
+                continue;
+            }
+            let entry = dir.find(name)?;
+            let info = EntryInfo::new(dir.info.ino, &entry);
+            if entry.is_dir() {
+                dir = Arc::new(DirWrapper {
+                    dir: entry.to_dir(),
+                    info,
+                    parent: Some(dir.clone()),
+                    fs: dir.fs.clone(),
+                });
+            } else if names.peek().is_some() {
+                return Err(VfsError::NotADirectory);
             } else {
-                if let Ok(file) = self.0.open_file(path) {
-                    Ok(FatFileSystem::new_file(file))
-                } else {
-                    Err(VfsError::IsADirectory)
-                }
+                let file = OpenFile {
+                    file: entry.to_file(),
+                    pos: 0,
+                    size: entry.len(),
+                    accessed: info.accessed,
+                    modified: info.modified,
+                };
+                return Ok(Arc::new(FileWrapper {
+                    inner: RwLock::new(file),
+                    info,
+                    fs: dir.fs.clone(),
+                }));
             }
-        } else {
-            Err(VfsError::NotFound)
         }
+        Ok(dir)
     }
 
     fn create(&self, path: &RelPath, ty: VfsNodeType, _mode: VfsNodePerm) -> VfsResult {
@@ -185,11 +380,11 @@ impl VfsNodeOps for DirWrapper<'static> {
         }
         match ty {
             VfsNodeType::File => {
-                self.0.create_file(path).map_err(as_vfs_err)?;
+                self.dir.create_file(path).map_err(as_vfs_err)?;
                 Ok(())
             }
             VfsNodeType::Dir => {
-                self.0.create_dir(path).map_err(as_vfs_err)?;
+                self.dir.create_dir(path).map_err(as_vfs_err)?;
                 Ok(())
             }
             _ => Err(VfsError::Unsupported),
@@ -201,11 +396,11 @@ impl VfsNodeOps for DirWrapper<'static> {
         if path.is_empty() {
             return ax_err!(PermissionDenied);
         }
-        self.0.remove(path).map_err(as_vfs_err)
+        self.dir.remove(path).map_err(as_vfs_err)
     }
 
     fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
-        let mut iter = self.0.iter().skip(start_idx);
+        let mut iter = self.dir.iter().skip(start_idx);
         for (i, out_entry) in dirents.iter_mut().enumerate() {
             let x = iter.next();
             match x {
@@ -232,16 +427,34 @@ impl VfsNodeOps for DirWrapper<'static> {
             src_path, dst_path
         );
 
-        self.0
-            .rename(src_path, &self.0, dst_path)
+        self.dir
+            .rename(src_path, &self.dir, dst_path)
             .map_err(as_vfs_err)
     }
 }
 
 impl VfsOps for FatFileSystem {
     fn root_dir(&self) -> VfsNodeRef {
-        let root_dir = unsafe { (*self.root_dir.get()).as_ref().unwrap() };
-        root_dir.clone()
+        let info = EntryInfo {
+            ino: ROOT_INO,
+            attrs: FileAttributes::DIRECTORY,
+            accessed: Duration::ZERO,
+            modified: Duration::ZERO,
+            created: Duration::ZERO,
+        };
+        // SAFETY: the filesystem is not moved in the `Arc`, and it outlives
+        // the directory as the directory holds the `Arc`.
+        let inner = unsafe { &*(&self.inner as *const fatfs::FileSystem<_, _, _>) };
+        Arc::new(DirWrapper {
+            dir: inner.root_dir(),
+            info,
+            parent: None,
+            fs: self.this.upgrade().unwrap(),
+        })
+    }
+
+    fn use_page_cache(&self) -> bool {
+        true
     }
 }
 
@@ -304,6 +517,69 @@ impl Seek for Disk {
     }
 }
 
+fn eq_name(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_uppercase)
+        .eq(b.chars().flat_map(char::to_uppercase))
+}
+
+/// Days since 1970-01-01 of the date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The date of the days since 1970-01-01, as `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// FAT keeps the local time, which is taken as UTC.
+fn from_fat_date(date: Date) -> Duration {
+    let days = days_from_civil(date.year as i64, date.month as u32, date.day as u32);
+    Duration::from_secs(days.max(0) as u64 * 86400)
+}
+
+fn from_fat_time(time: DateTime) -> Duration {
+    let t = time.time;
+    from_fat_date(time.date)
+        + Duration::from_secs(t.hour as u64 * 3600 + t.min as u64 * 60 + t.sec as u64)
+        + Duration::from_millis(t.millis as u64)
+}
+
+/// Converts the time since the epoch, within the range of FAT from 1980 to
+/// 2107.
+fn to_fat_time(time: Duration) -> DateTime {
+    let min = days_from_civil(1980, 1, 1) as u64 * 86400;
+    let max = days_from_civil(2108, 1, 1) as u64 * 86400 - 1;
+    let secs = time.as_secs().clamp(min, max);
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs = secs % 86400;
+    DateTime::new(
+        Date::new(year as u16, month as u16, day as u16),
+        Time::new(
+            (secs / 3600) as u16,
+            (secs / 60 % 60) as u16,
+            (secs % 60) as u16,
+            time.subsec_millis() as u16,
+        ),
+    )
+}
+
 const fn as_vfs_err(err: fatfs::Error<()>) -> VfsError {
     use fatfs::Error::*;
     match err {
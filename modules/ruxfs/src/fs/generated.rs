@@ -0,0 +1,126 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Nodes whose content is generated when accessed, shared by the procfs and
+//! the sysfs.
+
+use alloc::{boxed::Box, string::String, sync::Arc};
+use axfs_vfs::{
+    RelPath, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
+    VfsResult,
+};
+
+/// Splits the first component of the path.
+pub(crate) fn split_path<'a>(path: &'a RelPath) -> (&'a str, Option<RelPath<'a>>) {
+    path.find('/').map_or((path, None), |n| {
+        (&path[..n], Some(RelPath::new(&path[n + 1..])))
+    })
+}
+
+/// Looks up the rest of the path in the node, if any.
+pub(crate) fn lookup_rest(node: VfsNodeRef, rest: Option<RelPath>) -> VfsResult<VfsNodeRef> {
+    match rest {
+        Some(rest) => node.lookup(&rest),
+        None => Ok(node),
+    }
+}
+
+/// Fills the directory entries from the `start_idx`-th one.
+pub(crate) fn read_entries<I>(entries: I, start_idx: usize, dirents: &mut [VfsDirEntry]) -> usize
+where
+    I: Iterator<Item = (String, VfsNodeType)>,
+{
+    let mut count = 0;
+    for ((name, ty), ent) in entries.skip(start_idx).zip(dirents.iter_mut()) {
+        *ent = VfsDirEntry::new(&name, ty);
+        count += 1;
+    }
+    count
+}
+
+/// A read-only file whose content is generated when read.
+pub(crate) struct GeneratedFile {
+    ino: u64,
+    generate: Box<dyn Fn() -> String + Send + Sync>,
+}
+
+impl GeneratedFile {
+    pub(crate) fn new(
+        ino: u64,
+        generate: impl Fn() -> String + Send + Sync + 'static,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            ino,
+            generate: Box::new(generate),
+        })
+    }
+}
+
+impl VfsNodeOps for GeneratedFile {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        // the size is unknown until generated, as in procfs of Linux
+        Ok(VfsNodeAttr::new(
+            self.ino,
+            VfsNodePerm::from_bits_truncate(0o444),
+            VfsNodeType::File,
+            0,
+            0,
+        ))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> VfsResult<usize> {
+        let content = (self.generate)();
+        let start = content.len().min(offset as usize);
+        let len = (content.len() - start).min(buf.len());
+        buf[..len].copy_from_slice(&content.as_bytes()[start..start + len]);
+        Ok(len)
+    }
+
+    fn truncate(&self, _size: u64) -> VfsResult {
+        Err(VfsError::PermissionDenied)
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
+}
+
+/// A symbolic link whose target is generated when read, e.g. `/proc/self`.
+pub(crate) struct GeneratedLink {
+    ino: u64,
+    target: Box<dyn Fn() -> String + Send + Sync>,
+}
+
+impl GeneratedLink {
+    pub(crate) fn new(ino: u64, target: impl Fn() -> String + Send + Sync + 'static) -> Arc<Self> {
+        Arc::new(Self {
+            ino,
+            target: Box::new(target),
+        })
+    }
+}
+
+impl VfsNodeOps for GeneratedLink {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            self.ino,
+            VfsNodePerm::default_symlink(),
+            VfsNodeType::SymLink,
+            (self.target)().len() as _,
+            0,
+        ))
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let target = (self.target)();
+        let len = target.len().min(buf.len());
+        buf[..len].copy_from_slice(&target.as_bytes()[..len]);
+        Ok(len)
+    }
+
+    axfs_vfs::impl_vfs_non_dir_default! {}
+}
@@ -7,23 +7,69 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "myfs")] {
-        pub mod myfs;
-    } else if #[cfg(feature = "fatfs")] {
-        pub mod fatfs;
-    // TODO: wait for CI support for ext4
-    // } else if #[cfg(feature = "lwext4_rust")] {
-    //     pub mod lwext4_rust;
-    } else if #[cfg(feature = "ext4_rs")] {
-        pub mod ext4_rs;
-    } else if #[cfg(feature = "another_ext4")] {
-        pub mod another_ext4;
-    }
-}
+#[cfg(feature = "myfs")]
+pub mod myfs;
+
+#[cfg(any(feature = "procfs", feature = "sysfs"))]
+mod generated;
+#[cfg(feature = "procfs")]
+pub mod procfs;
+#[cfg(feature = "sysfs")]
+pub mod sysfs;
+
+#[cfg(feature = "fatfs")]
+pub mod fatfs;
+// TODO: wait for CI support for ext4
+// #[cfg(feature = "lwext4_rust")]
+// pub mod lwext4_rust;
+#[cfg(feature = "another_ext4")]
+pub mod another_ext4;
+#[cfg(feature = "ext4_rs")]
+pub mod ext4_rs;
 
 #[cfg(feature = "devfs")]
 pub use axfs_devfs as devfs;
 
 #[cfg(feature = "ramfs")]
 pub use axfs_ramfs as ramfs;
+
+#[cfg(feature = "blkfs")]
+use {
+    crate::{dev::Disk, partition::FsType},
+    alloc::sync::Arc,
+    axfs_vfs::VfsOps,
+};
+
+/// The filesystem type of the first disk if none is recognized, the first
+/// enabled one in the order of FAT and ext4.
+#[cfg(feature = "blkfs")]
+pub(crate) const DEFAULT_BLKFS: FsType = if cfg!(feature = "fatfs") {
+    FsType::Fat
+} else {
+    FsType::Ext4
+};
+
+/// Creates the filesystem of type `ty` on the disk, returns [`None`] if the
+/// filesystem is not enabled.
+#[cfg(feature = "blkfs")]
+#[allow(unused_variables)]
+pub(crate) fn new_blkfs(disk: Disk, ty: FsType) -> Option<Arc<dyn VfsOps>> {
+    #[cfg(feature = "fatfs")]
+    if ty == FsType::Fat {
+        return Some(fatfs::FatFileSystem::new(disk));
+    }
+    // TODO: wait for CI support for ext4
+    // #[cfg(feature = "lwext4_rust")]
+    // if ty == FsType::Ext4 {
+    //     return Some(Arc::new(lwext4_rust::Ext4FileSystem::new(disk)));
+    // }
+    #[cfg(feature = "ext4_rs")]
+    if ty == FsType::Ext4 {
+        return Some(Arc::new(ext4_rs::Ext4FileSystem::new(disk)));
+    }
+    #[cfg(feature = "another_ext4")]
+    if ty == FsType::Ext4 {
+        return Some(Arc::new(another_ext4::Ext4FileSystem::new(disk)));
+    }
+    None
+}
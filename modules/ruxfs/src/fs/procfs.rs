@@ -0,0 +1,609 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The process filesystem mounted at `/proc`.
+//!
+//! The files are generated from the kernel state each time they are read,
+//! e.g. `/proc/<pid>/stat`, `/proc/loadavg` and `/proc/meminfo`. The state of
+//! the processes is provided by the task module through [`ProcfsIf`], as this
+//! crate does not depend on it, and the files under `/proc/net` are registered
+//! by the network module with [`register_proc_net_file`].
+//!
+//! The other entries, e.g. the tunables in `/proc/sys`, are kept in a RAM
+//! filesystem under the root directory.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use axfs_ramfs::{DirNode, RamFileSystem};
+use axfs_vfs::{
+    RelPath, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
+    VfsOps, VfsResult,
+};
+use core::time::Duration;
+use memory_addr::PAGE_SIZE_4K;
+use spin::Mutex;
+
+use super::generated::{lookup_rest, read_entries, split_path, GeneratedFile, GeneratedLink};
+
+/// Clock ticks per second of the times in `/proc`, i.e. `USER_HZ`.
+const USER_HZ: u64 = 100;
+
+/// Information of a process, shown in `/proc/<pid>/stat` and
+/// `/proc/<pid>/status`.
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    /// ID of the parent process, or 0 if there is none.
+    pub ppid: u64,
+    /// Name of the process.
+    pub name: String,
+    /// State of the process, `R` for running or ready, `S` for blocked and
+    /// `Z` for exited but not waited yet.
+    pub state: char,
+    /// Nice value of the process.
+    pub nice: isize,
+    /// CPU time spent by the process.
+    pub cpu_time: Duration,
+    /// Total size of the memory mappings in bytes.
+    pub vm_size: usize,
+    /// Size of the mapped pages in memory in bytes.
+    pub vm_rss: usize,
+}
+
+/// A memory mapping of a process, shown in `/proc/<pid>/maps`.
+#[derive(Debug, Clone)]
+pub struct MapArea {
+    /// Start address of the mapping.
+    pub start: usize,
+    /// End address of the mapping, exclusive.
+    pub end: usize,
+    /// Whether the mapping is readable.
+    pub readable: bool,
+    /// Whether the mapping is writable.
+    pub writable: bool,
+    /// Whether the mapping is executable.
+    pub executable: bool,
+    /// Whether the mapping is shared with other processes.
+    pub shared: bool,
+    /// Offset of the mapping in the file.
+    pub offset: usize,
+    /// Path of the mapped file, or empty for anonymous mappings.
+    pub path: String,
+}
+
+/// The interface to get the state of processes for the procfs.
+#[crate_interface::def_interface]
+pub trait ProcfsIf {
+    /// Returns the IDs of all processes.
+    fn pids() -> Vec<u64>;
+    /// Returns the ID of the current process.
+    fn current_pid() -> u64;
+    /// Returns the information of the process, or `None` if it does not
+    /// exist.
+    fn process(pid: u64) -> Option<ProcessInfo>;
+    /// Returns the memory mappings of the process.
+    fn maps(pid: u64) -> Vec<MapArea>;
+    /// Returns the file descriptors opened by the process, with the paths of
+    /// the files.
+    fn files(pid: u64) -> Vec<(usize, String)>;
+    /// Returns the command line arguments of the process.
+    fn cmdline(pid: u64) -> Vec<String>;
+    /// Returns the environment variables of the process.
+    fn environ(pid: u64) -> Vec<String>;
+    /// Returns the time since boot, and the time spent in idle.
+    fn uptime() -> (Duration, Duration);
+    /// Returns the load averages of the last 1, 5 and 15 minutes, in
+    /// hundredths.
+    fn loadavg() -> [u64; 3];
+}
+
+fn process(pid: u64) -> Option<ProcessInfo> {
+    crate_interface::call_interface!(ProcfsIf::process, pid)
+}
+
+/// Converts the time to clock ticks in `USER_HZ`.
+fn clock_ticks(time: Duration) -> u64 {
+    time.as_millis() as u64 * USER_HZ / 1000
+}
+
+/// Formats the time in seconds with 2 decimals.
+fn seconds(time: Duration) -> String {
+    format!("{}.{:02}", time.as_secs(), time.subsec_millis() / 10)
+}
+
+/// Joins the strings, each of which ends with a NUL.
+fn nul_separated(strs: Vec<String>) -> String {
+    strs.into_iter().map(|s| s + "\0").collect()
+}
+
+/* Files under the root directory. */
+
+fn loadavg() -> String {
+    let loads = crate_interface::call_interface!(ProcfsIf::loadavg);
+    let pids = crate_interface::call_interface!(ProcfsIf::pids);
+    let running = pids
+        .iter()
+        .filter(|&&pid| process(pid).is_some_and(|info| info.state == 'R'))
+        .count();
+    let last_pid = pids.iter().max().copied().unwrap_or(0);
+    format!(
+        "{}.{:02} {}.{:02} {}.{:02} {running}/{} {last_pid}\n",
+        loads[0] / 100,
+        loads[0] % 100,
+        loads[1] / 100,
+        loads[1] % 100,
+        loads[2] / 100,
+        loads[2] % 100,
+        pids.len(),
+    )
+}
+
+fn mounts() -> String {
+    crate::fops::root_dir().mount_table()
+}
+
+fn stat() -> String {
+    let (uptime, idle) = crate_interface::call_interface!(ProcfsIf::uptime);
+    let (idle, busy) = (clock_ticks(idle), clock_ticks(uptime.saturating_sub(idle)));
+    let pids = crate_interface::call_interface!(ProcfsIf::pids);
+    let states = pids
+        .iter()
+        .filter_map(|&pid| process(pid).map(|info| info.state))
+        .collect::<Vec<_>>();
+    let running = states.iter().filter(|&&state| state == 'R').count();
+    let blocked = states.iter().filter(|&&state| state == 'S').count();
+    // user nice system idle iowait irq softirq steal guest guest_nice
+    format!(
+        "cpu  {busy} 0 0 {idle} 0 0 0 0 0 0\n\
+        cpu0 {busy} 0 0 {idle} 0 0 0 0 0 0\n\
+        btime 0\n\
+        processes {}\n\
+        procs_running {running}\n\
+        procs_blocked {blocked}\n",
+        pids.len(),
+    )
+}
+
+fn uptime() -> String {
+    let (uptime, idle) = crate_interface::call_interface!(ProcfsIf::uptime);
+    format!("{} {}\n", seconds(uptime), seconds(idle))
+}
+
+/// Returns the files generated under the root directory.
+fn root_files() -> Vec<(&'static str, fn() -> String)> {
+    let mut files: Vec<(&'static str, fn() -> String)> = alloc::vec![
+        ("filesystems", crate::fstype::filesystems),
+        ("loadavg", loadavg),
+        ("mounts", mounts),
+        ("stat", stat),
+        ("uptime", uptime),
+    ];
+    #[cfg(feature = "alloc")]
+    files.extend([
+        ("cpuinfo", crate::arch::get_cpuinfo as fn() -> String),
+        ("meminfo", crate::arch::get_meminfo),
+    ]);
+    files
+}
+
+/// Files generated under `/proc/net`, registered by the network module.
+static NET_FILES: Mutex<Vec<(&'static str, fn() -> String)>> = Mutex::new(Vec::new());
+
+/// Registers a file generated under `/proc/net`, e.g. `route`.
+pub fn register_proc_net_file(name: &'static str, generate: fn() -> String) {
+    NET_FILES.lock().push((name, generate));
+}
+
+/* Files under the directories of processes. */
+
+fn pid_cmdline(pid: u64) -> String {
+    nul_separated(crate_interface::call_interface!(ProcfsIf::cmdline, pid))
+}
+
+fn pid_comm(pid: u64) -> String {
+    process(pid).map_or(String::new(), |info| info.name + "\n")
+}
+
+fn pid_environ(pid: u64) -> String {
+    nul_separated(crate_interface::call_interface!(ProcfsIf::environ, pid))
+}
+
+fn pid_maps(pid: u64) -> String {
+    let mut maps = String::new();
+    for area in crate_interface::call_interface!(ProcfsIf::maps, pid) {
+        let line = format!(
+            "{:08x}-{:08x} {}{}{}{} {:08x} 00:00 0",
+            area.start,
+            area.end,
+            if area.readable { 'r' } else { '-' },
+            if area.writable { 'w' } else { '-' },
+            if area.executable { 'x' } else { '-' },
+            if area.shared { 's' } else { 'p' },
+            area.offset,
+        );
+        if area.path.is_empty() {
+            maps += &format!("{line}\n");
+        } else {
+            maps += &format!("{line:<72} {}\n", area.path);
+        }
+    }
+    maps
+}
+
+fn pid_mounts(_pid: u64) -> String {
+    mounts()
+}
+
+fn pid_stat(pid: u64) -> String {
+    let Some(info) = process(pid) else {
+        return String::new();
+    };
+    // pid comm state ppid pgrp session tty_nr tpgid flags minflt cminflt
+    // majflt cmajflt utime stime cutime cstime priority nice num_threads
+    // itrealvalue starttime vsize rss
+    format!(
+        "{pid} ({}) {} {} {pid} {pid} 0 -1 0 0 0 0 0 {} 0 0 0 {} {} 1 0 0 {} {}\n",
+        info.name,
+        info.state,
+        info.ppid,
+        clock_ticks(info.cpu_time),
+        20 + info.nice,
+        info.nice,
+        info.vm_size,
+        info.vm_rss / PAGE_SIZE_4K,
+    )
+}
+
+fn pid_status(pid: u64) -> String {
+    let Some(info) = process(pid) else {
+        return String::new();
+    };
+    let state = match info.state {
+        'R' => "R (running)",
+        'S' => "S (sleeping)",
+        _ => "Z (zombie)",
+    };
+    format!(
+        "Name:\t{}\nState:\t{state}\nTgid:\t{pid}\nPid:\t{pid}\nPPid:\t{}\n\
+        VmSize:\t{:8} kB\nVmRSS:\t{:8} kB\n",
+        info.name,
+        info.ppid,
+        info.vm_size / 1024,
+        info.vm_rss / 1024,
+    )
+}
+
+/// Files generated under the directory of each process.
+const PROCESS_FILES: &[(&str, fn(u64) -> String)] = &[
+    ("cmdline", pid_cmdline),
+    ("comm", pid_comm),
+    ("environ", pid_environ),
+    ("maps", pid_maps),
+    ("mounts", pid_mounts),
+    ("stat", pid_stat),
+    ("status", pid_status),
+];
+
+/* Nodes. */
+
+/// Inode number of the `index`-th entry under the directory of the process,
+/// or of the directory itself if `index` is 0.
+const fn process_ino(pid: u64, index: u64) -> u64 {
+    (pid << 16) | index
+}
+
+/// The directory `/proc/<pid>/fd`, with a link to the file of each opened
+/// file descriptor.
+struct FdDir {
+    pid: u64,
+    parent: VfsNodeRef,
+}
+
+impl VfsNodeOps for FdDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            process_ino(self.pid, PROCESS_FILES.len() as u64 + 1),
+            VfsNodePerm::from_bits_truncate(0o500),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        let node: VfsNodeRef = match name {
+            "" => self,
+            ".." => self.parent.clone(),
+            _ => {
+                let fd = name.parse::<usize>().map_err(|_| VfsError::NotFound)?;
+                let (_, path) = crate_interface::call_interface!(ProcfsIf::files, self.pid)
+                    .into_iter()
+                    .find(|&(opened, _)| opened == fd)
+                    .ok_or(VfsError::NotFound)?;
+                let ino = process_ino(self.pid, 0x100 + fd as u64);
+                GeneratedLink::new(ino, move || path.clone())
+            }
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let files = crate_interface::call_interface!(ProcfsIf::files, self.pid);
+        let entries = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                files
+                    .into_iter()
+                    .map(|(fd, _)| (fd.to_string(), VfsNodeType::SymLink)),
+            );
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// Inode number of the directory `/proc/net`, followed by its files.
+const NET_DIR_INO: u64 = 0x100;
+
+/// The directory `/proc/net`, with the files registered by
+/// [`register_proc_net_file`].
+struct NetDir {
+    parent: VfsNodeRef,
+}
+
+impl VfsNodeOps for NetDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            NET_DIR_INO,
+            VfsNodePerm::from_bits_truncate(0o555),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        let node: VfsNodeRef = match name {
+            "" => self,
+            ".." => self.parent.clone(),
+            _ => {
+                let files = NET_FILES.lock();
+                let index = files
+                    .iter()
+                    .position(|(file, _)| *file == name)
+                    .ok_or(VfsError::NotFound)?;
+                GeneratedFile::new(NET_DIR_INO + 1 + index as u64, files[index].1)
+            }
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let files = NET_FILES.lock().clone();
+        let entries = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                files
+                    .into_iter()
+                    .map(|(name, _)| (name.to_string(), VfsNodeType::File)),
+            );
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The directory `/proc/<pid>` of a process.
+struct ProcessDir {
+    pid: u64,
+    parent: VfsNodeRef,
+}
+
+impl VfsNodeOps for ProcessDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            process_ino(self.pid, 0),
+            VfsNodePerm::from_bits_truncate(0o555),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        let pid = self.pid;
+        let node: VfsNodeRef = match name {
+            "" => self,
+            ".." => self.parent.clone(),
+            "fd" => Arc::new(FdDir {
+                pid,
+                parent: self.clone(),
+            }),
+            _ => {
+                let (index, &(_, generate)) = PROCESS_FILES
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (file, _))| *file == name)
+                    .ok_or(VfsError::NotFound)?;
+                GeneratedFile::new(process_ino(pid, index as u64 + 1), move || generate(pid))
+            }
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let entries = [".", "..", "fd"]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                PROCESS_FILES
+                    .iter()
+                    .map(|(name, _)| (name.to_string(), VfsNodeType::File)),
+            );
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The root directory of the procfs.
+struct ProcRootDir {
+    /// The entries not generated, e.g. `/proc/sys`.
+    static_dir: Arc<DirNode>,
+}
+
+impl ProcRootDir {
+    /// Whether the entry is generated, which can not be created or removed.
+    fn is_generated(&self, name: &str) -> bool {
+        name == "self"
+            || name == "net"
+            || root_files().iter().any(|(file, _)| *file == name)
+            || name.parse::<u64>().is_ok_and(|pid| process(pid).is_some())
+    }
+}
+
+impl VfsNodeOps for ProcRootDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            1,
+            VfsNodePerm::from_bits_truncate(0o555),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        self.static_dir.parent()
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        if name.is_empty() {
+            return Ok(self);
+        }
+        let files = root_files();
+        let node: VfsNodeRef = if name == "self" {
+            GeneratedLink::new(2, || {
+                crate_interface::call_interface!(ProcfsIf::current_pid).to_string()
+            })
+        } else if name == "net" {
+            Arc::new(NetDir { parent: self })
+        } else if let Some(index) = files.iter().position(|(file, _)| *file == name) {
+            GeneratedFile::new(3 + index as u64, files[index].1)
+        } else if let Some(pid) = name
+            .parse::<u64>()
+            .ok()
+            .filter(|&pid| process(pid).is_some())
+        {
+            Arc::new(ProcessDir { pid, parent: self })
+        } else {
+            return self.static_dir.clone().lookup(path);
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let static_entries = self.static_dir.get_entries().into_iter().map(|name| {
+            let ty = self
+                .static_dir
+                .clone()
+                .lookup(&RelPath::new(&name))
+                .and_then(|node| node.get_attr())
+                .map_or(VfsNodeType::File, |attr| attr.file_type());
+            (name, ty)
+        });
+        let entries = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                root_files()
+                    .into_iter()
+                    .map(|(name, _)| (name.to_string(), VfsNodeType::File)),
+            )
+            .chain(core::iter::once(("net".to_string(), VfsNodeType::Dir)))
+            .chain(core::iter::once(("self".to_string(), VfsNodeType::SymLink)))
+            .chain(
+                crate_interface::call_interface!(ProcfsIf::pids)
+                    .into_iter()
+                    .map(|pid| (pid.to_string(), VfsNodeType::Dir)),
+            )
+            .chain(static_entries);
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    fn create(&self, path: &RelPath, ty: VfsNodeType, mode: VfsNodePerm) -> VfsResult {
+        if self.is_generated(split_path(path).0) {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.static_dir.create(path, ty, mode)
+    }
+
+    fn unlink(&self, path: &RelPath) -> VfsResult {
+        if self.is_generated(split_path(path).0) {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.static_dir.unlink(path)
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The process filesystem, which implements [`VfsOps`].
+pub struct ProcFileSystem {
+    static_fs: RamFileSystem,
+    root: Arc<ProcRootDir>,
+}
+
+impl ProcFileSystem {
+    /// Creates a new procfs, with no static entries.
+    pub fn new() -> Self {
+        let static_fs = RamFileSystem::new();
+        let root = Arc::new(ProcRootDir {
+            static_dir: static_fs.root_dir_node(),
+        });
+        Self { static_fs, root }
+    }
+}
+
+impl Default for ProcFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VfsOps for ProcFileSystem {
+    fn mount(&self, parent: VfsNodeRef) -> VfsResult {
+        self.static_fs.mount(parent)
+    }
+
+    fn root_dir(&self) -> VfsNodeRef {
+        self.root.clone()
+    }
+}
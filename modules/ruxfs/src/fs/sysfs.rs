@@ -0,0 +1,561 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The system filesystem mounted at `/sys`.
+//!
+//! The entries of the devices are generated each time they are accessed:
+//!
+//! - `/sys/block/<dev>`: the registered disks and their partitions.
+//! - `/sys/devices/virtual/net/<if>`, linked from `/sys/class/net/<if>`: the
+//!   network interfaces, which are registered by the network module with
+//!   [`register_net_interface`], as this crate does not depend on it.
+//! - `/sys/devices/system/cpu`: the CPU topology.
+//! - `/sys/devices/system/clocksource`: the clock source of the platform.
+//!
+//! The other entries, e.g. `/sys/kernel/mm/transparent_hugepage/enabled`, are
+//! kept in a RAM filesystem under the root directory.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use axfs_ramfs::{DirNode, RamFileSystem};
+use axfs_vfs::{
+    RelPath, VfsDirEntry, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
+    VfsOps, VfsResult,
+};
+use core::fmt::Display;
+use core::sync::atomic::{AtomicU64, Ordering};
+use spin::Mutex;
+
+use super::generated::{lookup_rest, read_entries, split_path, GeneratedFile, GeneratedLink};
+
+/// Statistics of a network interface, shown in
+/// `/sys/class/net/<if>/statistics`.
+#[derive(Debug, Default)]
+pub struct NetStatistics {
+    /// Bytes received.
+    pub rx_bytes: AtomicU64,
+    /// Packets received.
+    pub rx_packets: AtomicU64,
+    /// Errors on receiving.
+    pub rx_errors: AtomicU64,
+    /// Received packets dropped.
+    pub rx_dropped: AtomicU64,
+    /// Bytes transmitted.
+    pub tx_bytes: AtomicU64,
+    /// Packets transmitted.
+    pub tx_packets: AtomicU64,
+    /// Errors on transmitting.
+    pub tx_errors: AtomicU64,
+    /// Packets dropped on transmitting.
+    pub tx_dropped: AtomicU64,
+}
+
+impl NetStatistics {
+    /// Counts a received packet of `len` bytes.
+    pub fn add_rx(&self, len: usize) {
+        self.rx_packets.fetch_add(1, Ordering::Relaxed);
+        self.rx_bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Counts a transmitted packet of `len` bytes.
+    pub fn add_tx(&self, len: usize) {
+        self.tx_packets.fetch_add(1, Ordering::Relaxed);
+        self.tx_bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// The counters with the names of their files.
+    fn counters(&self) -> [(&'static str, &AtomicU64); 8] {
+        [
+            ("rx_bytes", &self.rx_bytes),
+            ("rx_dropped", &self.rx_dropped),
+            ("rx_errors", &self.rx_errors),
+            ("rx_packets", &self.rx_packets),
+            ("tx_bytes", &self.tx_bytes),
+            ("tx_dropped", &self.tx_dropped),
+            ("tx_errors", &self.tx_errors),
+            ("tx_packets", &self.tx_packets),
+        ]
+    }
+}
+
+#[derive(Clone)]
+struct NetInterface {
+    name: String,
+    address: [u8; 6],
+    mtu: usize,
+    stats: Arc<NetStatistics>,
+}
+
+/// Network interfaces in the order of registration.
+static NET_INTERFACES: Mutex<Vec<NetInterface>> = Mutex::new(Vec::new());
+
+/// Register a network interface with its hardware address and MTU.
+///
+/// Returns the statistics of the interface, which are to be updated by the
+/// caller on receiving and transmitting.
+pub fn register_net_interface(name: &str, address: [u8; 6], mtu: usize) -> Arc<NetStatistics> {
+    let stats = Arc::new(NetStatistics::default());
+    NET_INTERFACES.lock().push(NetInterface {
+        name: String::from(name),
+        address,
+        mtu,
+        stats: stats.clone(),
+    });
+    stats
+}
+
+/* Tree of the generated entries. */
+
+type Generate<T> = Arc<dyn Fn() -> T + Send + Sync>;
+
+/// A generated entry.
+#[derive(Clone)]
+enum Entry {
+    /// A file with the content generated when read.
+    File(Generate<String>),
+    /// A directory with the entries generated when accessed.
+    Dir(Generate<Vec<(String, Entry)>>),
+    /// A symbolic link to the target.
+    Link(String),
+}
+
+impl Entry {
+    fn node_type(&self) -> VfsNodeType {
+        match self {
+            Self::File(_) => VfsNodeType::File,
+            Self::Dir(_) => VfsNodeType::Dir,
+            Self::Link(_) => VfsNodeType::SymLink,
+        }
+    }
+}
+
+fn file(name: &str, generate: impl Fn() -> String + Send + Sync + 'static) -> (String, Entry) {
+    (name.to_string(), Entry::File(Arc::new(generate)))
+}
+
+/// A file with the value got when its directory is accessed.
+fn value(name: &str, value: impl Display) -> (String, Entry) {
+    let content = format!("{value}\n");
+    file(name, move || content.clone())
+}
+
+fn dir(
+    name: &str,
+    entries: impl Fn() -> Vec<(String, Entry)> + Send + Sync + 'static,
+) -> (String, Entry) {
+    (name.to_string(), Entry::Dir(Arc::new(entries)))
+}
+
+fn link(name: &str, target: &str) -> (String, Entry) {
+    (name.to_string(), Entry::Link(target.to_string()))
+}
+
+/// Inode number of the entry, from the hash of its path.
+fn ino(path: &str) -> u64 {
+    // FNV-1a
+    let hash = path.bytes().fold(0xcbf2_9ce4_8422_2325, |hash: u64, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    // 1 is the root directory
+    hash.max(2)
+}
+
+fn new_node(entry: Entry, path: String, parent: VfsNodeRef) -> VfsNodeRef {
+    match entry {
+        Entry::File(generate) => GeneratedFile::new(ino(&path), move || generate()),
+        Entry::Dir(entries) => Arc::new(GeneratedDir {
+            path,
+            entries,
+            parent,
+        }),
+        Entry::Link(target) => GeneratedLink::new(ino(&path), move || target.clone()),
+    }
+}
+
+/* Entries under the directories. */
+
+/// Formats the list of CPUs from 0 to `n - 1`, e.g. `0-3`.
+fn cpu_list(n: usize) -> String {
+    if n > 1 {
+        format!("0-{}", n - 1)
+    } else {
+        String::from("0")
+    }
+}
+
+fn cpu_entries() -> Vec<(String, Entry)> {
+    let cpus = ruxconfig::SMP;
+    let mut entries = vec![
+        value("kernel_max", cpus - 1),
+        value("offline", ""),
+        value("online", cpu_list(cpus)),
+        value("possible", cpu_list(cpus)),
+        value("present", cpu_list(cpus)),
+    ];
+    entries.extend((0..cpus).map(|cpu| {
+        dir(&format!("cpu{cpu}"), move || {
+            vec![
+                value("online", 1),
+                dir("topology", move || {
+                    // each CPU is a core of the only package, without SMT
+                    vec![
+                        value("core_id", cpu),
+                        value("core_siblings_list", cpu_list(cpus)),
+                        value("physical_package_id", 0),
+                        value("thread_siblings_list", cpu),
+                    ]
+                }),
+            ]
+        })
+    }));
+    entries
+}
+
+fn clocksource_entries() -> Vec<(String, Entry)> {
+    vec![dir("clocksource0", || {
+        vec![
+            value("available_clocksource", ruxhal::time::CLOCK_SOURCE),
+            value("current_clocksource", ruxhal::time::CLOCK_SOURCE),
+        ]
+    })]
+}
+
+fn net_entries() -> Vec<(String, Entry)> {
+    NET_INTERFACES
+        .lock()
+        .iter()
+        .map(|iface| {
+            let iface = iface.clone();
+            let name = iface.name.clone();
+            dir(&name, move || net_interface_entries(&iface))
+        })
+        .collect()
+}
+
+/// Links to the directories of the network interfaces in
+/// `/sys/devices/virtual/net`.
+fn class_net_entries() -> Vec<(String, Entry)> {
+    NET_INTERFACES
+        .lock()
+        .iter()
+        .map(|iface| {
+            let target = format!("../../devices/virtual/net/{}", iface.name);
+            link(&iface.name, &target)
+        })
+        .collect()
+}
+
+fn net_interface_entries(iface: &NetInterface) -> Vec<(String, Entry)> {
+    let address = iface
+        .address
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(":");
+    let stats = iface.stats.clone();
+    vec![
+        value("addr_len", iface.address.len()),
+        value("address", address),
+        value("mtu", iface.mtu),
+        value("operstate", "up"),
+        dir("statistics", move || {
+            (0..stats.counters().len())
+                .map(|i| {
+                    let stats = stats.clone();
+                    file(stats.counters()[i].0, move || {
+                        format!("{}\n", stats.counters()[i].1.load(Ordering::Relaxed))
+                    })
+                })
+                .collect()
+        }),
+    ]
+}
+
+/// Sizes in the sysfs are in 512-byte sectors, whatever the block size is.
+#[cfg(feature = "blkfs")]
+const SECTOR_SIZE: u64 = 512;
+
+/// Returns the disk which the partition is on, e.g. `vda` of `vda1`.
+#[cfg(feature = "blkfs")]
+fn disk_of<'a>(name: &str, names: &'a [String]) -> Option<&'a String> {
+    names.iter().find(|disk| {
+        name.strip_prefix(disk.as_str())
+            .is_some_and(|num| !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+#[cfg(feature = "blkfs")]
+fn block_entries() -> Vec<(String, Entry)> {
+    let names = crate::dev::names();
+    names
+        .iter()
+        .filter(|name| disk_of(name, &names).is_none())
+        .map(|disk| {
+            let parts = names
+                .iter()
+                .filter(|name| disk_of(name, &names) == Some(disk))
+                .cloned()
+                .collect::<Vec<_>>();
+            let name = disk.clone();
+            dir(disk, move || block_dev_entries(&name, &parts))
+        })
+        .collect()
+}
+
+/// Links to the directories of the disks and partitions in `/sys/block`.
+#[cfg(feature = "blkfs")]
+fn class_block_entries() -> Vec<(String, Entry)> {
+    let names = crate::dev::names();
+    names
+        .iter()
+        .map(|name| match disk_of(name, &names) {
+            Some(disk) => link(name, &format!("../../block/{disk}/{name}")),
+            None => link(name, &format!("../../block/{name}")),
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "blkfs"))]
+fn block_entries() -> Vec<(String, Entry)> {
+    Vec::new()
+}
+
+#[cfg(not(feature = "blkfs"))]
+fn class_block_entries() -> Vec<(String, Entry)> {
+    Vec::new()
+}
+
+#[cfg(feature = "blkfs")]
+fn block_dev_entries(name: &str, parts: &[String]) -> Vec<(String, Entry)> {
+    use crate::dev::{BLOCK_SIZE, BUFFER_SIZE, MAX_REQUEST, READ_AHEAD};
+
+    let Some(disk) = crate::dev::open(name) else {
+        return Vec::new();
+    };
+    let mut entries = vec![
+        dir("queue", || {
+            vec![
+                value("hw_sector_size", BLOCK_SIZE),
+                value("logical_block_size", BLOCK_SIZE),
+                value("max_sectors_kb", MAX_REQUEST as usize * BUFFER_SIZE / 1024),
+                value("physical_block_size", BLOCK_SIZE),
+                value("read_ahead_kb", READ_AHEAD as usize * BUFFER_SIZE / 1024),
+                value("rotational", 0),
+            ]
+        }),
+        value("removable", 0),
+        value("ro", 0),
+        value("size", disk.size() / SECTOR_SIZE),
+    ];
+    entries.extend(parts.iter().filter_map(|part| {
+        let size = crate::dev::open(part)?.size() / SECTOR_SIZE;
+        let number = part[name.len()..].to_string();
+        Some(dir(part, move || {
+            vec![value("partition", &number), value("size", size)]
+        }))
+    }));
+    entries
+}
+
+/// Returns the entries generated under the root directory.
+fn root_entries() -> Vec<(String, Entry)> {
+    vec![
+        dir("block", block_entries),
+        dir("class", || {
+            vec![
+                dir("block", class_block_entries),
+                dir("net", class_net_entries),
+            ]
+        }),
+        dir("devices", || {
+            vec![
+                dir("system", || {
+                    vec![
+                        dir("clocksource", clocksource_entries),
+                        dir("cpu", cpu_entries),
+                    ]
+                }),
+                dir("virtual", || vec![dir("net", net_entries)]),
+            ]
+        }),
+    ]
+}
+
+/* Nodes. */
+
+/// A directory whose entries are generated when accessed.
+struct GeneratedDir {
+    /// Path from the root of the sysfs, for the inode number.
+    path: String,
+    entries: Generate<Vec<(String, Entry)>>,
+    parent: VfsNodeRef,
+}
+
+impl VfsNodeOps for GeneratedDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            ino(&self.path),
+            VfsNodePerm::from_bits_truncate(0o555),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        Some(self.parent.clone())
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        let node = match name {
+            "" => self,
+            ".." => self.parent.clone(),
+            _ => {
+                let (_, entry) = (self.entries)()
+                    .into_iter()
+                    .find(|(entry, _)| entry == name)
+                    .ok_or(VfsError::NotFound)?;
+                let path = format!("{}/{name}", self.path);
+                new_node(entry, path, self)
+            }
+        };
+        lookup_rest(node, rest)
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let entries = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                (self.entries)()
+                    .into_iter()
+                    .map(|(name, entry)| (name, entry.node_type())),
+            );
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The root directory of the sysfs.
+struct SysRootDir {
+    /// The entries not generated, e.g. `/sys/kernel`.
+    static_dir: Arc<DirNode>,
+}
+
+impl SysRootDir {
+    /// Whether the entry is generated, which can not be created or removed.
+    fn is_generated(&self, name: &str) -> bool {
+        root_entries().iter().any(|(entry, _)| entry == name)
+    }
+}
+
+impl VfsNodeOps for SysRootDir {
+    fn get_attr(&self) -> VfsResult<VfsNodeAttr> {
+        Ok(VfsNodeAttr::new(
+            1,
+            VfsNodePerm::from_bits_truncate(0o555),
+            VfsNodeType::Dir,
+            0,
+            0,
+        ))
+    }
+
+    fn parent(&self) -> Option<VfsNodeRef> {
+        self.static_dir.parent()
+    }
+
+    fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
+        let (name, rest) = split_path(path);
+        if name.is_empty() {
+            return Ok(self);
+        }
+        match root_entries().into_iter().find(|(entry, _)| entry == name) {
+            Some((_, entry)) => lookup_rest(new_node(entry, name.to_string(), self), rest),
+            None => self.static_dir.clone().lookup(path),
+        }
+    }
+
+    fn read_dir(&self, start_idx: usize, dirents: &mut [VfsDirEntry]) -> VfsResult<usize> {
+        let static_entries = self.static_dir.get_entries().into_iter().map(|name| {
+            let ty = self
+                .static_dir
+                .clone()
+                .lookup(&RelPath::new(&name))
+                .and_then(|node| node.get_attr())
+                .map_or(VfsNodeType::File, |attr| attr.file_type());
+            (name, ty)
+        });
+        let entries = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), VfsNodeType::Dir))
+            .chain(
+                root_entries()
+                    .into_iter()
+                    .map(|(name, entry)| (name, entry.node_type())),
+            )
+            .chain(static_entries);
+        Ok(read_entries(entries, start_idx, dirents))
+    }
+
+    fn create(&self, path: &RelPath, ty: VfsNodeType, mode: VfsNodePerm) -> VfsResult {
+        if self.is_generated(split_path(path).0) {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.static_dir.create(path, ty, mode)
+    }
+
+    fn unlink(&self, path: &RelPath) -> VfsResult {
+        if self.is_generated(split_path(path).0) {
+            return Err(VfsError::PermissionDenied);
+        }
+        self.static_dir.unlink(path)
+    }
+
+    axfs_vfs::impl_vfs_dir_default! {}
+}
+
+/// The system filesystem, which implements [`VfsOps`].
+pub struct SysFileSystem {
+    static_fs: RamFileSystem,
+    root: Arc<SysRootDir>,
+}
+
+impl SysFileSystem {
+    /// Creates a new sysfs, with no static entries.
+    pub fn new() -> Self {
+        let static_fs = RamFileSystem::new();
+        let root = Arc::new(SysRootDir {
+            static_dir: static_fs.root_dir_node(),
+        });
+        Self { static_fs, root }
+    }
+}
+
+impl Default for SysFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VfsOps for SysFileSystem {
+    fn mount(&self, parent: VfsNodeRef) -> VfsResult {
+        self.static_fs.mount(parent)
+    }
+
+    fn root_dir(&self) -> VfsNodeRef {
+        self.root.clone()
+    }
+}
@@ -0,0 +1,136 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Registry of filesystem types, which can be mounted at runtime by
+//! [`fops::mount`](crate::fops::mount).
+//!
+//! The filesystems enabled by cargo features are registered by default, e.g.
+//! `tmpfs`, `proc`, `vfat` and `ext4`. Other types can be added by
+//! [`register`].
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use axerrno::{AxError, AxResult};
+use axfs_vfs::VfsOps;
+use spin::Mutex;
+
+/// Creates a filesystem from the source, e.g. `/dev/vda1`, and the mount
+/// options.
+pub type MountFn = fn(source: &str, data: &str) -> AxResult<Arc<dyn VfsOps>>;
+
+struct FileSystemType {
+    name: &'static str,
+    /// Whether the filesystem is not backed by a block device.
+    nodev: bool,
+    mount: MountFn,
+}
+
+impl FileSystemType {
+    const fn new(name: &'static str, nodev: bool, mount: MountFn) -> Self {
+        Self { name, nodev, mount }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref FS_TYPES: Mutex<Vec<FileSystemType>> = Mutex::new(builtin_types());
+}
+
+fn builtin_types() -> Vec<FileSystemType> {
+    #[allow(unused_mut)]
+    let mut types = Vec::new();
+    #[cfg(feature = "ramfs")]
+    {
+        types.push(FileSystemType::new("tmpfs", true, |_, _| {
+            Ok(crate::mounts::ramfs())
+        }));
+        types.push(FileSystemType::new("ramfs", true, |_, _| {
+            Ok(crate::mounts::ramfs())
+        }));
+    }
+    #[cfg(feature = "devfs")]
+    types.push(FileSystemType::new("devtmpfs", true, |_, _| {
+        Ok(crate::mounts::devfs())
+    }));
+    #[cfg(feature = "procfs")]
+    types.push(FileSystemType::new("proc", true, |_, _| {
+        Ok(crate::mounts::procfs()?)
+    }));
+    #[cfg(feature = "sysfs")]
+    types.push(FileSystemType::new("sysfs", true, |_, _| {
+        Ok(crate::mounts::sysfs()?)
+    }));
+    #[cfg(feature = "fatfs")]
+    types.push(FileSystemType::new("vfat", false, |source, _| {
+        new_blkfs(source, crate::partition::FsType::Fat)
+    }));
+    #[cfg(any(feature = "ext4_rs", feature = "another_ext4"))]
+    types.push(FileSystemType::new("ext4", false, |source, _| {
+        new_blkfs(source, crate::partition::FsType::Ext4)
+    }));
+    types
+}
+
+/// Opens the disk or partition of `source`, and creates the filesystem of
+/// type `ty` on it.
+#[cfg(feature = "blkfs")]
+#[allow(unused)]
+fn new_blkfs(source: &str, ty: crate::partition::FsType) -> AxResult<Arc<dyn VfsOps>> {
+    let name = source.strip_prefix("/dev/").unwrap_or(source);
+    let mut disk = crate::dev::open(name).ok_or(AxError::NotFound)?;
+    let size = disk.size();
+    if crate::partition::probe(|off, buf| disk.read_at(off, buf), size) != Some(ty) {
+        warn!("no {ty:?} filesystem found on {source}");
+        return Err(AxError::InvalidInput);
+    }
+    crate::fs::new_blkfs(disk, ty).ok_or(AxError::Unsupported)
+}
+
+/// Register a filesystem type, which replaces the one with the same name.
+///
+/// `nodev` is set if the filesystem is not backed by a block device.
+pub fn register(name: &'static str, nodev: bool, mount: MountFn) {
+    let mut types = FS_TYPES.lock();
+    types.retain(|ty| ty.name != name);
+    types.push(FileSystemType::new(name, nodev, mount));
+}
+
+/// Check if the filesystem type is registered.
+pub fn is_registered(name: &str) -> bool {
+    FS_TYPES.lock().iter().any(|ty| ty.name == name)
+}
+
+/// Check if the filesystem type is backed by a block device.
+pub fn requires_dev(name: &str) -> bool {
+    FS_TYPES
+        .lock()
+        .iter()
+        .any(|ty| ty.name == name && !ty.nodev)
+}
+
+/// Create a filesystem of the type from the source and the mount options.
+pub fn new_fs(name: &str, source: &str, data: &str) -> AxResult<Arc<dyn VfsOps>> {
+    let mount = FS_TYPES
+        .lock()
+        .iter()
+        .find(|ty| ty.name == name)
+        .map(|ty| ty.mount)
+        .ok_or(AxError::NotFound)?;
+    mount(source, data)
+}
+
+/// Returns the registered filesystem types in the format of
+/// `/proc/filesystems`.
+pub fn filesystems() -> String {
+    let mut list = String::new();
+    for ty in FS_TYPES.lock().iter() {
+        list += if ty.nodev { "nodev\t" } else { "\t" };
+        list += ty.name;
+        list.push('\n');
+    }
+    list
+}
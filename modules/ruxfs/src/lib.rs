@@ -15,6 +15,10 @@
 //!
 //! - `fatfs`: Use [FAT] as the main filesystem and mount it on `/`. Requires
 //!   `blkfs` to be enabled.
+//! - `ext4_rs`, `another_ext4`: Support ext4 filesystems on block devices.
+//!   Several block filesystems can be enabled together, and the filesystem on
+//!   each disk or partition is selected by probing its superblock (see
+//!   [`partition`]).
 //! - `devfs`: Mount [`axfs_devfs::DeviceFileSystem`] on `/dev`. This feature is
 //!   **enabled** by default.
 //! - `ramfs`: Mount [`axfs_ramfs::RamFileSystem`] on `/tmp`. This feature is
@@ -47,7 +51,12 @@ mod directory;
 pub mod fifo;
 mod file;
 pub mod fops;
+pub mod fstype;
 pub mod fuse_st;
+#[cfg(feature = "alloc")]
+pub mod page_cache;
+#[cfg(feature = "blkfs")]
+pub mod partition;
 pub mod root;
 
 pub use directory::Directory;
@@ -83,116 +92,169 @@ pub type RelPath<'a> = axfs_vfs::RelPath<'a>;
 
 #[cfg(feature = "myfs")]
 pub use fs::myfs::MyFileSystemIf;
+#[cfg(feature = "procfs")]
+pub use fs::procfs::{register_proc_net_file, MapArea, ProcessInfo, ProcfsIf};
+#[cfg(feature = "sysfs")]
+pub use fs::sysfs::{register_net_interface, NetStatistics};
+#[cfg(feature = "etcfs")]
+pub use mounts::set_nameservers;
 
 use alloc::vec::Vec;
 
 #[cfg(feature = "blkfs")]
 use ruxdriver::{prelude::*, AxDeviceContainer};
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "myfs")] {
-    } else if #[cfg(feature = "fatfs")] {
-        use lazy_init::LazyInit;
-        use alloc::sync::Arc;
-    // TODO: wait for CI support for ext4
-    // } else if #[cfg(feature = "lwext4_rust")] {
-    //     use lazy_init::LazyInit;
-    //     use alloc::sync::Arc;
-    } else if #[cfg(feature = "ext4_rs")] {
-        use lazy_init::LazyInit;
-        use alloc::sync::Arc;
-    } else if #[cfg(feature = "another_ext4")] {
-        use lazy_init::LazyInit;
-        use alloc::sync::Arc;
-    }
-}
-
 use alloc::string::String;
 pub use root::MountPoint;
 
 /// Initialize an empty filesystems by ramfs.
 #[cfg(not(feature = "blkfs"))]
 pub fn init_tempfs() -> MountPoint {
-    MountPoint::new(String::from("/"), mounts::ramfs())
+    MountPoint::new(String::from("/"), mounts::ramfs()).with_source("rootfs", "ramfs")
 }
 
 /// Initializes filesystems by block devices.
+///
+/// The partitions on each disk, or the whole disk without a partition table,
+/// are mounted if the filesystems on them are recognized. They are named as
+/// `vda`, `vda1`, `vdb`, etc. The one named by the `RUX_ROOT` environment
+/// variable at build time, or the first one, is mounted on `/`, and the others
+/// are mounted on `/<name>`.
+///
+/// Returns the mount points with the root one first, and the devices which are
+/// not used.
 #[cfg(feature = "blkfs")]
-pub fn init_blkfs(mut blk_devs: AxDeviceContainer<AxBlockDevice>) -> MountPoint {
+pub fn init_blkfs(
+    mut blk_devs: AxDeviceContainer<AxBlockDevice>,
+) -> (Vec<MountPoint>, Vec<AxBlockDevice>) {
     info!("Initialize filesystems...");
 
-    let dev = blk_devs.take_one().expect("No block device found!");
-    info!("  use block device 0: {:?}", dev.device_name());
-
-    let disk = self::dev::Disk::new(dev);
-    cfg_if::cfg_if! {
-        if #[cfg(feature = "myfs")] { // override the default filesystem
-            let blk_fs = fs::myfs::new_myfs(disk);
-        } else if #[cfg(feature = "fatfs")] {
-            static FAT_FS: LazyInit<Arc<fs::fatfs::FatFileSystem>> = LazyInit::new();
-            FAT_FS.init_by(Arc::new(fs::fatfs::FatFileSystem::new(disk)));
-            FAT_FS.init();
-            let blk_fs = FAT_FS.clone();
-        // TODO: wait for CI support for ext4
-        // } else if #[cfg(feature = "lwext4_rust")] {
-        //     static EXT4_FS: LazyInit<Arc<fs::lwext4_rust::Ext4FileSystem>> = LazyInit::new();
-        //     EXT4_FS.init_by(Arc::new(fs::lwext4_rust::Ext4FileSystem::new(disk)));
-        //     let blk_fs = EXT4_FS.clone();
-        } else if #[cfg(feature = "ext4_rs")] {
-            static EXT4_FS: LazyInit<Arc<fs::ext4_rs::Ext4FileSystem>> = LazyInit::new();
-            EXT4_FS.init_by(Arc::new(fs::ext4_rs::Ext4FileSystem::new(disk)));
-            let blk_fs = EXT4_FS.clone();
-        } else if #[cfg(feature = "another_ext4")] {
-            static EXT4_FS: LazyInit<Arc<fs::another_ext4::Ext4FileSystem>> = LazyInit::new();
-            EXT4_FS.init_by(Arc::new(fs::another_ext4::Ext4FileSystem::new(disk)));
-            let blk_fs = EXT4_FS.clone();
-        } else {
-            compile_error!("Please enable one of the block filesystems!");
-        }
+    #[cfg(feature = "myfs")] // override the default filesystem
+    {
+        let dev = blk_devs.take_one().expect("No block device found!");
+        info!("  use block device 0: {:?}", dev.device_name());
+        let disk = self::dev::Disk::new(dev);
+        self::dev::register("vda", &disk);
+        let blk_fs = fs::myfs::new_myfs(disk);
+        let unused = core::iter::from_fn(|| blk_devs.take_one()).collect();
+        let root = MountPoint::new(String::from("/"), blk_fs).with_source("/dev/vda", "myfs");
+        (alloc::vec![root], unused)
     }
+    #[cfg(not(feature = "myfs"))]
+    {
+        #[cfg(not(any(feature = "fatfs", feature = "ext4_rs", feature = "another_ext4")))]
+        compile_error!("Please enable one of the block filesystems!");
+
+        let mut volumes = Vec::new();
+        let mut unused = Vec::new();
+        for (i, mut dev) in core::iter::from_fn(|| blk_devs.take_one()).enumerate() {
+            let name = alloc::format!("vd{}", (b'a' + i as u8) as char);
+            info!("  use block device {i} as {name}: {:?}", dev.device_name());
+            let size = dev.num_blocks() * dev.block_size() as u64;
+            let parts = partition::parse(|off, buf| partition::read_raw(&mut dev, off, buf), size)
+                .unwrap_or_else(|e| {
+                    warn!("failed to read the partition table of {name}: {e:?}");
+                    Vec::new()
+                });
+            if parts.is_empty() {
+                let ty = partition::probe(|off, buf| partition::read_raw(&mut dev, off, buf), size);
+                // keep the default filesystem on the first disk, which may be formatted later
+                if ty.is_none() && i > 0 {
+                    info!("  no filesystem found on {name}");
+                    unused.push(dev);
+                    continue;
+                }
+                let disk = self::dev::Disk::new(dev);
+                self::dev::register(&name, &disk);
+                volumes.push((name, disk, ty.unwrap_or(fs::DEFAULT_BLKFS)));
+                continue;
+            }
+            let disk = self::dev::Disk::new(dev);
+            self::dev::register(&name, &disk);
+            for part in parts {
+                let name = alloc::format!("{name}{}", part.number);
+                let mut part_disk = disk.partition(&part);
+                self::dev::register(&name, &part_disk);
+                match partition::probe(|off, buf| part_disk.read_at(off, buf), part.size) {
+                    Some(ty) => volumes.push((name, part_disk, ty)),
+                    None => info!("  no filesystem found on {name}"),
+                }
+            }
+        }
 
-    MountPoint::new(String::from("/"), blk_fs)
+        let root = option_env!("RUX_ROOT")
+            .and_then(|root| volumes.iter().position(|(name, ..)| name == root))
+            .unwrap_or(0);
+        if root < volumes.len() {
+            let volume = volumes.remove(root);
+            volumes.insert(0, volume);
+        }
+        let mut mount_points = Vec::new();
+        for (name, disk, ty) in volumes {
+            let path = if mount_points.is_empty() {
+                String::from("/")
+            } else {
+                alloc::format!("/{name}")
+            };
+            info!("  mount {name} ({ty:?}) on {path}");
+            match fs::new_blkfs(disk, ty) {
+                Some(blk_fs) => mount_points.push(
+                    MountPoint::new(path, blk_fs)
+                        .with_source(&alloc::format!("/dev/{name}"), ty.name()),
+                ),
+                None => warn!("filesystem {ty:?} on {name} is not enabled"),
+            }
+        }
+        assert!(!mount_points.is_empty(), "No block filesystem found!");
+        (mount_points, unused)
+    }
 }
 
 /// Initializes common filesystems.
 pub fn prepare_commonfs(mount_points: &mut Vec<self::root::MountPoint>) {
     #[cfg(feature = "devfs")]
     {
-        let mount_point = MountPoint::new(String::from("/dev"), mounts::devfs());
+        let mount_point = MountPoint::new(String::from("/dev"), mounts::devfs())
+            .with_source("devtmpfs", "devtmpfs");
         mount_points.push(mount_point);
     }
 
     #[cfg(feature = "ramfs")]
     {
-        let mount_point = MountPoint::new(String::from("/tmp"), mounts::ramfs());
+        let mount_point =
+            MountPoint::new(String::from("/tmp"), mounts::ramfs()).with_source("tmpfs", "tmpfs");
         mount_points.push(mount_point);
     }
 
-    // Mount another ramfs as procfs
+    // Mount procfs
     #[cfg(feature = "procfs")]
     {
-        let mount_point = MountPoint::new(String::from("/proc"), mounts::procfs().unwrap());
+        let mount_point = MountPoint::new(String::from("/proc"), mounts::procfs().unwrap())
+            .with_source("proc", "proc");
         mount_points.push(mount_point);
     }
 
-    // Mount another ramfs as sysfs
+    // Mount sysfs
     #[cfg(feature = "sysfs")]
     {
-        let mount_point = MountPoint::new(String::from("/sys"), mounts::sysfs().unwrap());
+        let mount_point = MountPoint::new(String::from("/sys"), mounts::sysfs().unwrap())
+            .with_source("sysfs", "sysfs");
         mount_points.push(mount_point);
     }
 
     // Mount another ramfs as etcfs
     #[cfg(feature = "etcfs")]
     {
-        let mount_point = MountPoint::new(String::from("/etc"), mounts::etcfs().unwrap());
+        let mount_point = MountPoint::new(String::from("/etc"), mounts::etcfs().unwrap())
+            .with_source("none", "ramfs");
         mount_points.push(mount_point);
     }
 
     // Mount another ramfs as mntfs
     #[cfg(feature = "mntfs")]
     {
-        let mount_point = MountPoint::new(String::from("/mnt"), mounts::mntfs().unwrap());
+        let mount_point = MountPoint::new(String::from("/mnt"), mounts::mntfs().unwrap())
+            .with_source("none", "ramfs");
         mount_points.push(mount_point);
     }
 }
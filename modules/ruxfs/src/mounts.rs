@@ -9,9 +9,14 @@
 
 use alloc::sync::Arc;
 use axfs_vfs::{RelPath, VfsNodeType, VfsOps, VfsResult};
+#[cfg(feature = "etcfs")]
+use {
+    alloc::{format, string::String, vec::Vec},
+    axfs_vfs::VfsNodeRef,
+    core::net::IpAddr,
+    spin::Mutex,
+};
 
-#[cfg(feature = "alloc")]
-use crate::arch::{get_cpuinfo, get_meminfo};
 use crate::fs;
 
 #[cfg(feature = "devfs")]
@@ -29,6 +34,12 @@ pub(crate) fn devfs() -> Arc<fs::devfs::DeviceFileSystem> {
     devfs.add("urandom", Arc::new(urandom));
     devfs.add("pts", pts);
     devfs.add("fuse", Arc::new(fuse));
+    // the block devices which can be mounted at runtime
+    #[cfg(feature = "blkfs")]
+    for name in crate::dev::names() {
+        let disk = crate::dev::open(&name).unwrap();
+        devfs.add(name.leak(), Arc::new(crate::dev::BlockDevNode::new(disk)));
+    }
     Arc::new(devfs)
 }
 
@@ -38,33 +49,13 @@ pub(crate) fn ramfs() -> Arc<fs::ramfs::RamFileSystem> {
 }
 
 #[cfg(feature = "procfs")]
-pub(crate) fn procfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
+pub(crate) fn procfs() -> VfsResult<Arc<fs::procfs::ProcFileSystem>> {
     use axfs_vfs::VfsNodePerm;
 
-    let procfs = fs::ramfs::RamFileSystem::new();
+    // the other files are generated from the kernel state when read
+    let procfs = fs::procfs::ProcFileSystem::new();
     let proc_root = procfs.root_dir();
 
-    #[cfg(feature = "alloc")]
-    {
-        // Create /proc/cpuinfo
-        proc_root.create(
-            &RelPath::new("cpuinfo"),
-            VfsNodeType::File,
-            VfsNodePerm::default_file(),
-        )?;
-        let file_cpuinfo = proc_root.clone().lookup(&RelPath::new("cpuinfo"))?;
-        file_cpuinfo.write_at(0, get_cpuinfo().as_bytes())?;
-
-        // Create /proc/meminfo
-        proc_root.create(
-            &RelPath::new("meminfo"),
-            VfsNodeType::File,
-            VfsNodePerm::default_file(),
-        )?;
-        let file_meminfo = proc_root.clone().lookup(&RelPath::new("meminfo"))?;
-        file_meminfo.write_at(0, get_meminfo().as_bytes())?;
-    }
-
     // Create /proc/sys/net/core/somaxconn
     proc_root.create_recursive(
         &RelPath::new("sys/net/core/somaxconn"),
@@ -87,25 +78,17 @@ pub(crate) fn procfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
         .lookup(&RelPath::new("sys/vm/overcommit_memory"))?;
     file_over.write_at(0, b"0\n")?;
 
-    // Create /proc/self/stat
-    proc_root.create_recursive(
-        &RelPath::new("self/stat"),
-        VfsNodeType::File,
-        VfsNodePerm::default_file(),
-    )?;
-
     Ok(Arc::new(procfs))
 }
 
 #[cfg(feature = "sysfs")]
-pub(crate) fn sysfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
+pub(crate) fn sysfs() -> VfsResult<Arc<fs::sysfs::SysFileSystem>> {
     use axfs_vfs::VfsNodePerm;
 
-    let sysfs = fs::ramfs::RamFileSystem::new();
+    // the entries of the devices are generated when accessed
+    let sysfs = fs::sysfs::SysFileSystem::new();
     let sys_root = sysfs.root_dir();
 
-    debug!("sysfs: {:?}", sys_root.get_attr());
-
     // Create /sys/kernel/mm/transparent_hugepage/enabled
     sys_root.create_recursive(
         &RelPath::new("kernel/mm/transparent_hugepage/enabled"),
@@ -117,20 +100,55 @@ pub(crate) fn sysfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
         .lookup(&RelPath::new("kernel/mm/transparent_hugepage/enabled"))?;
     file_hp.write_at(0, b"always [madvise] never\n")?;
 
-    // Create /sys/devices/system/clocksource/clocksource0/current_clocksource
-    sys_root.create_recursive(
-        &RelPath::new("devices/system/clocksource/clocksource0/current_clocksource"),
-        VfsNodeType::File,
-        VfsNodePerm::default_file(),
-    )?;
-    let file_cc = sys_root.clone().lookup(&RelPath::new(
-        "devices/system/clocksource/clocksource0/current_clocksource",
-    ))?;
-    file_cc.write_at(0, b"tsc\n")?;
-
     Ok(Arc::new(sysfs))
 }
 
+/// The name servers in `/etc/resolv.conf`, and the file once `/etc` is mounted.
+#[cfg(feature = "etcfs")]
+struct ResolvConf {
+    servers: Vec<IpAddr>,
+    file: Option<VfsNodeRef>,
+}
+
+#[cfg(feature = "etcfs")]
+static RESOLV_CONF: Mutex<ResolvConf> = Mutex::new(ResolvConf {
+    servers: Vec::new(),
+    file: None,
+});
+
+/// Generates the content of `/etc/resolv.conf`, with the public name servers
+/// if none is given.
+#[cfg(feature = "etcfs")]
+fn resolv_conf(servers: &[IpAddr]) -> String {
+    let mut content = String::new();
+    if servers.is_empty() {
+        content.push_str("nameserver 8.8.8.8\nnameserver 114.114.114.114\n");
+    }
+    for server in servers {
+        content.push_str(&format!("nameserver {server}\n"));
+    }
+    content.push_str("options edns0 trust-ad\nsearch lan\n");
+    content
+}
+
+/// Sets the name servers in `/etc/resolv.conf`, e.g. the ones received by
+/// DHCP, which are registered by the network module as this crate does not
+/// depend on it.
+#[cfg(feature = "etcfs")]
+pub fn set_nameservers(servers: &[IpAddr]) {
+    let mut resolv = RESOLV_CONF.lock();
+    resolv.servers = servers.to_vec();
+    if let Some(file) = &resolv.file {
+        let content = resolv_conf(&resolv.servers);
+        if let Err(e) = file
+            .truncate(0)
+            .and_then(|_| file.write_at(0, content.as_bytes()))
+        {
+            warn!("failed to update /etc/resolv.conf: {:?}", e);
+        }
+    }
+}
+
 #[cfg(feature = "etcfs")]
 pub(crate) fn etcfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
     use axfs_vfs::VfsNodePerm;
@@ -194,21 +212,16 @@ pub(crate) fn etcfs() -> VfsResult<Arc<fs::ramfs::RamFileSystem>> {
     let file_services = etc_root.clone().lookup(&RelPath::new("services"))?;
     file_services.write_at(0, b"ssh		22/tcp")?;
 
-    // Create /etc/resolv.conf
+    // Create /etc/resolv.conf, which is rewritten by `set_nameservers` later
     etc_root.create(
         &RelPath::new("resolv.conf"),
         VfsNodeType::File,
         VfsNodePerm::default_file(),
     )?;
     let file_resolv = etc_root.clone().lookup(&RelPath::new("resolv.conf"))?;
-    file_resolv.write_at(
-        0,
-        b"nameserver 8.8.8.8\n\
-        nameserver 114.114.114.114\n\
-        options edns0 trust-ad\n\
-        search lan\n
-        ",
-    )?;
+    let mut resolv = RESOLV_CONF.lock();
+    file_resolv.write_at(0, resolv_conf(&resolv.servers).as_bytes())?;
+    resolv.file = Some(file_resolv);
 
     Ok(Arc::new(etcfs))
 }
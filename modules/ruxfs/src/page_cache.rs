@@ -0,0 +1,447 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Page cache of regular files.
+//!
+//! Regular files in the filesystems which [use the page cache] are read and
+//! written in pages cached in memory. The pages of a file are shared by all
+//! the opened files of the same inode, whatever links they are opened by, and
+//! by the shared file mappings, which map the cached pages directly. Files
+//! without [inode numbers] are not cached.
+//!
+//! Writes within the file are kept in the dirty pages, which are written back
+//! by `fsync`, `sync`, the last close of the file and unmounting. Writes which
+//! extend the file are written through, so that the size of the file in the
+//! filesystem is always up to date. Clean pages are evicted in LRU order when
+//! the page allocator runs out of memory.
+//!
+//! [use the page cache]: axfs_vfs::VfsOps::use_page_cache
+//! [inode numbers]: axfs_vfs::VfsNodeOps::get_inode
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use axalloc::global_allocator;
+use axerrno::{AxError, AxResult};
+use axfs_vfs::{AbsPath, VfsNodeRef, VfsOps};
+use memory_addr::PAGE_SIZE_4K;
+use spin::{mutex::Mutex, Once};
+use spinlock::SpinNoIrq;
+
+const PAGE_SIZE: u64 = PAGE_SIZE_4K as u64;
+
+/// Identity of a cached file: its filesystem, by the address of the
+/// [`VfsOps`], and its inode number.
+type FileKey = (usize, u64);
+
+/// Page caches by the identities of the files.
+static CACHES: SpinNoIrq<BTreeMap<FileKey, Arc<PageCache>>> = SpinNoIrq::new(BTreeMap::new());
+
+/// Incremented on each access of a page, to find the least recently used one.
+static CLOCK: AtomicU64 = AtomicU64::new(0);
+
+/// A cached page of a file.
+struct Page {
+    /// Address of the page allocated by `axalloc`.
+    vaddr: usize,
+    /// Modified by writes since the last write-back.
+    dirty: bool,
+    /// Mapped by a shared writable mapping, whose writes can not be tracked.
+    mapped_writable: bool,
+    /// Time of the last access.
+    accessed: u64,
+}
+
+impl Page {
+    fn alloc() -> AxResult<Self> {
+        let vaddr = global_allocator()
+            .alloc_pages(1, PAGE_SIZE_4K)
+            .map_err(|_| AxError::NoMemory)?;
+        Ok(Self {
+            vaddr,
+            dirty: false,
+            mapped_writable: false,
+            accessed: CLOCK.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+
+    fn data(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.vaddr as *const u8, PAGE_SIZE_4K) }
+    }
+
+    fn data_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.vaddr as *mut u8, PAGE_SIZE_4K) }
+    }
+
+    /// Whether the page is mapped by some process.
+    fn is_mapped(&self) -> bool {
+        global_allocator().page_ref_count(self.vaddr) > 1
+    }
+
+    fn needs_write_back(&self) -> bool {
+        self.dirty || self.mapped_writable
+    }
+}
+
+impl Drop for Page {
+    fn drop(&mut self) {
+        // mapped pages are freed after being unmapped
+        global_allocator().page_ref_dec(self.vaddr);
+    }
+}
+
+struct CacheInner {
+    pages: BTreeMap<u64, Page>,
+    /// The node of an opened file to write back the pages, set when the pages
+    /// are modified.
+    writer: Option<VfsNodeRef>,
+}
+
+impl CacheInner {
+    /// Returns the page at `index`, reading it from `node` if not cached.
+    fn page(&mut self, node: &VfsNodeRef, index: u64) -> AxResult<&mut Page> {
+        if !self.pages.contains_key(&index) {
+            let mut page = Page::alloc()?;
+            let buf = page.data_mut();
+            let mut len = 0;
+            while len < buf.len() {
+                match node.read_at(index * PAGE_SIZE + len as u64, &mut buf[len..])? {
+                    0 => break,
+                    n => len += n,
+                }
+            }
+            buf[len..].fill(0);
+            self.pages.insert(index, page);
+        }
+        let page = self.pages.get_mut(&index).unwrap();
+        page.accessed = CLOCK.fetch_add(1, Ordering::Relaxed);
+        Ok(page)
+    }
+
+    /// Writes back the page at `index` if needed.
+    fn write_back(&mut self, index: u64, size: u64) -> AxResult {
+        let Some(node) = self.writer.clone() else {
+            return Ok(());
+        };
+        let Some(page) = self.pages.get_mut(&index) else {
+            return Ok(());
+        };
+        if !page.needs_write_back() {
+            return Ok(());
+        }
+        let pos = index * PAGE_SIZE;
+        if pos < size {
+            let data = &page.data()[..(size - pos).min(PAGE_SIZE) as usize];
+            let mut len = 0;
+            while len < data.len() {
+                match node.write_at(pos + len as u64, &data[len..])? {
+                    0 => return Err(AxError::WriteZero),
+                    n => len += n,
+                }
+            }
+        }
+        page.dirty = false;
+        if !page.is_mapped() {
+            page.mapped_writable = false;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> AxResult {
+        let Some(node) = &self.writer else {
+            return Ok(());
+        };
+        let size = node.get_attr()?.size();
+        let indexes = self
+            .pages
+            .iter()
+            .filter(|(_, page)| page.needs_write_back())
+            .map(|(&index, _)| index)
+            .collect::<Vec<_>>();
+        for index in indexes {
+            self.write_back(index, size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cached pages of a regular file.
+///
+/// The node of the opened file is passed to each operation, to read the pages
+/// not cached yet.
+pub struct PageCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl PageCache {
+    const fn new() -> Self {
+        Self {
+            inner: Mutex::new(CacheInner {
+                pages: BTreeMap::new(),
+                writer: None,
+            }),
+        }
+    }
+
+    /// Reads the file at `offset` through the cached pages.
+    pub fn read_at(&self, node: &VfsNodeRef, offset: u64, buf: &mut [u8]) -> AxResult<usize> {
+        let size = node.get_attr()?.size();
+        if offset >= size {
+            return Ok(0);
+        }
+        let len = (buf.len() as u64).min(size - offset) as usize;
+        let mut inner = self.inner.lock();
+        let mut read = 0;
+        while read < len {
+            let pos = offset + read as u64;
+            let start = (pos % PAGE_SIZE) as usize;
+            let n = (len - read).min(PAGE_SIZE_4K - start);
+            let page = inner.page(node, pos / PAGE_SIZE)?;
+            buf[read..read + n].copy_from_slice(&page.data()[start..start + n]);
+            read += n;
+        }
+        Ok(len)
+    }
+
+    /// Writes the file at `offset` through the cached pages.
+    pub fn write_at(&self, node: &VfsNodeRef, offset: u64, buf: &[u8]) -> AxResult<usize> {
+        let size = node.get_attr()?.size();
+        let mut inner = self.inner.lock();
+        if offset + buf.len() as u64 > size {
+            let len = node.write_at(offset, buf)?;
+            let mut written = 0;
+            while written < len {
+                let pos = offset + written as u64;
+                let start = (pos % PAGE_SIZE) as usize;
+                let n = (len - written).min(PAGE_SIZE_4K - start);
+                if let Some(page) = inner.pages.get_mut(&(pos / PAGE_SIZE)) {
+                    page.data_mut()[start..start + n].copy_from_slice(&buf[written..written + n]);
+                }
+                written += n;
+            }
+            return Ok(len);
+        }
+        let mut written = 0;
+        while written < buf.len() {
+            let pos = offset + written as u64;
+            let start = (pos % PAGE_SIZE) as usize;
+            let n = (buf.len() - written).min(PAGE_SIZE_4K - start);
+            let page = inner.page(node, pos / PAGE_SIZE)?;
+            page.data_mut()[start..start + n].copy_from_slice(&buf[written..written + n]);
+            page.dirty = true;
+            written += n;
+        }
+        inner.writer = Some(node.clone());
+        Ok(buf.len())
+    }
+
+    /// Drops the cached pages beyond `size`, before the file is truncated.
+    pub fn truncate(&self, size: u64) {
+        let mut inner = self.inner.lock();
+        drop(inner.pages.split_off(&size.div_ceil(PAGE_SIZE)));
+        if let Some(page) = inner.pages.get_mut(&(size / PAGE_SIZE)) {
+            page.data_mut()[(size % PAGE_SIZE) as usize..].fill(0);
+        }
+    }
+
+    /// Returns the address of the page at `offset` to be mapped, with a new
+    /// reference to it for the mapping.
+    ///
+    /// The page is always written back while mapped if `writable` is set.
+    pub fn map_page(&self, node: &VfsNodeRef, offset: u64, writable: bool) -> AxResult<usize> {
+        let mut inner = self.inner.lock();
+        let page = inner.page(node, offset / PAGE_SIZE)?;
+        global_allocator().page_ref_inc(page.vaddr);
+        let vaddr = page.vaddr;
+        if writable {
+            page.mapped_writable = true;
+            inner.writer = Some(node.clone());
+        }
+        Ok(vaddr)
+    }
+
+    /// Writes back the page at `offset` with `node`, if it is modified.
+    pub fn sync_page(&self, node: &VfsNodeRef, offset: u64) -> AxResult {
+        let size = node.get_attr()?.size();
+        let mut inner = self.inner.lock();
+        inner.writer.get_or_insert_with(|| node.clone());
+        inner.write_back(offset / PAGE_SIZE, size)
+    }
+
+    /// Writes back all the modified pages.
+    pub fn sync(&self) -> AxResult {
+        self.inner.lock().sync()
+    }
+
+    /// Called when an opened file with `node` is closed, writes back the
+    /// modified pages if `node` is used to write them back.
+    pub fn release(&self, node: &VfsNodeRef) -> AxResult {
+        let mut inner = self.inner.lock();
+        if !inner
+            .writer
+            .as_ref()
+            .is_some_and(|writer| Arc::ptr_eq(writer, node))
+        {
+            return Ok(());
+        }
+        let res = inner.sync();
+        inner.writer = None;
+        res
+    }
+}
+
+fn fs_id(fs: &Arc<dyn VfsOps>) -> usize {
+    Arc::as_ptr(fs) as *const () as usize
+}
+
+/// Gets the identity of the file at `path` opened as `node`, if it is read
+/// and written through the page cache.
+fn key(path: &AbsPath, node: &VfsNodeRef) -> Option<FileKey> {
+    let fs = crate::fops::root_dir().page_cache_fs(&path.to_rel())?;
+    Some((fs_id(&fs), node.get_inode()?))
+}
+
+/// Gets the page cache of the file at `path` opened as `node`, creates one if
+/// not exists. Returns [`None`] if the file is not read and written through
+/// the page cache.
+pub(crate) fn open(path: &AbsPath, node: &VfsNodeRef) -> Option<Arc<PageCache>> {
+    static RECLAIM: Once = Once::new();
+    let key = key(path, node)?;
+    RECLAIM.call_once(|| global_allocator().set_reclaim(reclaim));
+    let cache = CACHES
+        .lock()
+        .entry(key)
+        .or_insert_with(|| Arc::new(PageCache::new()))
+        .clone();
+    Some(cache)
+}
+
+/// Gets the page cache of the file at `path` opened as `node`, if exists.
+pub(crate) fn get(path: &AbsPath, node: &VfsNodeRef) -> Option<Arc<PageCache>> {
+    CACHES.lock().get(&key(path, node)?).cloned()
+}
+
+/// Writes back and forgets the page caches of the files that `which` returns
+/// true for.
+///
+/// The files already opened still use their page caches.
+fn forget(mut which: impl FnMut(&FileKey) -> bool) {
+    let mut removed = Vec::new();
+    CACHES.lock().retain(|key, cache| {
+        if which(key) {
+            removed.push(cache.clone());
+            false
+        } else {
+            true
+        }
+    });
+    for cache in removed {
+        if let Err(e) = cache.sync() {
+            warn!("failed to write back a page cache: {e:?}");
+        }
+    }
+}
+
+/// Called before the file at `path` opened as `node` is unlinked, forgets
+/// its page cache unless it has other links, since the inode number may be
+/// reused by another file.
+pub(crate) fn unlink(path: &AbsPath, node: &VfsNodeRef) {
+    if node.get_attr().is_ok_and(|attr| attr.nlink() > 1) {
+        return;
+    }
+    if let Some(key) = key(path, node) {
+        forget(|k| *k == key);
+    }
+}
+
+/// Called after the file `old_node` at `old` is renamed to `new`, where it is
+/// opened as `new_node`. The page cache is moved if the identity of the file
+/// changes, as some filesystems such as FAT derive inode numbers from paths.
+pub(crate) fn rename(old: &AbsPath, old_node: &VfsNodeRef, new: &AbsPath, new_node: &VfsNodeRef) {
+    let (Some(old_key), Some(new_key)) = (key(old, old_node), key(new, new_node)) else {
+        return;
+    };
+    if old_key == new_key {
+        return;
+    }
+    if new_node.get_attr().is_ok_and(|attr| attr.is_dir()) {
+        // the identities of the files under it may be changed too
+        forget(|k| k.0 == old_key.0);
+        return;
+    }
+    let mut caches = CACHES.lock();
+    if let Some(cache) = caches.remove(&old_key) {
+        caches.insert(new_key, cache);
+    }
+}
+
+/// Writes back and forgets the page caches of the files in `fs`, which is
+/// being unmounted.
+pub(crate) fn unmount(fs: &Arc<dyn VfsOps>) {
+    let fs = fs_id(fs);
+    forget(|k| k.0 == fs);
+}
+
+/// Writes back the modified pages of all files.
+pub fn sync_all() -> AxResult {
+    let caches = CACHES.lock().values().cloned().collect::<Vec<_>>();
+    for cache in caches {
+        cache.sync()?;
+    }
+    Ok(())
+}
+
+/// Returns the number of pages cached for all files, as `Cached` in
+/// `/proc/meminfo`.
+pub fn cached_pages() -> usize {
+    let caches = CACHES.lock().values().cloned().collect::<Vec<_>>();
+    caches
+        .iter()
+        .map(|cache| cache.inner.lock().pages.len())
+        .sum()
+}
+
+/// Evicts at most `num_pages` least recently used pages, which are clean and
+/// not mapped. Returns the number of pages evicted.
+///
+/// Set as the reclaimer of `axalloc`, so it does not allocate memory, and the
+/// page caches locked are skipped.
+fn reclaim(num_pages: usize) -> usize {
+    let Some(caches) = CACHES.try_lock() else {
+        return 0;
+    };
+    let mut evicted = 0;
+    while evicted < num_pages {
+        let mut lru: Option<(&Arc<PageCache>, u64, u64)> = None;
+        for cache in caches.values() {
+            let Some(inner) = cache.inner.try_lock() else {
+                continue;
+            };
+            for (&index, page) in inner.pages.iter() {
+                if page.needs_write_back() || page.is_mapped() {
+                    continue;
+                }
+                if lru.map_or(true, |(_, _, accessed)| page.accessed < accessed) {
+                    lru = Some((cache, index, page.accessed));
+                }
+            }
+        }
+        let Some((cache, index, _)) = lru else {
+            break;
+        };
+        let Some(mut inner) = cache.inner.try_lock() else {
+            break;
+        };
+        inner.pages.remove(&index);
+        evicted += 1;
+    }
+    evicted
+}
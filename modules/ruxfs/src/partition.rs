@@ -0,0 +1,388 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Partition tables and filesystem probing.
+//!
+//! Both [MBR] (with the logical partitions in an extended partition) and
+//! [GPT] partition tables are supported. Each partition can be opened as its
+//! own [`Disk`](crate::dev::Disk) by [`Disk::partition`](crate::dev::Disk::partition).
+//!
+//! The data are read by a `read` function, which reads whole sectors at the
+//! given offset in bytes, so that the raw block devices can be probed before
+//! being opened.
+//!
+//! [MBR]: https://en.wikipedia.org/wiki/Master_boot_record
+//! [GPT]: https://en.wikipedia.org/wiki/GUID_Partition_Table
+
+use alloc::vec;
+use alloc::vec::Vec;
+use axerrno::{AxError, AxResult};
+use ruxdriver::prelude::*;
+
+const SECTOR_SIZE: usize = 512;
+
+/// The maximum number of partitions in an extended partition or a GPT.
+const MAX_PARTITIONS: usize = 128;
+
+/// The maximum size of a GPT entry, which is a power of two of at least 128.
+const MAX_GPT_ENTRY_SIZE: usize = 4096;
+
+/// A partition on a disk.
+#[derive(Debug, Clone, Copy)]
+pub struct Partition {
+    /// Number of the partition, starting from 1. Logical partitions in an
+    /// extended partition start from 5.
+    pub number: usize,
+    /// Offset of the partition on the disk in bytes.
+    pub start: u64,
+    /// Size of the partition in bytes.
+    pub size: u64,
+}
+
+/// Filesystem types which can be recognized by the superblock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsType {
+    /// FAT12, FAT16 or FAT32.
+    Fat,
+    /// ext2, ext3 or ext4.
+    Ext4,
+}
+
+impl FsType {
+    /// Name of the filesystem type, as in `/proc/mounts`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Fat => "vfat",
+            Self::Ext4 => "ext4",
+        }
+    }
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(buf[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(buf: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+}
+
+/// Checks if the sector is the boot sector of a FAT filesystem.
+fn is_fat_boot_sector(sector: &[u8]) -> bool {
+    read_u16(sector, 510) == 0xaa55
+        && matches!(sector[0], 0xeb | 0xe9)
+        && (&sector[54..57] == b"FAT" || &sector[82..87] == b"FAT32")
+}
+
+/// Recognizes the filesystem on a disk or partition of `size` bytes by its
+/// superblock.
+pub fn probe<F>(mut read: F, size: u64) -> Option<FsType>
+where
+    F: FnMut(u64, &mut [u8]) -> DevResult,
+{
+    let mut sector = [0u8; SECTOR_SIZE];
+    if size >= SECTOR_SIZE as u64 && read(0, &mut sector).is_ok() && is_fat_boot_sector(&sector) {
+        return Some(FsType::Fat);
+    }
+    // the superblock of ext2/3/4 is at offset 1024, with the magic at 56
+    if size >= 2048 && read(1024, &mut sector).is_ok() && read_u16(&sector, 56) == 0xef53 {
+        return Some(FsType::Ext4);
+    }
+    None
+}
+
+/// Converts an offset in sectors to bytes, a malformed table may give an
+/// offset overflowing.
+fn sector_offset(sector: u64) -> AxResult<u64> {
+    sector
+        .checked_mul(SECTOR_SIZE as u64)
+        .ok_or(AxError::InvalidData)
+}
+
+/// Parses the partition table on a disk of `size` bytes.
+///
+/// Returns an empty list if there is no partition table, e.g. the disk holds
+/// a filesystem directly, or [`InvalidData`](AxError::InvalidData) if the
+/// table is malformed.
+pub fn parse<F>(mut read_dev: F, size: u64) -> AxResult<Vec<Partition>>
+where
+    F: FnMut(u64, &mut [u8]) -> DevResult,
+{
+    let mut read = |offset, buf: &mut [u8]| read_dev(offset, buf).map_err(|_| AxError::Io);
+    let mut mbr = [0u8; SECTOR_SIZE];
+    if size < SECTOR_SIZE as u64 {
+        return Ok(Vec::new());
+    }
+    read(0, &mut mbr)?;
+    if read_u16(&mbr, 510) != 0xaa55 || is_fat_boot_sector(&mbr) {
+        return Ok(Vec::new());
+    }
+    let entries = (0..4)
+        .map(|i| &mbr[446 + i * 16..446 + (i + 1) * 16])
+        .collect::<Vec<_>>();
+    // the boot indicator must be 0x00 or 0x80 in a valid MBR
+    if entries.iter().any(|entry| entry[0] & 0x7f != 0) {
+        return Ok(Vec::new());
+    }
+    let mut parts = if entries.iter().any(|entry| entry[4] == 0xee) {
+        parse_gpt(&mut read)?
+    } else {
+        parse_mbr(&mut read, &entries)?
+    };
+    parts.retain(|part| {
+        let valid = part.size > 0 && part.start.saturating_add(part.size) <= size;
+        if !valid {
+            warn!("partition {} exceeds the disk, ignored", part.number);
+        }
+        valid
+    });
+    Ok(parts)
+}
+
+fn parse_mbr<F>(read: &mut F, entries: &[&[u8]]) -> AxResult<Vec<Partition>>
+where
+    F: FnMut(u64, &mut [u8]) -> AxResult,
+{
+    let mut parts = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let ty = entry[4];
+        let start = read_u32(entry, 8) as u64;
+        let count = read_u32(entry, 12) as u64;
+        if ty == 0 || count == 0 {
+            continue;
+        }
+        if matches!(ty, 0x05 | 0x0f | 0x85) {
+            parse_extended(read, start, &mut parts)?;
+            continue;
+        }
+        parts.push(Partition {
+            number: i + 1,
+            start: start * SECTOR_SIZE as u64,
+            size: count * SECTOR_SIZE as u64,
+        });
+    }
+    Ok(parts)
+}
+
+/// Parses the chain of extended boot records in the extended partition at
+/// sector `base`.
+fn parse_extended<F>(read: &mut F, base: u64, parts: &mut Vec<Partition>) -> AxResult
+where
+    F: FnMut(u64, &mut [u8]) -> AxResult,
+{
+    let mut ebr = [0u8; SECTOR_SIZE];
+    let mut sector = base;
+    for number in 5..5 + MAX_PARTITIONS {
+        read(sector * SECTOR_SIZE as u64, &mut ebr)?;
+        if read_u16(&ebr, 510) != 0xaa55 {
+            break;
+        }
+        let (logical, next) = (&ebr[446..462], &ebr[462..478]);
+        let count = read_u32(logical, 12) as u64;
+        if logical[4] != 0 && count != 0 {
+            parts.push(Partition {
+                number,
+                start: (sector + read_u32(logical, 8) as u64) * SECTOR_SIZE as u64,
+                size: count * SECTOR_SIZE as u64,
+            });
+        }
+        // the next EBR is relative to the extended partition
+        if next[4] == 0 {
+            break;
+        }
+        sector = base + read_u32(next, 8) as u64;
+    }
+    Ok(())
+}
+
+fn parse_gpt<F>(read: &mut F) -> AxResult<Vec<Partition>>
+where
+    F: FnMut(u64, &mut [u8]) -> AxResult,
+{
+    let mut header = [0u8; SECTOR_SIZE];
+    read(SECTOR_SIZE as u64, &mut header)?;
+    if &header[0..8] != b"EFI PART" {
+        warn!("invalid GPT header");
+        return Ok(Vec::new());
+    }
+    let entries_lba = read_u64(&header, 72);
+    let num_entries = (read_u32(&header, 80) as usize).min(MAX_PARTITIONS);
+    let entry_size = read_u32(&header, 84) as usize;
+    if !entry_size.is_power_of_two() || !(128..=MAX_GPT_ENTRY_SIZE).contains(&entry_size) {
+        warn!("invalid GPT entry size {entry_size}");
+        return Err(AxError::InvalidData);
+    }
+    // at most `MAX_PARTITIONS * MAX_GPT_ENTRY_SIZE` bytes
+    let len = (num_entries * entry_size).div_ceil(SECTOR_SIZE) * SECTOR_SIZE;
+    let mut entries = vec![0u8; len];
+    read(sector_offset(entries_lba)?, &mut entries)?;
+
+    let mut parts = Vec::new();
+    for i in 0..num_entries {
+        let entry = &entries[i * entry_size..(i + 1) * entry_size];
+        // unused entries have a zero type GUID
+        if entry[0..16].iter().all(|&b| b == 0) {
+            continue;
+        }
+        let first = read_u64(entry, 32);
+        let last = read_u64(entry, 40);
+        if last < first {
+            continue;
+        }
+        let count = (last - first).checked_add(1).ok_or(AxError::InvalidData)?;
+        parts.push(Partition {
+            number: i + 1,
+            start: sector_offset(first)?,
+            size: sector_offset(count)?,
+        });
+    }
+    Ok(parts)
+}
+
+/// Reads whole sectors of a raw block device at `offset` in bytes.
+pub fn read_raw(dev: &mut AxBlockDevice, offset: u64, buf: &mut [u8]) -> DevResult {
+    let block_size = dev.block_size();
+    if block_size == 0 || offset % block_size as u64 != 0 || buf.len() % block_size != 0 {
+        return Err(DevError::InvalidParam);
+    }
+    dev.read_blocks(offset / block_size as u64, buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISK_SECTORS: usize = 4096;
+
+    fn read_from(disk: &[u8]) -> impl FnMut(u64, &mut [u8]) -> DevResult + '_ {
+        |offset, buf| {
+            let start = offset as usize;
+            let src = disk.get(start..start + buf.len()).ok_or(DevError::Io)?;
+            buf.copy_from_slice(src);
+            Ok(())
+        }
+    }
+
+    fn sector(disk: &mut [u8], lba: usize) -> &mut [u8] {
+        &mut disk[lba * SECTOR_SIZE..(lba + 1) * SECTOR_SIZE]
+    }
+
+    /// Sets the `i`-th entry of the MBR or EBR in `sector`.
+    fn set_mbr_entry(sector: &mut [u8], i: usize, ty: u8, start: u32, count: u32) {
+        let entry = &mut sector[446 + i * 16..446 + (i + 1) * 16];
+        entry[4] = ty;
+        entry[8..12].copy_from_slice(&start.to_le_bytes());
+        entry[12..16].copy_from_slice(&count.to_le_bytes());
+        sector[510..512].copy_from_slice(&0xaa55u16.to_le_bytes());
+    }
+
+    /// Creates a disk with a GPT of 128 entries of `entry_size` bytes from
+    /// LBA 2, with the partitions of `(first, last)` sectors.
+    fn gpt_disk(entry_size: u32, parts: &[(u64, u64)]) -> Vec<u8> {
+        let mut disk = vec![0u8; DISK_SECTORS * SECTOR_SIZE];
+        set_mbr_entry(sector(&mut disk, 0), 0, 0xee, 1, DISK_SECTORS as u32 - 1);
+        let header = sector(&mut disk, 1);
+        header[0..8].copy_from_slice(b"EFI PART");
+        header[72..80].copy_from_slice(&2u64.to_le_bytes());
+        header[80..84].copy_from_slice(&128u32.to_le_bytes());
+        header[84..88].copy_from_slice(&entry_size.to_le_bytes());
+        let entries = &mut disk[2 * SECTOR_SIZE..];
+        for (i, &(first, last)) in parts.iter().enumerate() {
+            let entry = &mut entries[i * 128..(i + 1) * 128];
+            entry[0..16].fill(0xaa);
+            entry[32..40].copy_from_slice(&first.to_le_bytes());
+            entry[40..48].copy_from_slice(&last.to_le_bytes());
+        }
+        disk
+    }
+
+    fn layout(parts: &[Partition]) -> Vec<(usize, u64, u64)> {
+        parts
+            .iter()
+            .map(|part| (part.number, part.start, part.size))
+            .collect()
+    }
+
+    #[test]
+    fn no_partition_table() {
+        let disk = vec![0u8; DISK_SECTORS * SECTOR_SIZE];
+        let size = disk.len() as u64;
+        assert!(parse(read_from(&disk), size).unwrap().is_empty());
+    }
+
+    #[test]
+    fn mbr_partitions() {
+        let mut disk = vec![0u8; DISK_SECTORS * SECTOR_SIZE];
+        let size = disk.len() as u64;
+        let mbr = sector(&mut disk, 0);
+        set_mbr_entry(mbr, 0, 0x83, 64, 1024);
+        set_mbr_entry(mbr, 2, 0x0c, 2048, 1024);
+        // exceeds the disk
+        set_mbr_entry(mbr, 3, 0x83, 3072, 2048);
+        assert_eq!(
+            layout(&parse(read_from(&disk), size).unwrap()),
+            [(1, 64 * 512, 1024 * 512), (3, 2048 * 512, 1024 * 512)]
+        );
+    }
+
+    #[test]
+    fn logical_partitions() {
+        let mut disk = vec![0u8; DISK_SECTORS * SECTOR_SIZE];
+        let size = disk.len() as u64;
+        set_mbr_entry(sector(&mut disk, 0), 1, 0x05, 1000, 2000);
+        // each logical partition is relative to its EBR, and the next EBR to
+        // the extended partition
+        let ebr = sector(&mut disk, 1000);
+        set_mbr_entry(ebr, 0, 0x83, 8, 500);
+        set_mbr_entry(ebr, 1, 0x05, 600, 1000);
+        set_mbr_entry(sector(&mut disk, 1600), 0, 0x83, 8, 900);
+        assert_eq!(
+            layout(&parse(read_from(&disk), size).unwrap()),
+            [(5, 1008 * 512, 500 * 512), (6, 1608 * 512, 900 * 512)]
+        );
+    }
+
+    #[test]
+    fn gpt_partitions() {
+        let disk = gpt_disk(128, &[(34, 1033), (2048, 4095)]);
+        let size = disk.len() as u64;
+        assert_eq!(
+            layout(&parse(read_from(&disk), size).unwrap()),
+            [(1, 34 * 512, 1000 * 512), (2, 2048 * 512, 2048 * 512)]
+        );
+    }
+
+    #[test]
+    fn gpt_invalid_entry_size() {
+        for entry_size in [0, 127, 129, 192, 8192, 1 << 31] {
+            let disk = gpt_disk(entry_size, &[(34, 1033)]);
+            let size = disk.len() as u64;
+            assert_eq!(
+                parse(read_from(&disk), size).unwrap_err(),
+                AxError::InvalidData,
+                "entry size {entry_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn gpt_overflowing_sectors() {
+        for part in [(u64::MAX / 256, u64::MAX / 256), (0, u64::MAX)] {
+            let disk = gpt_disk(128, &[part]);
+            let size = disk.len() as u64;
+            assert_eq!(
+                parse(read_from(&disk), size).unwrap_err(),
+                AxError::InvalidData
+            );
+        }
+    }
+}
@@ -11,13 +11,17 @@
 //! appropriate filesystem based on the mount points.
 //!
 //! `RootDirectory::lookup_mounted_fs()` performs the distribution of operations.
+//!
+//! The mount points also record the source and type of the filesystems, which
+//! are listed in `/proc/mounts` by [`RootDirectory::mount_table`].
 
 use alloc::{format, string::String, sync::Arc, vec::Vec};
-use axerrno::{ax_err, AxResult};
+use axerrno::{ax_err, AxError, AxResult};
 use axfs_vfs::{
     AbsPath, RelPath, VfsError, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeRef, VfsNodeType,
     VfsOps, VfsResult,
 };
+use core::time::Duration;
 use spinlock::SpinNoIrq;
 
 /// mount point information
@@ -27,6 +31,12 @@ pub struct MountPoint {
     pub path: String,
     /// mounted filesystem
     pub fs: Arc<dyn VfsOps>,
+    /// mounted device or other source, e.g. `/dev/vda1`
+    pub source: String,
+    /// filesystem type, e.g. `ext4`
+    pub fstype: String,
+    /// whether the filesystem is mounted read-only
+    pub read_only: bool,
 }
 
 // pub(crate) static ROOT_DIR: LazyInit<Arc<RootDirectory>> = LazyInit::new();
@@ -34,7 +44,20 @@ pub struct MountPoint {
 impl MountPoint {
     /// create new MountPoint from data
     pub fn new(path: String, fs: Arc<dyn VfsOps>) -> Self {
-        Self { path, fs }
+        Self {
+            path,
+            fs,
+            source: String::from("none"),
+            fstype: String::from("none"),
+            read_only: false,
+        }
+    }
+
+    /// set the source and the filesystem type of the mount point
+    pub fn with_source(mut self, source: &str, fstype: &str) -> Self {
+        self.source = String::from(source);
+        self.fstype = String::from(fstype);
+        self
     }
 }
 
@@ -44,22 +67,44 @@ impl Drop for MountPoint {
     }
 }
 
+/// A directory mounted at another path by a bind mount.
+pub struct BindFs {
+    dir: VfsNodeRef,
+}
+
+impl BindFs {
+    /// Create a bind mount of the directory.
+    pub fn new(dir: VfsNodeRef) -> Self {
+        Self { dir }
+    }
+}
+
+impl VfsOps for BindFs {
+    fn root_dir(&self) -> VfsNodeRef {
+        self.dir.clone()
+    }
+}
+
 /// Root directory of the main filesystem
 pub struct RootDirectory {
     main_fs: Arc<dyn VfsOps>,
+    root: SpinNoIrq<MountPoint>,
     mount_points: SpinNoIrq<Vec<MountPoint>>,
 }
 
 impl RootDirectory {
-    /// Creates a new `RootDirectory` with the specified main filesystem.
-    pub const fn new(main_fs: Arc<dyn VfsOps>) -> Self {
+    /// Creates a new `RootDirectory` with the main filesystem mounted on `/`.
+    pub fn new(root: MountPoint) -> Self {
         Self {
-            main_fs,
+            main_fs: root.fs.clone(),
+            root: SpinNoIrq::new(root),
             mount_points: SpinNoIrq::new(Vec::new()),
         }
     }
 
     /// Mount the specified filesystem at the specified path.
+    ///
+    /// The mount point is created if it does not exist.
     pub fn mount(&self, mp: MountPoint) -> AxResult {
         info!("Root dir mounting {}", mp.path);
         if mp.path == "/" {
@@ -68,47 +113,140 @@ impl RootDirectory {
         if !mp.path.starts_with('/') {
             return ax_err!(InvalidInput, "mount path must start with '/'");
         }
-        let mut already_mount = self.mount_points.lock();
-        if already_mount.iter().any(|m| m.path == mp.path) {
-            return ax_err!(InvalidInput, "mount point already exists");
+        if self.mount_points.lock().iter().any(|m| m.path == mp.path) {
+            return ax_err!(ResourceBusy, "mount point already exists");
         }
         let rel_path = RelPath::new(&mp.path[1..]);
-        // create the mount point in the main filesystem if it does not exist
-        match self.main_fs.root_dir().lookup(&rel_path) {
+        // create the mount point in the mounted filesystems if it does not exist
+        match self.lookup_mounted_fs_then(&rel_path, |fs, rest| fs.root_dir().lookup(rest)) {
             Ok(node) => {
                 if !node.get_attr()?.is_dir() {
-                    return ax_err!(InvalidInput, "mount point is not a directory");
+                    return ax_err!(NotADirectory, "mount point is not a directory");
                 }
                 // TODO: permission check
             }
             Err(VfsError::NotFound) => {
-                self.main_fs.root_dir().create(
-                    &rel_path,
-                    VfsNodeType::Dir,
-                    VfsNodePerm::default_dir(),
-                )?;
+                self.lookup_mounted_fs_then(&rel_path, |fs, rest| {
+                    fs.root_dir()
+                        .create(rest, VfsNodeType::Dir, VfsNodePerm::default_dir())
+                })?;
             }
             Err(e) => {
                 return Err(e);
             }
         }
-        let parent = if let Some((parent_path, _)) = rel_path.rsplit_once('/') {
-            self.main_fs.root_dir().lookup(&RelPath::new(parent_path))?
-        } else {
-            self.main_fs.root_dir()
-        };
+        let parent_path = rel_path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let parent = self.lookup_mounted_fs_then(&RelPath::new(parent_path), |fs, rest| {
+            fs.root_dir().lookup(rest)
+        })?;
         // Ensure the parent directory exists
         mp.fs.mount(parent)?;
 
+        let mut already_mount = self.mount_points.lock();
+        if already_mount.iter().any(|m| m.path == mp.path) {
+            return ax_err!(ResourceBusy, "mount point already exists");
+        }
         already_mount.push(mp);
         Ok(())
     }
 
     /// Unmount the filesystem at the specified path.
-    pub fn umount(&self, path: &AbsPath) {
-        self.mount_points
-            .lock()
-            .retain(|mp| mp.path != path.to_string());
+    ///
+    /// Fails if the path is not a mount point, or other filesystems are
+    /// mounted under it.
+    pub fn umount(&self, path: &AbsPath) -> AxResult {
+        let path = path.to_string();
+        let mut mount_points = self.mount_points.lock();
+        let idx = mount_points
+            .iter()
+            .position(|mp| mp.path == path)
+            .ok_or(AxError::InvalidInput)?;
+        let nested = format!("{path}/");
+        if mount_points.iter().any(|mp| mp.path.starts_with(&nested)) {
+            return ax_err!(ResourceBusy, "filesystems mounted under the mount point");
+        }
+        #[cfg(feature = "alloc")]
+        crate::page_cache::unmount(&mount_points[idx].fs);
+        let mp = mount_points.remove(idx);
+        drop(mount_points);
+        info!("Root dir unmounted {}", mp.path);
+        Ok(())
+    }
+
+    /// Change whether the filesystem at the specified path is read-only.
+    pub fn remount(&self, path: &AbsPath, read_only: bool) -> AxResult {
+        if path.to_string() == "/" {
+            self.root.lock().read_only = read_only;
+            return Ok(());
+        }
+        let mut mount_points = self.mount_points.lock();
+        let mp = mount_points
+            .iter_mut()
+            .find(|mp| mp.path == path.to_string())
+            .ok_or(AxError::InvalidInput)?;
+        mp.read_only = read_only;
+        Ok(())
+    }
+
+    /// Check if the filesystem containing the path is mounted read-only.
+    pub fn is_read_only(&self, path: &RelPath) -> bool {
+        let (idx, len) = self.lookup_mounted_fs(path);
+        if len > 0 {
+            self.mount_points.lock()[idx].read_only
+        } else {
+            self.root.lock().read_only
+        }
+    }
+
+    /// Check if the source, e.g. a block device, is mounted.
+    ///
+    /// The source is compared as is, so the path of a device should be
+    /// canonicalized first.
+    pub fn is_source_mounted(&self, source: &str) -> bool {
+        self.root.lock().source == source
+            || self
+                .mount_points
+                .lock()
+                .iter()
+                .any(|mp| mp.source == source)
+    }
+
+    /// Returns the mounted filesystems in the format of `/proc/mounts`.
+    pub fn mount_table(&self) -> String {
+        fn escape(s: &str) -> String {
+            if s.is_empty() {
+                return String::from("none");
+            }
+            let mut escaped = String::new();
+            for c in s.chars() {
+                match c {
+                    ' ' | '\t' | '\n' | '\\' => escaped += &format!("\\{:03o}", c as u32),
+                    _ => escaped.push(c),
+                }
+            }
+            escaped
+        }
+
+        let mut table = String::new();
+        let root = self.root.lock();
+        let mount_points = self.mount_points.lock();
+        for mp in core::iter::once(&*root).chain(mount_points.iter()) {
+            table += &format!(
+                "{} {} {} {} 0 0\n",
+                escape(&mp.source),
+                escape(&mp.path),
+                escape(&mp.fstype),
+                if mp.read_only { "ro" } else { "rw" },
+            );
+        }
+        table
+    }
+
+    fn check_writable(&self, path: &RelPath) -> AxResult {
+        if self.is_read_only(path) {
+            return ax_err!(ReadOnlyFilesystem);
+        }
+        Ok(())
     }
 
     /// Check if path is a mount point
@@ -141,6 +279,25 @@ impl RootDirectory {
         (idx, max_len)
     }
 
+    /// Check if two paths are in the same filesystem.
+    pub(crate) fn same_fs(&self, path1: &RelPath, path2: &RelPath) -> bool {
+        let (idx1, len1) = self.lookup_mounted_fs(path1);
+        let (idx2, len2) = self.lookup_mounted_fs(path2);
+        match (len1, len2) {
+            (0, 0) => true,
+            (0, _) | (_, 0) => false,
+            _ => idx1 == idx2,
+        }
+    }
+
+    /// Get the filesystem at the path, if its files are read and written
+    /// through the page cache.
+    pub(crate) fn page_cache_fs(&self, path: &RelPath) -> Option<Arc<dyn VfsOps>> {
+        self.lookup_mounted_fs_then(path, |fs, _| Ok(fs.use_page_cache().then_some(fs)))
+            .ok()
+            .flatten()
+    }
+
     /// Check if path matches a mountpoint, dispatch the operation to the matched filesystem
     fn lookup_mounted_fs_then<F, T>(&self, path: &RelPath, f: F) -> AxResult<T>
     where
@@ -167,11 +324,20 @@ impl VfsNodeOps for RootDirectory {
         Ok(())
     }
 
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        self.main_fs.root_dir().set_owner(uid, gid)
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        self.main_fs.root_dir().set_times(atime, mtime)
+    }
+
     fn lookup(self: Arc<Self>, path: &RelPath) -> VfsResult<VfsNodeRef> {
         self.lookup_mounted_fs_then(path, |fs, rest_path| fs.root_dir().lookup(rest_path))
     }
 
     fn create(&self, path: &RelPath, ty: VfsNodeType, mode: VfsNodePerm) -> VfsResult {
+        self.check_writable(path)?;
         self.lookup_mounted_fs_then(path, |fs, rest_path| {
             if rest_path.is_empty() {
                 Ok(()) // already exists
@@ -181,7 +347,30 @@ impl VfsNodeOps for RootDirectory {
         })
     }
 
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        self.check_writable(path)?;
+        self.lookup_mounted_fs_then(path, |fs, rest_path| {
+            if rest_path.is_empty() {
+                ax_err!(AlreadyExists)
+            } else {
+                fs.root_dir().symlink(rest_path, target)
+            }
+        })
+    }
+
+    fn link(&self, path: &RelPath, src: VfsNodeRef) -> VfsResult<VfsNodeRef> {
+        self.check_writable(path)?;
+        self.lookup_mounted_fs_then(path, |fs, rest_path| {
+            if rest_path.is_empty() {
+                ax_err!(AlreadyExists)
+            } else {
+                fs.root_dir().link(rest_path, src)
+            }
+        })
+    }
+
     fn unlink(&self, path: &RelPath) -> VfsResult {
+        self.check_writable(path)?;
         self.lookup_mounted_fs_then(path, |fs, rest_path| {
             if rest_path.is_empty() {
                 ax_err!(PermissionDenied) // cannot remove mount points
@@ -200,6 +389,7 @@ impl VfsNodeOps for RootDirectory {
         if src_path.len() == src_len {
             return ax_err!(PermissionDenied); // cannot rename mount points
         }
+        self.check_writable(src_path)?;
         if src_len > 0 {
             let mounts = self.mount_points.lock();
             mounts[src_idx].fs.root_dir().rename(
@@ -2599,6 +2599,26 @@ impl VfsNodeOps for FuseNode {
         Ok(())
     }
 
+    fn symlink(&self, path: &RelPath, target: &str) -> VfsResult {
+        let (name, raw_rest) = split_path(path);
+        if let Some(rest) = raw_rest {
+            if name.is_empty() || name == "." {
+                return VfsNodeOps::symlink(self, &RelPath::new(rest), target);
+            }
+            return self
+                .try_get(&RelPath::new(name))?
+                .symlink(&RelPath::new(rest), target);
+        }
+        FuseNode::symlink(self, &RelPath::new(name), &RelPath::new(target))
+    }
+
+    fn readlink(&self, buf: &mut [u8]) -> VfsResult<usize> {
+        let target = FuseNode::readlink(self)?;
+        let len = target.len().min(buf.len());
+        buf[..len].copy_from_slice(&target.as_bytes()[..len]);
+        Ok(len)
+    }
+
     fn unlink(&self, path: &RelPath) -> VfsResult {
         let (name, raw_rest) = split_path(path);
         if let Some(rest) = raw_rest {
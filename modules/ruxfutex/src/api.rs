@@ -13,8 +13,8 @@ use axerrno::{AxError, AxResult};
 use log::{debug, trace};
 
 use super::{
-    types::{FutexBucket, FutexKey},
-    FUTEX_BUCKETS,
+    types::{FutexBucket, FutexKey, FutexWakeOp},
+    FUTEX_BUCKETS, FUTEX_REQUEUED,
 };
 
 /// The bitset that matches any task,
@@ -33,6 +33,11 @@ fn futex_wait_timeout(
     let (_, futex_bucket) = FUTEX_BUCKETS.get_bucket(futex_key);
 
     let condition = || {
+        // Do not sleep if a signal is already pending
+        #[cfg(feature = "signal")]
+        if ruxtask::Signal::has_pending() {
+            return Err(AxError::Interrupted);
+        }
         // Check the futex value
         let actual_val = futex_key.load_val();
         trace!("futex_wait: expected {futex_val}, found {actual_val}");
@@ -46,7 +51,7 @@ fn futex_wait_timeout(
     };
 
     // Lock the queue before checking futex value.
-    match timeout {
+    let wait_in_bucket = || match timeout {
         Some(timeout) => {
             #[cfg(feature = "irq")]
             let wait_timeout = if is_relative {
@@ -56,10 +61,38 @@ fn futex_wait_timeout(
             };
             #[cfg(not(feature = "irq"))]
             let wait_timeout = FutexBucket::wait_timeout_absolutely_meta_if;
-            let _is_timeout = wait_timeout(futex_bucket, timeout, futex_key, condition)?;
-            Ok(())
+            wait_timeout(futex_bucket, timeout, futex_key, condition)
         }
         None => futex_bucket.wait_meta_if(futex_key, condition),
+    };
+    let wait = || {
+        let res = wait_in_bucket();
+        unqueue_requeued();
+        res
+    };
+
+    #[cfg(feature = "signal")]
+    {
+        // A signal sent to the task wakes it up without a notification, and
+        // the wait fails with `AxError::Interrupted`. A task woken up by
+        // `futex_wake` succeeds even if a signal arrives at the same time.
+        let notified = !ruxtask::Signal::interruptible(wait)?;
+        if !notified && ruxtask::Signal::has_pending() {
+            return Err(AxError::Interrupted);
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "signal"))]
+    wait().map(|_| ())
+}
+
+/// Removes the current task from the bucket it has been requeued to, since
+/// a timeout or a signal only removes it from the original bucket.
+fn unqueue_requeued() {
+    let task_id = ruxtask::current().id().as_u64();
+    let bucket = FUTEX_REQUEUED.lock().remove(&task_id);
+    if let Some(idx) = bucket {
+        FUTEX_BUCKETS.buckets[idx].remove_current();
     }
 }
 
@@ -75,7 +108,9 @@ fn futex_wait_timeout(
 /// operations on the same futex word. If the thread starts
 /// to sleep, it is considered a waiter on this futex word.
 /// If the futex value does not match val, then the call fails
-/// immediately with the error [`AxError::WouldBlock`].
+/// immediately with the error [`AxError::WouldBlock`]. With the `signal`
+/// feature, if a signal is pending or arrives during the wait, the call
+/// fails with [`AxError::Interrupted`].
 ///
 /// The purpose of the comparison with the expected value is
 /// to prevent lost wake-ups. If another thread changed the
@@ -97,6 +132,7 @@ fn futex_wait_timeout(
 /// [`FUTEX_BITSET_MATCH_ANY`].
 ///
 /// [`AxError::WouldBlock`]: axerrno::AxError::WouldBlock
+/// [`AxError::Interrupted`]: axerrno::AxError::Interrupted
 pub fn futex_wait(
     futex_addr: *const i32,
     futex_val: i32,
@@ -210,3 +246,88 @@ pub fn futex_wake_bitset(futex_addr: *const i32, max_count: usize, bitset: u32)
     });
     Ok(task_count)
 }
+
+/// This operation wakes at most `max_wake` of the waiters that are waiting on
+/// the futex word at the address `futex_addr`, and then moves at most
+/// `max_requeue` of the remaining waiters to wait on the futex word at the
+/// address `futex_addr2`, without waking them up. It is commonly used to avoid
+/// the "thundering herd" effect when all waiters would otherwise be woken up
+/// to contend on another lock.
+///
+/// If `futex_val` is not [`None`], the operation is performed only if the
+/// futex word at `futex_addr` still contains the value, otherwise it fails
+/// with the error [`AxError::WouldBlock`]. This corresponds to
+/// `FUTEX_CMP_REQUEUE`, and `FUTEX_REQUEUE` if `futex_val` is [`None`].
+///
+/// Returns the total number of waiters that were woken up or requeued.
+///
+/// [`AxError::WouldBlock`]: axerrno::AxError::WouldBlock
+pub fn futex_requeue(
+    futex_addr: *const i32,
+    max_wake: usize,
+    futex_addr2: *const i32,
+    max_requeue: usize,
+    futex_val: Option<i32>,
+) -> AxResult<usize> {
+    debug!(
+        "futex_requeue addr: {:#x}, max_wake: {}, addr2: {:#x}, max_requeue: {}, val: {:?}",
+        futex_addr as usize, max_wake, futex_addr2 as usize, max_requeue, futex_val
+    );
+
+    let futex_key = FutexKey::new(futex_addr, FUTEX_BITSET_MATCH_ANY);
+    let futex_key2 = FutexKey::new(futex_addr2, FUTEX_BITSET_MATCH_ANY);
+    let (_, futex_bucket) = FUTEX_BUCKETS.get_bucket(futex_key);
+    let (idx2, futex_bucket2) = FUTEX_BUCKETS.get_bucket(futex_key2);
+
+    let condition = || match futex_val {
+        Some(val) if futex_key.load_val() != val => Err(AxError::WouldBlock),
+        _ => Ok(()),
+    };
+    let (woken, requeued) = futex_bucket.requeue_task_if(
+        futex_bucket2,
+        max_wake,
+        max_requeue,
+        condition,
+        // tasks woken up by timers or signals are removed by themselves
+        |task, &key| task.is_blocked() && key == futex_key,
+        |task, key| {
+            *key = key.requeue(futex_addr2);
+            FUTEX_REQUEUED.lock().insert(task.id().as_u64(), idx2);
+        },
+    )?;
+    trace!("futex_requeue: woken: {woken}, requeued: {requeued}");
+    Ok(woken + requeued)
+}
+
+/// This operation atomically modifies the futex word at the address
+/// `futex_addr2` as encoded in `op`, wakes at most `max_wake` of the waiters
+/// on the futex word at the address `futex_addr`, and if the old value of
+/// the futex word at `futex_addr2` satisfies the comparison encoded in `op`,
+/// also wakes at most `max_wake2` of the waiters on it.
+///
+/// `op` is encoded the same as the `val3` argument of `FUTEX_WAKE_OP` in
+/// Linux, an invalid one fails with the error [`AxError::InvalidInput`].
+///
+/// Returns the total number of waiters that were woken up.
+///
+/// [`AxError::InvalidInput`]: axerrno::AxError::InvalidInput
+pub fn futex_wake_op(
+    futex_addr: *const i32,
+    max_wake: usize,
+    futex_addr2: *const i32,
+    max_wake2: usize,
+    op: u32,
+) -> AxResult<usize> {
+    debug!(
+        "futex_wake_op addr: {:#x}, max_wake: {}, addr2: {:#x}, max_wake2: {}, op: {:#x}",
+        futex_addr as usize, max_wake, futex_addr2 as usize, max_wake2, op
+    );
+
+    let op = FutexWakeOp::decode(op)?;
+    let old_val = op.apply(futex_addr2);
+    let mut count = futex_wake(futex_addr, max_wake)?;
+    if op.compare(old_val) {
+        count += futex_wake(futex_addr2, max_wake2)?;
+    }
+    Ok(count)
+}
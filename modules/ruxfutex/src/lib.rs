@@ -23,12 +23,22 @@ extern crate alloc;
 extern crate log;
 
 mod api;
+mod pi;
+mod robust;
 mod types;
 
 pub use api::{
-    futex_wait, futex_wait_bitset, futex_wake, futex_wake_bitset, FUTEX_BITSET_MATCH_ANY,
+    futex_requeue, futex_wait, futex_wait_bitset, futex_wake, futex_wake_bitset, futex_wake_op,
+    FUTEX_BITSET_MATCH_ANY,
 };
+pub use pi::{
+    futex_lock_pi, futex_trylock_pi, futex_unlock_pi, FUTEX_OWNER_DIED, FUTEX_TID_MASK,
+    FUTEX_WAITERS,
+};
+pub use robust::{futex_exit_robust_list, RobustList, RobustListHead};
 
+use alloc::collections::BTreeMap;
+use spinlock::SpinNoIrq;
 use types::FutexVec;
 
 use core::ops::Deref;
@@ -43,6 +53,10 @@ lazy_static! {
     static ref FUTEX_BUCKETS: FutexVec = FutexVec::new(BUCKET_COUNT);
 }
 
+/// Indices of the buckets that waiting tasks have been requeued to, keyed by
+/// the task ids.
+static FUTEX_REQUEUED: SpinNoIrq<BTreeMap<u64, usize>> = SpinNoIrq::new(BTreeMap::new());
+
 /// Inits the futex module.
 pub fn init_futex() {
     let _ = FUTEX_BUCKETS.deref();
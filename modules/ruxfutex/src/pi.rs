@@ -0,0 +1,302 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Priority-inheritance futexes.
+//!
+//! The futex word of a PI futex holds the TID of its owner, or 0 if it is not
+//! locked. A task blocked on a PI futex boosts the priority of the owner to
+//! its own, until the owner unlocks the futex, which hands it over to the
+//! waiter with the highest priority.
+//!
+//! Only the owner of the futex is boosted, the boost is not propagated if the
+//! owner is itself blocked on another PI futex.
+
+use core::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use alloc::{collections::BTreeMap, sync::Arc};
+use axerrno::{ax_err, AxError, AxResult};
+use log::{debug, trace};
+use ruxtask::AxTaskRef;
+use spinlock::SpinNoIrq;
+
+use super::{api::FUTEX_BITSET_MATCH_ANY, types::FutexKey, FUTEX_BUCKETS};
+
+/// There are tasks blocked on the futex.
+pub const FUTEX_WAITERS: u32 = 0x8000_0000;
+/// The owner of the futex exited without unlocking it.
+pub const FUTEX_OWNER_DIED: u32 = 0x4000_0000;
+/// Bits of the TID of the owner in the futex word.
+pub const FUTEX_TID_MASK: u32 = 0x3fff_ffff;
+
+/// The owner of a PI futex, boosted by the tasks blocked on the futex.
+struct PiState {
+    owner: AxTaskRef,
+    /// The highest priority of the waiters, the lower the higher.
+    prio: isize,
+}
+
+/// States of the PI futexes with waiters, keyed by their addresses.
+static PI_STATES: SpinNoIrq<BTreeMap<usize, PiState>> = SpinNoIrq::new(BTreeMap::new());
+
+#[inline]
+pub(crate) fn futex_word<'a>(futex_addr: *const i32) -> &'a AtomicU32 {
+    unsafe { &*(futex_addr as *const AtomicU32) }
+}
+
+#[inline]
+fn current_tid() -> u32 {
+    ruxtask::current().id().as_u64() as u32 & FUTEX_TID_MASK
+}
+
+/// Boosts `owner` of the futex at `addr` to `prio` if it is higher.
+fn pi_boost(addr: usize, owner: &AxTaskRef, prio: isize) {
+    let mut states = PI_STATES.lock();
+    let state = states.entry(addr).or_insert_with(|| PiState {
+        owner: owner.clone(),
+        prio,
+    });
+    if !Arc::ptr_eq(&state.owner, owner) {
+        // the futex has changed hands since the last waiter came
+        state.owner = owner.clone();
+        state.prio = prio;
+    }
+    state.prio = state.prio.min(prio);
+    if prio < owner.priority() {
+        trace!("futex pi: boost {} to {}", owner.id_name(), prio);
+        ruxtask::set_task_priority(owner, prio);
+    }
+}
+
+/// Drops the states of the PI futexes at `addr` (or all if [`None`]) owned by
+/// the current task, and restores its priority from the remaining ones.
+pub(crate) fn pi_unboost(addr: Option<usize>) {
+    let curr = ruxtask::current();
+    let curr = curr.as_task_ref();
+    let mut states = PI_STATES.lock();
+    match addr {
+        Some(addr) => {
+            if states
+                .get(&addr)
+                .is_some_and(|state| Arc::ptr_eq(&state.owner, curr))
+            {
+                states.remove(&addr);
+            }
+        }
+        None => states.retain(|_, state| !Arc::ptr_eq(&state.owner, curr)),
+    }
+    let prio = states
+        .values()
+        .filter(|state| Arc::ptr_eq(&state.owner, curr))
+        .map(|state| state.prio)
+        .fold(curr.normal_priority(), isize::min);
+    if prio != curr.priority() {
+        trace!("futex pi: restore {} to {}", curr.id_name(), prio);
+        ruxtask::set_task_priority(curr, prio);
+    }
+}
+
+/// This operation tries to acquire the PI futex at the address `futex_addr`,
+/// by atomically setting the futex word to the TID of the caller if it is 0.
+/// If the futex is owned by another task, the caller marks the futex word
+/// with [`FUTEX_WAITERS`], boosts the owner to its own priority, and sleeps
+/// until the futex is handed over to it by [`futex_unlock_pi`].
+///
+/// `find_task` looks up the owner by its TID.
+///
+/// If `timeout` is not [`None`], it specifies an absolute timeout, after which
+/// the call fails with [`AxError::TimedOut`]. The call fails with
+/// [`AxError::Deadlock`] if the futex is already owned by the caller, or
+/// [`AxError::NoSuchProcess`] if the owner does not exist. With the `signal`
+/// feature, it fails with [`AxError::Interrupted`] if interrupted by a signal.
+///
+/// [`AxError::TimedOut`]: axerrno::AxError::TimedOut
+/// [`AxError::Deadlock`]: axerrno::AxError::Deadlock
+/// [`AxError::NoSuchProcess`]: axerrno::AxError::NoSuchProcess
+/// [`AxError::Interrupted`]: axerrno::AxError::Interrupted
+pub fn futex_lock_pi<F>(
+    futex_addr: *const i32,
+    timeout: Option<Duration>,
+    find_task: F,
+) -> AxResult<()>
+where
+    F: Fn(u32) -> Option<AxTaskRef>,
+{
+    debug!(
+        "futex_lock_pi addr: {:#x}, timeout: {:?}",
+        futex_addr as usize, timeout
+    );
+
+    let futex_key = FutexKey::new(futex_addr, FUTEX_BITSET_MATCH_ANY);
+    let (_, futex_bucket) = FUTEX_BUCKETS.get_bucket(futex_key);
+    let word = futex_word(futex_addr);
+    let tid = current_tid();
+
+    loop {
+        let val = word.load(Ordering::SeqCst);
+        let owner = val & FUTEX_TID_MASK;
+        if owner == 0 {
+            // keep `FUTEX_WAITERS` for the other waiters
+            if word
+                .compare_exchange(val, val | tid, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return Ok(());
+            }
+            continue;
+        }
+        if owner == tid {
+            return ax_err!(Deadlock, "futex_lock_pi: already owned by the caller");
+        }
+        if val & FUTEX_WAITERS == 0
+            && word
+                .compare_exchange(val, val | FUTEX_WAITERS, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+        {
+            continue;
+        }
+        let val = val | FUTEX_WAITERS;
+
+        let owner_task = find_task(owner).ok_or(AxError::NoSuchProcess)?;
+        pi_boost(futex_key.addr(), &owner_task, ruxtask::current().priority());
+        drop(owner_task);
+
+        let condition = || {
+            #[cfg(feature = "signal")]
+            if ruxtask::Signal::has_pending() {
+                return Err(AxError::Interrupted);
+            }
+            // the futex has been unlocked or handed over
+            if word.load(Ordering::SeqCst) != val {
+                return Err(AxError::WouldBlock);
+            }
+            Ok(())
+        };
+        let wait = || match timeout {
+            Some(deadline) => {
+                futex_bucket.wait_timeout_absolutely_meta_if(deadline, futex_key, condition)
+            }
+            None => futex_bucket.wait_meta_if(futex_key, condition),
+        };
+        #[cfg(feature = "signal")]
+        let res = ruxtask::Signal::interruptible(wait);
+        #[cfg(not(feature = "signal"))]
+        let res = wait();
+
+        if word.load(Ordering::SeqCst) & FUTEX_TID_MASK == tid {
+            // handed over by the owner
+            return Ok(());
+        }
+        match res {
+            // woken up without a notification, by the timer or a signal
+            Ok(true) => {
+                #[cfg(feature = "signal")]
+                if ruxtask::Signal::has_pending() {
+                    return Err(AxError::Interrupted);
+                }
+                if timeout.is_some() {
+                    return Err(AxError::TimedOut);
+                }
+            }
+            Ok(false) | Err(AxError::WouldBlock) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// This operation is like [`futex_lock_pi`], except that it fails with
+/// [`AxError::WouldBlock`] instead of sleeping if the futex is owned by
+/// another task.
+///
+/// [`AxError::WouldBlock`]: axerrno::AxError::WouldBlock
+pub fn futex_trylock_pi(futex_addr: *const i32) -> AxResult<()> {
+    debug!("futex_trylock_pi addr: {:#x}", futex_addr as usize);
+
+    let word = futex_word(futex_addr);
+    let tid = current_tid();
+    loop {
+        let val = word.load(Ordering::SeqCst);
+        match val & FUTEX_TID_MASK {
+            0 => {
+                if word
+                    .compare_exchange(val, val | tid, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_ok()
+                {
+                    return Ok(());
+                }
+            }
+            owner if owner == tid => {
+                return ax_err!(Deadlock, "futex_trylock_pi: already owned by the caller")
+            }
+            _ => return Err(AxError::WouldBlock),
+        }
+    }
+}
+
+/// This operation unlocks the PI futex at the address `futex_addr` owned by
+/// the caller. If there are waiters, the futex is handed over to the one with
+/// the highest priority, by setting the futex word to its TID, with
+/// [`FUTEX_WAITERS`] if there are still other waiters. The caller is restored
+/// to its normal priority, unless it is still boosted by other PI futexes.
+///
+/// The call fails with [`AxError::NotPermitted`] if the caller is not the
+/// owner of the futex.
+///
+/// [`AxError::NotPermitted`]: axerrno::AxError::NotPermitted
+pub fn futex_unlock_pi(futex_addr: *const i32) -> AxResult<()> {
+    debug!("futex_unlock_pi addr: {:#x}", futex_addr as usize);
+
+    let futex_key = FutexKey::new(futex_addr, FUTEX_BITSET_MATCH_ANY);
+    let (_, futex_bucket) = FUTEX_BUCKETS.get_bucket(futex_key);
+    let word = futex_word(futex_addr);
+    let tid = current_tid();
+
+    loop {
+        let val = word.load(Ordering::SeqCst);
+        if val & FUTEX_TID_MASK != tid {
+            return ax_err!(NotPermitted, "futex_unlock_pi: not owned by the caller");
+        }
+        if val & FUTEX_WAITERS == 0 {
+            if word
+                .compare_exchange(val, 0, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                break;
+            }
+            continue;
+        }
+
+        futex_bucket.notify_one_by_key_if(
+            false,
+            |task, &key| task.is_blocked() && key == futex_key,
+            |task, _| task.priority(),
+            |task, remaining| {
+                let new_val = match task {
+                    Some(task) => {
+                        let new_tid = task.id().as_u64() as u32 & FUTEX_TID_MASK;
+                        trace!("futex_unlock_pi: hand over to {}", task.id_name());
+                        if remaining > 0 {
+                            new_tid | FUTEX_WAITERS
+                        } else {
+                            new_tid
+                        }
+                    }
+                    None => 0,
+                };
+                word.store(new_val, Ordering::SeqCst);
+            },
+        );
+        break;
+    }
+
+    pi_unboost(Some(futex_key.addr()));
+    Ok(())
+}
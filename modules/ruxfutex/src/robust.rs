@@ -0,0 +1,105 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Robust futexes, released by the kernel when their owners exit.
+//!
+//! See [robust futex ABI] for the layout of the list.
+//!
+//! [robust futex ABI]: https://docs.kernel.org/locking/robust-futex-ABI.html
+
+use core::sync::atomic::Ordering;
+
+use log::{debug, warn};
+
+use super::{
+    api::futex_wake,
+    pi::{futex_word, pi_unboost, FUTEX_OWNER_DIED, FUTEX_TID_MASK, FUTEX_WAITERS},
+};
+
+/// Maximum number of entries walked in a robust list, against circular lists.
+const ROBUST_LIST_LIMIT: usize = 2048;
+
+/// An entry of the robust list, embedded in a lock of the user.
+#[repr(C)]
+pub struct RobustList {
+    /// The next entry, with bit 0 set if the lock is a PI futex.
+    pub next: usize,
+}
+
+/// The head of the robust list, same as `struct robust_list_head` of Linux.
+#[repr(C)]
+pub struct RobustListHead {
+    /// The first entry, or the head itself if the list is empty.
+    pub list: RobustList,
+    /// Offset of the futex word from an entry.
+    pub futex_offset: isize,
+    /// The entry being locked or unlocked, which may not be in the list yet.
+    pub list_op_pending: usize,
+}
+
+/// Marks the futex word at `futex_addr` with [`FUTEX_OWNER_DIED`] if it is
+/// owned by the exiting task `tid`, and wakes up a waiter to recover it.
+fn handle_futex_death(futex_addr: usize, tid: u32) {
+    let word = futex_word(futex_addr as *const i32);
+    loop {
+        let val = word.load(Ordering::SeqCst);
+        if val & FUTEX_TID_MASK != tid {
+            return;
+        }
+        let new_val = (val & FUTEX_WAITERS) | FUTEX_OWNER_DIED;
+        if word
+            .compare_exchange(val, new_val, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            if val & FUTEX_WAITERS != 0 {
+                // PI waiters take over the futex as it has no owner now
+                let _ = futex_wake(futex_addr as *const i32, 1);
+            }
+            return;
+        }
+    }
+}
+
+/// Releases the futexes still held by the current task on exit, which are
+/// recorded in the robust list at `head` and the PI futexes it owns.
+///
+/// The futex word of each lock in the robust list that is owned by the task
+/// is set to [`FUTEX_OWNER_DIED`], with [`FUTEX_WAITERS`] kept, and a waiter
+/// is woken up.
+pub fn futex_exit_robust_list(head: usize) {
+    pi_unboost(None);
+    if head == 0 {
+        return;
+    }
+    let tid = ruxtask::current().id().as_u64() as u32 & FUTEX_TID_MASK;
+    debug!("futex_exit_robust_list head: {head:#x}, tid: {tid}");
+
+    let head_ref = unsafe { &*(head as *const RobustListHead) };
+    let futex_addr = |entry: usize| (entry & !1).wrapping_add_signed(head_ref.futex_offset);
+    let pending = head_ref.list_op_pending;
+
+    let mut entry = head_ref.list.next;
+    let mut limit = ROBUST_LIST_LIMIT;
+    while entry != head && entry & !1 != 0 {
+        if limit == 0 {
+            warn!("futex_exit_robust_list: too many entries, maybe circular");
+            break;
+        }
+        limit -= 1;
+        // fetch the next entry first, as the lock may be freed once released
+        let next = unsafe { (*((entry & !1) as *const RobustList)).next };
+        if entry != pending {
+            handle_futex_death(futex_addr(entry), tid);
+        }
+        entry = next;
+    }
+    if pending & !1 != 0 {
+        handle_futex_death(futex_addr(pending), tid);
+    }
+}
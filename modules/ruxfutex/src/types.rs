@@ -15,6 +15,7 @@ use core::{
 
 use ahash::AHasher;
 use alloc::vec::Vec;
+use axerrno::{ax_err, AxResult};
 
 use ruxtask::WaitQueueWithMetadata;
 
@@ -70,6 +71,95 @@ impl FutexKey {
     pub fn bitset(&self) -> u32 {
         self.bitset
     }
+
+    /// Create the key of a waiter requeued to the futex at `addr`.
+    #[inline]
+    pub fn requeue(&self, addr: *const i32) -> Self {
+        Self::new(addr, self.bitset)
+    }
+}
+
+/// The operation of `FUTEX_WAKE_OP` encoded in its `val3` argument:
+///
+/// ```text
+/// +---+---+-----------+-----------+
+/// |op |cmp|   oparg   |  cmparg   |
+/// +---+---+-----------+-----------+
+///   4   4       12          12      <== # of bits
+/// ```
+pub(crate) struct FutexWakeOp {
+    op: u32,
+    cmp: u32,
+    oparg: i32,
+    cmparg: i32,
+}
+
+impl FutexWakeOp {
+    const OP_SET: u32 = 0;
+    const OP_ADD: u32 = 1;
+    const OP_OR: u32 = 2;
+    const OP_ANDN: u32 = 3;
+    const OP_XOR: u32 = 4;
+    /// Use `1 << oparg` as the operand.
+    const OP_OPARG_SHIFT: u32 = 8;
+
+    const CMP_EQ: u32 = 0;
+    const CMP_NE: u32 = 1;
+    const CMP_LT: u32 = 2;
+    const CMP_LE: u32 = 3;
+    const CMP_GT: u32 = 4;
+    const CMP_GE: u32 = 5;
+
+    pub fn decode(bits: u32) -> AxResult<Self> {
+        let op = bits >> 28;
+        let cmp = (bits >> 24) & 0xf;
+        // both arguments are sign-extended 12-bit values
+        let mut oparg = ((bits << 8) as i32) >> 20;
+        let cmparg = ((bits << 20) as i32) >> 20;
+
+        if op & Self::OP_OPARG_SHIFT != 0 {
+            // out-of-range shifts are truncated, as Linux does
+            oparg = 1 << (oparg & 31);
+        }
+        let op = op & !Self::OP_OPARG_SHIFT;
+        if op > Self::OP_XOR || cmp > Self::CMP_GE {
+            return ax_err!(InvalidInput, "invalid futex wake op: {:#x}", bits);
+        }
+        Ok(Self {
+            op,
+            cmp,
+            oparg,
+            cmparg,
+        })
+    }
+
+    /// Apply the operation to the futex word at `addr` atomically, returning
+    /// its old value.
+    pub fn apply(&self, addr: *const i32) -> i32 {
+        let atomic = unsafe { &*(addr as *const AtomicI32) };
+        let ordering = atomic::Ordering::SeqCst;
+        match self.op {
+            Self::OP_SET => atomic.swap(self.oparg, ordering),
+            Self::OP_ADD => atomic.fetch_add(self.oparg, ordering),
+            Self::OP_OR => atomic.fetch_or(self.oparg, ordering),
+            Self::OP_ANDN => atomic.fetch_and(!self.oparg, ordering),
+            Self::OP_XOR => atomic.fetch_xor(self.oparg, ordering),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Compare the old value of the futex word with `cmparg`.
+    pub fn compare(&self, old_val: i32) -> bool {
+        match self.cmp {
+            Self::CMP_EQ => old_val == self.cmparg,
+            Self::CMP_NE => old_val != self.cmparg,
+            Self::CMP_LT => old_val < self.cmparg,
+            Self::CMP_LE => old_val <= self.cmparg,
+            Self::CMP_GT => old_val > self.cmparg,
+            Self::CMP_GE => old_val >= self.cmparg,
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl PartialEq for FutexKey {
@@ -8,6 +8,8 @@
  */
 
 mod context;
+#[cfg(feature = "signal")]
+mod signal;
 pub(crate) mod trap;
 
 use core::arch::asm;
@@ -18,6 +20,11 @@ use memory_addr::{PhysAddr, VirtAddr};
 use tock_registers::interfaces::{Readable, Writeable};
 
 pub use self::context::{FpState, TaskContext, TrapFrame};
+#[cfg(feature = "signal")]
+pub use self::signal::{
+    call_signal_handler, resume_signal_handler, signal_return_trampoline, FpsimdContext, MContext,
+    UContext,
+};
 
 /// Allows the current CPU to respond to interrupts.
 #[inline]
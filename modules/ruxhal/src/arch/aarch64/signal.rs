@@ -0,0 +1,293 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Signal frames of AArch64.
+
+use core::arch::naked_asm;
+
+use super::TrapFrame;
+use crate::trap::SignalStack;
+
+/// Magic number of [`FpsimdContext`].
+const FPSIMD_MAGIC: u32 = 0x4650_8001;
+
+/// Condition flags (NZCV) in `SPSR`, the only ones signal handlers are
+/// allowed to change.
+const SPSR_NZCV: u64 = 0xf << 28;
+
+/// `SPSR.I`
+const SPSR_I: u64 = 1 << 7;
+
+/// `SPSR.M` of a trap taken from EL0.
+const SPSR_M_EL0T: u64 = 0;
+
+/// FP/SIMD state saved in [`MContext::reserved`], same as `fpsimd_context`
+/// of Linux.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FpsimdContext {
+    magic: u32,
+    size: u32,
+    /// Floating-point status register.
+    pub fpsr: u32,
+    /// Floating-point control register.
+    pub fpcr: u32,
+    /// SIMD registers `V0`-`V31`.
+    pub vregs: [u128; 32],
+}
+
+/// Machine context, same as `mcontext_t` of musl.
+#[repr(C, align(16))]
+pub struct MContext {
+    /// Faulting address of the trap.
+    pub fault_address: u64,
+    /// General registers `X0`-`X30`.
+    pub regs: [u64; 31],
+    /// Stack pointer.
+    pub sp: u64,
+    /// Program counter.
+    pub pc: u64,
+    /// Processor state.
+    pub pstate: u64,
+    /// Extra contexts, starting with a [`FpsimdContext`].
+    pub reserved: [u8; 4096],
+}
+
+/// User context of a signal frame, same as `ucontext_t` of musl.
+#[repr(C, align(16))]
+pub struct UContext {
+    /// Context flags.
+    pub flags: usize,
+    /// Context to resume when this one returns, unused.
+    pub link: usize,
+    /// Signal stack used by this context.
+    pub stack: SignalStack,
+    /// Signals blocked in this context.
+    pub sigmask: [u64; 16],
+    /// Saved registers.
+    pub mcontext: MContext,
+}
+
+impl UContext {
+    /// Saves the context interrupted by the trap `tf`, with the blocked
+    /// signals `sigmask` and the signal stack `stack`.
+    pub fn save(&mut self, tf: &TrapFrame, sigmask: u64, stack: SignalStack) {
+        self.flags = 0;
+        self.link = 0;
+        self.stack = stack;
+        self.sigmask = [0; 16];
+        self.sigmask[0] = sigmask;
+        self.mcontext.fault_address = 0;
+        self.mcontext.regs = tf.r;
+        self.mcontext.sp = tf.sp() as u64;
+        self.mcontext.pc = tf.elr;
+        self.mcontext.pstate = tf.spsr;
+        self.mcontext.reserved = [0; 4096];
+        let fpsimd = self.fpsimd_mut();
+        fpsimd.magic = FPSIMD_MAGIC;
+        fpsimd.size = core::mem::size_of::<FpsimdContext>() as u32;
+        #[cfg(feature = "fp_simd")]
+        unsafe {
+            save_fpsimd(fpsimd)
+        }
+    }
+
+    /// Restores the trap frame `tf` from this context, which may have been
+    /// modified by the signal handler. Returns the signals to be blocked.
+    ///
+    /// The stack pointer is not restored, since it is not saved in the trap
+    /// frame of a trap taken from EL1.
+    pub fn restore(&self, tf: &mut TrapFrame) -> u64 {
+        tf.r = self.mcontext.regs;
+        tf.elr = self.mcontext.pc;
+        tf.spsr = (tf.spsr & !SPSR_NZCV) | (self.mcontext.pstate & SPSR_NZCV);
+        #[cfg(feature = "fp_simd")]
+        if self.fpsimd().magic == FPSIMD_MAGIC {
+            unsafe { restore_fpsimd(self.fpsimd()) }
+        }
+        self.sigmask[0]
+    }
+
+    #[cfg(feature = "fp_simd")]
+    fn fpsimd(&self) -> &FpsimdContext {
+        unsafe { &*(self.mcontext.reserved.as_ptr() as *const FpsimdContext) }
+    }
+
+    fn fpsimd_mut(&mut self) -> &mut FpsimdContext {
+        unsafe { &mut *(self.mcontext.reserved.as_mut_ptr() as *mut FpsimdContext) }
+    }
+}
+
+impl TrapFrame {
+    /// The stack pointer of the interrupted context.
+    pub fn sp(&self) -> usize {
+        if self.spsr & 0xf == SPSR_M_EL0T {
+            self.usp as usize
+        } else {
+            // the trap frame is pushed right below the interrupted stack
+            self as *const _ as usize + core::mem::size_of::<TrapFrame>()
+        }
+    }
+
+    /// Whether IRQs were enabled in the interrupted context.
+    pub const fn irqs_enabled(&self) -> bool {
+        self.spsr & SPSR_I == 0
+    }
+}
+
+#[cfg(feature = "fp_simd")]
+unsafe fn save_fpsimd(fpsimd: &mut FpsimdContext) {
+    let fpsr: u64;
+    let fpcr: u64;
+    core::arch::asm!(
+        "
+        stp     q0, q1, [{0}, 0 * 16]
+        stp     q2, q3, [{0}, 2 * 16]
+        stp     q4, q5, [{0}, 4 * 16]
+        stp     q6, q7, [{0}, 6 * 16]
+        stp     q8, q9, [{0}, 8 * 16]
+        stp     q10, q11, [{0}, 10 * 16]
+        stp     q12, q13, [{0}, 12 * 16]
+        stp     q14, q15, [{0}, 14 * 16]
+        stp     q16, q17, [{0}, 16 * 16]
+        stp     q18, q19, [{0}, 18 * 16]
+        stp     q20, q21, [{0}, 20 * 16]
+        stp     q22, q23, [{0}, 22 * 16]
+        stp     q24, q25, [{0}, 24 * 16]
+        stp     q26, q27, [{0}, 26 * 16]
+        stp     q28, q29, [{0}, 28 * 16]
+        stp     q30, q31, [{0}, 30 * 16]
+        mrs     {1}, fpsr
+        mrs     {2}, fpcr",
+        in(reg) fpsimd.vregs.as_mut_ptr(),
+        out(reg) fpsr,
+        out(reg) fpcr,
+    );
+    fpsimd.fpsr = fpsr as u32;
+    fpsimd.fpcr = fpcr as u32;
+}
+
+#[cfg(feature = "fp_simd")]
+unsafe fn restore_fpsimd(fpsimd: &FpsimdContext) {
+    core::arch::asm!(
+        "
+        ldp     q0, q1, [{0}, 0 * 16]
+        ldp     q2, q3, [{0}, 2 * 16]
+        ldp     q4, q5, [{0}, 4 * 16]
+        ldp     q6, q7, [{0}, 6 * 16]
+        ldp     q8, q9, [{0}, 8 * 16]
+        ldp     q10, q11, [{0}, 10 * 16]
+        ldp     q12, q13, [{0}, 12 * 16]
+        ldp     q14, q15, [{0}, 14 * 16]
+        ldp     q16, q17, [{0}, 16 * 16]
+        ldp     q18, q19, [{0}, 18 * 16]
+        ldp     q20, q21, [{0}, 20 * 16]
+        ldp     q22, q23, [{0}, 22 * 16]
+        ldp     q24, q25, [{0}, 24 * 16]
+        ldp     q26, q27, [{0}, 26 * 16]
+        ldp     q28, q29, [{0}, 28 * 16]
+        ldp     q30, q31, [{0}, 30 * 16]
+        msr     fpsr, {1}
+        msr     fpcr, {2}",
+        in(reg) fpsimd.vregs.as_ptr(),
+        in(reg) fpsimd.fpsr as u64,
+        in(reg) fpsimd.fpcr as u64,
+    );
+}
+
+/// Calls the signal handler `handler` with `args` as its arguments.
+///
+/// The handler runs on the stack `sp`, or on the current stack if `sp` is 0,
+/// and returns to `restorer`. The stack pointer of the caller is saved to
+/// `ctx`, so that [`resume_signal_handler`] can return from this function.
+///
+/// # Safety
+///
+/// `handler` and `restorer` must be valid functions, and `sp` must be the top
+/// of a valid stack.
+#[unsafe(naked)]
+pub unsafe extern "C" fn call_signal_handler(
+    _ctx: *mut usize,
+    _handler: usize,
+    _args: &[usize; 3],
+    _sp: usize,
+    _restorer: usize,
+) {
+    // x0: ctx, x1: handler, x2: args, x3: sp, x4: restorer
+    naked_asm!(
+        "
+        sub     sp, sp, 20 * 8
+        stp     x19, x20, [sp, 0 * 8]
+        stp     x21, x22, [sp, 2 * 8]
+        stp     x23, x24, [sp, 4 * 8]
+        stp     x25, x26, [sp, 6 * 8]
+        stp     x27, x28, [sp, 8 * 8]
+        stp     x29, x30, [sp, 10 * 8]
+        stp     d8, d9, [sp, 12 * 8]
+        stp     d10, d11, [sp, 14 * 8]
+        stp     d12, d13, [sp, 16 * 8]
+        stp     d14, d15, [sp, 18 * 8]
+        mov     x9, sp
+        str     x9, [x0]
+
+        // keep `ctx` in a callee-saved register for `signal_return_trampoline`
+        mov     x19, x0
+        cbz     x3, 1f
+        and     x3, x3, #0xfffffffffffffff0
+        mov     sp, x3
+    1:
+        mov     x30, x4
+        mov     x9, x1
+        ldr     x10, [x2, 16]
+        ldp     x0, x1, [x2]
+        mov     x2, x10
+        br      x9",
+    )
+}
+
+/// Returns from [`call_signal_handler`] whose stack pointer was saved to `ctx`.
+///
+/// # Safety
+///
+/// `ctx` must be saved by a [`call_signal_handler`] which has not returned.
+#[unsafe(naked)]
+pub unsafe extern "C" fn resume_signal_handler(_ctx: *const usize) -> ! {
+    naked_asm!(
+        "
+        ldr     x9, [x0]
+        mov     sp, x9
+        ldp     d14, d15, [sp, 18 * 8]
+        ldp     d12, d13, [sp, 16 * 8]
+        ldp     d10, d11, [sp, 14 * 8]
+        ldp     d8, d9, [sp, 12 * 8]
+        ldp     x29, x30, [sp, 10 * 8]
+        ldp     x27, x28, [sp, 8 * 8]
+        ldp     x25, x26, [sp, 6 * 8]
+        ldp     x23, x24, [sp, 4 * 8]
+        ldp     x21, x22, [sp, 2 * 8]
+        ldp     x19, x20, [sp, 0 * 8]
+        add     sp, sp, 20 * 8
+        ret",
+    )
+}
+
+/// Default return address of signal handlers without `SA_RESTORER`.
+///
+/// # Safety
+///
+/// It can only be returned to by signal handlers called by [`call_signal_handler`].
+#[unsafe(naked)]
+pub unsafe extern "C" fn signal_return_trampoline() -> ! {
+    naked_asm!(
+        "
+        mov     x0, x19
+        b       {resume}",
+        resume = sym resume_signal_handler,
+    )
+}
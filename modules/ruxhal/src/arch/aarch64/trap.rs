@@ -62,6 +62,8 @@ fn handle_sync_exception(tf: &mut TrapFrame) {
             debug!("Handle supervisor call {}", tf.r[8]);
             #[cfg(feature = "irq")]
             enable_irqs();
+            #[cfg(feature = "signal")]
+            let prev_frame = crate::trap::set_syscall_frame(tf);
             let result = crate::trap::handle_syscall(
                 tf.r[8] as usize,
                 [
@@ -73,6 +75,8 @@ fn handle_sync_exception(tf: &mut TrapFrame) {
                     tf.r[5] as _,
                 ],
             );
+            #[cfg(feature = "signal")]
+            crate::trap::set_syscall_frame(prev_frame);
             tf.r[0] = result as u64;
             #[cfg(feature = "irq")]
             disable_irqs();
@@ -136,11 +140,15 @@ fn handle_sync_exception(tf: &mut TrapFrame) {
     }
     #[cfg(feature = "signal")]
     {
-        crate::trap::handle_signal();
+        crate::trap::handle_signal(tf);
     }
 }
 
 #[no_mangle]
-fn handle_irq_exception(_tf: &TrapFrame) {
-    crate::trap::handle_irq_extern(0)
+fn handle_irq_exception(_tf: &mut TrapFrame) {
+    crate::trap::handle_irq_extern(0);
+    #[cfg(feature = "signal")]
+    {
+        crate::trap::handle_signal(_tf);
+    }
 }
@@ -12,12 +12,18 @@ mod macros;
 
 mod context;
 mod reg_satp;
+#[cfg(feature = "signal")]
+mod signal;
 mod trap;
 use memory_addr::{PhysAddr, VirtAddr};
 use riscv::asm;
 use riscv::register::{satp, sstatus, stvec};
 
 pub use self::context::{GeneralRegisters, TaskContext, TrapFrame};
+#[cfg(feature = "signal")]
+pub use self::signal::{
+    call_signal_handler, resume_signal_handler, signal_return_trampoline, MContext, UContext,
+};
 
 /// Allows the current CPU to respond to interrupts.
 #[inline]
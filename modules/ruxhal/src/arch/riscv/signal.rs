@@ -0,0 +1,181 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Signal frames of RISC-V.
+
+use core::arch::naked_asm;
+
+use super::{GeneralRegisters, TrapFrame};
+use crate::trap::SignalStack;
+
+/// `sstatus.SPIE`
+const SSTATUS_SPIE: usize = 1 << 5;
+
+/// Machine context, same as `mcontext_t` of musl.
+///
+/// FP registers are not saved, since the kernel does not use them.
+#[repr(C, align(16))]
+pub struct MContext {
+    /// `pc` followed by general registers `x1`-`x31`.
+    pub gregs: [usize; 32],
+    /// FP registers, unused.
+    pub fpregs: [u64; 66],
+}
+
+/// User context of a signal frame, same as `ucontext_t` of musl.
+#[repr(C, align(16))]
+pub struct UContext {
+    /// Context flags.
+    pub flags: usize,
+    /// Context to resume when this one returns, unused.
+    pub link: usize,
+    /// Signal stack used by this context.
+    pub stack: SignalStack,
+    /// Signals blocked in this context.
+    pub sigmask: [u64; 16],
+    /// Saved registers.
+    pub mcontext: MContext,
+}
+
+impl UContext {
+    /// Saves the context interrupted by the trap `tf`, with the blocked
+    /// signals `sigmask` and the signal stack `stack`.
+    pub fn save(&mut self, tf: &TrapFrame, sigmask: u64, stack: SignalStack) {
+        self.flags = 0;
+        self.link = 0;
+        self.stack = stack;
+        self.sigmask = [0; 16];
+        self.sigmask[0] = sigmask;
+        self.mcontext.gregs[0] = tf.sepc;
+        self.mcontext.gregs[1..].copy_from_slice(regs_as_array(&tf.regs));
+        self.mcontext.fpregs = [0; 66];
+    }
+
+    /// Restores the trap frame `tf` from this context, which may have been
+    /// modified by the signal handler. Returns the signals to be blocked.
+    pub fn restore(&self, tf: &mut TrapFrame) -> u64 {
+        tf.sepc = self.mcontext.gregs[0];
+        let regs = unsafe { &mut *(&mut tf.regs as *mut _ as *mut [usize; 31]) };
+        regs.copy_from_slice(&self.mcontext.gregs[1..]);
+        self.sigmask[0]
+    }
+}
+
+/// General registers in the order of `x1`-`x31`.
+fn regs_as_array(regs: &GeneralRegisters) -> &[usize; 31] {
+    unsafe { &*(regs as *const _ as *const [usize; 31]) }
+}
+
+impl TrapFrame {
+    /// The stack pointer of the interrupted context.
+    pub const fn sp(&self) -> usize {
+        self.regs.sp
+    }
+
+    /// Whether IRQs were enabled in the interrupted context.
+    pub const fn irqs_enabled(&self) -> bool {
+        self.sstatus & SSTATUS_SPIE != 0
+    }
+}
+
+/// Calls the signal handler `handler` with `args` as its arguments.
+///
+/// The handler runs on the stack `sp`, or on the current stack if `sp` is 0,
+/// and returns to `restorer`. The stack pointer of the caller is saved to
+/// `ctx`, so that [`resume_signal_handler`] can return from this function.
+///
+/// # Safety
+///
+/// `handler` and `restorer` must be valid functions, and `sp` must be the top
+/// of a valid stack.
+#[unsafe(naked)]
+pub unsafe extern "C" fn call_signal_handler(
+    _ctx: *mut usize,
+    _handler: usize,
+    _args: &[usize; 3],
+    _sp: usize,
+    _restorer: usize,
+) {
+    // a0: ctx, a1: handler, a2: args, a3: sp, a4: restorer
+    naked_asm!(
+        "
+        addi    sp, sp, -14 * 8
+        sd      ra, 0(sp)
+        sd      s0, 8(sp)
+        sd      s1, 16(sp)
+        sd      s2, 24(sp)
+        sd      s3, 32(sp)
+        sd      s4, 40(sp)
+        sd      s5, 48(sp)
+        sd      s6, 56(sp)
+        sd      s7, 64(sp)
+        sd      s8, 72(sp)
+        sd      s9, 80(sp)
+        sd      s10, 88(sp)
+        sd      s11, 96(sp)
+        sd      sp, 0(a0)
+
+        // keep `ctx` in a callee-saved register for `signal_return_trampoline`
+        mv      s11, a0
+        beqz    a3, 1f
+        andi    sp, a3, -16
+    1:
+        mv      ra, a4
+        mv      t0, a1
+        ld      a0, 0(a2)
+        ld      a1, 8(a2)
+        ld      a2, 16(a2)
+        jr      t0",
+    )
+}
+
+/// Returns from [`call_signal_handler`] whose stack pointer was saved to `ctx`.
+///
+/// # Safety
+///
+/// `ctx` must be saved by a [`call_signal_handler`] which has not returned.
+#[unsafe(naked)]
+pub unsafe extern "C" fn resume_signal_handler(_ctx: *const usize) -> ! {
+    naked_asm!(
+        "
+        ld      sp, 0(a0)
+        ld      ra, 0(sp)
+        ld      s0, 8(sp)
+        ld      s1, 16(sp)
+        ld      s2, 24(sp)
+        ld      s3, 32(sp)
+        ld      s4, 40(sp)
+        ld      s5, 48(sp)
+        ld      s6, 56(sp)
+        ld      s7, 64(sp)
+        ld      s8, 72(sp)
+        ld      s9, 80(sp)
+        ld      s10, 88(sp)
+        ld      s11, 96(sp)
+        addi    sp, sp, 14 * 8
+        ret",
+    )
+}
+
+/// Default return address of signal handlers, since `SA_RESTORER` is not
+/// used on RISC-V.
+///
+/// # Safety
+///
+/// It can only be returned to by signal handlers called by [`call_signal_handler`].
+#[unsafe(naked)]
+pub unsafe extern "C" fn signal_return_trampoline() -> ! {
+    naked_asm!(
+        "
+        mv      a0, s11
+        la      t0, {resume}
+        jr      t0",
+        resume = sym resume_signal_handler,
+    )
+}
@@ -33,6 +33,8 @@ fn riscv_trap_handler(tf: &mut TrapFrame, _from_user: bool) {
         Trap::Exception(E::UserEnvCall) => {
             #[cfg(feature = "irq")]
             enable_irqs();
+            #[cfg(feature = "signal")]
+            let prev_frame = crate::trap::set_syscall_frame(tf);
             let ret = crate::trap::handle_syscall(
                 tf.regs.a7,
                 [
@@ -44,6 +46,8 @@ fn riscv_trap_handler(tf: &mut TrapFrame, _from_user: bool) {
                     tf.regs.a5 as _,
                 ],
             );
+            #[cfg(feature = "signal")]
+            crate::trap::set_syscall_frame(prev_frame);
             tf.regs.a0 = ret as _;
             #[cfg(feature = "irq")]
             disable_irqs();
@@ -72,4 +76,6 @@ fn riscv_trap_handler(tf: &mut TrapFrame, _from_user: bool) {
             );
         }
     }
+    #[cfg(feature = "signal")]
+    crate::trap::handle_signal(tf);
 }
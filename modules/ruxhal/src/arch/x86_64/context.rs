@@ -8,7 +8,7 @@
  */
 
 use core::{arch::naked_asm, fmt};
-use memory_addr::VirtAddr;
+use memory_addr::{PhysAddr, VirtAddr};
 
 /// Saved registers when a trap (interrupt or exception) occurs.
 #[allow(missing_docs)]
@@ -192,11 +192,35 @@ impl TaskContext {
         self.fs_base = tls_area.as_usize();
     }
 
+    /// This function copy the content from src to dst, the content size is given by parameter "size".
+    /// It's only supposed to use this function for processes stack's copying.
+    ///
+    /// The callee-saved registers are pushed onto the current stack before copying,
+    /// so the `rsp` saved here is valid on the copied stack as well, as long as it
+    /// is mapped to the same virtual address. When the new task is switched to, it
+    /// returns from this function just like the current task does.
+    ///
+    /// # Safety
+    ///
+    /// - `src` must be the bottom of the current stack, and the current `rsp` must
+    ///   lie in `[src, src + size)`.
+    /// - `dst` must be a valid pointer to a memory region of at least `size` bytes.
+    /// - `size` must be a multiple of 8.
+    pub unsafe fn save_current_content(&mut self, src: *const u8, dst: *mut u8, size: usize) {
+        #[cfg(feature = "fp_simd")]
+        self.ext_state.save();
+        #[cfg(feature = "tls")]
+        {
+            self.fs_base = super::read_thread_pointer();
+        }
+        unsafe { save_current_context(src, dst, size, &mut self.rsp) }
+    }
+
     /// Switches to another task.
     ///
     /// It first saves the current task's context from CPU to this place, and then
     /// restores the next task's context from `next_ctx` to CPU.
-    pub fn switch_to(&mut self, next_ctx: &Self) {
+    pub fn switch_to(&mut self, next_ctx: &Self, page_table_addr: PhysAddr) {
         #[cfg(feature = "fp_simd")]
         {
             self.ext_state.save();
@@ -207,12 +231,58 @@ impl TaskContext {
             self.fs_base = super::read_thread_pointer();
             unsafe { super::write_thread_pointer(next_ctx.fs_base) };
         }
-        unsafe { context_switch(&mut self.rsp, &next_ctx.rsp) }
+        // switch to the next process's page table, stack would be unavailable before context switch finished
+        unsafe {
+            context_switch(
+                &mut self.rsp,
+                &next_ctx.rsp,
+                page_table_addr.as_usize() as u64,
+            )
+        }
     }
 }
 
 #[unsafe(naked)]
-unsafe extern "C" fn context_switch(_current_stack: &mut u64, _next_stack: &u64) {
+unsafe extern "C" fn save_current_context(
+    _src: *const u8,
+    _dst: *mut u8,
+    _size: usize,
+    _current_stack: &mut u64,
+) {
+    // rdi: src, rsi: dst, rdx: size, rcx: current_stack
+    naked_asm!(
+        "
+        push    rbp
+        push    rbx
+        push    r12
+        push    r13
+        push    r14
+        push    r15
+        mov     [rcx], rsp
+
+        // copy the stack content, including the registers pushed above
+        mov     rcx, rdx
+        shr     rcx, 3
+        xchg    rdi, rsi
+        cld
+        rep     movsq
+
+        pop     r15
+        pop     r14
+        pop     r13
+        pop     r12
+        pop     rbx
+        pop     rbp
+        ret",
+    )
+}
+
+#[unsafe(naked)]
+unsafe extern "C" fn context_switch(
+    _current_stack: &mut u64,
+    _next_stack: &u64,
+    _page_table_addr: u64,
+) {
     naked_asm!(
         "
         push    rbp
@@ -223,6 +293,12 @@ unsafe extern "C" fn context_switch(_current_stack: &mut u64, _next_stack: &u64)
         push    r15
         mov     [rdi], rsp
 
+        // switch to next task's page table
+        mov     rax, cr3
+        cmp     rax, rdx
+        je      1f
+        mov     cr3, rdx
+    1:
         mov     rsp, [rsi]
         pop     r15
         pop     r14
@@ -10,6 +10,8 @@
 mod context;
 mod gdt;
 mod idt;
+#[cfg(feature = "signal")]
+mod signal;
 
 mod trap;
 
@@ -39,6 +41,10 @@ use {
 pub use self::context::{ExtendedState, FxsaveArea, TaskContext, TrapFrame};
 pub use self::gdt::GdtStruct;
 pub use self::idt::IdtStruct;
+#[cfg(feature = "signal")]
+pub use self::signal::{
+    call_signal_handler, resume_signal_handler, signal_return_trampoline, MContext, UContext,
+};
 pub use x86_64::structures::tss::TaskStateSegment;
 
 /// Allows the current CPU to respond to interrupts.
@@ -0,0 +1,222 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Signal frames of x86_64.
+
+use core::arch::naked_asm;
+
+use super::{FxsaveArea, TrapFrame};
+use crate::trap::SignalStack;
+
+const REG_RSP: usize = 15;
+const REG_RIP: usize = 16;
+const REG_EFL: usize = 17;
+
+/// Flags in `RFLAGS` that signal handlers are allowed to change:
+/// CF, PF, AF, ZF, SF, TF, DF, OF, RF and AC.
+const RFLAGS_USER_MASK: u64 = 0x50dd5;
+
+/// `RFLAGS.IF`
+const RFLAGS_IF: u64 = 1 << 9;
+
+/// Machine context, same as `mcontext_t` of musl.
+#[repr(C)]
+#[derive(Debug)]
+pub struct MContext {
+    /// General registers, in the order from `REG_R8` to `REG_CR2`.
+    pub gregs: [u64; 23],
+    /// Pointer to the saved FP/SIMD state.
+    pub fpregs: usize,
+    _reserved: [u64; 8],
+}
+
+/// User context of a signal frame, same as `ucontext_t` of musl.
+///
+/// The FP/SIMD state is saved in an aligned area after the fields defined by
+/// musl, and [`MContext::fpregs`] points to it.
+#[repr(C, align(16))]
+pub struct UContext {
+    /// Context flags.
+    pub flags: usize,
+    /// Context to resume when this one returns, unused.
+    pub link: usize,
+    /// Signal stack used by this context.
+    pub stack: SignalStack,
+    /// Saved registers.
+    pub mcontext: MContext,
+    /// Signals blocked in this context.
+    pub sigmask: [u64; 16],
+    _fpregs_mem: [u64; 64],
+    fpstate: FxsaveArea,
+}
+
+impl UContext {
+    /// Saves the context interrupted by the trap `tf`, with the blocked
+    /// signals `sigmask` and the signal stack `stack`.
+    ///
+    /// The context refers to itself, so it must be saved in place.
+    pub fn save(&mut self, tf: &TrapFrame, sigmask: u64, stack: SignalStack) {
+        self.flags = 0;
+        self.link = 0;
+        self.stack = stack;
+        self.mcontext.gregs = [
+            tf.r8,
+            tf.r9,
+            tf.r10,
+            tf.r11,
+            tf.r12,
+            tf.r13,
+            tf.r14,
+            tf.r15,
+            tf.rdi,
+            tf.rsi,
+            tf.rbp,
+            tf.rbx,
+            tf.rdx,
+            tf.rax,
+            tf.rcx,
+            tf.rsp,
+            tf.rip,
+            tf.rflags,
+            tf.cs,
+            tf.error_code,
+            tf.vector,
+            0,
+            0,
+        ];
+        self.mcontext.fpregs = &self.fpstate as *const _ as usize;
+        self.sigmask = [0; 16];
+        self.sigmask[0] = sigmask;
+        #[cfg(feature = "fp_simd")]
+        unsafe {
+            core::arch::x86_64::_fxsave64(&mut self.fpstate as *mut _ as *mut u8)
+        }
+    }
+
+    /// Restores the trap frame `tf` from this context, which may have been
+    /// modified by the signal handler. Returns the signals to be blocked.
+    pub fn restore(&self, tf: &mut TrapFrame) -> u64 {
+        let gregs = &self.mcontext.gregs;
+        tf.r8 = gregs[0];
+        tf.r9 = gregs[1];
+        tf.r10 = gregs[2];
+        tf.r11 = gregs[3];
+        tf.r12 = gregs[4];
+        tf.r13 = gregs[5];
+        tf.r14 = gregs[6];
+        tf.r15 = gregs[7];
+        tf.rdi = gregs[8];
+        tf.rsi = gregs[9];
+        tf.rbp = gregs[10];
+        tf.rbx = gregs[11];
+        tf.rdx = gregs[12];
+        tf.rax = gregs[13];
+        tf.rcx = gregs[14];
+        tf.rsp = gregs[REG_RSP];
+        tf.rip = gregs[REG_RIP];
+        tf.rflags = (tf.rflags & !RFLAGS_USER_MASK) | (gregs[REG_EFL] & RFLAGS_USER_MASK);
+        #[cfg(feature = "fp_simd")]
+        if self.mcontext.fpregs != 0 {
+            unsafe { core::arch::x86_64::_fxrstor64(self.mcontext.fpregs as *const u8) }
+        }
+        self.sigmask[0]
+    }
+}
+
+impl TrapFrame {
+    /// The stack pointer of the interrupted context.
+    pub const fn sp(&self) -> usize {
+        self.rsp as usize
+    }
+
+    /// Whether IRQs were enabled in the interrupted context.
+    pub const fn irqs_enabled(&self) -> bool {
+        self.rflags & RFLAGS_IF != 0
+    }
+}
+
+/// Calls the signal handler `handler` with `args` as its arguments.
+///
+/// The handler runs on the stack `sp`, or on the current stack if `sp` is 0,
+/// and returns to `restorer`. The stack pointer of the caller is saved to
+/// `ctx`, so that [`resume_signal_handler`] can return from this function.
+///
+/// # Safety
+///
+/// `handler` and `restorer` must be valid functions, and `sp` must be the top
+/// of a valid stack.
+#[unsafe(naked)]
+pub unsafe extern "C" fn call_signal_handler(
+    _ctx: *mut usize,
+    _handler: usize,
+    _args: &[usize; 3],
+    _sp: usize,
+    _restorer: usize,
+) {
+    // rdi: ctx, rsi: handler, rdx: args, rcx: sp, r8: restorer
+    naked_asm!(
+        "
+        push    rbp
+        push    rbx
+        push    r12
+        push    r13
+        push    r14
+        push    r15
+        mov     [rdi], rsp
+
+        // keep `ctx` in a callee-saved register for `signal_return_trampoline`
+        mov     rbx, rdi
+        test    rcx, rcx
+        jz      1f
+        mov     rsp, rcx
+    1:
+        and     rsp, -16
+        push    r8
+        mov     rax, rsi
+        mov     rdi, [rdx]
+        mov     rsi, [rdx + 8]
+        mov     rdx, [rdx + 16]
+        jmp     rax",
+    )
+}
+
+/// Returns from [`call_signal_handler`] whose stack pointer was saved to `ctx`.
+///
+/// # Safety
+///
+/// `ctx` must be saved by a [`call_signal_handler`] which has not returned.
+#[unsafe(naked)]
+pub unsafe extern "C" fn resume_signal_handler(_ctx: *const usize) -> ! {
+    naked_asm!(
+        "
+        mov     rsp, [rdi]
+        pop     r15
+        pop     r14
+        pop     r13
+        pop     r12
+        pop     rbx
+        pop     rbp
+        ret",
+    )
+}
+
+/// Default return address of signal handlers without `SA_RESTORER`.
+///
+/// # Safety
+///
+/// It can only be returned to by signal handlers called by [`call_signal_handler`].
+#[unsafe(naked)]
+pub unsafe extern "C" fn signal_return_trampoline() -> ! {
+    naked_asm!(
+        "
+        mov     rdi, rbx
+        jmp     {resume}",
+        resume = sym resume_signal_handler,
+    )
+}
@@ -24,7 +24,7 @@ const IRQ_VECTOR_START: u8 = 0x20;
 const IRQ_VECTOR_END: u8 = 0xff;
 
 #[no_mangle]
-fn x86_trap_handler(tf: &TrapFrame) {
+fn x86_trap_handler(tf: &mut TrapFrame) {
     match tf.vector as u8 {
         PAGE_FAULT_VECTOR => {
             if tf.is_user() {
@@ -66,7 +66,11 @@ fn x86_trap_handler(tf: &TrapFrame) {
         }
         #[cfg(all(feature = "paging", feature = "irq", feature = "smp"))]
         INVALID_TLB_VECTOR => flush_tlb_ipi_handler(),
-        IRQ_VECTOR_START..=IRQ_VECTOR_END => crate::trap::handle_irq_extern(tf.vector as _),
+        IRQ_VECTOR_START..=IRQ_VECTOR_END => {
+            crate::trap::handle_irq_extern(tf.vector as _);
+            #[cfg(feature = "signal")]
+            crate::trap::handle_signal(tf);
+        }
         _ => {
             panic!(
                 "Unhandled exception {} (error_code = {:#x}) @ {:#x}:\n{:#x?}",
@@ -0,0 +1,101 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Kernel command line passed by the bootloader.
+//!
+//! It comes from the multiboot command line on x86_64, or `/chosen/bootargs`
+//! in the device tree on other platforms, in the format of:
+//!
+//! ```text
+//! <params>;<args>;<envs>
+//! ```
+//!
+//! where `<params>` are kernel parameters separated by whitespaces, e.g.
+//! `ip=10.0.2.15/24 gw=10.0.2.2 log=info`, and `<args>` and `<envs>` are the
+//! arguments and environment variables of the application separated by
+//! commas. The parameters can be read by [`param`] or [`param_or`], which
+//! fall back to the compile-time default if absent, and the arguments and
+//! environment variables by [`args`] and [`envs`].
+
+/// Returns the whole command line, or an empty string if there is none.
+pub fn raw() -> &'static str {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        let cmdline_buf: &'static [u8] = unsafe { &*core::ptr::addr_of!(crate::COMLINE_BUF) };
+        let len = cmdline_buf
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(cmdline_buf.len());
+        core::str::from_utf8(&cmdline_buf[..len]).unwrap_or("")
+    }
+    #[cfg(any(
+        platform_family = "riscv64-qemu-virt",
+        platform_family = "aarch64-qemu-virt",
+        platform_family = "aarch64-raspi"
+    ))]
+    {
+        dtb::get_node("chosen")
+            .and_then(|chosen| chosen.find_prop("bootargs").map(|prop| prop.str()))
+            .unwrap_or("")
+    }
+    #[cfg(not(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        platform_family = "riscv64-qemu-virt",
+        platform_family = "aarch64-qemu-virt",
+        platform_family = "aarch64-raspi"
+    )))]
+    {
+        ""
+    }
+}
+
+/// Returns the kernel parameters, the part before the first `;`.
+///
+/// The whole command line is the arguments and environment variables if
+/// there is no `;`, so there are no parameters.
+fn params() -> &'static str {
+    raw().split_once(';').map_or("", |(params, _)| params)
+}
+
+/// Returns the value of the kernel parameter `key`, the last one wins if it
+/// is given more than once.
+///
+/// A parameter without `=` has an empty value.
+pub fn param(key: &str) -> Option<&'static str> {
+    params()
+        .split_whitespace()
+        .filter_map(|param| match param.split_once('=') {
+            Some((k, v)) => (k == key).then_some(v),
+            None => (param == key).then_some(""),
+        })
+        .last()
+}
+
+/// Returns the value of the kernel parameter `key`, or `default` if absent.
+pub fn param_or(key: &str, default: &'static str) -> &'static str {
+    param(key).unwrap_or(default)
+}
+
+/// Returns the `index`-th part of the command line separated by `;`, the
+/// last part is the rest of the command line.
+fn part(index: usize) -> &'static str {
+    raw().splitn(3, ';').nth(index).unwrap_or("")
+}
+
+/// Returns the arguments of the application.
+pub fn args() -> impl Iterator<Item = &'static str> {
+    part(1).split(',').filter(|arg| !arg.is_empty())
+}
+
+/// Returns the environment variables of the application, in the format of
+/// `KEY=VALUE`.
+pub fn envs() -> impl Iterator<Item = &'static str> {
+    part(2).split(',').filter(|env| !env.is_empty())
+}
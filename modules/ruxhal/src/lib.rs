@@ -45,6 +45,7 @@ extern crate log;
 extern crate alloc;
 
 pub mod arch;
+pub mod cmdline;
 pub mod cpu;
 pub mod mem;
 mod platform;
@@ -15,6 +15,9 @@ use aarch64_cpu::registers::{
 use ratio::Ratio;
 use tock_registers::interfaces::{Readable, Writeable};
 
+/// Name of the clock source, i.e. the counter of the generic timer.
+pub const CLOCK_SOURCE: &str = "arch_sys_counter";
+
 static mut CNTPCT_TO_NANOS_RATIO: Ratio = Ratio::zero();
 static mut NANOS_TO_CNTPCT_RATIO: Ratio = Ratio::zero();
 
@@ -43,6 +43,9 @@ pub mod mem {
 }
 
 pub mod time {
+    /// Name of the clock source.
+    pub const CLOCK_SOURCE: &str = "jiffies";
+
     /// Returns the current clock time in hardware ticks.
     pub fn current_ticks() -> u64 {
         0
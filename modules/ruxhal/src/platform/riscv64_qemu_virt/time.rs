@@ -9,6 +9,9 @@
 
 use riscv::register::time;
 
+/// Name of the clock source, i.e. the `time` CSR.
+pub const CLOCK_SOURCE: &str = "riscv_clocksource";
+
 const NANOS_PER_TICK: u64 = crate::time::NANOS_PER_SEC / ruxconfig::TIMER_FREQUENCY as u64;
 
 /// Returns the current clock time in hardware ticks.
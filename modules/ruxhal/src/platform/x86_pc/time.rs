@@ -18,6 +18,9 @@ static mut NANOS_TO_LAPIC_TICKS_RATIO: ratio::Ratio = ratio::Ratio::zero();
 #[cfg(feature = "rtc")]
 pub use crate::platform::x86_pc::rtc::*;
 
+/// Name of the clock source, i.e. the time stamp counter.
+pub const CLOCK_SOURCE: &str = "tsc";
+
 static mut INIT_TICK: u64 = 0;
 static mut CPU_FREQ_MHZ: u64 = ruxconfig::TIMER_FREQUENCY as u64 / 1_000_000;
 
@@ -22,7 +22,7 @@ pub type TimeValue = Duration;
 pub use crate::platform::irq::TIMER_IRQ_NUM;
 #[cfg(feature = "irq")]
 pub use crate::platform::time::set_oneshot_timer;
-pub use crate::platform::time::{current_ticks, nanos_to_ticks, ticks_to_nanos};
+pub use crate::platform::time::{current_ticks, nanos_to_ticks, ticks_to_nanos, CLOCK_SOURCE};
 #[cfg(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64"))]
 #[cfg(feature = "rtc")]
 pub use crate::platform::time::{rtc_read_time, rtc_write_time};
@@ -8,6 +8,8 @@
  */
 
 //! Trap handling.
+#[cfg(feature = "signal")]
+use crate::arch::TrapFrame;
 use crate_interface::{call_interface, def_interface};
 #[cfg(feature = "paging")]
 use page_table::MappingFlags;
@@ -35,6 +37,19 @@ impl From<PageFaultCause> for MappingFlags {
     }
 }
 
+/// An alternate stack for signal handlers, same as `stack_t` in Linux.
+#[cfg(feature = "signal")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignalStack {
+    /// Base address of the stack.
+    pub sp: usize,
+    /// `SS_ONSTACK` or `SS_DISABLE`.
+    pub flags: i32,
+    /// Size of the stack in bytes.
+    pub size: usize,
+}
+
 /// Trap handler interface.
 ///
 /// This trait is defined with the [`#[def_interface]`][1] attribute. Users
@@ -58,10 +73,22 @@ pub trait TrapHandler {
     fn handle_page_fault(_vaddr: usize, _caus: PageFaultCause) -> bool {
         panic!("No handle_page_fault implement");
     }
-    /// Handles signal for every trap.
+    /// Delivers pending signals before returning from a trap.
+    ///
+    /// `tf` is the context interrupted by the trap, which signal handlers
+    /// can inspect and modify through their `ucontext_t`.
     #[cfg(feature = "signal")]
-    fn handle_signal() {
-        panic!("No handle_page_fault implement");
+    fn handle_signal(_tf: &mut TrapFrame) {
+        panic!("No handle_signal implement");
+    }
+    /// Sets the trap frame of the syscall being handled by the current task,
+    /// or null once it returns, so that signals interrupting the syscall can
+    /// be delivered with it.
+    ///
+    /// Returns the previous one, which is set back after the syscall.
+    #[cfg(feature = "signal")]
+    fn set_syscall_frame(_tf: *mut TrapFrame) -> *mut TrapFrame {
+        panic!("No set_syscall_frame implement");
     }
 }
 
@@ -87,6 +114,12 @@ pub(crate) fn handle_page_fault(vaddr: usize, cause: PageFaultCause) -> bool {
 
 #[allow(dead_code)]
 #[cfg(feature = "signal")]
-pub(crate) fn handle_signal() {
-    call_interface!(TrapHandler::handle_signal)
+pub(crate) fn handle_signal(tf: &mut TrapFrame) {
+    call_interface!(TrapHandler::handle_signal, tf)
+}
+
+#[allow(dead_code)]
+#[cfg(feature = "signal")]
+pub(crate) fn set_syscall_frame(tf: *mut TrapFrame) -> *mut TrapFrame {
+    call_interface!(TrapHandler::set_syscall_frame, tf)
 }
@@ -57,7 +57,11 @@ const PAGE_NUM_MIN: usize = 1024;
 /// address is still on linear mapping region.
 /// use `do_pte_map` to do actually page mapping after call this function.
 pub fn alloc_page_preload() -> Result<VirtAddr, PagingError> {
-    if global_allocator().available_pages() < PAGE_NUM_MIN {
+    let available = global_allocator().available_pages();
+    // evict the page cache before swapping out the mapped pages
+    if available < PAGE_NUM_MIN
+        && available + global_allocator().reclaim(PAGE_NUM_MIN - available) < PAGE_NUM_MIN
+    {
         warn!(
             "available page num is {:?}",
             global_allocator().available_pages()
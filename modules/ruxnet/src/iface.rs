@@ -0,0 +1,45 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Information of the network interfaces and the routes, for the
+//! configuration of the network by the applications, e.g. `ifconfig` and
+//! `route`.
+
+use alloc::string::String;
+use core::net::{IpAddr, Ipv4Addr};
+
+/// Information of a network interface.
+#[derive(Debug, Clone)]
+pub struct IfaceInfo {
+    /// Name of the interface, e.g. `eth0`.
+    pub name: String,
+    /// Index of the interface, starting from 1.
+    pub index: usize,
+    /// Hardware address.
+    pub mac: [u8; 6],
+    /// Maximum transmission unit.
+    pub mtu: usize,
+    /// Whether it's the loopback interface.
+    pub loopback: bool,
+    /// The IPv4 address and its prefix length, if configured.
+    pub ipv4: Option<(Ipv4Addr, u8)>,
+}
+
+/// A route of the routing table.
+#[derive(Debug, Clone)]
+pub struct RouteInfo {
+    /// Address of the destination network.
+    pub dest: IpAddr,
+    /// Prefix length of the destination network.
+    pub prefix_len: u8,
+    /// The gateway, or `None` if the network is on the link of the interface.
+    pub gateway: Option<IpAddr>,
+    /// Name of the outgoing interface.
+    pub dev: String,
+}
@@ -17,12 +17,18 @@
 //!
 //! - [`TcpSocket`]: A TCP socket that provides POSIX-like APIs.
 //! - [`UdpSocket`]: A UDP socket that provides POSIX-like APIs.
+//! - [`RawSocket`] and [`IcmpSocket`]: Raw IP sockets and ICMP echo sockets
+//!   for `ping`, which are not supported by lwip.
 //! - [`dns_query`]: Function for DNS query.
+//! - [`interfaces`] and [`routes`]: Configuration of the interfaces and the
+//!   routing table.
 //!
 //! # Cargo Features
 //!
 //! - `smoltcp`: Use [smoltcp] as the underlying network stack. This is enabled
 //!   by default.
+//! - `irq`: Poll the interfaces in a network task woken up by the interrupts
+//!   of the NICs, on which the blocking socket operations sleep.
 //!
 //! [smoltcp]: https://github.com/smoltcp-rs/smoltcp
 
@@ -36,6 +42,7 @@ extern crate log;
 extern crate alloc;
 
 pub mod address;
+pub mod iface;
 pub mod message;
 pub mod socket;
 pub mod socket_node;
@@ -59,7 +66,9 @@ cfg_if::cfg_if! {
 
 pub use self::net_impl::TcpSocket;
 pub use self::net_impl::UdpSocket;
+pub use self::net_impl::{add_route, del_route, interfaces, routes, set_ipv4_addr};
 pub use self::net_impl::{dns_query, poll_interfaces};
+pub use self::net_impl::{IcmpSocket, RawSocket};
 
 use axerrno::LinuxError;
 use ruxdriver::{prelude::*, AxDeviceContainer};
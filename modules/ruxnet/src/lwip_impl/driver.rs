@@ -28,6 +28,7 @@ use lwip_rust::bindings::{
     NETIF_FLAG_ETHERNET,
 };
 use ruxdriver::prelude::*;
+use ruxfs::NetStatistics;
 
 const RX_BUF_QUEUE_SIZE: usize = 64;
 
@@ -37,13 +38,16 @@ unsafe impl Send for NetifWrapper {}
 struct DeviceWrapper {
     inner: RefCell<AxNetDevice>, // use `RefCell` is enough since it's wrapped in `Mutex` in `InterfaceWrapper`.
     rx_buf_queue: VecDeque<NetBufBox>,
+    /// Statistics shown in `/sys/class/net/<if>/statistics`.
+    stats: Arc<NetStatistics>,
 }
 
 impl DeviceWrapper {
-    fn new(inner: AxNetDevice) -> Self {
+    fn new(inner: AxNetDevice, stats: Arc<NetStatistics>) -> Self {
         Self {
             inner: RefCell::new(inner),
             rx_buf_queue: VecDeque::with_capacity(RX_BUF_QUEUE_SIZE),
+            stats,
         }
     }
 
@@ -51,11 +55,15 @@ impl DeviceWrapper {
         while self.rx_buf_queue.len() < RX_BUF_QUEUE_SIZE {
             match self.inner.borrow_mut().receive() {
                 Ok(bufptr) => unsafe {
+                    self.stats.add_rx(bufptr.packet_len());
                     self.rx_buf_queue.push_back(NetBuf::from_buf_ptr(bufptr));
                 },
                 Err(DevError::Again) => break, // TODO: better method to avoid error type conversion
                 Err(err) => {
                     warn!("receive failed: {err:?}");
+                    self.stats
+                        .rx_errors
+                        .fetch_add(1, core::sync::atomic::Ordering::Relaxed);
                     break;
                 }
             }
@@ -184,10 +192,15 @@ extern "C" fn ethif_output(netif: *mut netif, p: *mut pbuf) -> err_t {
             );
             dev.transmit(NetBuf::into_buf_ptr(Box::new(tx_buf)))
                 .unwrap();
+            dev_wrapper.stats.add_tx(tot_len.into());
             err_enum_t_ERR_OK as err_t
         }
     } else {
         error!("[ethif_output] dev can't transmit");
+        dev_wrapper
+            .stats
+            .tx_dropped
+            .fetch_add(1, core::sync::atomic::Ordering::Relaxed);
         err_enum_t_ERR_MEM as err_t
     }
 }
@@ -231,10 +244,11 @@ pub fn init_netdev(net_dev: AxNetDevice) {
             let mut netif: netif = unsafe { core::mem::zeroed() };
             netif.hwaddr_len = 6;
             netif.hwaddr = dev.mac_address().0;
+            let stats = ruxfs::register_net_interface("eth0", netif.hwaddr, 1500);
 
             ETH0.init_by(InterfaceWrapper {
                 name: "eth0",
-                dev: Arc::new(Mutex::new(DeviceWrapper::new(dev))),
+                dev: Arc::new(Mutex::new(DeviceWrapper::new(dev, stats))),
                 netif: Mutex::new(NetifWrapper(netif)),
             });
 
@@ -12,20 +12,68 @@
 mod addr;
 mod dns;
 mod driver;
+mod raw;
 mod tcp;
 mod udp;
 
 pub use self::addr::{IpAddr, Ipv4Addr, SocketAddr};
 pub use self::dns::dns_query;
 pub use self::driver::{init, init_netdev, poll_interfaces};
+pub use self::raw::{IcmpSocket, RawSocket};
 pub use self::tcp::TcpSocket;
 pub use self::udp::UdpSocket;
 use core::ffi::c_uint;
 use ruxhal::time::current_time;
 
+use crate::iface::{IfaceInfo, RouteInfo};
+
+use alloc::vec::Vec;
+use axerrno::{ax_err, AxResult};
 use axsync::Mutex;
 use lazy_init::LazyInit;
 
+/// Returns the information of the network interfaces, which is not
+/// supported by lwip.
+pub fn interfaces() -> Vec<IfaceInfo> {
+    Vec::new()
+}
+
+/// Sets the IPv4 address of the interface.
+pub fn set_ipv4_addr(_name: &str, _addr: core::net::Ipv4Addr, _prefix_len: u8) -> AxResult {
+    ax_err!(Unsupported, "set_ipv4_addr() is not supported by lwip")
+}
+
+/// Adds a route to the network via the gateway.
+pub fn add_route(
+    _dest: core::net::IpAddr,
+    _prefix_len: u8,
+    _gateway: Option<core::net::IpAddr>,
+    _dev: Option<&str>,
+) -> AxResult {
+    ax_err!(Unsupported, "add_route() is not supported by lwip")
+}
+
+/// Removes the route to the network.
+pub fn del_route(
+    _dest: core::net::IpAddr,
+    _prefix_len: u8,
+    _gateway: Option<core::net::IpAddr>,
+    _dev: Option<&str>,
+) -> AxResult {
+    ax_err!(Unsupported, "del_route() is not supported by lwip")
+}
+
+/// Returns the routes of the routing table, which is not supported by lwip.
+pub fn routes() -> Vec<RouteInfo> {
+    Vec::new()
+}
+
+/// Returns the waiters of the readiness of the inet sockets, which is `None`
+/// as they have to be polled.
+pub(crate) fn poll_waiters() -> Option<&'static axio::PollWaiters> {
+    None
+}
+
 static LWIP_MUTEX: LazyInit<Mutex<u32>> = LazyInit::new();
 
 const RECV_QUEUE_LEN: usize = 16;
@@ -0,0 +1,109 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Raw and ICMP echo sockets, which are not supported by lwip, so they can't
+//! be created and have no values.
+
+use alloc::string::String;
+use axerrno::AxResult;
+use axio::PollState;
+use core::convert::Infallible;
+use core::net::SocketAddr;
+use iovec::IoVecsOutput;
+
+use crate::message::{MessageFlags, MessageReadInfo};
+
+macro_rules! unsupported_socket {
+    ($name:ident) => {
+        impl $name {
+            pub fn local_addr(&self) -> AxResult<SocketAddr> {
+                match self.0 {}
+            }
+
+            pub fn peer_addr(&self) -> AxResult<SocketAddr> {
+                match self.0 {}
+            }
+
+            pub fn is_ipv6(&self) -> bool {
+                match self.0 {}
+            }
+
+            pub fn bound_device(&self) -> Option<String> {
+                match self.0 {}
+            }
+
+            pub fn bind_to_device(&self, _dev: Option<&str>) -> AxResult {
+                match self.0 {}
+            }
+
+            pub fn is_nonblocking(&self) -> bool {
+                match self.0 {}
+            }
+
+            pub fn set_nonblocking(&self, _nonblocking: bool) {
+                match self.0 {}
+            }
+
+            pub fn bind(&self, _local_addr: SocketAddr) -> AxResult {
+                match self.0 {}
+            }
+
+            pub fn connect(&self, _addr: SocketAddr) -> AxResult {
+                match self.0 {}
+            }
+
+            pub fn send_to(&self, _buf: &[u8], _remote_addr: SocketAddr) -> AxResult<usize> {
+                match self.0 {}
+            }
+
+            pub fn send(&self, _buf: &[u8]) -> AxResult<usize> {
+                match self.0 {}
+            }
+
+            pub fn recv_from(&self, _buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
+                match self.0 {}
+            }
+
+            pub fn recvmsg(
+                &self,
+                _iovecs: &mut IoVecsOutput,
+                _flags: MessageFlags,
+            ) -> AxResult<MessageReadInfo> {
+                match self.0 {}
+            }
+
+            pub fn poll(&self) -> AxResult<PollState> {
+                match self.0 {}
+            }
+        }
+    };
+}
+
+/// A raw IP socket, which is not supported by lwip.
+pub struct RawSocket(Infallible);
+
+/// An ICMP echo socket, which is not supported by lwip.
+pub struct IcmpSocket(Infallible);
+
+unsupported_socket!(RawSocket);
+unsupported_socket!(IcmpSocket);
+
+impl RawSocket {
+    pub fn protocol(&self) -> u8 {
+        match self.0 {}
+    }
+
+    pub fn hdrincl(&self) -> bool {
+        match self.0 {}
+    }
+
+    pub fn set_hdrincl(&self, _hdrincl: bool) {
+        match self.0 {}
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     net_impl::{driver::lwip_loop_once, RECV_QUEUE_LEN},
     IpAddr, SocketAddr,
 };
-use alloc::{boxed::Box, collections::VecDeque};
+use alloc::{boxed::Box, collections::VecDeque, string::String};
 use axerrno::{ax_err, AxError, AxResult};
 use axio::PollState;
 use axsync::Mutex;
@@ -144,6 +144,33 @@ impl UdpSocket {
         }
     }
 
+    /// Returns whether this is an `AF_INET6` socket, which is not supported.
+    #[inline]
+    pub fn is_ipv6(&self) -> bool {
+        false
+    }
+
+    /// Returns whether this `AF_INET6` socket is restricted to IPv6 peers.
+    #[inline]
+    pub fn is_v6only(&self) -> bool {
+        false
+    }
+
+    /// Restricts this `AF_INET6` socket to IPv6 peers (`IPV6_V6ONLY`).
+    pub fn set_v6only(&self, _v6only: bool) -> AxResult {
+        ax_err!(InvalidInput, "socket set_v6only() failed: not IPv6")
+    }
+
+    /// Returns the interface bound by [`bind_to_device`](Self::bind_to_device).
+    pub fn bound_device(&self) -> Option<String> {
+        None
+    }
+
+    /// Binds this socket to the interface (`SO_BINDTODEVICE`).
+    pub fn bind_to_device(&self, _dev: Option<&str>) -> AxResult {
+        ax_err!(Unsupported, "socket bind_to_device() failed")
+    }
+
     /// Returns whether this socket is in nonblocking mode.
     #[inline]
     pub fn is_nonblocking(&self) -> bool {
@@ -7,20 +7,21 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::net::{IpAddr, SocketAddr};
-use smoltcp::wire::{IpAddress, IpEndpoint, Ipv4Address};
+use axerrno::{ax_err, AxResult};
+use core::net::{IpAddr, Ipv6Addr, SocketAddr};
+use smoltcp::wire::{IpAddress, IpEndpoint, Ipv4Address, Ipv6Address};
 
 pub const fn from_core_ipaddr(ip: IpAddr) -> IpAddress {
     match ip {
         IpAddr::V4(ipv4) => IpAddress::Ipv4(Ipv4Address(ipv4.octets())),
-        _ => panic!("IPv6 not supported"),
+        IpAddr::V6(ipv6) => IpAddress::Ipv6(Ipv6Address(ipv6.octets())),
     }
 }
 
 pub const fn into_core_ipaddr(ip: IpAddress) -> IpAddr {
     match ip {
         IpAddress::Ipv4(ipv4) => IpAddr::V4(unsafe { core::mem::transmute(ipv4.0) }),
-        // _ => panic!("IPv6 not supported"),
+        IpAddress::Ipv6(ipv6) => IpAddr::V6(unsafe { core::mem::transmute(ipv6.0) }),
     }
 }
 
@@ -35,8 +36,35 @@ pub const fn into_core_sockaddr(addr: IpEndpoint) -> SocketAddr {
     SocketAddr::new(into_core_ipaddr(addr.addr), addr.port)
 }
 
+/// Converts the endpoint into the address of an `AF_INET6` socket, on which
+/// IPv4 addresses are shown as IPv4-mapped ones, e.g. `::ffff:10.0.2.2`.
+pub fn into_core_sockaddr_v6(addr: IpEndpoint) -> SocketAddr {
+    match into_core_sockaddr(addr) {
+        SocketAddr::V4(v4) if v4.ip().is_unspecified() => {
+            SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), v4.port())
+        }
+        SocketAddr::V4(v4) => SocketAddr::new(v4.ip().to_ipv6_mapped().into(), v4.port()),
+        addr => addr,
+    }
+}
+
+/// Converts the address given to an `AF_INET` socket, or an `AF_INET6` one if
+/// `ipv6`, into the endpoint, where IPv4-mapped addresses are IPv4 ones unless
+/// the socket is `v6only`.
+pub fn socket_endpoint(addr: SocketAddr, ipv6: bool, v6only: bool) -> AxResult<IpEndpoint> {
+    match addr {
+        SocketAddr::V4(_) if !ipv6 => Ok(from_core_sockaddr(addr)),
+        SocketAddr::V6(v6) if ipv6 => match v6.ip().to_ipv4_mapped() {
+            Some(_) if v6only => ax_err!(InvalidInput, "IPv4-mapped address on IPv6-only socket"),
+            Some(v4) => Ok(from_core_sockaddr(SocketAddr::new(v4.into(), v6.port()))),
+            None => Ok(from_core_sockaddr(addr)),
+        },
+        _ => ax_err!(InvalidInput, "address family mismatched"),
+    }
+}
+
 pub fn is_unspecified(ip: IpAddress) -> bool {
-    ip.as_bytes() == [0, 0, 0, 0]
+    ip.is_unspecified()
 }
 
 pub const UNSPECIFIED_IP: IpAddress = IpAddress::v4(0, 0, 0, 0);
@@ -0,0 +1,145 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! DHCPv4 client, which configures an interface with the leased IPv4 address,
+//! the default gateway and the DNS servers.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::net::IpAddr;
+use core::time::Duration;
+
+use smoltcp::iface::SocketHandle;
+use smoltcp::socket::dhcpv4::{self, Event};
+use smoltcp::wire::{IpAddress, Ipv4Address, Ipv4Cidr};
+
+use super::addr::into_core_ipaddr;
+use super::{dns, IFACE_LIST, SOCKET_SET};
+
+/// How long the boot waits for the first lease.
+const DHCP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval of polling the DHCP socket while waiting for the first lease.
+const DHCP_DISCOVER_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Interval of polling the DHCP socket in background to renew the lease.
+const DHCP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A lease copied out of the DHCP socket.
+struct Lease {
+    address: Ipv4Cidr,
+    router: Option<Ipv4Address>,
+    dns_servers: Vec<IpAddress>,
+}
+
+/// Starts the DHCP client on the interface.
+///
+/// It waits for the first lease for at most [`DHCP_TIMEOUT`], then keeps
+/// renewing the lease in a background task.
+pub(crate) fn start(iface_name: &str) {
+    let handle = SOCKET_SET.add(dhcpv4::Socket::new());
+    info!("DHCP: discovering on {:?}...", iface_name);
+
+    let deadline = ruxhal::time::current_time() + DHCP_TIMEOUT;
+    while !poll(handle, iface_name) {
+        if ruxhal::time::current_time() >= deadline {
+            warn!(
+                "DHCP: no lease on {:?}, keep trying in background",
+                iface_name
+            );
+            break;
+        }
+        sleep(DHCP_DISCOVER_INTERVAL);
+    }
+
+    let iface_name = iface_name.to_string();
+    ruxtask::spawn(move || loop {
+        poll(handle, &iface_name);
+        sleep(DHCP_POLL_INTERVAL);
+    });
+}
+
+/// Sleeps between the polls of the DHCP socket.
+///
+/// Without the `irq` feature, [`ruxtask::sleep`] busy-waits and other tasks
+/// can't run, so it yields until the deadline instead.
+fn sleep(dur: Duration) {
+    #[cfg(feature = "irq")]
+    ruxtask::sleep(dur);
+    #[cfg(not(feature = "irq"))]
+    {
+        let deadline = ruxhal::time::current_time() + dur;
+        while ruxhal::time::current_time() < deadline {
+            ruxtask::yield_now();
+        }
+    }
+}
+
+/// Polls the interface and applies the event of the DHCP socket, returns
+/// whether a lease is acquired or renewed with a new configuration.
+fn poll(handle: SocketHandle, iface_name: &str) -> bool {
+    SOCKET_SET.poll_interfaces(Some(iface_name.to_string()));
+    let event = SOCKET_SET.with_socket_mut::<dhcpv4::Socket, _, _>(handle, |socket| {
+        socket.poll().map(|event| match event {
+            Event::Configured(config) => Some(Lease {
+                address: config.address,
+                router: config.router,
+                dns_servers: config
+                    .dns_servers
+                    .iter()
+                    .map(|&s| IpAddress::Ipv4(s))
+                    .collect(),
+            }),
+            Event::Deconfigured => None,
+        })
+    });
+
+    let binding = IFACE_LIST.lock();
+    let iface = binding
+        .iter()
+        .find(|iface| iface.name() == iface_name)
+        .unwrap();
+    match event {
+        None => false,
+        Some(Some(lease)) => {
+            let ip = IpAddress::Ipv4(lease.address.address());
+            iface.setup_ip_addr(ip, lease.address.prefix_len());
+            info!("DHCP: leased on {:?}:", iface_name);
+            info!("  ip:       {}", lease.address);
+            match lease.router {
+                Some(router) => {
+                    iface.setup_gateway(IpAddress::Ipv4(router));
+                    info!("  gateway:  {}", router);
+                }
+                None => iface.remove_ipv4_gateway(),
+            }
+            drop(binding);
+
+            if !lease.dns_servers.is_empty() {
+                for server in lease.dns_servers.iter() {
+                    info!("  dns:      {}", server);
+                }
+                dns::set_servers(&lease.dns_servers);
+                let servers: Vec<IpAddr> = lease
+                    .dns_servers
+                    .iter()
+                    .map(|&s| into_core_ipaddr(s))
+                    .collect();
+                ruxfs::set_nameservers(&servers);
+            }
+            true
+        }
+        Some(None) => {
+            warn!("DHCP: lease on {:?} lost", iface_name);
+            iface.setup_ip_addr(IpAddress::v4(0, 0, 0, 0), 0);
+            iface.remove_ipv4_gateway();
+            false
+        }
+    }
+}
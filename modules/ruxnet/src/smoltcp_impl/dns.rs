@@ -9,14 +9,37 @@
 
 use alloc::vec::Vec;
 use axerrno::{ax_err_type, AxError, AxResult};
+use axsync::Mutex;
 use core::net::IpAddr;
 
 use smoltcp::iface::SocketHandle;
 use smoltcp::socket::dns::{self, GetQueryResultError, StartQueryError};
-use smoltcp::wire::DnsQueryType;
+use smoltcp::wire::{DnsQueryType, IpAddress};
 
 use super::addr::into_core_ipaddr;
-use super::{SocketSetWrapper, IFACE_LIST, SOCKET_SET};
+use super::{
+    poll_seq, route_dev, wait_for_poll, SocketSetWrapper, DNS_SEVER, IFACE_LIST, SOCKET_SET,
+};
+
+/// The DNS servers received by DHCP, the `dns` parameter is used if empty.
+static DNS_SERVERS: Mutex<Vec<IpAddress>> = Mutex::new(Vec::new());
+
+/// Returns the DNS servers of new queries.
+pub(super) fn servers() -> Vec<IpAddress> {
+    let servers = DNS_SERVERS.lock();
+    if !servers.is_empty() {
+        return servers.clone();
+    }
+    let server_addr = ruxhal::cmdline::param_or("dns", DNS_SEVER)
+        .parse()
+        .expect("invalid DNS server address");
+    alloc::vec![server_addr]
+}
+
+/// Replaces the DNS servers of new queries.
+pub(super) fn set_servers(servers: &[IpAddress]) {
+    *DNS_SERVERS.lock() = servers.to_vec();
+}
 
 /// A DNS socket.
 struct DnsSocket {
@@ -44,11 +67,16 @@ impl DnsSocket {
     pub fn query(&self, name: &str, query_type: DnsQueryType) -> AxResult<Vec<IpAddr>> {
         // let local_addr = self.local_addr.unwrap_or_else(f);
         let handle = self.handle.ok_or_else(|| ax_err_type!(InvalidInput))?;
+        // the source address is chosen by the interface to the first server
+        let server = *servers()
+            .first()
+            .ok_or_else(|| ax_err_type!(NotFound, "socket query() failed: no DNS server"))?;
+        let dev = route_dev(server, None)?;
         let binding = IFACE_LIST.lock();
         let iface = &binding
             .iter()
-            .find(|iface| iface.name() == "eth0")
-            .unwrap()
+            .find(|iface| iface.name() == dev)
+            .ok_or_else(|| ax_err_type!(NoSuchDevice))?
             .iface;
         let query_handle = SOCKET_SET
             .with_socket_mut::<dns::Socket, _, _>(handle, |socket| {
@@ -65,7 +93,10 @@ impl DnsSocket {
                     ax_err_type!(InvalidInput, "socket query() failed: too long name")
                 }
             })?;
+        // the interfaces are locked again to be polled
+        drop(binding);
         loop {
+            let seq = poll_seq();
             SOCKET_SET.poll_interfaces(None);
             match SOCKET_SET.with_socket_mut::<dns::Socket, _, _>(handle, |socket| {
                 socket.get_query_result(query_handle).map_err(|e| match e {
@@ -82,7 +113,7 @@ impl DnsSocket {
                     }
                     return Ok(res);
                 }
-                Err(AxError::WouldBlock) => ruxtask::yield_now(),
+                Err(AxError::WouldBlock) => wait_for_poll(seq),
                 Err(e) => return Err(e),
             }
         }
@@ -100,5 +131,9 @@ impl Drop for DnsSocket {
 /// Public function for DNS query.
 pub fn dns_query(name: &str) -> AxResult<alloc::vec::Vec<IpAddr>> {
     let socket = DnsSocket::new();
-    socket.query(name, DnsQueryType::A)
+    // fall back to the IPv6 addresses of IPv6-only hosts
+    match socket.query(name, DnsQueryType::A) {
+        Ok(addrs) if !addrs.is_empty() => Ok(addrs),
+        _ => socket.query(name, DnsQueryType::Aaaa),
+    }
 }
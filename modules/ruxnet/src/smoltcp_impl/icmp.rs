@@ -0,0 +1,367 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! ICMP echo sockets (`SOCK_DGRAM` with `IPPROTO_ICMP` or `IPPROTO_ICMPV6`),
+//! which ping without the privilege of raw sockets, as the ping sockets of
+//! Linux.
+//!
+//! Only echo requests can be sent, whose identifier is replaced by the one of
+//! the socket, i.e. the port it's bound on, and only the echo replies with the
+//! identifier are received. The checksums are filled in on sending.
+
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::string::String;
+use alloc::vec;
+use axerrno::{ax_err, ax_err_type, AxError, AxResult};
+use axio::PollState;
+use axsync::Mutex;
+use iovec::IoVecsOutput;
+use spin::RwLock;
+
+use smoltcp::iface::SocketHandle;
+use smoltcp::socket::icmp::{self, BindError, SendError};
+use smoltcp::wire::{IpAddress, IpEndpoint, IpVersion};
+
+use crate::message::{MessageFlags, MessageReadInfo};
+
+use super::addr::{into_core_sockaddr, into_core_sockaddr_v6, socket_endpoint, UNSPECIFIED_IP};
+use super::{block_on, iface_exists, route_dev, SocketSetWrapper, SOCKET_SET, STANDARD_MTU};
+
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+/// Length of the header of echo messages, with the identifier at 4..6.
+const ECHO_HEADER_LEN: usize = 8;
+
+/// An ICMP echo socket that provides POSIX-like APIs.
+pub struct IcmpSocket {
+    handle: SocketHandle,
+    /// The identifier of the echo messages, given once bound.
+    ident: RwLock<Option<u16>>,
+    local_addr: RwLock<Option<IpAddress>>,
+    peer_addr: RwLock<Option<IpAddress>>,
+    nonblock: AtomicBool,
+    /// The interface bound by `SO_BINDTODEVICE`.
+    bound_dev: RwLock<Option<String>>,
+    /// Whether it's an `AF_INET6` socket of ICMPv6.
+    ipv6: bool,
+}
+
+impl IcmpSocket {
+    /// Creates a new ICMP echo socket.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self::new_in(false)
+    }
+
+    /// Creates a new ICMPv6 echo socket.
+    pub fn new_v6() -> Self {
+        Self::new_in(true)
+    }
+
+    fn new_in(ipv6: bool) -> Self {
+        let socket = SocketSetWrapper::new_icmp_socket();
+        let handle = SOCKET_SET.add(socket);
+        Self {
+            handle,
+            ident: RwLock::new(None),
+            local_addr: RwLock::new(None),
+            peer_addr: RwLock::new(None),
+            nonblock: AtomicBool::new(false),
+            bound_dev: RwLock::new(None),
+            ipv6,
+        }
+    }
+
+    /// Returns the local address bound with the identifier as the port, or
+    /// the unspecified one if not bound.
+    pub fn local_addr(&self) -> AxResult<SocketAddr> {
+        let addr = self.local_addr.read().unwrap_or(UNSPECIFIED_IP);
+        let ident = self.ident.read().unwrap_or(0);
+        Ok(self.to_sockaddr(IpEndpoint::new(addr, ident)))
+    }
+
+    /// Returns the remote address, or
+    /// [`Err(NotConnected)`](AxError::NotConnected) if not connected.
+    pub fn peer_addr(&self) -> AxResult<SocketAddr> {
+        self.remote_addr()
+            .map(|addr| self.to_sockaddr(IpEndpoint::new(addr, 0)))
+    }
+
+    /// Returns whether this is an `AF_INET6` socket.
+    #[inline]
+    pub fn is_ipv6(&self) -> bool {
+        self.ipv6
+    }
+
+    /// Returns the interface bound by [`bind_to_device`](Self::bind_to_device).
+    pub fn bound_device(&self) -> Option<String> {
+        self.bound_dev.read().clone()
+    }
+
+    /// Binds this socket to the interface (`SO_BINDTODEVICE`), so that its
+    /// packets are sent through the interface regardless of the routes, or
+    /// unbinds it if `None`.
+    pub fn bind_to_device(&self, dev: Option<&str>) -> AxResult {
+        if dev.is_some_and(|dev| !iface_exists(dev)) {
+            return ax_err!(NoSuchDevice, "socket bind_to_device() failed");
+        }
+        *self.bound_dev.write() = dev.map(String::from);
+        Ok(())
+    }
+
+    /// Returns whether this socket is in nonblocking mode.
+    #[inline]
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblock.load(Ordering::Acquire)
+    }
+
+    /// Moves this socket into or out of nonblocking mode.
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblock.store(nonblocking, Ordering::Release);
+    }
+
+    /// Binds an unbound socket to the given address, whose port is the
+    /// identifier of the echo messages, or an ephemeral one if 0.
+    ///
+    /// It's done by the first [`send_to`](Self::send_to) if not called.
+    pub fn bind(&self, local_addr: SocketAddr) -> AxResult {
+        let mut ident = self.ident.write();
+        if ident.is_some() {
+            return ax_err!(InvalidInput, "socket bind() failed: already bound");
+        }
+
+        let local_endpoint = self.to_endpoint(local_addr)?;
+        let local_ident = match local_endpoint.port {
+            0 => get_ephemeral_ident(),
+            port => port,
+        };
+        SOCKET_SET.with_socket_mut::<icmp::Socket, _, _>(self.handle, |socket| {
+            socket
+                .bind(icmp::Endpoint::Ident(local_ident))
+                .or_else(|e| match e {
+                    BindError::InvalidState => ax_err!(AlreadyExists, "socket bind() failed"),
+                    BindError::Unaddressable => ax_err!(InvalidInput, "socket bind() failed"),
+                })
+        })?;
+
+        *ident = Some(local_ident);
+        *self.local_addr.write() = Some(local_endpoint.addr);
+        debug!(
+            "ICMP socket {}: bound on ident {}",
+            self.handle, local_ident
+        );
+        Ok(())
+    }
+
+    /// Connects the socket to the remote address, which is the destination of
+    /// [`send`](Self::send), and only the replies from it are received then.
+    pub fn connect(&self, addr: SocketAddr) -> AxResult {
+        let mut self_peer_addr = self.peer_addr.write();
+
+        let remote_addr = self.to_endpoint(addr)?.addr;
+        if self.ident.read().is_none() {
+            self.bind(self.unspecified_addr())?;
+        }
+
+        *self_peer_addr = Some(remote_addr);
+        debug!("ICMP socket {}: connected to {}", self.handle, addr);
+        Ok(())
+    }
+
+    /// Sends an echo request to the given address. On success, returns the
+    /// number of bytes written.
+    pub fn send_to(&self, buf: &[u8], remote_addr: SocketAddr) -> AxResult<usize> {
+        let remote_addr = self.to_endpoint(remote_addr)?.addr;
+        if remote_addr.is_unspecified() {
+            return ax_err!(InvalidInput, "socket send_to() failed: invalid address");
+        }
+        self.send_impl(buf, remote_addr)
+    }
+
+    /// Sends an echo request to the remote address to which it is connected.
+    pub fn send(&self, buf: &[u8]) -> AxResult<usize> {
+        self.send_impl(buf, self.remote_addr()?)
+    }
+
+    /// Receives a single echo reply on the socket. On success, returns the
+    /// number of bytes read and the origin.
+    pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
+        self.recv_impl(|data, src| {
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            (len, self.to_sockaddr(IpEndpoint::new(src, 0)))
+        })
+    }
+
+    pub fn recvmsg(
+        &self,
+        iovecs: &mut IoVecsOutput,
+        flags: MessageFlags,
+    ) -> AxResult<MessageReadInfo> {
+        if flags.contains(MessageFlags::MSG_PEEK) {
+            return ax_err!(Unsupported, "socket recvmsg() failed: MSG_PEEK");
+        }
+        self.recv_impl(|data, src| MessageReadInfo {
+            bytes_read: iovecs.write(data),
+            bytes_total: data.len(),
+            address: Some(self.to_sockaddr(IpEndpoint::new(src, 0)).into()),
+            ancillary_data: vec![],
+        })
+    }
+
+    /// Whether the socket is readable or writable.
+    pub fn poll(&self) -> AxResult<PollState> {
+        let bound = self.ident.read().is_some();
+        SOCKET_SET.with_socket_mut::<icmp::Socket, _, _>(self.handle, |socket| {
+            Ok(PollState {
+                readable: bound && socket.can_recv(),
+                writable: socket.can_send(),
+                pollhup: false,
+            })
+        })
+    }
+}
+
+/// Private methods
+impl IcmpSocket {
+    fn remote_addr(&self) -> AxResult<IpAddress> {
+        self.peer_addr.read().ok_or(AxError::NotConnected)
+    }
+
+    fn to_endpoint(&self, addr: SocketAddr) -> AxResult<IpEndpoint> {
+        // IPv4-mapped addresses are not supported by ICMPv6 sockets
+        socket_endpoint(addr, self.ipv6, true)
+    }
+
+    fn to_sockaddr(&self, endpoint: IpEndpoint) -> SocketAddr {
+        if self.ipv6 {
+            into_core_sockaddr_v6(endpoint)
+        } else {
+            into_core_sockaddr(endpoint)
+        }
+    }
+
+    /// The address to bind an unbound socket on, with an ephemeral identifier.
+    fn unspecified_addr(&self) -> SocketAddr {
+        let ip = if self.ipv6 {
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        };
+        SocketAddr::new(ip, 0)
+    }
+
+    /// Whether the message received is an echo reply to this socket, as the
+    /// smoltcp socket also receives the echo requests with the identifier,
+    /// and the messages of both ICMP versions.
+    fn accepts(&self, data: &[u8], src: IpAddress) -> bool {
+        let (version, reply) = if self.ipv6 {
+            (IpVersion::Ipv6, ICMPV6_ECHO_REPLY)
+        } else {
+            (IpVersion::Ipv4, ICMP_ECHO_REPLY)
+        };
+        src.version() == version
+            && data.first() == Some(&reply)
+            && self.peer_addr.read().map_or(true, |peer| peer == src)
+    }
+
+    fn send_impl(&self, buf: &[u8], remote_addr: IpAddress) -> AxResult<usize> {
+        let request = if self.ipv6 {
+            ICMPV6_ECHO_REQUEST
+        } else {
+            ICMP_ECHO_REQUEST
+        };
+        if buf.len() < ECHO_HEADER_LEN || buf[0] != request || buf[1] != 0 {
+            return ax_err!(InvalidInput, "socket send() failed: not an echo request");
+        }
+        if buf.len() > STANDARD_MTU {
+            return ax_err!(InvalidInput, "socket send() failed: message too long");
+        }
+        if self.ident.read().is_none() {
+            self.bind(self.unspecified_addr())?;
+        }
+        let ident = self.ident.read().unwrap();
+        let mut packet = buf.to_vec();
+        packet[4..6].copy_from_slice(&ident.to_be_bytes());
+
+        let iface_name = route_dev(remote_addr, self.bound_dev.read().as_deref())?;
+
+        block_on(
+            self.is_nonblocking(),
+            || {
+                SOCKET_SET.with_socket_mut::<icmp::Socket, _, _>(self.handle, |socket| {
+                    if socket.can_send() {
+                        socket
+                            .send_slice(&packet, remote_addr)
+                            .map_err(|e| match e {
+                                SendError::BufferFull => AxError::WouldBlock,
+                                SendError::Unaddressable => {
+                                    ax_err_type!(InvalidInput, "socket send() failed")
+                                }
+                            })?;
+                        Ok(buf.len())
+                    } else {
+                        // tx buffer is full
+                        Err(AxError::WouldBlock)
+                    }
+                })
+            },
+            Some(iface_name),
+        )
+    }
+
+    fn recv_impl<F, T>(&self, mut f: F) -> AxResult<T>
+    where
+        F: FnMut(&[u8], IpAddress) -> T,
+    {
+        if self.ident.read().is_none() {
+            return ax_err!(NotConnected, "socket recv() failed");
+        }
+
+        block_on(
+            self.is_nonblocking(),
+            || {
+                SOCKET_SET.with_socket_mut::<icmp::Socket, _, _>(self.handle, |socket| loop {
+                    // drop the messages not accepted, until no more data
+                    let (data, src) = socket.recv().map_err(|_| AxError::WouldBlock)?;
+                    if self.accepts(data, src) {
+                        return Ok(f(data, src));
+                    }
+                })
+            },
+            None,
+        )
+    }
+}
+
+impl Drop for IcmpSocket {
+    fn drop(&mut self) {
+        SOCKET_SET.remove(self.handle);
+    }
+}
+
+fn get_ephemeral_ident() -> u16 {
+    const IDENT_START: u16 = 0x15b3;
+    const IDENT_END: u16 = 0xffff;
+    static CURR: Mutex<u16> = Mutex::new(IDENT_START);
+    let mut curr = CURR.lock();
+
+    let ident = *curr;
+    if *curr == IDENT_END {
+        *curr = IDENT_START;
+    } else {
+        *curr += 1;
+    }
+    ident
+}
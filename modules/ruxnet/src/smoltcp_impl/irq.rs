@@ -0,0 +1,119 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The network task, which polls the interfaces on the interrupts of the NICs.
+//!
+//! The devices can't be locked in the interrupt context, so the IRQ handler
+//! only masks the interrupts of the NICs and wakes up the task. The task then
+//! acknowledges the interrupts, polls all the interfaces, which wakes up the
+//! sockets, and unmasks the interrupts. Besides, it polls on the timers of
+//! the sockets, and every [`POLL_INTERVAL`] if any NIC has no interrupt.
+
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+use ruxtask::WaitQueue;
+use spinlock::SpinNoIrq;
+
+use super::{IFACE_LIST, SOCKET_SET};
+
+/// Interval of polling the NICs without interrupts.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// IRQs of the NICs, which share one handler.
+static NET_IRQS: SpinNoIrq<Vec<usize>> = SpinNoIrq::new(Vec::new());
+/// Whether any NIC has no interrupt, so it's polled periodically.
+static NEED_POLLING: AtomicBool = AtomicBool::new(false);
+/// Whether the interrupts are masked until they are acknowledged.
+static IRQ_PENDING: AtomicBool = AtomicBool::new(false);
+/// Whether the network task is requested to poll.
+static WAKEUP: AtomicBool = AtomicBool::new(false);
+static NET_TASK_WQ: WaitQueue = WaitQueue::new();
+
+/// Registers the IRQ of a NIC, or has it polled periodically if it has no
+/// interrupt.
+pub(super) fn register_irq(irq_num: Option<usize>) {
+    let Some(irq_num) = irq_num else {
+        NEED_POLLING.store(true, Ordering::Release);
+        return;
+    };
+    let mut irqs = NET_IRQS.lock();
+    if irqs.contains(&irq_num) {
+        // shared with another NIC
+        return;
+    }
+    irqs.push(irq_num);
+    if ruxhal::irq::register_handler(irq_num, irq_handler) {
+        info!("  irq:      {}", irq_num);
+    } else {
+        irqs.pop();
+        NEED_POLLING.store(true, Ordering::Release);
+    }
+}
+
+/// Whether all the NICs interrupt on receiving, otherwise the tasks blocked in
+/// the socket operations keep polling by themselves, rather than waiting for
+/// the periodical polls.
+pub(super) fn all_nics_interrupt() -> bool {
+    !NEED_POLLING.load(Ordering::Acquire)
+}
+
+/// Requests the network task to poll, and to recalculate the timers of the
+/// sockets.
+pub(super) fn wakeup_net_task() {
+    WAKEUP.store(true, Ordering::Release);
+    NET_TASK_WQ.notify_one(false);
+}
+
+/// Spawns the network task.
+pub(super) fn start() {
+    ruxtask::spawn(net_task);
+}
+
+fn irq_handler() {
+    set_irqs_enabled(false);
+    IRQ_PENDING.store(true, Ordering::Release);
+    wakeup_net_task();
+}
+
+fn set_irqs_enabled(enabled: bool) {
+    for &irq_num in NET_IRQS.lock().iter() {
+        ruxhal::irq::set_enable(irq_num, enabled);
+    }
+}
+
+fn net_task() {
+    loop {
+        WAKEUP.store(false, Ordering::Release);
+        let irq_pending = IRQ_PENDING.swap(false, Ordering::AcqRel);
+        if irq_pending {
+            // before polling, so that the packets arriving later interrupt again
+            for iface in IFACE_LIST.lock().iter() {
+                iface.ack_interrupt();
+            }
+        }
+        SOCKET_SET.poll_interfaces(None);
+        if irq_pending {
+            set_irqs_enabled(true);
+        }
+
+        let mut delay = SOCKET_SET.poll_delay();
+        if NEED_POLLING.load(Ordering::Acquire) {
+            delay = Some(delay.map_or(POLL_INTERVAL, |delay| delay.min(POLL_INTERVAL)));
+        }
+        let woken = || WAKEUP.load(Ordering::Acquire);
+        match delay {
+            Some(delay) => {
+                NET_TASK_WQ.wait_timeout_until(delay, woken);
+            }
+            None => NET_TASK_WQ.wait_until(woken),
+        }
+    }
+}
@@ -9,30 +9,52 @@
 
 mod addr;
 mod bench;
+mod dhcp;
 mod dns;
+mod icmp;
+#[cfg(feature = "irq")]
+mod irq;
 mod listen_table;
+mod raw;
+mod route;
 mod tcp;
 mod udp;
 
-use alloc::string::{String, ToString};
-use alloc::vec;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::{self, Vec};
 use core::cell::RefCell;
+use core::net::{IpAddr, Ipv4Addr};
 use core::ops::DerefMut;
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "irq")]
+use core::time::Duration;
 
+use axerrno::{ax_err, ax_err_type, AxError, AxResult};
+use axio::PollWaiters;
 use axsync::Mutex;
 use driver_net::{DevError, NetBufPtr};
 use lazy_init::LazyInit;
 use ruxdriver::prelude::*;
+use ruxfs::NetStatistics;
 use ruxhal::time::{current_time_nanos, NANOS_PER_MICROS};
 use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet};
 use smoltcp::phy::{Device, DeviceCapabilities, Medium, RxToken, TxToken};
 use smoltcp::socket::{self, AnySocket};
 use smoltcp::time::Instant;
-use smoltcp::wire::{EthernetAddress, HardwareAddress, IpAddress, IpCidr};
+use smoltcp::wire::{
+    EthernetAddress, HardwareAddress, IpAddress, IpCidr, IpProtocol, IpVersion, Ipv4Address,
+    Ipv6Address,
+};
 
+use self::addr::{from_core_ipaddr, into_core_ipaddr};
 use self::listen_table::ListenTable;
+use self::route::Route;
+use crate::iface::{IfaceInfo, RouteInfo};
 
 pub use self::dns::dns_query;
+pub use self::icmp::IcmpSocket;
+pub use self::raw::RawSocket;
 pub use self::tcp::TcpSocket;
 pub use self::udp::UdpSocket;
 
@@ -45,10 +67,14 @@ macro_rules! env_or_default {
     };
 }
 
+// defaults of the `ip`, `gw`, `ip6`, `gw6` and `dns` kernel parameters
 const IP: &str = env_or_default!("RUX_IP");
 const GATEWAY: &str = env_or_default!("RUX_GW");
+const IP6: &str = env_or_default!("RUX_IP6");
+const GATEWAY6: &str = env_or_default!("RUX_GW6");
 const DNS_SEVER: &str = "8.8.8.8";
 const IP_PREFIX: u8 = 24;
+const IP6_PREFIX: u8 = 64;
 
 const STANDARD_MTU: usize = 1500;
 
@@ -58,31 +84,135 @@ const TCP_RX_BUF_LEN: usize = 64 * 1024;
 const TCP_TX_BUF_LEN: usize = 64 * 1024;
 const UDP_RX_BUF_LEN: usize = 64 * 1024;
 const UDP_TX_BUF_LEN: usize = 64 * 1024;
+const RAW_RX_BUF_LEN: usize = 16 * 1024;
+const RAW_TX_BUF_LEN: usize = 16 * 1024;
+const ICMP_RX_BUF_LEN: usize = 16 * 1024;
+const ICMP_TX_BUF_LEN: usize = 16 * 1024;
 const LISTEN_QUEUE_SIZE: usize = 512;
 
 static LISTEN_TABLE: LazyInit<ListenTable> = LazyInit::new();
 static SOCKET_SET: LazyInit<SocketSetWrapper> = LazyInit::new();
 static IFACE_LIST: LazyInit<Mutex<vec::Vec<InterfaceWrapper>>> = LazyInit::new();
 
-fn route_dev(addr: [u8; 4]) -> String {
-    if addr[0] == 127 {
-        "loopback".to_string()
+/// Incremented on each poll which may have changed the states of the sockets.
+static POLL_SEQ: AtomicUsize = AtomicUsize::new(0);
+/// Tasks blocked in the socket operations.
+#[cfg(feature = "irq")]
+static SOCKET_WQ: ruxtask::WaitQueue = ruxtask::WaitQueue::new();
+/// Waiters of the readiness of the inet sockets, e.g. epoll instances.
+static POLL_WAITERS: PollWaiters = PollWaiters::new();
+
+/// Returns the sequence number of the polls changing the sockets, to be given
+/// to [`wait_for_poll`] later.
+fn poll_seq() -> usize {
+    POLL_SEQ.load(Ordering::Acquire)
+}
+
+/// Blocks the current task until the sockets may have changed after `seq`
+/// returned by [`poll_seq`].
+///
+/// It just yields if any NIC has no interrupt, or before the interrupts are
+/// enabled at boot, as the interfaces are not polled on receiving then.
+fn wait_for_poll(seq: usize) {
+    #[cfg(feature = "irq")]
+    if ruxhal::arch::irqs_enabled() && irq::all_nics_interrupt() {
+        // so that the network task knows the new timers of the sockets
+        irq::wakeup_net_task();
+        SOCKET_WQ.wait_until(|| poll_seq() != seq);
+        return;
+    }
+    let _ = seq;
+    ruxtask::yield_now();
+}
+
+/// Block the current thread until the given function completes or fails.
+///
+/// If the socket is non-blocking, it calls the function once and returns
+/// immediately. Otherwise, it may call the function multiple times if it
+/// returns [`Err(WouldBlock)`](AxError::WouldBlock).
+fn block_on<F, T>(nonblocking: bool, mut f: F, iface: Option<String>) -> AxResult<T>
+where
+    F: FnMut() -> AxResult<T>,
+{
+    if nonblocking {
+        let res = f();
+        SOCKET_SET.poll_interfaces(iface);
+        res
     } else {
-        "eth0".to_string()
+        loop {
+            let seq = poll_seq();
+            let res = f();
+            SOCKET_SET.poll_interfaces(iface.clone());
+            match res {
+                Ok(t) => return Ok(t),
+                Err(AxError::WouldBlock) => wait_for_poll(seq),
+                Err(e) => return Err(e),
+            }
+        }
     }
 }
 
+/// Wakes up the tasks blocked in the socket operations and the waiters of
+/// the readiness of the sockets.
+fn notify_sockets() {
+    POLL_SEQ.fetch_add(1, Ordering::AcqRel);
+    #[cfg(feature = "irq")]
+    SOCKET_WQ.notify_all(false);
+    POLL_WAITERS.notify();
+}
+
+/// Returns the waiters of the readiness of the inet sockets, which are
+/// notified by the polls driven by the network task, or `None` without the
+/// interrupts, as the sockets have to be polled by the waiters themselves.
+pub(crate) fn poll_waiters() -> Option<&'static PollWaiters> {
+    cfg!(feature = "irq").then_some(&POLL_WAITERS)
+}
+
+/// Returns the interface to send packets to the address, which is the one
+/// bound by `SO_BINDTODEVICE` if any, otherwise found by the routing table.
+fn route_dev(addr: IpAddress, bound_dev: Option<&str>) -> AxResult<String> {
+    if let Some(dev) = bound_dev {
+        Ok(dev.into())
+    } else if addr.is_loopback() {
+        Ok("loopback".into())
+    } else {
+        route::lookup(addr)
+            .map(|route| route.dev)
+            .ok_or_else(|| ax_err_type!(NetworkUnreachable, "no route to the address"))
+    }
+}
+
+/// Returns the source address of the packets to `dst` sent through the
+/// interface.
+fn source_addr(dev: &str, dst: IpAddress) -> AxResult<IpAddress> {
+    IFACE_LIST
+        .lock()
+        .iter()
+        .find(|iface| iface.name() == dev)
+        .and_then(|iface| iface.source_addr(dst))
+        .ok_or_else(|| ax_err_type!(NetworkUnreachable, "no source address on the interface"))
+}
+
+/// Whether the interface exists.
+fn iface_exists(name: &str) -> bool {
+    IFACE_LIST.lock().iter().any(|iface| iface.name() == name)
+}
+
 struct SocketSetWrapper<'a>(Mutex<SocketSet<'a>>);
 
 struct DeviceWrapper {
     inner: RefCell<AxNetDevice>, // use `RefCell` is enough since it's wrapped in `Mutex` in `InterfaceWrapper`.
+    /// Statistics shown in `/sys/class/net/<if>/statistics`.
+    stats: Arc<NetStatistics>,
 }
 
 struct InterfaceWrapper {
-    name: &'static str,
+    name: String,
     ether_addr: EthernetAddress,
     dev: Mutex<DeviceWrapper>,
     iface: Mutex<Interface>,
+    /// Generation of the routing table which the routes of `iface` are from.
+    routes_generation: AtomicUsize,
 }
 
 impl<'a> SocketSetWrapper<'a> {
@@ -108,9 +238,32 @@ impl<'a> SocketSetWrapper<'a> {
         socket::udp::Socket::new(udp_rx_buffer, udp_tx_buffer)
     }
 
+    pub fn new_raw_socket(version: IpVersion, protocol: IpProtocol) -> socket::raw::Socket<'a> {
+        let raw_rx_buffer = socket::raw::PacketBuffer::new(
+            vec![socket::raw::PacketMetadata::EMPTY; 8],
+            vec![0; RAW_RX_BUF_LEN],
+        );
+        let raw_tx_buffer = socket::raw::PacketBuffer::new(
+            vec![socket::raw::PacketMetadata::EMPTY; 8],
+            vec![0; RAW_TX_BUF_LEN],
+        );
+        socket::raw::Socket::new(version, protocol, raw_rx_buffer, raw_tx_buffer)
+    }
+
+    pub fn new_icmp_socket() -> socket::icmp::Socket<'a> {
+        let icmp_rx_buffer = socket::icmp::PacketBuffer::new(
+            vec![socket::icmp::PacketMetadata::EMPTY; 8],
+            vec![0; ICMP_RX_BUF_LEN],
+        );
+        let icmp_tx_buffer = socket::icmp::PacketBuffer::new(
+            vec![socket::icmp::PacketMetadata::EMPTY; 8],
+            vec![0; ICMP_TX_BUF_LEN],
+        );
+        socket::icmp::Socket::new(icmp_rx_buffer, icmp_tx_buffer)
+    }
+
     pub fn new_dns_socket() -> socket::dns::Socket<'a> {
-        let server_addr = DNS_SEVER.parse().expect("invalid DNS server address");
-        socket::dns::Socket::new(&[server_addr], vec![])
+        socket::dns::Socket::new(&dns::servers(), vec![])
     }
 
     pub fn add<T: AnySocket<'a>>(&self, socket: T) -> SocketHandle {
@@ -138,11 +291,26 @@ impl<'a> SocketSetWrapper<'a> {
     }
 
     pub fn poll_interfaces(&self, iface_name: Option<String>) {
+        let mut changed = false;
         for iface in IFACE_LIST.lock().iter() {
             if iface_name.is_none() || iface_name.clone().unwrap() == iface.name() {
-                iface.poll(&self.0);
+                changed |= iface.poll(&self.0);
             }
         }
+        if changed {
+            notify_sockets();
+        }
+    }
+
+    /// Returns how long until the interfaces should be polled for the timers
+    /// of the sockets, e.g. TCP retransmission, or `None` if there is no timer.
+    #[cfg(feature = "irq")]
+    pub fn poll_delay(&self) -> Option<Duration> {
+        IFACE_LIST
+            .lock()
+            .iter()
+            .filter_map(|iface| iface.poll_delay(&self.0))
+            .min()
     }
 
     pub fn remove(&self, handle: SocketHandle) {
@@ -152,17 +320,19 @@ impl<'a> SocketSetWrapper<'a> {
 }
 
 impl InterfaceWrapper {
-    fn new(name: &'static str, dev: AxNetDevice, ether_addr: EthernetAddress) -> Self {
+    fn new(name: &str, dev: AxNetDevice, ether_addr: EthernetAddress) -> Self {
         let mut config = Config::new(HardwareAddress::Ethernet(ether_addr));
         config.random_seed = RANDOM_SEED;
 
-        let mut dev = DeviceWrapper::new(dev);
+        let stats = ruxfs::register_net_interface(name, ether_addr.0, STANDARD_MTU);
+        let mut dev = DeviceWrapper::new(dev, stats);
         let iface = Mutex::new(Interface::new(config, &mut dev, Self::current_time()));
         Self {
-            name,
+            name: name.into(),
             ether_addr,
             dev: Mutex::new(dev),
             iface,
+            routes_generation: AtomicUsize::new(usize::MAX),
         }
     }
 
@@ -171,40 +341,145 @@ impl InterfaceWrapper {
     }
 
     pub fn name(&self) -> &str {
-        self.name
+        &self.name
     }
 
     pub fn ethernet_address(&self) -> EthernetAddress {
         self.ether_addr
     }
 
+    /// Adds an IP address to the interface, or replaces the IPv4 one as an
+    /// interface has only one IPv4 address, e.g. on renewing the DHCP lease.
+    ///
+    /// The route to the network of the address is replaced too.
     pub fn setup_ip_addr(&self, ip: IpAddress, prefix_len: u8) {
-        let mut iface = self.iface.lock();
-        iface.update_ip_addrs(|ip_addrs| {
-            ip_addrs.push(IpCidr::new(ip, prefix_len)).unwrap();
+        let cidr = IpCidr::new(ip, prefix_len);
+        let mut old_cidr = None;
+        self.iface.lock().update_ip_addrs(|ip_addrs| {
+            let ipv4 = ip_addrs
+                .iter_mut()
+                .find(|old| matches!((old, ip), (IpCidr::Ipv4(_), IpAddress::Ipv4(_))));
+            match ipv4 {
+                Some(old) => old_cidr = Some(core::mem::replace(old, cidr)),
+                None => ip_addrs.push(cidr).unwrap(),
+            }
         });
+
+        if let Some(old_cidr) = old_cidr {
+            let old_network = route::network(old_cidr);
+            route::remove_if(|route| {
+                route.dest == old_network && route.gateway.is_none() && route.dev == self.name
+            });
+        }
+        let is_link_local = matches!(ip, IpAddress::Ipv6(v6) if v6.is_link_local());
+        if !ip.is_unspecified() && !is_link_local {
+            // fails if another interface is on the same network, which keeps
+            // the first one
+            route::add(Route {
+                dest: cidr,
+                gateway: None,
+                dev: self.name.clone(),
+            })
+            .ok();
+        }
+    }
+
+    /// Returns the IPv4 address of the interface with its prefix length.
+    pub fn ipv4_addr(&self) -> Option<(Ipv4Address, u8)> {
+        self.iface
+            .lock()
+            .ip_addrs()
+            .iter()
+            .find_map(|cidr| match cidr {
+                IpCidr::Ipv4(v4) if !v4.address().is_unspecified() => {
+                    Some((v4.address(), v4.prefix_len()))
+                }
+                _ => None,
+            })
+    }
+
+    /// Returns the address of the interface to send packets to `dst` from,
+    /// which is of the same IP version, and preferably of the same scope for
+    /// IPv6, i.e. link-local or not.
+    pub fn source_addr(&self, dst: IpAddress) -> Option<IpAddress> {
+        let link_local =
+            |addr: &IpAddress| matches!(addr, IpAddress::Ipv6(v6) if v6.is_link_local());
+        let addrs: Vec<IpAddress> = self
+            .iface
+            .lock()
+            .ip_addrs()
+            .iter()
+            .map(|cidr| cidr.address())
+            .filter(|addr| addr.version() == dst.version() && !addr.is_unspecified())
+            .collect();
+        addrs
+            .iter()
+            .find(|addr| link_local(addr) == link_local(&dst))
+            .or(addrs.first())
+            .copied()
     }
 
+    /// Sets the default route of the IP version of the gateway to this
+    /// interface.
     pub fn setup_gateway(&self, gateway: IpAddress) {
-        let mut iface = self.iface.lock();
-        match gateway {
-            IpAddress::Ipv4(v4) => iface.routes_mut().add_default_ipv4_route(v4).unwrap(),
-        };
+        route::set_default(gateway, &self.name);
     }
 
-    pub fn poll(&self, sockets: &Mutex<SocketSet>) {
+    pub fn remove_ipv4_gateway(&self) {
+        route::remove_default(IpVersion::Ipv4, &self.name);
+    }
+
+    /// Updates the routes of smoltcp if the routing table has changed since
+    /// the last time.
+    fn sync_routes(&self, iface: &mut Interface) {
+        let generation = route::generation();
+        if self.routes_generation.swap(generation, Ordering::AcqRel) == generation {
+            return;
+        }
+        let routes = route::iface_routes(&self.name);
+        iface.routes_mut().update(|storage| {
+            storage.clear();
+            for route in routes {
+                if storage.push(route).is_err() {
+                    warn!("too many routes on {:?}, some are dropped", self.name);
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Polls the interface, returns whether the states of the sockets may
+    /// have changed.
+    pub fn poll(&self, sockets: &Mutex<SocketSet>) -> bool {
         let mut dev = self.dev.lock();
         let mut iface = self.iface.lock();
+        self.sync_routes(&mut iface);
         let mut sockets = sockets.lock();
         let timestamp = Self::current_time();
-        iface.poll(timestamp, dev.deref_mut(), &mut sockets);
+        iface.poll(timestamp, dev.deref_mut(), &mut sockets)
+    }
+
+    #[cfg(feature = "irq")]
+    fn poll_delay(&self, sockets: &Mutex<SocketSet>) -> Option<Duration> {
+        let mut iface = self.iface.lock();
+        let sockets = sockets.lock();
+        iface
+            .poll_delay(Self::current_time(), &sockets)
+            .map(|delay| Duration::from_micros(delay.total_micros()))
+    }
+
+    /// Acknowledges the interrupt of the NIC.
+    #[cfg(feature = "irq")]
+    fn ack_interrupt(&self) {
+        self.dev.lock().inner.borrow_mut().ack_interrupt();
     }
 }
 
 impl DeviceWrapper {
-    fn new(inner: AxNetDevice) -> Self {
+    fn new(inner: AxNetDevice, stats: Arc<NetStatistics>) -> Self {
         Self {
             inner: RefCell::new(inner),
+            stats,
         }
     }
 }
@@ -234,11 +509,15 @@ impl Device for DeviceWrapper {
             Err(err) => {
                 if !matches!(err, DevError::Again) {
                     warn!("receive failed: {:?}", err);
+                    self.stats.rx_errors.fetch_add(1, Ordering::Relaxed);
                 }
                 return None;
             }
         };
-        Some((AxNetRxToken(&self.inner, rx_buf), AxNetTxToken(&self.inner)))
+        Some((
+            AxNetRxToken(&self.inner, rx_buf, &self.stats),
+            AxNetTxToken(&self.inner, &self.stats),
+        ))
     }
 
     fn transmit(&mut self, _timestamp: Instant) -> Option<Self::TxToken<'_>> {
@@ -248,7 +527,7 @@ impl Device for DeviceWrapper {
             return None;
         }
         if dev.can_transmit() {
-            Some(AxNetTxToken(&self.inner))
+            Some(AxNetTxToken(&self.inner, &self.stats))
         } else {
             None
         }
@@ -263,8 +542,8 @@ impl Device for DeviceWrapper {
     }
 }
 
-struct AxNetRxToken<'a>(&'a RefCell<AxNetDevice>, NetBufPtr);
-struct AxNetTxToken<'a>(&'a RefCell<AxNetDevice>);
+struct AxNetRxToken<'a>(&'a RefCell<AxNetDevice>, NetBufPtr, &'a NetStatistics);
+struct AxNetTxToken<'a>(&'a RefCell<AxNetDevice>, &'a NetStatistics);
 
 impl<'a> RxToken for AxNetRxToken<'a> {
     fn preprocess(&self, sockets: &mut SocketSet<'_>) {
@@ -281,6 +560,7 @@ impl<'a> RxToken for AxNetRxToken<'a> {
             rx_buf.packet_len(),
             rx_buf.packet()
         );
+        self.2.add_rx(rx_buf.packet_len());
         let result = f(rx_buf.packet_mut());
         self.0.borrow_mut().recycle_rx_buffer(rx_buf).unwrap();
         result
@@ -296,20 +576,35 @@ impl<'a> TxToken for AxNetTxToken<'a> {
         let mut tx_buf = dev.alloc_tx_buffer(len).unwrap();
         let ret = f(tx_buf.packet_mut());
         dev.transmit(tx_buf).unwrap();
+        self.1.add_tx(len);
         ret
     }
 }
 
 fn snoop_tcp_packet(buf: &[u8], sockets: &mut SocketSet<'_>) -> Result<(), smoltcp::wire::Error> {
-    use smoltcp::wire::{EthernetFrame, IpProtocol, Ipv4Packet, TcpPacket};
+    use smoltcp::wire::{EthernetFrame, EthernetProtocol, Ipv4Packet, Ipv6Packet, TcpPacket};
 
     let ether_frame = EthernetFrame::new_checked(buf)?;
-    let ipv4_packet = Ipv4Packet::new_checked(ether_frame.payload())?;
+    let (src_ip, dst_ip, protocol, payload) = match ether_frame.ethertype() {
+        EthernetProtocol::Ipv4 => {
+            let packet = Ipv4Packet::new_checked(ether_frame.payload())?;
+            let src = IpAddress::Ipv4(packet.src_addr());
+            let dst = IpAddress::Ipv4(packet.dst_addr());
+            (src, dst, packet.next_header(), packet.payload())
+        }
+        EthernetProtocol::Ipv6 => {
+            let packet = Ipv6Packet::new_checked(ether_frame.payload())?;
+            let src = IpAddress::Ipv6(packet.src_addr());
+            let dst = IpAddress::Ipv6(packet.dst_addr());
+            (src, dst, packet.next_header(), packet.payload())
+        }
+        _ => return Ok(()),
+    };
 
-    if ipv4_packet.next_header() == IpProtocol::Tcp {
-        let tcp_packet = TcpPacket::new_checked(ipv4_packet.payload())?;
-        let src_addr = (ipv4_packet.src_addr(), tcp_packet.src_port()).into();
-        let dst_addr = (ipv4_packet.dst_addr(), tcp_packet.dst_port()).into();
+    if protocol == IpProtocol::Tcp {
+        let tcp_packet = TcpPacket::new_checked(payload)?;
+        let src_addr = (src_ip, tcp_packet.src_port()).into();
+        let dst_addr = (dst_ip, tcp_packet.dst_port()).into();
         let is_first = tcp_packet.syn() && !tcp_packet.ack();
         if is_first {
             // create a socket for the first incoming TCP packet, as the later accept() returns.
@@ -325,6 +620,9 @@ fn snoop_tcp_packet(buf: &[u8], sockets: &mut SocketSet<'_>) -> Result<(), smolt
 /// packets to the NIC.
 pub fn poll_interfaces() {
     SOCKET_SET.poll_interfaces(None);
+    // the sockets may have new timers unknown by the sleeping network task
+    #[cfg(feature = "irq")]
+    irq::wakeup_net_task();
 }
 
 /// Benchmark raw socket transmit bandwidth.
@@ -351,12 +649,157 @@ pub fn bench_receive() {
         .bench_receive_bandwidth();
 }
 
+/// Returns the information of the network interfaces.
+pub fn interfaces() -> Vec<IfaceInfo> {
+    IFACE_LIST
+        .lock()
+        .iter()
+        .enumerate()
+        .map(|(i, iface)| IfaceInfo {
+            name: iface.name().into(),
+            index: i + 1,
+            mac: iface.ethernet_address().0,
+            mtu: STANDARD_MTU,
+            loopback: iface.name() == "loopback",
+            ipv4: iface
+                .ipv4_addr()
+                .map(|(addr, prefix_len)| (Ipv4Addr::from(addr.0), prefix_len)),
+        })
+        .collect()
+}
+
+/// Sets the IPv4 address of the interface, which replaces the old one.
+pub fn set_ipv4_addr(name: &str, addr: Ipv4Addr, prefix_len: u8) -> AxResult {
+    if prefix_len > 32 {
+        return ax_err!(InvalidInput, "invalid IP prefix length");
+    }
+    let ifaces = IFACE_LIST.lock();
+    let iface = ifaces
+        .iter()
+        .find(|iface| iface.name() == name)
+        .ok_or_else(|| ax_err_type!(NoSuchDevice))?;
+    iface.setup_ip_addr(IpAddress::Ipv4(Ipv4Address(addr.octets())), prefix_len);
+    info!("set IP address of {:?} to {}/{}", name, addr, prefix_len);
+    Ok(())
+}
+
+/// Adds a route to the network via the gateway, through the interface on the
+/// link of the gateway if not given.
+pub fn add_route(
+    dest: IpAddr,
+    prefix_len: u8,
+    gateway: Option<IpAddr>,
+    dev: Option<&str>,
+) -> AxResult {
+    let dest = parse_route_dest(dest, prefix_len)?;
+    // the routes on the link are given by the addresses of the interfaces
+    let gateway = gateway
+        .map(from_core_ipaddr)
+        .ok_or_else(|| ax_err_type!(InvalidInput, "route add failed: no gateway"))?;
+    if gateway.version() != dest.address().version() {
+        return ax_err!(InvalidInput, "route add failed: mismatched gateway");
+    }
+    let dev = match dev {
+        Some(dev) if iface_exists(dev) => dev.into(),
+        Some(_) => return ax_err!(NoSuchDevice),
+        None => route::lookup(gateway)
+            .filter(|route| route.gateway.is_none())
+            .map(|route| route.dev)
+            .ok_or_else(|| {
+                ax_err_type!(NetworkUnreachable, "route add failed: no route to gateway")
+            })?,
+    };
+    route::add(Route {
+        dest,
+        gateway: Some(gateway),
+        dev,
+    })
+}
+
+/// Removes the route to the network, which also matches the gateway and the
+/// interface if given.
+pub fn del_route(
+    dest: IpAddr,
+    prefix_len: u8,
+    gateway: Option<IpAddr>,
+    dev: Option<&str>,
+) -> AxResult {
+    let dest = parse_route_dest(dest, prefix_len)?;
+    route::remove(dest, gateway.map(from_core_ipaddr), dev)
+}
+
+/// Returns the routes of the routing table.
+pub fn routes() -> Vec<RouteInfo> {
+    route::routes()
+        .into_iter()
+        .map(|route| RouteInfo {
+            dest: into_core_ipaddr(route.dest.address()),
+            prefix_len: route.dest.prefix_len(),
+            gateway: route.gateway.map(into_core_ipaddr),
+            dev: route.dev,
+        })
+        .collect()
+}
+
+fn parse_route_dest(dest: IpAddr, prefix_len: u8) -> AxResult<IpCidr> {
+    let max_prefix_len = match dest {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    };
+    if prefix_len > max_prefix_len {
+        return ax_err!(InvalidInput, "invalid route prefix length");
+    }
+    Ok(IpCidr::new(from_core_ipaddr(dest), prefix_len))
+}
+
+/// Returns the kernel parameter of the interface, e.g. `ip.eth1` for `ip`.
+/// The ones of `eth0` can also be given without the suffix, and default to
+/// the values set at build time.
+fn iface_param(name: &str, key: &str, default: &'static str) -> &'static str {
+    match ruxhal::cmdline::param(&alloc::format!("{key}.{name}")) {
+        Some(value) => value,
+        None if name == "eth0" => ruxhal::cmdline::param_or(key, default),
+        None => "",
+    }
+}
+
+/// Parses an IP address with an optional prefix length, e.g. `10.0.2.15/24`.
+fn parse_ip_cidr(cidr: &str, default_prefix_len: u8) -> (IpAddress, u8) {
+    let (ip, prefix_len) = match cidr.split_once('/') {
+        Some((ip, prefix)) => (ip, prefix.parse().expect("invalid IP prefix length")),
+        None => (cidr, default_prefix_len),
+    };
+    (ip.parse().expect("invalid IP address"), prefix_len)
+}
+
+/// Returns the IPv6 link-local address of the interface, which is formed from
+/// the MAC address in the modified EUI-64 format (RFC 4862).
+fn link_local_addr(ether_addr: EthernetAddress) -> IpAddress {
+    let mac = ether_addr.0;
+    let mut addr = [0; 16];
+    addr[..2].copy_from_slice(&[0xfe, 0x80]);
+    addr[8..].copy_from_slice(&[
+        mac[0] ^ 0x02,
+        mac[1],
+        mac[2],
+        0xff,
+        0xfe,
+        mac[3],
+        mac[4],
+        mac[5],
+    ]);
+    IpAddress::Ipv6(Ipv6Address(addr))
+}
+
 pub(crate) fn init() {
     let socketset = SocketSetWrapper::new();
 
     IFACE_LIST.init_by(Mutex::new(vec::Vec::new()));
     SOCKET_SET.init_by(socketset);
     LISTEN_TABLE.init_by(ListenTable::new());
+    ruxfs::register_proc_net_file("route", route::proc_net_route);
+    #[cfg(feature = "irq")]
+    irq::start();
 }
 
 pub(crate) fn init_netdev(net_dev: AxNetDevice) {
@@ -367,27 +810,70 @@ pub(crate) fn init_netdev(net_dev: AxNetDevice) {
 
             let ip = "127.0.0.1".parse().expect("invalid IP address");
             lo.setup_ip_addr(ip, IP_PREFIX);
+            lo.setup_ip_addr(IpAddress::Ipv6(Ipv6Address::LOOPBACK), 128);
 
             info!("created net interface {:?}:", lo.name());
             info!("  ether:    {}", lo.ethernet_address());
             info!("  ip:       {}/{}", "127.0.0.1", IP_PREFIX);
+            info!("  ip6:      {}/{}", Ipv6Address::LOOPBACK, 128);
             IFACE_LIST.lock().push(lo);
         }
         _ => {
+            // named in the order of probing, i.e. `eth0`, `eth1` and so on
+            let index = IFACE_LIST
+                .lock()
+                .iter()
+                .filter(|iface| iface.name() != "loopback")
+                .count();
+            let name = alloc::format!("eth{index}");
             let ether_addr = EthernetAddress(net_dev.mac_address().0);
-            let eth0 = InterfaceWrapper::new("eth0", net_dev, ether_addr);
-
-            let ip = IP.parse().expect("invalid IP address");
-            let gateway = GATEWAY.parse().expect("invalid gateway IP address");
-            eth0.setup_ip_addr(ip, IP_PREFIX);
-            eth0.setup_gateway(gateway);
+            #[cfg(feature = "irq")]
+            let irq_num = net_dev.irq_num();
+            let eth = InterfaceWrapper::new(&name, net_dev, ether_addr);
+            info!("created net interface {:?}:", eth.name());
+            info!("  ether:    {}", eth.ethernet_address());
+            #[cfg(feature = "irq")]
+            irq::register_irq(irq_num);
+
+            // the IPv4 address and gateway are leased by DHCP if the `ip`
+            // parameter is `dhcp`, otherwise they are set statically, and the
+            // prefix length can be given by the `ip` parameter, e.g. `10.0.2.15/24`
+            let ip = iface_param(&name, "ip", IP);
+            let use_dhcp = ip == "dhcp";
+            if !use_dhcp && !ip.is_empty() {
+                let (ip, prefix_len) = parse_ip_cidr(ip, IP_PREFIX);
+                eth.setup_ip_addr(ip, prefix_len);
+                info!("  ip:       {}/{}", ip, prefix_len);
+                let gateway = iface_param(&name, "gw", GATEWAY);
+                if !gateway.is_empty() {
+                    let gateway = gateway.parse().expect("invalid gateway IP address");
+                    eth.setup_gateway(gateway);
+                    info!("  gateway:  {}", gateway);
+                }
+            }
 
-            info!("created net interface {:?}:", eth0.name());
-            info!("  ether:    {}", eth0.ethernet_address());
-            info!("  ip:       {}/{}", ip, IP_PREFIX);
-            info!("  gateway:  {}", gateway);
+            // the link-local address is always configured, while the global one
+            // and the gateway are set statically by the `ip6` and `gw6` parameters
+            let link_local = link_local_addr(ether_addr);
+            eth.setup_ip_addr(link_local, IP6_PREFIX);
+            info!("  ip6:      {}/{}", link_local, IP6_PREFIX);
+            let ip6 = iface_param(&name, "ip6", IP6);
+            if !ip6.is_empty() {
+                let (ip6, prefix_len) = parse_ip_cidr(ip6, IP6_PREFIX);
+                eth.setup_ip_addr(ip6, prefix_len);
+                info!("  ip6:      {}/{}", ip6, prefix_len);
+            }
+            let gateway6 = iface_param(&name, "gw6", GATEWAY6);
+            if !gateway6.is_empty() {
+                let gateway6 = gateway6.parse().expect("invalid gateway IPv6 address");
+                eth.setup_gateway(gateway6);
+                info!("  gateway6: {}", gateway6);
+            }
 
-            IFACE_LIST.lock().push(eth0);
+            IFACE_LIST.lock().push(eth);
+            if use_dhcp {
+                dhcp::start(&name);
+            }
         }
     }
 }
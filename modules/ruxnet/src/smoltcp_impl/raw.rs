@@ -0,0 +1,443 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! Raw IP sockets (`SOCK_RAW`), which send and receive the packets of an IP
+//! protocol by themselves, e.g. the ICMP ones of `ping`.
+//!
+//! As on Linux, the IPv4 packets are received with their headers while the
+//! IPv6 ones are not, and the packets sent are given a header unless
+//! `IP_HDRINCL` or `IPV6_HDRINCL`, which is implied by `IPPROTO_RAW`. The
+//! ICMPv6 checksums are filled in on sending, as they cover the addresses.
+
+use core::net::SocketAddr;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use axerrno::{ax_err, ax_err_type, AxError, AxResult};
+use axio::PollState;
+use iovec::IoVecsOutput;
+use spin::RwLock;
+
+use smoltcp::iface::SocketHandle;
+use smoltcp::phy::ChecksumCapabilities;
+use smoltcp::socket::raw;
+use smoltcp::wire::{
+    Icmpv6Packet, IpAddress, IpEndpoint, IpProtocol, IpVersion, Ipv4Packet, Ipv4Repr, Ipv6Packet,
+    Ipv6Repr, IPV6_HEADER_LEN,
+};
+
+use crate::message::{MessageFlags, MessageReadInfo};
+
+use super::addr::{into_core_sockaddr, into_core_sockaddr_v6, socket_endpoint, UNSPECIFIED_IP};
+use super::{
+    block_on, iface_exists, route_dev, source_addr, SocketSetWrapper, SOCKET_SET, STANDARD_MTU,
+};
+
+/// The protocol of the sockets sending the packets of any protocol with their
+/// own headers, which receive nothing.
+const IPPROTO_RAW: u8 = 255;
+/// The hop limit of the packets sent without `IP_HDRINCL`.
+const DEFAULT_HOP_LIMIT: u8 = 64;
+
+/// A raw IP socket that provides POSIX-like APIs.
+pub struct RawSocket {
+    /// Replaced for `IPPROTO_RAW` by a socket of the protocol being sent, as
+    /// a smoltcp socket only sends the packets of its own protocol.
+    handle: RwLock<SocketHandle>,
+    protocol: u8,
+    local_addr: RwLock<Option<IpAddress>>,
+    peer_addr: RwLock<Option<IpAddress>>,
+    nonblock: AtomicBool,
+    /// Whether the IP headers are given on sending (`IP_HDRINCL`).
+    hdrincl: AtomicBool,
+    /// The interface bound by `SO_BINDTODEVICE`.
+    bound_dev: RwLock<Option<String>>,
+    /// Whether it's an `AF_INET6` socket.
+    ipv6: bool,
+}
+
+impl RawSocket {
+    /// Creates a new raw socket of IPv4 and the protocol.
+    pub fn new(protocol: u8) -> Self {
+        Self::new_in(false, protocol)
+    }
+
+    /// Creates a new raw socket of IPv6 and the protocol.
+    pub fn new_v6(protocol: u8) -> Self {
+        Self::new_in(true, protocol)
+    }
+
+    fn new_in(ipv6: bool, protocol: u8) -> Self {
+        let version = if ipv6 {
+            IpVersion::Ipv6
+        } else {
+            IpVersion::Ipv4
+        };
+        let socket = SocketSetWrapper::new_raw_socket(version, IpProtocol::from(protocol));
+        let handle = SOCKET_SET.add(socket);
+        Self {
+            handle: RwLock::new(handle),
+            protocol,
+            local_addr: RwLock::new(None),
+            peer_addr: RwLock::new(None),
+            nonblock: AtomicBool::new(false),
+            hdrincl: AtomicBool::new(protocol == IPPROTO_RAW),
+            bound_dev: RwLock::new(None),
+            ipv6,
+        }
+    }
+
+    /// Returns the protocol the socket is created with.
+    #[inline]
+    pub fn protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    /// Returns the local address bound, or the unspecified one if not bound.
+    /// The port is always 0.
+    pub fn local_addr(&self) -> AxResult<SocketAddr> {
+        let addr = self.local_addr.read().unwrap_or(UNSPECIFIED_IP);
+        Ok(self.to_sockaddr(addr))
+    }
+
+    /// Returns the remote address, or
+    /// [`Err(NotConnected)`](AxError::NotConnected) if not connected.
+    pub fn peer_addr(&self) -> AxResult<SocketAddr> {
+        self.remote_addr().map(|addr| self.to_sockaddr(addr))
+    }
+
+    /// Returns whether this is an `AF_INET6` socket.
+    #[inline]
+    pub fn is_ipv6(&self) -> bool {
+        self.ipv6
+    }
+
+    /// Returns whether the IP headers are given on sending.
+    #[inline]
+    pub fn hdrincl(&self) -> bool {
+        self.hdrincl.load(Ordering::Acquire)
+    }
+
+    /// Sets whether the IP headers are given on sending (`IP_HDRINCL` or
+    /// `IPV6_HDRINCL`), which is always set for `IPPROTO_RAW`.
+    pub fn set_hdrincl(&self, hdrincl: bool) {
+        self.hdrincl
+            .store(hdrincl || self.protocol == IPPROTO_RAW, Ordering::Release);
+    }
+
+    /// Returns the interface bound by [`bind_to_device`](Self::bind_to_device).
+    pub fn bound_device(&self) -> Option<String> {
+        self.bound_dev.read().clone()
+    }
+
+    /// Binds this socket to the interface (`SO_BINDTODEVICE`), so that its
+    /// packets are sent through the interface regardless of the routes, or
+    /// unbinds it if `None`.
+    pub fn bind_to_device(&self, dev: Option<&str>) -> AxResult {
+        if dev.is_some_and(|dev| !iface_exists(dev)) {
+            return ax_err!(NoSuchDevice, "socket bind_to_device() failed");
+        }
+        *self.bound_dev.write() = dev.map(String::from);
+        Ok(())
+    }
+
+    /// Returns whether this socket is in nonblocking mode.
+    #[inline]
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblock.load(Ordering::Acquire)
+    }
+
+    /// Moves this socket into or out of nonblocking mode.
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblock.store(nonblocking, Ordering::Release);
+    }
+
+    /// Binds the socket to the local address, which is the source of the
+    /// packets sent, and only the packets to it are received then. The port
+    /// is ignored.
+    pub fn bind(&self, local_addr: SocketAddr) -> AxResult {
+        *self.local_addr.write() = Some(self.to_ipaddr(local_addr)?);
+        Ok(())
+    }
+
+    /// Connects the socket to the remote address, which is the destination of
+    /// [`send`](Self::send), and only the packets from it are received then.
+    pub fn connect(&self, addr: SocketAddr) -> AxResult {
+        *self.peer_addr.write() = Some(self.to_ipaddr(addr)?);
+        debug!("raw socket {}: connected to {}", *self.handle.read(), addr);
+        Ok(())
+    }
+
+    /// Sends a packet to the given address. On success, returns the number
+    /// of bytes written.
+    pub fn send_to(&self, buf: &[u8], remote_addr: SocketAddr) -> AxResult<usize> {
+        let remote_addr = self.to_ipaddr(remote_addr)?;
+        if remote_addr.is_unspecified() {
+            return ax_err!(InvalidInput, "socket send_to() failed: invalid address");
+        }
+        self.send_impl(buf, remote_addr)
+    }
+
+    /// Sends a packet to the remote address to which it is connected.
+    pub fn send(&self, buf: &[u8]) -> AxResult<usize> {
+        self.send_impl(buf, self.remote_addr()?)
+    }
+
+    /// Receives a single packet on the socket. On success, returns the number
+    /// of bytes read and the origin.
+    pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
+        self.recv_impl(|data, src| {
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+            (len, self.to_sockaddr(src))
+        })
+    }
+
+    pub fn recvmsg(
+        &self,
+        iovecs: &mut IoVecsOutput,
+        flags: MessageFlags,
+    ) -> AxResult<MessageReadInfo> {
+        if flags.contains(MessageFlags::MSG_PEEK) {
+            return ax_err!(Unsupported, "socket recvmsg() failed: MSG_PEEK");
+        }
+        self.recv_impl(|data, src| MessageReadInfo {
+            bytes_read: iovecs.write(data),
+            bytes_total: data.len(),
+            address: Some(self.to_sockaddr(src).into()),
+            ancillary_data: vec![],
+        })
+    }
+
+    /// Whether the socket is readable or writable.
+    pub fn poll(&self) -> AxResult<PollState> {
+        let handle = *self.handle.read();
+        SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(handle, |socket| {
+            if self.protocol == IPPROTO_RAW {
+                while socket.recv().is_ok() {}
+            }
+            Ok(PollState {
+                readable: socket.can_recv(),
+                writable: socket.can_send(),
+                pollhup: false,
+            })
+        })
+    }
+}
+
+/// Private methods
+impl RawSocket {
+    fn remote_addr(&self) -> AxResult<IpAddress> {
+        self.peer_addr.read().ok_or(AxError::NotConnected)
+    }
+
+    fn to_ipaddr(&self, addr: SocketAddr) -> AxResult<IpAddress> {
+        // IPv4-mapped addresses are not supported by IPv6 sockets
+        socket_endpoint(addr, self.ipv6, true).map(|endpoint| endpoint.addr)
+    }
+
+    fn to_sockaddr(&self, addr: IpAddress) -> SocketAddr {
+        if self.ipv6 {
+            into_core_sockaddr_v6(IpEndpoint::new(addr, 0))
+        } else {
+            into_core_sockaddr(IpEndpoint::new(addr, 0))
+        }
+    }
+
+    /// Returns the content of the packet received if it's accepted by the
+    /// socket, which is with the IP header only for IPv4, and the source.
+    fn accept<'b>(&self, packet: &'b [u8]) -> Option<(&'b [u8], IpAddress)> {
+        if self.protocol == IPPROTO_RAW {
+            return None;
+        }
+        let (content, src, dst) = if self.ipv6 {
+            let header = Ipv6Packet::new_checked(packet).ok()?;
+            let src = IpAddress::Ipv6(header.src_addr());
+            let dst = IpAddress::Ipv6(header.dst_addr());
+            (&packet[IPV6_HEADER_LEN..], src, dst)
+        } else {
+            let header = Ipv4Packet::new_checked(packet).ok()?;
+            let src = IpAddress::Ipv4(header.src_addr());
+            let dst = IpAddress::Ipv4(header.dst_addr());
+            (packet, src, dst)
+        };
+        if self.peer_addr.read().is_some_and(|peer| peer != src) {
+            return None;
+        }
+        if self
+            .local_addr
+            .read()
+            .is_some_and(|local| !local.is_unspecified() && local != dst)
+        {
+            return None;
+        }
+        Some((content, src))
+    }
+
+    /// Builds the packet to send with the IP header.
+    fn build_packet(&self, payload: &[u8], src: IpAddress, dst: IpAddress) -> AxResult<Vec<u8>> {
+        let next_header = IpProtocol::from(self.protocol);
+        match (src, dst) {
+            (IpAddress::Ipv4(src_addr), IpAddress::Ipv4(dst_addr)) => {
+                let repr = Ipv4Repr {
+                    src_addr,
+                    dst_addr,
+                    next_header,
+                    payload_len: payload.len(),
+                    hop_limit: DEFAULT_HOP_LIMIT,
+                };
+                let mut packet = vec![0; repr.buffer_len() + payload.len()];
+                packet[repr.buffer_len()..].copy_from_slice(payload);
+                repr.emit(
+                    &mut Ipv4Packet::new_unchecked(&mut packet[..]),
+                    &ChecksumCapabilities::default(),
+                );
+                Ok(packet)
+            }
+            (IpAddress::Ipv6(src_addr), IpAddress::Ipv6(dst_addr)) => {
+                let repr = Ipv6Repr {
+                    src_addr,
+                    dst_addr,
+                    next_header,
+                    payload_len: payload.len(),
+                    hop_limit: DEFAULT_HOP_LIMIT,
+                };
+                let mut packet = vec![0; repr.buffer_len() + payload.len()];
+                packet[repr.buffer_len()..].copy_from_slice(payload);
+                repr.emit(&mut Ipv6Packet::new_unchecked(&mut packet[..]));
+                if next_header == IpProtocol::Icmpv6 && payload.len() >= 4 {
+                    Icmpv6Packet::new_unchecked(&mut packet[repr.buffer_len()..])
+                        .fill_checksum(&src, &dst);
+                }
+                Ok(packet)
+            }
+            _ => ax_err!(
+                InvalidInput,
+                "socket send() failed: address family mismatched"
+            ),
+        }
+    }
+
+    /// Checks the packet given with the IP header, and fills in the source
+    /// address if unspecified. Returns the packet and its protocol.
+    fn fill_header<F>(&self, buf: &[u8], src: F) -> AxResult<(Vec<u8>, u8)>
+    where
+        F: FnOnce() -> AxResult<IpAddress>,
+    {
+        let mut packet = buf.to_vec();
+        let invalid = |_| ax_err_type!(InvalidInput, "socket send() failed: invalid IP header");
+        let protocol = if self.ipv6 {
+            let mut header = Ipv6Packet::new_checked(&mut packet[..]).map_err(invalid)?;
+            if header.src_addr().is_unspecified() {
+                if let IpAddress::Ipv6(src) = src()? {
+                    header.set_src_addr(src);
+                }
+            }
+            header.next_header()
+        } else {
+            let mut header = Ipv4Packet::new_checked(&mut packet[..]).map_err(invalid)?;
+            if header.src_addr().is_unspecified() {
+                if let IpAddress::Ipv4(src) = src()? {
+                    header.set_src_addr(src);
+                }
+            }
+            header.next_header()
+        };
+        Ok((packet, protocol.into()))
+    }
+
+    /// Returns the smoltcp socket to send the packets of the protocol, which
+    /// is replaced for `IPPROTO_RAW` if it's of another protocol.
+    fn handle_for(&self, protocol: u8) -> AxResult<SocketHandle> {
+        let mut handle = self.handle.write();
+        let current = SOCKET_SET
+            .with_socket::<raw::Socket, _, _>(*handle, |socket| u8::from(socket.ip_protocol()));
+        if current == protocol {
+            return Ok(*handle);
+        }
+        if self.protocol != IPPROTO_RAW {
+            return ax_err!(InvalidInput, "socket send() failed: protocol mismatched");
+        }
+        let version = if self.ipv6 {
+            IpVersion::Ipv6
+        } else {
+            IpVersion::Ipv4
+        };
+        // the packets still queued in the old socket are dropped
+        SOCKET_SET.remove(*handle);
+        let socket = SocketSetWrapper::new_raw_socket(version, IpProtocol::from(protocol));
+        *handle = SOCKET_SET.add(socket);
+        Ok(*handle)
+    }
+
+    fn send_impl(&self, buf: &[u8], remote_addr: IpAddress) -> AxResult<usize> {
+        let iface_name = route_dev(remote_addr, self.bound_dev.read().as_deref())?;
+        let src = || match *self.local_addr.read() {
+            Some(addr) if !addr.is_unspecified() => Ok(addr),
+            _ => source_addr(&iface_name, remote_addr),
+        };
+
+        let (packet, protocol) = if self.hdrincl() {
+            self.fill_header(buf, src)?
+        } else {
+            (self.build_packet(buf, src()?, remote_addr)?, self.protocol)
+        };
+        if packet.len() > STANDARD_MTU {
+            return ax_err!(InvalidInput, "socket send() failed: message too long");
+        }
+        let handle = self.handle_for(protocol)?;
+
+        block_on(
+            self.is_nonblocking(),
+            || {
+                SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(handle, |socket| {
+                    if socket.can_send() {
+                        socket
+                            .send_slice(&packet)
+                            .map_err(|_| AxError::WouldBlock)?;
+                        Ok(buf.len())
+                    } else {
+                        // tx buffer is full
+                        Err(AxError::WouldBlock)
+                    }
+                })
+            },
+            Some(iface_name.clone()),
+        )
+    }
+
+    fn recv_impl<F, T>(&self, mut f: F) -> AxResult<T>
+    where
+        F: FnMut(&[u8], IpAddress) -> T,
+    {
+        block_on(
+            self.is_nonblocking(),
+            || {
+                let handle = *self.handle.read();
+                SOCKET_SET.with_socket_mut::<raw::Socket, _, _>(handle, |socket| loop {
+                    // drop the packets not accepted, until no more data
+                    let packet = socket.recv().map_err(|_| AxError::WouldBlock)?;
+                    if let Some((content, src)) = self.accept(packet) {
+                        return Ok(f(content, src));
+                    }
+                })
+            },
+            None,
+        )
+    }
+}
+
+impl Drop for RawSocket {
+    fn drop(&mut self) {
+        SOCKET_SET.remove(*self.handle.read());
+    }
+}
@@ -0,0 +1,274 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! The routing table, which selects the interface and the gateway of the
+//! outgoing packets by the longest prefix match.
+//!
+//! As all the interfaces share one socket set, a socket may send packets
+//! through any interface polled. So each interface is only given the parts
+//! of its routes not covered by the more specific routes, which are left to
+//! their own interfaces.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use axerrno::{ax_err, ax_err_type, AxResult};
+use axsync::Mutex;
+use core::fmt::Write;
+
+use smoltcp::iface::Route as IfaceRoute;
+use smoltcp::wire::{IpAddress, IpCidr, IpVersion, Ipv4Address, Ipv4Cidr, Ipv6Address, Ipv6Cidr};
+
+/// A route of the routing table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The destination network.
+    pub dest: IpCidr,
+    /// The gateway, or `None` if the network is on the link of the interface.
+    pub gateway: Option<IpAddress>,
+    /// Name of the outgoing interface.
+    pub dev: String,
+}
+
+impl Route {
+    /// Whether the routes to the network should be left to this one, which is
+    /// false for the networks of the interface itself and the loopback ones,
+    /// as they are not routed by smoltcp.
+    fn is_hole_of(&self, route: &Route) -> bool {
+        self.dest.prefix_len() > route.dest.prefix_len()
+            && route.dest.contains_subnet(&self.dest)
+            && !(self.gateway.is_none() && self.dev == route.dev)
+            && !self.dest.address().is_loopback()
+    }
+}
+
+struct RouteTable {
+    routes: Vec<Route>,
+    /// Incremented on each change, for the interfaces to update their routes.
+    generation: usize,
+}
+
+static ROUTE_TABLE: Mutex<RouteTable> = Mutex::new(RouteTable {
+    routes: Vec::new(),
+    generation: 0,
+});
+
+/// Adds a route, fails if there is one to the same network.
+pub fn add(mut route: Route) -> AxResult {
+    route.dest = network(route.dest);
+    let mut table = ROUTE_TABLE.lock();
+    if table.routes.iter().any(|old| old.dest == route.dest) {
+        return ax_err!(AlreadyExists, "route add failed: already exists");
+    }
+    debug!(
+        "route add {} via {:?} dev {}",
+        route.dest, route.gateway, route.dev
+    );
+    table.routes.push(route);
+    table.generation += 1;
+    Ok(())
+}
+
+/// Removes the route to the network, which also matches the gateway and the
+/// interface if given.
+pub fn remove(dest: IpCidr, gateway: Option<IpAddress>, dev: Option<&str>) -> AxResult {
+    let dest = network(dest);
+    let mut table = ROUTE_TABLE.lock();
+    let index = table
+        .routes
+        .iter()
+        .position(|route| {
+            route.dest == dest
+                && gateway.map_or(true, |gw| route.gateway == Some(gw))
+                && dev.map_or(true, |dev| route.dev == dev)
+        })
+        .ok_or_else(|| ax_err_type!(NotFound, "route del failed: no such route"))?;
+    let route = table.routes.remove(index);
+    debug!(
+        "route del {} via {:?} dev {}",
+        route.dest, route.gateway, route.dev
+    );
+    table.generation += 1;
+    Ok(())
+}
+
+/// Removes the routes matched by `f`.
+pub fn remove_if<F: FnMut(&Route) -> bool>(mut f: F) {
+    let mut table = ROUTE_TABLE.lock();
+    let len = table.routes.len();
+    table.routes.retain(|route| !f(route));
+    if table.routes.len() != len {
+        table.generation += 1;
+    }
+}
+
+/// Sets the default route of the IP version of the gateway, which replaces
+/// the old one.
+pub fn set_default(gateway: IpAddress, dev: &str) {
+    let dest = match gateway {
+        IpAddress::Ipv4(_) => IpCidr::new(IpAddress::v4(0, 0, 0, 0), 0),
+        IpAddress::Ipv6(_) => IpCidr::new(IpAddress::Ipv6(Ipv6Address::UNSPECIFIED), 0),
+    };
+    remove_if(|route| route.dest == dest);
+    add(Route {
+        dest,
+        gateway: Some(gateway),
+        dev: dev.into(),
+    })
+    .unwrap();
+}
+
+/// Removes the default route of the IP version on the interface.
+pub fn remove_default(version: IpVersion, dev: &str) {
+    remove_if(|route| {
+        route.dest.prefix_len() == 0
+            && route.dest.address().version() == version
+            && route.dev == dev
+    });
+}
+
+/// Returns the route to the address with the longest prefix, the first added
+/// one if several match.
+pub fn lookup(addr: IpAddress) -> Option<Route> {
+    let table = ROUTE_TABLE.lock();
+    let mut matched: Option<&Route> = None;
+    for route in table.routes.iter() {
+        if route.dest.contains_addr(&addr)
+            && matched.map_or(true, |m| route.dest.prefix_len() > m.dest.prefix_len())
+        {
+            matched = Some(route);
+        }
+    }
+    matched.cloned()
+}
+
+/// Returns all the routes in the order of addition.
+pub fn routes() -> Vec<Route> {
+    ROUTE_TABLE.lock().routes.clone()
+}
+
+/// Returns the generation of the routing table, which changes on each update.
+pub fn generation() -> usize {
+    ROUTE_TABLE.lock().generation
+}
+
+/// Returns the routes given to smoltcp for the interface, with the more
+/// specific networks via other gateways or interfaces cut off.
+pub fn iface_routes(dev: &str) -> Vec<IfaceRoute> {
+    let table = ROUTE_TABLE.lock();
+    let mut iface_routes = Vec::new();
+    for route in table.routes.iter().filter(|route| route.dev == dev) {
+        // the networks on the link are reached by the addresses of the interface
+        let Some(gateway) = route.gateway else {
+            continue;
+        };
+        let holes: Vec<IpCidr> = table
+            .routes
+            .iter()
+            .filter(|hole| hole.is_hole_of(route))
+            .map(|hole| hole.dest)
+            .collect();
+        for cidr in subtract(route.dest, &holes) {
+            iface_routes.push(IfaceRoute {
+                cidr,
+                via_router: gateway,
+                preferred_until: None,
+                expires_at: None,
+            });
+        }
+    }
+    iface_routes
+}
+
+/// Returns the parts of the network not covered by the holes, in the fewest
+/// networks.
+fn subtract(cidr: IpCidr, holes: &[IpCidr]) -> Vec<IpCidr> {
+    if holes.iter().any(|hole| hole.contains_subnet(&cidr)) {
+        Vec::new()
+    } else if holes.iter().any(|hole| cidr.contains_subnet(hole)) {
+        let mut parts = Vec::new();
+        for half in split(cidr) {
+            parts.extend(subtract(half, holes));
+        }
+        parts
+    } else {
+        alloc::vec![cidr]
+    }
+}
+
+/// Returns the network of the address, with the host bits cleared.
+pub fn network(cidr: IpCidr) -> IpCidr {
+    let prefix_len = cidr.prefix_len();
+    match cidr {
+        IpCidr::Ipv4(cidr) => {
+            let mask = u32::MAX.checked_shr(prefix_len as u32).unwrap_or(0);
+            let addr = u32::from_be_bytes(cidr.address().0) & !mask;
+            IpCidr::Ipv4(Ipv4Cidr::new(Ipv4Address(addr.to_be_bytes()), prefix_len))
+        }
+        IpCidr::Ipv6(cidr) => {
+            let mask = u128::MAX.checked_shr(prefix_len as u32).unwrap_or(0);
+            let addr = u128::from_be_bytes(cidr.address().0) & !mask;
+            IpCidr::Ipv6(Ipv6Cidr::new(Ipv6Address(addr.to_be_bytes()), prefix_len))
+        }
+    }
+}
+
+/// Splits the network into the two halves with a prefix longer by one.
+fn split(cidr: IpCidr) -> [IpCidr; 2] {
+    let prefix_len = cidr.prefix_len() + 1;
+    match network(cidr) {
+        IpCidr::Ipv4(cidr) => {
+            let network = u32::from_be_bytes(cidr.address().0);
+            let half = 1 << (32 - prefix_len);
+            [network, network | half].map(|addr| {
+                IpCidr::Ipv4(Ipv4Cidr::new(Ipv4Address(addr.to_be_bytes()), prefix_len))
+            })
+        }
+        IpCidr::Ipv6(cidr) => {
+            let network = u128::from_be_bytes(cidr.address().0);
+            let half = 1 << (128 - prefix_len);
+            [network, network | half].map(|addr| {
+                IpCidr::Ipv6(Ipv6Cidr::new(Ipv6Address(addr.to_be_bytes()), prefix_len))
+            })
+        }
+    }
+}
+
+/// Generates `/proc/net/route`, which lists the IPv4 routes in the format of
+/// Linux, with the addresses in the byte order of the host.
+pub fn proc_net_route() -> String {
+    const RTF_UP: u16 = 0x1;
+    const RTF_GATEWAY: u16 = 0x2;
+
+    let mut content = String::from(
+        "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n",
+    );
+    for route in ROUTE_TABLE.lock().routes.iter() {
+        let IpCidr::Ipv4(dest) = route.dest else {
+            continue;
+        };
+        let (gateway, flags) = match route.gateway {
+            Some(IpAddress::Ipv4(gateway)) => (gateway.0, RTF_UP | RTF_GATEWAY),
+            _ => ([0; 4], RTF_UP),
+        };
+        let mask = u32::MAX
+            .checked_shl(32 - dest.prefix_len() as u32)
+            .unwrap_or(0)
+            .to_be_bytes();
+        let _ = writeln!(
+            content,
+            "{}\t{:08X}\t{:08X}\t{:04X}\t0\t0\t0\t{:08X}\t0\t0\t0",
+            route.dev,
+            u32::from_ne_bytes(dest.address().0),
+            u32::from_ne_bytes(gateway),
+            flags,
+            u32::from_ne_bytes(mask),
+        );
+    }
+    content
+}
@@ -20,12 +20,17 @@ use axsync::Mutex;
 use iovec::IoVecsOutput;
 use smoltcp::iface::SocketHandle;
 use smoltcp::socket::tcp::{self, ConnectError, State};
-use smoltcp::wire::{IpEndpoint, IpListenEndpoint};
+use smoltcp::wire::{IpEndpoint, IpListenEndpoint, IpVersion};
 
 use crate::message::{MessageFlags, MessageReadInfo};
 
-use super::addr::{from_core_sockaddr, into_core_sockaddr, is_unspecified, UNSPECIFIED_ENDPOINT};
-use super::{route_dev, SocketSetWrapper, IFACE_LIST, LISTEN_TABLE, SOCKET_SET};
+use super::addr::{
+    into_core_sockaddr, into_core_sockaddr_v6, is_unspecified, socket_endpoint,
+    UNSPECIFIED_ENDPOINT,
+};
+use super::{
+    block_on, iface_exists, route_dev, SocketSetWrapper, IFACE_LIST, LISTEN_TABLE, SOCKET_SET,
+};
 
 // State transitions:
 // CLOSED -(connect)-> BUSY -> CONNECTING -> CONNECTED -(shutdown)-> BUSY -> CLOSED
@@ -56,6 +61,14 @@ pub struct TcpSocket {
     peer_addr: UnsafeCell<IpEndpoint>,
     nonblock: AtomicBool,
     iface_name: Mutex<Option<String>>,
+    /// The interface bound by `SO_BINDTODEVICE`.
+    bound_dev: Mutex<Option<String>>,
+    /// Whether it's an `AF_INET6` socket.
+    ipv6: bool,
+    v6only: AtomicBool,
+    /// Whether a local address is bound, the state may be `STATE_CLOSED` even
+    /// so after a failed connection.
+    bound: AtomicBool,
 }
 
 unsafe impl Sync for TcpSocket {}
@@ -63,6 +76,16 @@ unsafe impl Sync for TcpSocket {}
 impl TcpSocket {
     /// Creates a new TCP socket.
     pub const fn new(nonblock: bool) -> Self {
+        Self::new_in(nonblock, false)
+    }
+
+    /// Creates a new TCP socket of IPv6, which also talks to IPv4 peers by
+    /// IPv4-mapped addresses unless [`set_v6only`](Self::set_v6only).
+    pub const fn new_v6(nonblock: bool) -> Self {
+        Self::new_in(nonblock, true)
+    }
+
+    const fn new_in(nonblock: bool, ipv6: bool) -> Self {
         Self {
             state: AtomicU8::new(STATE_CLOSED),
             handle: UnsafeCell::new(None),
@@ -70,6 +93,10 @@ impl TcpSocket {
             peer_addr: UnsafeCell::new(UNSPECIFIED_ENDPOINT),
             nonblock: AtomicBool::new(nonblock),
             iface_name: Mutex::new(None),
+            bound_dev: Mutex::new(None),
+            ipv6,
+            v6only: AtomicBool::new(false),
+            bound: AtomicBool::new(false),
         }
     }
 
@@ -78,6 +105,7 @@ impl TcpSocket {
         handle: SocketHandle,
         local_addr: IpEndpoint,
         peer_addr: IpEndpoint,
+        ipv6: bool,
     ) -> Self {
         Self {
             state: AtomicU8::new(STATE_CONNECTED),
@@ -86,6 +114,10 @@ impl TcpSocket {
             peer_addr: UnsafeCell::new(peer_addr),
             nonblock: AtomicBool::new(false),
             iface_name: Mutex::new(None),
+            bound_dev: Mutex::new(None),
+            ipv6,
+            v6only: AtomicBool::new(false),
+            bound: AtomicBool::new(true),
         }
     }
 
@@ -95,7 +127,7 @@ impl TcpSocket {
     pub fn local_addr(&self) -> AxResult<SocketAddr> {
         match self.get_state() {
             STATE_CONNECTED | STATE_LISTENING | STATE_BUSY => {
-                Ok(into_core_sockaddr(unsafe { self.local_addr.get().read() }))
+                Ok(self.to_sockaddr(unsafe { self.local_addr.get().read() }))
             }
             _ => Err(AxError::NotConnected),
         }
@@ -107,12 +139,53 @@ impl TcpSocket {
     pub fn peer_addr(&self) -> AxResult<SocketAddr> {
         match self.get_state() {
             STATE_CONNECTED | STATE_LISTENING => {
-                Ok(into_core_sockaddr(unsafe { self.peer_addr.get().read() }))
+                Ok(self.to_sockaddr(unsafe { self.peer_addr.get().read() }))
             }
             _ => Err(AxError::NotConnected),
         }
     }
 
+    /// Returns whether this is an `AF_INET6` socket.
+    #[inline]
+    pub fn is_ipv6(&self) -> bool {
+        self.ipv6
+    }
+
+    /// Returns whether this `AF_INET6` socket is restricted to IPv6 peers.
+    #[inline]
+    pub fn is_v6only(&self) -> bool {
+        self.v6only.load(Ordering::Acquire)
+    }
+
+    /// Restricts this `AF_INET6` socket to IPv6 peers (`IPV6_V6ONLY`), it must
+    /// be called before [`bind`](Self::bind).
+    pub fn set_v6only(&self, v6only: bool) -> AxResult {
+        if !self.ipv6 {
+            return ax_err!(InvalidInput, "socket set_v6only() failed: not IPv6");
+        }
+        if self.bound.load(Ordering::Acquire) {
+            return ax_err!(InvalidInput, "socket set_v6only() failed: already bound");
+        }
+        self.v6only.store(v6only, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the interface bound by [`bind_to_device`](Self::bind_to_device).
+    pub fn bound_device(&self) -> Option<String> {
+        self.bound_dev.lock().clone()
+    }
+
+    /// Binds this socket to the interface (`SO_BINDTODEVICE`), so that its
+    /// packets are sent through the interface regardless of the routes, or
+    /// unbinds it if `None`.
+    pub fn bind_to_device(&self, dev: Option<&str>) -> AxResult {
+        if dev.is_some_and(|dev| !iface_exists(dev)) {
+            return ax_err!(NoSuchDevice, "socket bind_to_device() failed");
+        }
+        *self.bound_dev.lock() = dev.map(String::from);
+        Ok(())
+    }
+
     /// Returens if this socket is listening
     #[inline]
     pub fn is_listening(&self) -> bool {
@@ -142,18 +215,15 @@ impl TcpSocket {
     ///
     /// The local port is generated automatically.
     pub fn connect(&self, remote_addr: SocketAddr) -> AxResult {
-        let iface_name = Some(match remote_addr {
-            SocketAddr::V4(addr) => route_dev(addr.ip().octets()),
-            _ => panic!("IPv6 not supported"),
-        });
-        *self.iface_name.lock() = iface_name;
+        let remote_endpoint = self.to_endpoint(remote_addr)?;
+        let iface_name = route_dev(remote_endpoint.addr, self.bound_dev.lock().as_deref())?;
+        *self.iface_name.lock() = Some(iface_name);
         self.update_state(STATE_CLOSED, STATE_CONNECTING, || {
             // SAFETY: no other threads can read or write these fields.
             let handle = unsafe { self.handle.get().read() }
                 .unwrap_or_else(|| SOCKET_SET.add(SocketSetWrapper::new_tcp_socket()));
 
             // TODO: check remote addr unreachable
-            let remote_endpoint = from_core_sockaddr(remote_addr);
             let bound_endpoint = self.bound_endpoint()?;
             let binding = IFACE_LIST.lock();
             let iface = &binding
@@ -185,11 +255,13 @@ impl TcpSocket {
                 self.peer_addr.get().write(remote_endpoint);
                 self.handle.get().write(Some(handle));
             }
+            self.bound.store(true, Ordering::Release);
             Ok(())
         })
         .unwrap_or_else(|_| ax_err!(AlreadyExists, "socket connect() failed: already connected"))?; // EISCONN
 
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 let PollState { writable, .. } = self.poll_connect()?;
                 if !writable {
@@ -231,8 +303,9 @@ impl TcpSocket {
                 if old != UNSPECIFIED_ENDPOINT {
                     return ax_err!(InvalidInput, "socket bind() failed: already bound");
                 }
-                self.local_addr.get().write(from_core_sockaddr(local_addr));
+                self.local_addr.get().write(self.to_endpoint(local_addr)?);
             }
+            self.bound.store(true, Ordering::Release);
             Ok(())
         })
         .unwrap_or_else(|_| ax_err!(InvalidInput, "socket bind() failed: already bound"))
@@ -248,7 +321,7 @@ impl TcpSocket {
             unsafe {
                 (*self.local_addr.get()).port = bound_endpoint.port;
             }
-            LISTEN_TABLE.listen(bound_endpoint)?;
+            LISTEN_TABLE.listen(bound_endpoint, self.listen_version())?;
             debug!("TCP socket listening on {}", bound_endpoint);
             Ok(())
         })
@@ -268,11 +341,14 @@ impl TcpSocket {
 
         // SAFETY: `self.local_addr` should be initialized after `bind()`.
         let local_port = unsafe { self.local_addr.get().read().port };
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 let (handle, (local_addr, peer_addr)) = LISTEN_TABLE.accept(local_port)?;
                 debug!("TCP socket accepted a new connection {}", peer_addr);
-                Ok(TcpSocket::new_connected(handle, local_addr, peer_addr))
+                Ok(TcpSocket::new_connected(
+                    handle, local_addr, peer_addr, self.ipv6,
+                ))
             },
             None,
         )
@@ -321,7 +397,8 @@ impl TcpSocket {
 
         // SAFETY: `self.handle` should be initialized in a connected socket.
         let handle = unsafe { self.handle.get().read().unwrap() };
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
                     if !socket.is_active() {
@@ -373,7 +450,8 @@ impl TcpSocket {
         }
         // SAFETY: `self.handle` should be initialized in a connected socket.
         let handle = unsafe { self.handle.get().read().unwrap() };
-        let bytes_read = self.block_on(
+        let bytes_read = block_on(
+            self.is_nonblocking(),
             || {
                 SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
                     if !socket.is_active() {
@@ -417,7 +495,8 @@ impl TcpSocket {
 
         // SAFETY: `self.handle` should be initialized in a connected socket.
         let handle = unsafe { self.handle.get().read().unwrap() };
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 SOCKET_SET.with_socket_mut::<tcp::Socket, _, _>(handle, |socket| {
                     if !socket.is_active() || !socket.may_send() {
@@ -506,6 +585,28 @@ impl TcpSocket {
         self.get_state() == STATE_CONNECTED
     }
 
+    fn to_endpoint(&self, addr: SocketAddr) -> AxResult<IpEndpoint> {
+        socket_endpoint(addr, self.ipv6, self.is_v6only())
+    }
+
+    fn to_sockaddr(&self, endpoint: IpEndpoint) -> SocketAddr {
+        if self.ipv6 {
+            into_core_sockaddr_v6(endpoint)
+        } else {
+            into_core_sockaddr(endpoint)
+        }
+    }
+
+    /// The IP version of the connections accepted by the listener, or `None`
+    /// for both.
+    fn listen_version(&self) -> Option<IpVersion> {
+        match (self.ipv6, self.is_v6only()) {
+            (false, _) => Some(IpVersion::Ipv4),
+            (true, true) => Some(IpVersion::Ipv6),
+            (true, false) => None,
+        }
+    }
+
     fn bound_endpoint(&self) -> AxResult<IpListenEndpoint> {
         // SAFETY: no other threads can read or write `self.local_addr`.
         let local_addr = unsafe { self.local_addr.get().read() };
@@ -578,32 +679,6 @@ impl TcpSocket {
             pollhup: false,
         })
     }
-
-    /// Block the current thread until the given function completes or fails.
-    ///
-    /// If the socket is non-blocking, it calls the function once and returns
-    /// immediately. Otherwise, it may call the function multiple times if it
-    /// returns [`Err(WouldBlock)`](AxError::WouldBlock).
-    fn block_on<F, T>(&self, mut f: F, iface: Option<String>) -> AxResult<T>
-    where
-        F: FnMut() -> AxResult<T>,
-    {
-        if self.is_nonblocking() {
-            let res = f();
-            SOCKET_SET.poll_interfaces(iface.clone());
-            res
-        } else {
-            loop {
-                let res = f();
-                SOCKET_SET.poll_interfaces(iface.clone());
-                match res {
-                    Ok(t) => return Ok(t),
-                    Err(AxError::WouldBlock) => ruxtask::yield_now(),
-                    Err(e) => return Err(e),
-                }
-            }
-        }
-    }
 }
 
 impl Drop for TcpSocket {
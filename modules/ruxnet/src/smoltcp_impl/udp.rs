@@ -7,7 +7,7 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use core::sync::atomic::{AtomicBool, Ordering};
 
 use alloc::string::String;
@@ -20,12 +20,12 @@ use spin::RwLock;
 
 use smoltcp::iface::SocketHandle;
 use smoltcp::socket::udp::{self, BindError, SendError};
-use smoltcp::wire::{IpEndpoint, IpListenEndpoint};
+use smoltcp::wire::{IpAddress, IpEndpoint, IpListenEndpoint};
 
 use crate::message::{MessageFlags, MessageReadInfo};
 
-use super::addr::{from_core_sockaddr, into_core_sockaddr, is_unspecified, UNSPECIFIED_ENDPOINT};
-use super::{route_dev, SocketSetWrapper, SOCKET_SET};
+use super::addr::{into_core_sockaddr, into_core_sockaddr_v6, is_unspecified, socket_endpoint};
+use super::{block_on, iface_exists, route_dev, SocketSetWrapper, SOCKET_SET};
 
 /// A UDP socket that provides POSIX-like APIs.
 pub struct UdpSocket {
@@ -33,12 +33,27 @@ pub struct UdpSocket {
     local_addr: RwLock<Option<IpEndpoint>>,
     peer_addr: RwLock<Option<IpEndpoint>>,
     nonblock: AtomicBool,
+    /// The interface bound by `SO_BINDTODEVICE`.
+    bound_dev: RwLock<Option<String>>,
+    /// Whether it's an `AF_INET6` socket.
+    ipv6: bool,
+    v6only: AtomicBool,
 }
 
 impl UdpSocket {
     /// Creates a new UDP socket.
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
+        Self::new_in(false)
+    }
+
+    /// Creates a new UDP socket of IPv6, which also talks to IPv4 peers by
+    /// IPv4-mapped addresses unless [`set_v6only`](Self::set_v6only).
+    pub fn new_v6() -> Self {
+        Self::new_in(true)
+    }
+
+    fn new_in(ipv6: bool) -> Self {
         let socket = SocketSetWrapper::new_udp_socket();
         let handle = SOCKET_SET.add(socket);
         Self {
@@ -46,6 +61,9 @@ impl UdpSocket {
             local_addr: RwLock::new(None),
             peer_addr: RwLock::new(None),
             nonblock: AtomicBool::new(false),
+            bound_dev: RwLock::new(None),
+            ipv6,
+            v6only: AtomicBool::new(false),
         }
     }
 
@@ -53,7 +71,9 @@ impl UdpSocket {
     /// [`Err(NotConnected)`](AxError::NotConnected) if not connected.
     pub fn local_addr(&self) -> AxResult<SocketAddr> {
         match self.local_addr.try_read() {
-            Some(addr) => addr.map(into_core_sockaddr).ok_or(AxError::NotConnected),
+            Some(addr) => addr
+                .map(|addr| self.to_sockaddr(addr))
+                .ok_or(AxError::NotConnected),
             None => Err(AxError::NotConnected),
         }
     }
@@ -61,7 +81,48 @@ impl UdpSocket {
     /// Returns the remote address and port, or
     /// [`Err(NotConnected)`](AxError::NotConnected) if not connected.
     pub fn peer_addr(&self) -> AxResult<SocketAddr> {
-        self.remote_endpoint().map(into_core_sockaddr)
+        self.remote_endpoint().map(|addr| self.to_sockaddr(addr))
+    }
+
+    /// Returns whether this is an `AF_INET6` socket.
+    #[inline]
+    pub fn is_ipv6(&self) -> bool {
+        self.ipv6
+    }
+
+    /// Returns whether this `AF_INET6` socket is restricted to IPv6 peers.
+    #[inline]
+    pub fn is_v6only(&self) -> bool {
+        self.v6only.load(Ordering::Acquire)
+    }
+
+    /// Restricts this `AF_INET6` socket to IPv6 peers (`IPV6_V6ONLY`), it must
+    /// be called before [`bind`](Self::bind).
+    pub fn set_v6only(&self, v6only: bool) -> AxResult {
+        if !self.ipv6 {
+            return ax_err!(InvalidInput, "socket set_v6only() failed: not IPv6");
+        }
+        if self.local_addr.read().is_some() {
+            return ax_err!(InvalidInput, "socket set_v6only() failed: already bound");
+        }
+        self.v6only.store(v6only, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the interface bound by [`bind_to_device`](Self::bind_to_device).
+    pub fn bound_device(&self) -> Option<String> {
+        self.bound_dev.read().clone()
+    }
+
+    /// Binds this socket to the interface (`SO_BINDTODEVICE`), so that its
+    /// packets are sent through the interface regardless of the routes, or
+    /// unbinds it if `None`.
+    pub fn bind_to_device(&self, dev: Option<&str>) -> AxResult {
+        if dev.is_some_and(|dev| !iface_exists(dev)) {
+            return ax_err!(NoSuchDevice, "socket bind_to_device() failed");
+        }
+        *self.bound_dev.write() = dev.map(String::from);
+        Ok(())
     }
 
     /// Returns whether this socket is in nonblocking mode.
@@ -97,7 +158,7 @@ impl UdpSocket {
             return ax_err!(InvalidInput, "socket bind() failed: already bound");
         }
 
-        let local_endpoint = from_core_sockaddr(local_addr);
+        let local_endpoint = self.to_endpoint(local_addr)?;
         let endpoint = IpListenEndpoint {
             addr: (!is_unspecified(local_endpoint.addr)).then_some(local_endpoint.addr),
             port: local_endpoint.port,
@@ -120,14 +181,14 @@ impl UdpSocket {
         if remote_addr.port() == 0 || remote_addr.ip().is_unspecified() {
             return ax_err!(InvalidInput, "socket send_to() failed: invalid address");
         }
-        self.send_impl(buf, from_core_sockaddr(remote_addr))
+        self.send_impl(buf, self.to_endpoint(remote_addr)?)
     }
 
     /// Receives a single datagram message on the socket. On success, returns
     /// the number of bytes read and the origin.
     pub fn recv_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
         self.recv_impl(|socket| match socket.recv_slice(buf) {
-            Ok((len, meta)) => Ok((len, into_core_sockaddr(meta.endpoint))),
+            Ok((len, meta)) => Ok((len, self.to_sockaddr(meta.endpoint))),
             Err(_) => ax_err!(BadState, "socket recv_from() failed"),
         })
     }
@@ -136,7 +197,7 @@ impl UdpSocket {
     /// the queue. On success, returns the number of bytes read and the origin.
     pub fn peek_from(&self, buf: &mut [u8]) -> AxResult<(usize, SocketAddr)> {
         self.recv_impl(|socket| match socket.peek_slice(buf) {
-            Ok((len, meta)) => Ok((len, into_core_sockaddr(meta.endpoint))),
+            Ok((len, meta)) => Ok((len, self.to_sockaddr(meta.endpoint))),
             Err(_) => ax_err!(BadState, "socket recv_from() failed"),
         })
     }
@@ -151,11 +212,12 @@ impl UdpSocket {
     pub fn connect(&self, addr: SocketAddr) -> AxResult {
         let mut self_peer_addr = self.peer_addr.write();
 
+        let remote_endpoint = self.to_endpoint(addr)?;
         if self.local_addr.read().is_none() {
-            self.bind(into_core_sockaddr(UNSPECIFIED_ENDPOINT))?;
+            self.bind(self.unspecified_addr())?;
         }
 
-        *self_peer_addr = Some(from_core_sockaddr(addr));
+        *self_peer_addr = Some(remote_endpoint);
         debug!("UDP socket {}: connected to {}", self.handle, addr);
         Ok(())
     }
@@ -205,7 +267,7 @@ impl UdpSocket {
             Ok(MessageReadInfo {
                 bytes_read,
                 bytes_total: data.len(),
-                address: Some(into_core_sockaddr(meta.endpoint).into()),
+                address: Some(self.to_sockaddr(meta.endpoint).into()),
                 ancillary_data: vec![],
             })
         })
@@ -249,17 +311,46 @@ impl UdpSocket {
         }
     }
 
+    fn to_endpoint(&self, addr: SocketAddr) -> AxResult<IpEndpoint> {
+        socket_endpoint(addr, self.ipv6, self.is_v6only())
+    }
+
+    fn to_sockaddr(&self, endpoint: IpEndpoint) -> SocketAddr {
+        if self.ipv6 {
+            into_core_sockaddr_v6(endpoint)
+        } else {
+            into_core_sockaddr(endpoint)
+        }
+    }
+
+    /// The address to bind an unbound socket on, with an ephemeral port.
+    fn unspecified_addr(&self) -> SocketAddr {
+        let ip = if self.ipv6 {
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        };
+        SocketAddr::new(ip, 0)
+    }
+
+    /// Whether a datagram from `addr` can be received, as a socket bound on the
+    /// unspecified address receives both IPv4 and IPv6 ones.
+    fn can_recv_from(&self, addr: IpAddress) -> bool {
+        match addr {
+            IpAddress::Ipv4(_) => !self.ipv6 || !self.is_v6only(),
+            IpAddress::Ipv6(_) => self.ipv6,
+        }
+    }
+
     fn send_impl(&self, buf: &[u8], remote_endpoint: IpEndpoint) -> AxResult<usize> {
         if self.local_addr.read().is_none() {
-            let res = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 0));
-            self.bind(res)?;
+            self.bind(self.unspecified_addr())?;
         }
 
-        let mut addr = [0u8; 4];
-        addr.copy_from_slice(remote_endpoint.addr.as_bytes());
-        let iface_name = route_dev(addr);
+        let iface_name = route_dev(remote_endpoint.addr, self.bound_dev.read().as_deref())?;
 
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
                     if socket.can_send() {
@@ -290,9 +381,17 @@ impl UdpSocket {
             return ax_err!(NotConnected, "socket send() failed");
         }
 
-        self.block_on(
+        block_on(
+            self.is_nonblocking(),
             || {
                 SOCKET_SET.with_socket_mut::<udp::Socket, _, _>(self.handle, |socket| {
+                    // drop the datagrams of the other IP version
+                    while let Ok(addr) = socket.peek().map(|(_, meta)| meta.endpoint.addr) {
+                        if self.can_recv_from(addr) {
+                            break;
+                        }
+                        socket.recv().ok();
+                    }
                     if socket.can_recv() {
                         // data available
                         op(socket)
@@ -305,27 +404,6 @@ impl UdpSocket {
             None,
         )
     }
-
-    fn block_on<F, T>(&self, mut f: F, iface: Option<String>) -> AxResult<T>
-    where
-        F: FnMut() -> AxResult<T>,
-    {
-        if self.is_nonblocking() {
-            let res = f();
-            SOCKET_SET.poll_interfaces(iface.clone());
-            res
-        } else {
-            loop {
-                let res = f();
-                SOCKET_SET.poll_interfaces(iface.clone());
-                match res {
-                    Ok(t) => return Ok(t),
-                    Err(AxError::WouldBlock) => ruxtask::yield_now(),
-                    Err(e) => return Err(e),
-                }
-            }
-        }
-    }
 }
 
 impl Drop for UdpSocket {
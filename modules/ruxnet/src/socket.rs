@@ -12,7 +12,7 @@ use core::net::SocketAddr;
 use alloc::{sync::Arc, vec::Vec};
 use axerrno::{LinuxError, LinuxResult};
 use axfs_vfs::AbsPath;
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use axsync::Mutex;
 use iovec::{IoVecsInput, IoVecsOutput};
 use ruxfdtable::{FileLike, RuxStat};
@@ -22,7 +22,7 @@ use crate::{
     address::SocketAddress,
     message::{ControlMessageData, MessageFlags, MessageReadInfo},
     unix::UnixSocket,
-    ShutdownFlags, TcpSocket, UdpSocket,
+    IcmpSocket, RawSocket, ShutdownFlags, TcpSocket, UdpSocket,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -32,8 +32,11 @@ pub enum SocketType {
     /// Used by TCP and UNIX stream sockets
     Stream,
     /// Connectionless, unreliable datagrams (SOCK_DGRAM)
-    /// Used by UDP and UNIX datagram sockets
+    /// Used by UDP and UNIX datagram sockets, and ICMP echo sockets
     Datagram,
+    /// Raw packets of an IP protocol (SOCK_RAW)
+    /// Same as SOCK_DGRAM for UNIX sockets
+    Raw,
 }
 
 impl TryFrom<u32> for SocketType {
@@ -43,6 +46,7 @@ impl TryFrom<u32> for SocketType {
         match ty {
             1 => Ok(SocketType::Stream),
             2 => Ok(SocketType::Datagram),
+            3 => Ok(SocketType::Raw),
             _ => Err(LinuxError::EAFNOSUPPORT),
         }
     }
@@ -53,6 +57,7 @@ impl From<SocketType> for u32 {
         match value {
             SocketType::Stream => 1,
             SocketType::Datagram => 2,
+            SocketType::Raw => 3,
         }
     }
 }
@@ -89,6 +94,10 @@ pub enum Socket {
     Tcp(Mutex<TcpSocket>),
     /// Udp
     Udp(Mutex<UdpSocket>),
+    /// Raw IP
+    Raw(Mutex<RawSocket>),
+    /// ICMP echo
+    Icmp(Mutex<IcmpSocket>),
     /// Unix
     Unix(UnixSocket),
 }
@@ -125,15 +134,29 @@ impl FileLike for Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().poll()?),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().poll()?),
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().poll()?),
+            Socket::Icmp(icmpsocket) => Ok(icmpsocket.lock().poll()?),
             Socket::Unix(unixsocket) => unixsocket.poll(),
         }
     }
 
+    fn poll_waiters(&self) -> Option<&PollWaiters> {
+        match self {
+            // inet sockets are only updated when the interfaces are polled
+            Socket::Udp(_) | Socket::Tcp(_) | Socket::Raw(_) | Socket::Icmp(_) => {
+                crate::net_impl::poll_waiters()
+            }
+            Socket::Unix(unixsocket) => Some(unixsocket.poll_waiters()),
+        }
+    }
+
     fn set_flags(&self, flags: OpenFlags) -> LinuxResult {
         let nonblock = flags.contains(OpenFlags::O_NONBLOCK);
         match self {
             Socket::Udp(udpsocket) => udpsocket.lock().set_nonblocking(nonblock),
             Socket::Tcp(tcpsocket) => tcpsocket.lock().set_nonblocking(nonblock),
+            Socket::Raw(rawsocket) => rawsocket.lock().set_nonblocking(nonblock),
+            Socket::Icmp(icmpsocket) => icmpsocket.lock().set_nonblocking(nonblock),
             Socket::Unix(unixsocket) => unixsocket.set_nonblocking(nonblock),
         }
         Ok(())
@@ -143,6 +166,8 @@ impl FileLike for Socket {
         let nonblock = match self {
             Socket::Udp(udpsocket) => udpsocket.lock().is_nonblocking(),
             Socket::Tcp(tcpsocket) => tcpsocket.lock().is_nonblocking(),
+            Socket::Raw(rawsocket) => rawsocket.lock().is_nonblocking(),
+            Socket::Icmp(icmpsocket) => icmpsocket.lock().is_nonblocking(),
             Socket::Unix(unixsocket) => unixsocket.is_nonblocking(),
         };
         if nonblock {
@@ -154,12 +179,85 @@ impl FileLike for Socket {
 }
 
 impl Socket {
+    /// Creates an `AF_INET` or `AF_INET6` socket of the protocol, or the
+    /// default one of the type if 0. Only TCP and UDP over IPv4 are supported
+    /// by lwip.
+    ///
+    /// `SOCK_DGRAM` with `IPPROTO_ICMP` or `IPPROTO_ICMPV6` creates an ICMP
+    /// echo socket, and `SOCK_RAW` requires a protocol.
+    pub fn new_inet(
+        domain: SocketDomain,
+        socktype: SocketType,
+        protocol: u32,
+        nonblock: bool,
+    ) -> LinuxResult<Self> {
+        const IPPROTO_TCP: u32 = 6;
+        const IPPROTO_UDP: u32 = 17;
+
+        #[cfg(not(feature = "lwip"))]
+        {
+            const IPPROTO_ICMP: u32 = 1;
+            const IPPROTO_ICMPV6: u32 = 58;
+            const IPPROTO_RAW: u32 = 255;
+
+            let ipv6 = domain == SocketDomain::Inet6;
+            let icmp = if ipv6 { IPPROTO_ICMPV6 } else { IPPROTO_ICMP };
+            match (socktype, protocol) {
+                (SocketType::Datagram, p) if p == icmp => {
+                    let icmpsocket = if ipv6 {
+                        IcmpSocket::new_v6()
+                    } else {
+                        IcmpSocket::new()
+                    };
+                    icmpsocket.set_nonblocking(nonblock);
+                    return Ok(Socket::Icmp(Mutex::new(icmpsocket)));
+                }
+                (SocketType::Raw, 1..=IPPROTO_RAW) => {
+                    let rawsocket = if ipv6 {
+                        RawSocket::new_v6(protocol as u8)
+                    } else {
+                        RawSocket::new(protocol as u8)
+                    };
+                    rawsocket.set_nonblocking(nonblock);
+                    return Ok(Socket::Raw(Mutex::new(rawsocket)));
+                }
+                _ => {}
+            }
+        }
+        match (socktype, protocol) {
+            (SocketType::Stream, 0 | IPPROTO_TCP) | (SocketType::Datagram, 0 | IPPROTO_UDP) => {}
+            _ => return Err(LinuxError::EPROTONOSUPPORT),
+        }
+        Ok(match (domain, socktype) {
+            (SocketDomain::Inet, SocketType::Stream) => {
+                Socket::Tcp(Mutex::new(TcpSocket::new(nonblock)))
+            }
+            (SocketDomain::Inet, SocketType::Datagram) => Socket::Udp(Mutex::new(UdpSocket::new())),
+            #[cfg(not(feature = "lwip"))]
+            (SocketDomain::Inet6, SocketType::Stream) => {
+                Socket::Tcp(Mutex::new(TcpSocket::new_v6(nonblock)))
+            }
+            #[cfg(not(feature = "lwip"))]
+            (SocketDomain::Inet6, SocketType::Datagram) => {
+                Socket::Udp(Mutex::new(UdpSocket::new_v6()))
+            }
+            _ => return Err(LinuxError::EAFNOSUPPORT),
+        })
+    }
+
     /// Returns the address family/domain of the socket.
     pub fn domain(&self) -> SocketDomain {
-        match self {
-            Socket::Tcp(_) => SocketDomain::Inet,
-            Socket::Udp(_) => SocketDomain::Inet,
-            Socket::Unix(_) => SocketDomain::Unix,
+        let ipv6 = match self {
+            Socket::Tcp(tcpsocket) => tcpsocket.lock().is_ipv6(),
+            Socket::Udp(udpsocket) => udpsocket.lock().is_ipv6(),
+            Socket::Raw(rawsocket) => rawsocket.lock().is_ipv6(),
+            Socket::Icmp(icmpsocket) => icmpsocket.lock().is_ipv6(),
+            Socket::Unix(_) => return SocketDomain::Unix,
+        };
+        if ipv6 {
+            SocketDomain::Inet6
+        } else {
+            SocketDomain::Inet
         }
     }
 
@@ -167,7 +265,8 @@ impl Socket {
     pub fn socket_type(&self) -> SocketType {
         match self {
             Socket::Tcp(_) => SocketType::Stream,
-            Socket::Udp(_) => SocketType::Datagram,
+            Socket::Udp(_) | Socket::Icmp(_) => SocketType::Datagram,
+            Socket::Raw(_) => SocketType::Raw,
             Socket::Unix(unixsocket) => unixsocket.socket_type(),
         }
     }
@@ -186,26 +285,34 @@ impl Socket {
     /// For UNIX sockets: binds to a filesystem path
     pub fn bind(self: Arc<Self>, address: SocketAddress) -> LinuxResult {
         match *self {
-            Socket::Udp(ref udpsocket) => {
-                if let SocketAddress::Inet(ipv4_addr) = address {
-                    udpsocket
-                        .lock()
-                        .bind(SocketAddr::V4(ipv4_addr))
-                        .map_err(LinuxError::from)
-                } else {
-                    Err(LinuxError::EINVAL)
+            Socket::Udp(ref udpsocket) => match address {
+                SocketAddress::Unix(_) => Err(LinuxError::EINVAL),
+                _ => {
+                    let udpsocket = udpsocket.lock();
+                    Ok(udpsocket.bind(inet_addr(address, udpsocket.is_ipv6())?)?)
                 }
-            }
-            Socket::Tcp(ref tcpsocket) => {
-                if let SocketAddress::Inet(ipv4_addr) = address {
-                    tcpsocket
-                        .lock()
-                        .bind(SocketAddr::V4(ipv4_addr))
-                        .map_err(LinuxError::from)
-                } else {
-                    Err(LinuxError::EINVAL)
+            },
+            Socket::Tcp(ref tcpsocket) => match address {
+                SocketAddress::Unix(_) => Err(LinuxError::EINVAL),
+                _ => {
+                    let tcpsocket = tcpsocket.lock();
+                    Ok(tcpsocket.bind(inet_addr(address, tcpsocket.is_ipv6())?)?)
                 }
-            }
+            },
+            Socket::Raw(ref rawsocket) => match address {
+                SocketAddress::Unix(_) => Err(LinuxError::EINVAL),
+                _ => {
+                    let rawsocket = rawsocket.lock();
+                    Ok(rawsocket.bind(inet_addr(address, rawsocket.is_ipv6())?)?)
+                }
+            },
+            Socket::Icmp(ref icmpsocket) => match address {
+                SocketAddress::Unix(_) => Err(LinuxError::EINVAL),
+                _ => {
+                    let icmpsocket = icmpsocket.lock();
+                    Ok(icmpsocket.bind(inet_addr(address, icmpsocket.is_ipv6())?)?)
+                }
+            },
             Socket::Unix(ref unixsocket) => unixsocket.bind(self.clone(), address),
         }
     }
@@ -214,7 +321,7 @@ impl Socket {
     /// backlog specifies the maximum pending connections queue size.
     pub fn listen(&self, backlog: i32) -> LinuxResult {
         match self {
-            Socket::Udp(_) => Err(LinuxError::EOPNOTSUPP),
+            Socket::Udp(_) | Socket::Raw(_) | Socket::Icmp(_) => Err(LinuxError::EOPNOTSUPP),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().listen()?),
             Socket::Unix(unixsocket) => Ok(unixsocket.listen(backlog)?),
         }
@@ -224,7 +331,7 @@ impl Socket {
     /// Returns a new Socket for the accepted connection.
     pub fn accept(&self) -> LinuxResult<Arc<Socket>> {
         match self {
-            Socket::Udp(_) => Err(LinuxError::EOPNOTSUPP),
+            Socket::Udp(_) | Socket::Raw(_) | Socket::Icmp(_) => Err(LinuxError::EOPNOTSUPP),
             Socket::Tcp(tcpsocket) => Ok(Arc::new(Socket::Tcp(Mutex::new(
                 tcpsocket.lock().accept()?,
             )))),
@@ -236,8 +343,22 @@ impl Socket {
     /// For datagram sockets, this sets the default destination.
     pub fn connect(self: Arc<Self>, address: SocketAddress) -> LinuxResult {
         match *self {
-            Socket::Udp(ref udpsocket) => Ok(udpsocket.lock().connect(address.into())?),
-            Socket::Tcp(ref tcpsocket) => Ok(tcpsocket.lock().connect(address.into())?),
+            Socket::Udp(ref udpsocket) => {
+                let udpsocket = udpsocket.lock();
+                Ok(udpsocket.connect(inet_addr(address, udpsocket.is_ipv6())?)?)
+            }
+            Socket::Tcp(ref tcpsocket) => {
+                let tcpsocket = tcpsocket.lock();
+                Ok(tcpsocket.connect(inet_addr(address, tcpsocket.is_ipv6())?)?)
+            }
+            Socket::Raw(ref rawsocket) => {
+                let rawsocket = rawsocket.lock();
+                Ok(rawsocket.connect(inet_addr(address, rawsocket.is_ipv6())?)?)
+            }
+            Socket::Icmp(ref icmpsocket) => {
+                let icmpsocket = icmpsocket.lock();
+                Ok(icmpsocket.connect(inet_addr(address, icmpsocket.is_ipv6())?)?)
+            }
             Socket::Unix(ref unixsocket) => unixsocket.connect(self.clone(), address),
         }
     }
@@ -247,6 +368,8 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().local_addr()?.into()),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().local_addr()?.into()),
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().local_addr()?.into()),
+            Socket::Icmp(icmpsocket) => Ok(icmpsocket.lock().local_addr()?.into()),
             Socket::Unix(unixsocket) => unixsocket.local_addr(),
         }
     }
@@ -256,6 +379,8 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().peer_addr()?.into()),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().peer_addr()?.into()),
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().peer_addr()?.into()),
+            Socket::Icmp(icmpsocket) => Ok(icmpsocket.lock().peer_addr()?.into()),
             Socket::Unix(unixsocket) => unixsocket.peer_addr(),
         }
     }
@@ -309,12 +434,9 @@ impl Socket {
             Socket::Udp(udpsocket) => {
                 let udpsocket = udpsocket.lock();
                 if let Some(address) = address {
-                    if let SocketAddress::Inet(ipv4_addr) = address {
-                        for buf in iovecs.as_slices() {
-                            bytes_send += udpsocket.send_to(buf, SocketAddr::V4(ipv4_addr))?;
-                        }
-                    } else {
-                        return Err(LinuxError::EAFNOSUPPORT);
+                    let address = inet_addr(address, udpsocket.is_ipv6())?;
+                    for buf in iovecs.as_slices() {
+                        bytes_send += udpsocket.send_to(buf, address)?;
                     }
                 } else {
                     for buf in iovecs.as_slices() {
@@ -322,6 +444,38 @@ impl Socket {
                     }
                 }
             }
+            Socket::Raw(rawsocket) => {
+                let rawsocket = rawsocket.lock();
+                match address {
+                    Some(address) => {
+                        let address = inet_addr(address, rawsocket.is_ipv6())?;
+                        for buf in iovecs.as_slices() {
+                            bytes_send += rawsocket.send_to(buf, address)?;
+                        }
+                    }
+                    None => {
+                        for buf in iovecs.as_slices() {
+                            bytes_send += rawsocket.send(buf)?;
+                        }
+                    }
+                }
+            }
+            Socket::Icmp(icmpsocket) => {
+                let icmpsocket = icmpsocket.lock();
+                match address {
+                    Some(address) => {
+                        let address = inet_addr(address, icmpsocket.is_ipv6())?;
+                        for buf in iovecs.as_slices() {
+                            bytes_send += icmpsocket.send_to(buf, address)?;
+                        }
+                    }
+                    None => {
+                        for buf in iovecs.as_slices() {
+                            bytes_send += icmpsocket.send(buf)?;
+                        }
+                    }
+                }
+            }
             Socket::Unix(unixsocket) => {
                 bytes_send += unixsocket.sendmsg(iovecs, address, ancillary_data, flags)?
             }
@@ -334,6 +488,8 @@ impl Socket {
         match self {
             Socket::Udp(udpsocket) => Ok(udpsocket.lock().recv_from(buf).map(|e| e.0)?),
             Socket::Tcp(tcpsocket) => Ok(tcpsocket.lock().recv(buf, flags)?),
+            Socket::Raw(rawsocket) => Ok(rawsocket.lock().recv_from(buf).map(|e| e.0)?),
+            Socket::Icmp(icmpsocket) => Ok(icmpsocket.lock().recv_from(buf).map(|e| e.0)?),
             Socket::Unix(unixsocket) => {
                 let info = unixsocket.recvmsg(&mut IoVecsOutput::from_single_buffer(buf), flags)?;
                 Ok(info.bytes_read)
@@ -357,6 +513,14 @@ impl Socket {
                 let size = tcpsocket.lock().recv(buf, MessageFlags::empty())?;
                 Ok((size, None))
             }
+            Socket::Raw(rawsocket) => {
+                let (size, addr) = rawsocket.lock().recv_from(buf)?;
+                Ok((size, Some(addr.into())))
+            }
+            Socket::Icmp(icmpsocket) => {
+                let (size, addr) = icmpsocket.lock().recv_from(buf)?;
+                Ok((size, Some(addr.into())))
+            }
             Socket::Unix(unixsocket) => {
                 let info = unixsocket.recvmsg(&mut IoVecsOutput::from_single_buffer(buf), flags)?;
                 Ok((info.bytes_read, info.address))
@@ -379,6 +543,14 @@ impl Socket {
                 .lock()
                 .recvmsg(iovecs, flags)
                 .map_err(LinuxError::from),
+            Socket::Raw(rawsocket) => rawsocket
+                .lock()
+                .recvmsg(iovecs, flags)
+                .map_err(LinuxError::from),
+            Socket::Icmp(icmpsocket) => icmpsocket
+                .lock()
+                .recvmsg(iovecs, flags)
+                .map_err(LinuxError::from),
             Socket::Unix(unixsocket) => unixsocket.recvmsg(iovecs, flags),
         }
     }
@@ -397,7 +569,26 @@ impl Socket {
                 tcpsocket.lock().shutdown()?;
                 Ok(())
             }
+            // nothing to shut down but the connection
+            Socket::Raw(rawsocket) => {
+                rawsocket.lock().peer_addr()?;
+                Ok(())
+            }
+            Socket::Icmp(icmpsocket) => {
+                icmpsocket.lock().peer_addr()?;
+                Ok(())
+            }
             Socket::Unix(unixsocket) => unixsocket.shutdown(how),
         }
     }
 }
+
+/// Converts `address` to the IP address of a network socket, which must be an
+/// IPv4 address, or an IPv6 address for an `AF_INET6` socket.
+fn inet_addr(address: SocketAddress, ipv6: bool) -> LinuxResult<SocketAddr> {
+    match address {
+        SocketAddress::Inet(addr) => Ok(SocketAddr::V4(addr)),
+        SocketAddress::Inet6(addr) if ipv6 => Ok(SocketAddr::V6(addr)),
+        SocketAddress::Inet6(_) | SocketAddress::Unix(_) => Err(LinuxError::EAFNOSUPPORT),
+    }
+}
@@ -11,9 +11,10 @@
 use alloc::sync::{Arc, Weak};
 use axerrno::{ax_err, LinuxError, LinuxResult};
 use axfs_vfs::{
-    impl_vfs_non_dir_default, AbsPath, RelPath, VfsNodeAttr, VfsNodeOps, VfsNodePerm, VfsNodeType,
-    VfsResult,
+    current_time, impl_vfs_non_dir_default, AbsPath, RelPath, VfsNodeAttr, VfsNodeOps, VfsNodePerm,
+    VfsNodeType, VfsResult,
 };
+use core::time::Duration;
 use ruxfs::fops::lookup;
 use spin::rwlock::RwLock;
 
@@ -32,15 +33,18 @@ pub struct SocketNode {
 impl SocketNode {
     /// Creates a new socket filesystem node bound to a specific socket.
     fn new(socket: Arc<Socket>) -> Self {
+        // FIXME: use a proper inode number
+        let mut attr = VfsNodeAttr::new(
+            u64::MAX, // Placeholder inode number, should be replaced with a proper allocator
+            VfsNodePerm::default_socket(),
+            VfsNodeType::Socket,
+            0,
+            0,
+        );
+        let now = current_time();
+        attr.set_times(now, now, now);
         Self {
-            // FIXME: use a proper inode number
-            attr: RwLock::new(VfsNodeAttr::new(
-                u64::MAX, // Placeholder inode number, should be replaced with a proper allocator
-                VfsNodePerm::default_socket(),
-                VfsNodeType::Socket,
-                0,
-                0,
-            )),
+            attr: RwLock::new(attr),
             bound_socket: Arc::downgrade(&socket),
         }
     }
@@ -58,7 +62,27 @@ impl VfsNodeOps for SocketNode {
     }
 
     fn set_mode(&self, mode: VfsNodePerm) -> VfsResult {
-        self.attr.write().set_perm(mode);
+        let mut attr = self.attr.write();
+        attr.set_perm(mode);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> VfsResult {
+        let mut attr = self.attr.write();
+        let (uid, gid) = (uid.unwrap_or(attr.uid()), gid.unwrap_or(attr.gid()));
+        attr.set_owner(uid, gid);
+        let (atime, mtime) = (attr.atime(), attr.mtime());
+        attr.set_times(atime, mtime, current_time());
+        Ok(())
+    }
+
+    fn set_times(&self, atime: Option<Duration>, mtime: Option<Duration>) -> VfsResult {
+        let mut attr = self.attr.write();
+        let atime = atime.unwrap_or(attr.atime());
+        let mtime = mtime.unwrap_or(attr.mtime());
+        attr.set_times(atime, mtime, current_time());
         Ok(())
     }
 
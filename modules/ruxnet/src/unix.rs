@@ -12,7 +12,7 @@ use alloc::sync::Arc;
 use alloc::sync::Weak;
 use alloc::vec::Vec;
 use axerrno::{LinuxError, LinuxResult};
-use axio::PollState;
+use axio::{PollState, PollWaiters};
 use spin::mutex::Mutex;
 
 use core::sync::atomic::{AtomicBool, Ordering};
@@ -37,6 +37,8 @@ pub struct UnixSocket {
     nonblock: AtomicBool,
     /// The internal state protected by a mutex
     inner: Mutex<UnixSocketInner>,
+    /// Waiters notified when the state changes
+    waiters: PollWaiters,
 }
 
 /// Internal state of a UNIX domain socket
@@ -116,8 +118,13 @@ impl AcceptQueue {
 }
 
 impl UnixSocket {
-    /// Creates a new UNIX domain socket
+    /// Creates a new UNIX domain socket, where `SOCK_RAW` is `SOCK_DGRAM` as
+    /// on Linux.
     pub fn create_socket(socktype: SocketType, nonblock: bool) -> Arc<Socket> {
+        let socktype = match socktype {
+            SocketType::Raw => SocketType::Datagram,
+            socktype => socktype,
+        };
         Arc::new(Socket::Unix(UnixSocket {
             socktype,
             nonblock: AtomicBool::new(nonblock),
@@ -130,6 +137,7 @@ impl UnixSocket {
                 shutdown_write: false,
                 peer_hangup: false,
             }),
+            waiters: PollWaiters::new(),
         }))
     }
 
@@ -245,7 +253,7 @@ impl UnixSocket {
         let mut self_inner = self.inner.lock();
         self_inner.peer_address = Some(address);
         match self.socktype {
-            SocketType::Datagram => {
+            SocketType::Datagram | SocketType::Raw => {
                 self_inner.state = UnixSocketState::Connected(Arc::downgrade(&peer_socket))
             }
             SocketType::Stream => {
@@ -279,6 +287,7 @@ impl UnixSocket {
                                 shutdown_write: false,
                                 peer_hangup: false,
                             }),
+                            waiters: PollWaiters::new(),
                         };
                         let new_socket = Arc::new(Socket::Unix(new_unix_socket));
                         self_inner.state = UnixSocketState::Connected(Arc::downgrade(&new_socket));
@@ -286,6 +295,10 @@ impl UnixSocket {
                     }
                     _ => return Err(LinuxError::ECONNREFUSED),
                 }
+                drop(listener);
+                drop(self_inner);
+                // the listener becomes readable
+                peer.waiters.notify();
             }
         }
         Ok(())
@@ -347,8 +360,9 @@ impl UnixSocket {
                         return Err(LinuxError::EISCONN);
                     }
                 };
-                let mut peer_inner = peer.as_unix_socket().inner.lock();
-                if self.socktype == SocketType::Stream {
+                let peer = peer.as_unix_socket();
+                let mut peer_inner = peer.inner.lock();
+                let res = if self.socktype == SocketType::Stream {
                     peer_inner
                         .messages
                         .write_stream(src_data, local_address, ancillary_data)
@@ -356,7 +370,12 @@ impl UnixSocket {
                     peer_inner
                         .messages
                         .write_dgram(src_data, local_address, ancillary_data)
+                };
+                drop(peer_inner);
+                if res.is_ok() {
+                    peer.waiters.notify();
                 }
+                res
             },
             flags,
         )
@@ -381,7 +400,7 @@ impl UnixSocket {
                             inner.messages.read_stream(dst_data)
                         }
                     }
-                    SocketType::Datagram => {
+                    SocketType::Datagram | SocketType::Raw => {
                         if flags.contains(MessageFlags::MSG_PEEK) {
                             inner.messages.peek_dgram(dst_data)
                         } else {
@@ -393,6 +412,12 @@ impl UnixSocket {
                 if info.bytes_read == 0 && !inner.shutdown_read && info.address.is_none() {
                     return Err(LinuxError::EAGAIN);
                 }
+                let peer = inner.peer();
+                drop(inner);
+                if let Some(peer) = peer {
+                    // the peer may be able to write again
+                    peer.as_unix_socket().waiters.notify();
+                }
                 Ok(info)
             },
             flags,
@@ -406,7 +431,7 @@ impl UnixSocket {
             SocketType::Stream => match inner.state {
                 UnixSocketState::Disconnected => Ok(PollState::default()),
                 UnixSocketState::Listening(ref accept_queue) => {
-                    let readable = accept_queue.sockets.is_empty();
+                    let readable = !accept_queue.sockets.is_empty();
                     Ok(PollState {
                         readable,
                         writable: false,
@@ -431,7 +456,7 @@ impl UnixSocket {
                     })
                 }
             },
-            SocketType::Datagram => {
+            SocketType::Datagram | SocketType::Raw => {
                 let readable = !inner.messages.is_empty();
                 let writable = inner.messages.available_capacity() > 0 && !inner.shutdown_write;
                 Ok(PollState {
@@ -455,9 +480,18 @@ impl UnixSocket {
         if how.contains(ShutdownFlags::READ) {
             inner.shutdown_read = true;
         }
+        drop(peer_inner);
+        drop(inner);
+        self.waiters.notify();
+        peer.as_unix_socket().waiters.notify();
         Ok(())
     }
 
+    /// Waiters notified when the socket becomes readable, writable or hung up
+    pub fn poll_waiters(&self) -> &PollWaiters {
+        &self.waiters
+    }
+
     /// Helper for blocking/non-blocking operations
     fn block_on<F, T>(&self, mut f: F, flags: MessageFlags) -> LinuxResult<T>
     where
@@ -481,9 +515,12 @@ impl Drop for UnixSocket {
     fn drop(&mut self) {
         if let UnixSocketState::Connected(ref peer) = self.inner.lock().state {
             if let Some(peer_socket) = peer.upgrade() {
-                let mut peer_inner = peer_socket.as_unix_socket().inner.lock();
+                let peer = peer_socket.as_unix_socket();
+                let mut peer_inner = peer.inner.lock();
                 peer_inner.state = UnixSocketState::Closed;
                 peer_inner.peer_hangup = true;
+                drop(peer_inner);
+                peer.waiters.notify();
             }
         }
     }
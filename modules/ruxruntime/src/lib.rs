@@ -151,6 +151,7 @@ fn is_init_ok() -> bool {
 /// and the secondary CPUs call [`rust_main_secondary`].
 #[cfg_attr(not(test), no_mangle)]
 pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
+    let log_level = ruxhal::cmdline::param_or("log", option_env!("RUX_LOG").unwrap_or(""));
     ax_println!("{}", LOGO);
     ax_println!(
         "\
@@ -166,11 +167,11 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
         option_env!("RUX_TARGET").unwrap_or(""),
         option_env!("RUX_SMP").unwrap_or(""),
         option_env!("RUX_MODE").unwrap_or(""),
-        option_env!("RUX_LOG").unwrap_or(""),
+        log_level,
     );
 
     axlog::init();
-    axlog::set_max_level(option_env!("RUX_LOG").unwrap_or("")); // no effect if set `log-level-*` features
+    axlog::set_max_level(log_level); // no effect if set `log-level-*` features
     info!("Logging is enabled.");
 
     #[cfg(feature = "alloc")]
@@ -226,38 +227,52 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
         {
             extern crate alloc;
             use alloc::vec::Vec;
+            // stamp the nodes of in-memory filesystems with the wall clock
+            axfs_vfs::set_clock(ruxhal::time::current_time);
             // By default, mount_points[0] will be rootfs
             let mut mount_points: Vec<ruxfs::root::MountPoint> = Vec::new();
-            #[cfg(any(feature = "blkfs", feature = "fusefs"))]
-            let mut block_dev = Some(all_devices.block);
+            #[cfg(all(feature = "fusefs", not(feature = "blkfs")))]
+            let unused_block_devs = {
+                let mut block_devs = all_devices.block;
+                core::iter::from_fn(|| block_devs.take_one()).collect()
+            };
 
             //setup ramfs as rootfs if no other filesystem can be mounted
             #[cfg(not(any(feature = "blkfs", feature = "virtio-9p", feature = "net-9p")))]
             mount_points.push(ruxfs::init_tempfs());
 
-            // setup and initialize blkfs as one mountpoint for rootfs
+            // setup and initialize blkfs as mountpoints, the first one for rootfs
             #[cfg(feature = "blkfs")]
-            mount_points.push(ruxfs::init_blkfs(block_dev.take().unwrap()));
+            #[allow(unused_variables)]
+            let unused_block_devs = {
+                let (blk_mount_points, unused) = ruxfs::init_blkfs(all_devices.block);
+                mount_points.extend(blk_mount_points);
+                unused
+            };
 
             // setup and initialize 9pfs as mountpoint
             #[cfg(feature = "virtio-9p")]
             mount_points.push(rux9p::init_virtio_9pfs(
                 all_devices._9p,
-                option_env!("RUX_ANAME_9P").unwrap_or(""),
-                option_env!("RUX_PROTOCOL_9P").unwrap_or(""),
+                ruxhal::cmdline::param_or("9p.aname", option_env!("RUX_ANAME_9P").unwrap_or("")),
+                ruxhal::cmdline::param_or(
+                    "9p.protocol",
+                    option_env!("RUX_PROTOCOL_9P").unwrap_or(""),
+                ),
             ));
             #[cfg(feature = "net-9p")]
             mount_points.push(rux9p::init_net_9pfs(
-                option_env!("RUX_9P_ADDR").unwrap_or(""),
-                option_env!("RUX_ANAME_9P").unwrap_or(""),
-                option_env!("RUX_PROTOCOL_9P").unwrap_or(""),
+                ruxhal::cmdline::param_or("9p.addr", option_env!("RUX_9P_ADDR").unwrap_or("")),
+                ruxhal::cmdline::param_or("9p.aname", option_env!("RUX_ANAME_9P").unwrap_or("")),
+                ruxhal::cmdline::param_or(
+                    "9p.protocol",
+                    option_env!("RUX_PROTOCOL_9P").unwrap_or(""),
+                ),
             ));
 
-            // setup and initialize fusefs as mountpoint
+            // setup and initialize fusefs on the block devices not used by blkfs
             #[cfg(feature = "fusefs")]
-            if let Some(block_dev) = block_dev.take() {
-                mount_points.push(ruxvda::init_vdafs(block_dev));
-            }
+            mount_points.extend(ruxvda::init_vdafs(unused_block_devs));
 
             ruxfs::prepare_commonfs(&mut mount_points);
 
@@ -332,50 +347,19 @@ pub extern "C" fn rust_main(cpu_id: usize, dtb: usize) -> ! {
     }
 }
 
-#[cfg(feature = "alloc")]
-cfg_if::cfg_if! {
-    if #[cfg(any(target_arch = "x86", target_arch = "x86_64"))] {
-        fn get_boot_str() -> &'static str {
-            let cmdline_buf: &[u8] = unsafe { &ruxhal::COMLINE_BUF };
-            let mut len = 0;
-            for c in cmdline_buf.iter() {
-                if *c == 0 {
-                    break;
-                }
-                len += 1;
-            }
-            core::str::from_utf8(&cmdline_buf[..len]).unwrap()
-        }
-    } else {
-        fn get_boot_str() -> &'static str {
-            dtb::get_node("chosen").unwrap().prop("bootargs").str()
-        }
-    }
-}
-
 // initialize environ variables and Command line parameters
 #[cfg(feature = "alloc")]
 fn init_cmdline(argc: &mut c_int) {
     use alloc::vec::Vec;
-    let mut boot_str = get_boot_str();
-    (_, boot_str) = match boot_str.split_once(';') {
-        Some((a, b)) => (a, b),
-        None => ("", ""),
-    };
-    let (args, envs) = match boot_str.split_once(';') {
-        Some((a, e)) => (a, e),
-        None => ("", ""),
-    };
     // set env
-    let envs: Vec<&str> = envs.split(',').collect();
-    for i in envs {
-        boot_add_environ(i);
+    for env in ruxhal::cmdline::envs() {
+        boot_add_environ(env);
     }
     // set args
     unsafe {
         RUX_ENVIRON.push(core::ptr::null_mut());
         environ = RUX_ENVIRON.as_mut_ptr();
-        let args: Vec<&str> = args.split(',').filter(|i| !i.is_empty()).collect();
+        let args: Vec<&str> = ruxhal::cmdline::args().collect();
         *argc = args.len() as c_int;
         init_argv(args);
     }
@@ -435,16 +419,19 @@ fn init_interrupt() {
         ruxhal::time::set_oneshot_timer(deadline);
     }
 
+    /// Sends the signals of expired interval timers, which are delivered
+    /// before returning from the timer IRQ.
     #[cfg(feature = "signal")]
     fn do_signal() {
+        use ruxtask::signal::{SIGALRM, SIGPROF, SIGVTALRM, SI_TIMER};
         let now_ns = ruxhal::time::current_time_nanos();
-        // timer signal num
-        let timers = [14, 26, 27]; // what is the number?
+        // signals sent by ITIMER_REAL, ITIMER_VIRTUAL and ITIMER_PROF
+        let timers = [SIGALRM, SIGVTALRM, SIGPROF];
         for (which, timer) in timers.iter().enumerate() {
             let mut ddl = Signal::timer_deadline(which, None).unwrap();
             let interval = Signal::timer_interval(which, None).unwrap();
             if ddl != 0 && now_ns >= ddl {
-                Signal::signal(*timer, true);
+                Signal::signal(*timer, SI_TIMER);
                 if interval == 0 {
                     ddl = 0;
                 } else {
@@ -453,16 +440,6 @@ fn init_interrupt() {
                 Signal::timer_deadline(which, Some(ddl));
             }
         }
-        let signal = Signal::signal(-1, true).unwrap();
-        for signum in 0..32 {
-            if signal & (1 << signum) != 0
-            /* TODO: && support mask */
-            {
-                Signal::signal(signum as i8, false);
-                Signal::sigaction(signum as u8, None, None);
-                Signal::signal_handle(signum as u8);
-            }
-        }
     }
 
     ruxhal::irq::register_handler(TIMER_IRQ_NUM, || {
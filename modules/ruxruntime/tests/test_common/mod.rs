@@ -313,6 +313,125 @@ fn test_devfs_ramfs() -> Result<()> {
     Ok(())
 }
 
+fn test_mount() -> Result<()> {
+    let target = fs::absolute_path("/mnt-test")?;
+    let file = fs::absolute_path("/mnt-test/test.txt")?;
+
+    // mount a tmpfs at runtime
+    ruxfs::fops::mount("none", &target, "tmpfs", "", false)?;
+    assert_err!(
+        ruxfs::fops::mount("none", &target, "tmpfs", "", false),
+        ResourceBusy
+    );
+    assert_err!(
+        ruxfs::fops::mount("none", &target, "unknown", "", false),
+        NotFound
+    );
+    assert_eq!(fs::write(&file, "test"), Ok(()));
+    if let Ok(table) = fs::read_to_string(&fs::absolute_path("/proc/mounts")?) {
+        assert!(table.contains("none /mnt-test tmpfs rw 0 0\n"));
+    }
+
+    // read-only after remounted
+    ruxfs::fops::remount(&target, true)?;
+    assert_err!(fs::write(&file, "test"), ReadOnlyFilesystem);
+    assert_err!(
+        fs::create_dir(&fs::absolute_path("/mnt-test/dir")?),
+        ReadOnlyFilesystem
+    );
+    assert_err!(fs::remove_file(&file), ReadOnlyFilesystem);
+    assert_eq!(fs::read(&file), Ok("test".into()));
+    ruxfs::fops::remount(&target, false)?;
+
+    // bind mount
+    let bind = fs::absolute_path("/tmp/bind")?;
+    ruxfs::fops::bind_mount(&target, &bind, false)?;
+    assert_eq!(
+        fs::read(&fs::absolute_path("/tmp/bind/test.txt")?),
+        Ok("test".into())
+    );
+    assert_eq!(ruxfs::fops::umount(&bind), Ok(()));
+
+    assert_eq!(ruxfs::fops::umount(&target), Ok(()));
+    assert_err!(fs::read(&file), NotFound);
+    assert_err!(ruxfs::fops::umount(&target), InvalidInput);
+
+    println!("test_mount() OK!");
+    Ok(())
+}
+
+fn test_procfs() -> Result<()> {
+    let pid = ruxtask::current().process_id().as_u64();
+    let stat = fs::read_to_string(&fs::absolute_path("/proc/self/stat")?)?;
+    assert!(stat.starts_with(&format!("{pid} (")));
+    let status = fs::read_to_string(&fs::absolute_path(&format!("/proc/{pid}/status"))?)?;
+    assert!(status.contains(&format!("Pid:\t{pid}\n")));
+    assert_err!(
+        fs::read(&fs::absolute_path(&format!("/proc/{}/stat", u32::MAX))?),
+        NotFound
+    );
+
+    // generated on each read
+    let uptime = fs::read_to_string(&fs::absolute_path("/proc/uptime")?)?;
+    assert_eq!(uptime.split_whitespace().count(), 2);
+    let loadavg = fs::read_to_string(&fs::absolute_path("/proc/loadavg")?)?;
+    assert_eq!(loadavg.split_whitespace().count(), 5);
+
+    // static entries are kept
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path("/proc/sys/vm/overcommit_memory")?)?,
+        "0\n"
+    );
+    assert_err!(fs::remove_file(&fs::absolute_path("/proc/uptime")?));
+
+    println!("test_procfs() OK!");
+    Ok(())
+}
+
+fn test_sysfs() -> Result<()> {
+    let clocksource = "/sys/devices/system/clocksource/clocksource0/current_clocksource";
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(clocksource)?)?,
+        format!("{}\n", ruxhal::time::CLOCK_SOURCE)
+    );
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(
+            "/sys/devices/system/cpu/cpu0/topology/core_id"
+        )?)?,
+        "0\n"
+    );
+
+    // generated from the registered network interfaces
+    let stats = ruxfs::register_net_interface("test0", [0x52, 0x54, 0, 0x12, 0x34, 0x56], 1500);
+    stats.add_rx(64);
+    let iface = "/sys/devices/virtual/net/test0";
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(&format!("{iface}/address"))?)?,
+        "52:54:00:12:34:56\n"
+    );
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(&format!("{iface}/statistics/rx_bytes"))?)?,
+        "64\n"
+    );
+    stats.add_tx(100);
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(&format!("{iface}/statistics/tx_bytes"))?)?,
+        "100\n"
+    );
+
+    // static entries are kept
+    assert_eq!(
+        fs::read_to_string(&fs::absolute_path(
+            "/sys/kernel/mm/transparent_hugepage/enabled"
+        )?)?,
+        "always [madvise] never\n"
+    );
+    assert_err!(fs::remove_dir(&fs::absolute_path("/sys/block")?));
+
+    println!("test_sysfs() OK!");
+    Ok(())
+}
+
 pub fn test_all() {
     test_read_write_file().expect("test_read_write_file() failed");
     test_read_dir().expect("test_read_dir() failed");
@@ -320,4 +439,7 @@ pub fn test_all() {
     test_create_file_dir().expect("test_create_file_dir() failed");
     test_remove_file_dir().expect("test_remove_file_dir() failed");
     test_devfs_ramfs().expect("test_devfs_ramfs() failed");
+    test_mount().expect("test_mount() failed");
+    test_procfs().expect("test_procfs() failed");
+    test_sysfs().expect("test_sysfs() failed");
 }
@@ -13,6 +13,7 @@ mod test_common;
 
 use driver_block::ramdisk::RamDisk;
 use ruxdriver::AxDeviceContainer;
+use ruxfs::{api as fs, AbsPath, OpenFlags};
 
 const IMG_PATH: &str = "resources/fat16.img";
 
@@ -32,14 +33,42 @@ fn test_fatfs() {
     ruxtask::init_scheduler(); // call this to use `axsync::Mutex`.
                                // By default, mount_points[0] will be rootfs
     let mut mount_points: Vec<ruxfs::root::MountPoint> = Vec::new();
-    // setup and initialize blkfs as one mountpoint for rootfs
-    mount_points.push(ruxfs::init_blkfs(AxDeviceContainer::from_one(Box::new(
-        disk,
-    ))));
+    // setup and initialize blkfs as mountpoints, the first one for rootfs
+    let (blk_mount_points, _) = ruxfs::init_blkfs(AxDeviceContainer::from_one(Box::new(disk)));
+    mount_points.extend(blk_mount_points);
     ruxfs::prepare_commonfs(&mut mount_points);
 
     // setup and initialize rootfs
     ruxtask::fs::init_rootfs(mount_points);
 
     test_common::test_all();
+    test_fatfs_attr();
+}
+
+fn test_fatfs_attr() {
+    let attr = |path| fs::get_attr(&AbsPath::new(path)).unwrap();
+
+    // inode numbers are stable and distinct
+    let long = attr("/long.txt");
+    assert_eq!(long.ino(), attr("/long.txt").ino());
+    assert_eq!(long.ino(), attr("/very/../LONG.TXT").ino());
+    assert_ne!(long.ino(), attr("/short.txt").ino());
+    assert_ne!(attr("/very").ino(), attr("/very/long").ino());
+    assert_ne!(attr("/").ino(), attr("/very").ino());
+
+    // 512-byte clusters
+    assert_eq!(long.size(), 14000);
+    assert_eq!(long.blocks(), 28);
+    assert!(attr("/very").is_dir());
+
+    // writing past the end fills the gap with zero
+    let path = AbsPath::new("/gap.txt");
+    let file = fs::open_file(&path, OpenFlags::O_RDWR | OpenFlags::O_CREAT).unwrap();
+    file.write_at(1000, b"end").unwrap();
+    drop(file);
+    let data = fs::read(&path).unwrap();
+    assert_eq!(data.len(), 1003);
+    assert!(data[..1000].iter().all(|&b| b == 0));
+    assert_eq!(attr("/gap.txt").blocks(), 2);
+    fs::remove_file(&path).unwrap();
 }
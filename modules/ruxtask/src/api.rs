@@ -100,6 +100,25 @@ pub fn on_timer_tick() {
     RUN_QUEUE.lock().scheduler_timer_tick();
 }
 
+/// Sets a timer which calls `f` in the timer interrupt at `deadline`.
+///
+/// Returns the ID of the timer, which can be passed to [`cancel_timer`].
+#[cfg(feature = "irq")]
+#[doc(cfg(feature = "irq"))]
+pub fn set_timer<F>(deadline: ruxhal::time::TimeValue, f: F) -> usize
+where
+    F: FnOnce(ruxhal::time::TimeValue) + Send + 'static,
+{
+    crate::timers::set_timer(deadline, alloc::boxed::Box::new(f))
+}
+
+/// Cancels the timer `id` set by [`set_timer`], if it has not expired yet.
+#[cfg(feature = "irq")]
+#[doc(cfg(feature = "irq"))]
+pub fn cancel_timer(id: usize) {
+    crate::timers::cancel_timer(id);
+}
+
 /// Spawns a new task with the given parameters.
 ///
 /// Returns the task reference.
@@ -128,12 +147,9 @@ where
     TaskInner::new_musl(f, name, stack_size, tls, set_tid, tl)
 }
 
-// temporarily only support aarch64
-#[cfg(all(
-    any(target_arch = "aarch64", target_arch = "riscv64"),
-    feature = "paging",
-    feature = "fs"
-))]
+/// Fork the current process, returns the child task in the parent process,
+/// and `None` in the child process.
+#[cfg(all(feature = "paging", feature = "fs"))]
 pub fn fork_task() -> Option<AxTaskRef> {
     use core::mem::ManuallyDrop;
 
@@ -208,6 +224,14 @@ pub fn set_priority(prio: isize) -> bool {
     RUN_QUEUE.lock().set_current_priority(prio)
 }
 
+/// Set the priority used by the scheduler for `task`, without changing the
+/// priority set by [`set_priority`]. It is used by priority inheritance.
+///
+/// Returns `true` if the priority is set successfully.
+pub fn set_task_priority(task: &AxTaskRef, prio: isize) -> bool {
+    RUN_QUEUE.lock().set_task_priority(task, prio)
+}
+
 /// Current task gives up the CPU time voluntarily, and switches to another
 /// ready task.
 pub fn yield_now() {
@@ -223,6 +247,13 @@ impl ruxfs::fifo::SchedYieldIf for SchedYieldIfImpl {
     fn yield_now() {
         yield_now();
     }
+
+    fn interrupted() -> bool {
+        #[cfg(feature = "signal")]
+        return crate::Signal::interrupted();
+        #[cfg(not(feature = "signal"))]
+        false
+    }
 }
 
 /// Current task is going to sleep for the given duration.
@@ -17,7 +17,7 @@ use axerrno::{ax_err, AxResult};
 use axfs_vfs::VfsNodeRef;
 use ruxfdtable::{FdTable, FileLike, OpenFlags};
 use ruxfs::{
-    fops::{lookup, CurrentWorkingDirectoryOps},
+    fops::{lookup, resolve_path, CurrentWorkingDirectoryOps},
     root::{MountPoint, RootDirectory},
     AbsPath, RelPath,
 };
@@ -123,12 +123,8 @@ impl FileSystem {
 
 /// Initializes the file system.
 pub fn init_rootfs(mount_points: Vec<MountPoint>) {
-    let main_fs = mount_points
-        .first()
-        .expect("No filesystem found")
-        .fs
-        .clone();
-    let root_dir = RootDirectory::new(main_fs);
+    let root = mount_points.first().expect("No filesystem found").clone();
+    let root_dir = RootDirectory::new(root);
 
     for mp in mount_points.iter().skip(1) {
         let message = format!("failed to mount filesystem at {}", mp.path);
@@ -174,6 +170,7 @@ pub fn current_dir() -> AxResult<AbsPath<'static>> {
 
 /// Sets the current directory.
 pub fn set_current_dir(path: AbsPath<'static>) -> AxResult {
+    let path = resolve_path(&path, true)?;
     let node = lookup(&path)?;
     let attr = node.get_attr()?;
     if !attr.is_dir() {
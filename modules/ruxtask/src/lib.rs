@@ -58,6 +58,7 @@ cfg_if::cfg_if! {
         // TODO: make fs module optional
         // #[cfg(feature = "fs")]
         pub mod fs;
+        mod procfs;
         #[cfg(feature = "irq")]
         /// load average
         pub mod loadavg;
@@ -86,13 +87,13 @@ cfg_if::cfg_if! {
         pub use self::api::{sleep, sleep_until, yield_now};
         pub use task::TaskState;
         #[cfg(feature = "signal")]
-        pub use self::signal::{rx_sigaction, Signal};
+        pub use self::signal::{rx_sigaction, SigInfo, Signal};
     } else {
         mod api_s;
         #[cfg(feature = "signal")]
         pub mod signal;
         pub use self::api_s::{sleep, sleep_until, yield_now};
         #[cfg(feature = "signal")]
-        pub use self::signal::{rx_sigaction, Signal};
+        pub use self::signal::{rx_sigaction, SigInfo, Signal};
     }
 }
@@ -8,6 +8,7 @@
  */
 
 use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
 
 use crate::AVENRUN;
 
@@ -29,6 +30,14 @@ static mut IDLE_CNT: AtomicU64 = AtomicU64::new(0);
 static mut ALL_CNT: AtomicU64 = AtomicU64::new(0);
 /// last update time
 static mut LAST_UPDATE: AtomicU64 = AtomicU64::new(0);
+/// count of idle ticks since boot
+static IDLE_TICKS: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the time spent in the idle task since boot, as in `/proc/uptime`.
+pub fn idle_time() -> Duration {
+    let ticks = IDLE_TICKS.load(Ordering::Relaxed);
+    Duration::from_nanos(ticks * ruxhal::time::NANOS_PER_SEC / ruxconfig::TICKS_PER_SEC as u64)
+}
 
 /*
  * a1 = a0 * e + a * (1 - e)
@@ -51,6 +60,7 @@ pub(crate) fn calc_load_tick(is_idle: bool) {
         unsafe {
             IDLE_CNT.fetch_add(1, Ordering::Relaxed);
         }
+        IDLE_TICKS.fetch_add(1, Ordering::Relaxed);
     }
     unsafe {
         ALL_CNT.fetch_add(1, Ordering::Relaxed);
@@ -0,0 +1,149 @@
+/* Copyright (c) [2023] [Syswonder Community]
+ *   [Ruxos] is licensed under Mulan PSL v2.
+ *   You can use this software according to the terms and conditions of the Mulan PSL v2.
+ *   You may obtain a copy of Mulan PSL v2 at:
+ *               http://license.coscl.org.cn/MulanPSL2
+ *   THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND, EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT, MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ *   See the Mulan PSL v2 for more details.
+ */
+
+//! State of the processes shown in the procfs.
+
+#![cfg(feature = "fs")]
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::time::Duration;
+use ruxfs::{MapArea, ProcessInfo, ProcfsIf};
+
+use crate::task::PROCESS_MAP;
+use crate::{current, AxTaskRef, TaskState};
+
+/// `PROT_READ` flag of mmap.
+#[cfg(feature = "paging")]
+const PROT_READ: u32 = 0x1;
+/// `PROT_WRITE` flag of mmap.
+#[cfg(feature = "paging")]
+const PROT_WRITE: u32 = 0x2;
+/// `PROT_EXEC` flag of mmap.
+#[cfg(feature = "paging")]
+const PROT_EXEC: u32 = 0x4;
+
+/// Gets the main task of the process.
+fn process_task(pid: u64) -> Option<AxTaskRef> {
+    PROCESS_MAP.lock().get(&pid).cloned()
+}
+
+struct ProcfsImpl;
+
+#[crate_interface::impl_interface]
+impl ProcfsIf for ProcfsImpl {
+    fn pids() -> Vec<u64> {
+        PROCESS_MAP.lock().keys().copied().collect()
+    }
+
+    fn current_pid() -> u64 {
+        current().process_id().as_u64()
+    }
+
+    fn process(pid: u64) -> Option<ProcessInfo> {
+        let task = process_task(pid)?;
+        let state = match task.state() {
+            TaskState::Running | TaskState::Ready => 'R',
+            TaskState::Blocked => 'S',
+            TaskState::Exited => 'Z',
+        };
+        #[cfg(feature = "paging")]
+        let (vm_size, vm_rss) = (
+            task.mm
+                .vma_map
+                .lock()
+                .values()
+                .map(|vma| vma.end_addr - vma.start_addr)
+                .sum(),
+            task.mm.mem_map.lock().len() * memory_addr::PAGE_SIZE_4K,
+        );
+        #[cfg(not(feature = "paging"))]
+        let (vm_size, vm_rss) = (0, 0);
+        Some(ProcessInfo {
+            ppid: task
+                .parent_process()
+                .map_or(0, |parent| parent.process_id().as_u64()),
+            name: task.name().to_string(),
+            state,
+            nice: task.normal_priority(),
+            cpu_time: task.cpu_time(),
+            vm_size,
+            vm_rss,
+        })
+    }
+
+    fn maps(pid: u64) -> Vec<MapArea> {
+        #[cfg(feature = "paging")]
+        if let Some(task) = process_task(pid) {
+            return task
+                .mm
+                .vma_map
+                .lock()
+                .values()
+                .map(|vma| MapArea {
+                    start: vma.start_addr,
+                    end: vma.end_addr,
+                    readable: vma.prot & PROT_READ != 0,
+                    writable: vma.prot & PROT_WRITE != 0,
+                    executable: vma.prot & PROT_EXEC != 0,
+                    shared: vma.is_shared(),
+                    offset: vma.offset,
+                    path: vma
+                        .file
+                        .as_ref()
+                        .map_or(String::new(), |file| file.path().to_string()),
+                })
+                .collect();
+        }
+        #[cfg(not(feature = "paging"))]
+        let _ = pid;
+        Vec::new()
+    }
+
+    fn files(pid: u64) -> Vec<(usize, String)> {
+        let Some(task) = process_task(pid) else {
+            return Vec::new();
+        };
+        let fs = task.fs.lock();
+        fs.as_ref().map_or(Vec::new(), |fs| {
+            fs.fd_table
+                .iter()
+                .map(|(fd, file)| (fd, file.path().to_string()))
+                .collect()
+        })
+    }
+
+    /// All processes run the application in the image, with the arguments
+    /// from the kernel command line.
+    fn cmdline(_pid: u64) -> Vec<String> {
+        ruxhal::cmdline::args().map(String::from).collect()
+    }
+
+    fn environ(_pid: u64) -> Vec<String> {
+        ruxhal::cmdline::envs().map(String::from).collect()
+    }
+
+    fn uptime() -> (Duration, Duration) {
+        let uptime = Duration::from_nanos(ruxhal::time::current_time_nanos());
+        #[cfg(feature = "irq")]
+        let idle = crate::loadavg::idle_time();
+        #[cfg(not(feature = "irq"))]
+        let idle = Duration::ZERO;
+        (uptime, idle)
+    }
+
+    fn loadavg() -> [u64; 3] {
+        let mut loads = [0; 3];
+        crate::get_avenrun(&mut loads);
+        // in fixed point with 16 bits of fraction
+        loads.map(|load| (load * 100) >> 16)
+    }
+}
@@ -54,7 +54,11 @@ impl AxRunQueue {
         use crate::loadavg;
         let curr = crate::current();
         loadavg::calc_load_tick(curr.is_idle());
-        if !curr.is_idle() && self.scheduler.task_tick(curr.as_task_ref()) {
+        if curr.is_idle() {
+            return;
+        }
+        curr.add_cpu_tick();
+        if self.scheduler.task_tick(curr.as_task_ref()) {
             #[cfg(feature = "preempt")]
             curr.set_preempt_pending(true);
         }
@@ -68,8 +72,33 @@ impl AxRunQueue {
     }
 
     pub fn set_current_priority(&mut self, prio: isize) -> bool {
-        self.scheduler
-            .set_priority(crate::current().as_task_ref(), prio)
+        let curr = crate::current();
+        if self.scheduler.set_priority(curr.as_task_ref(), prio) {
+            curr.set_prio(prio, true);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn set_task_priority(&mut self, task: &AxTaskRef, prio: isize) -> bool {
+        // a ready task is indexed by its priority in the scheduler
+        let ok = if task.is_ready() {
+            match self.scheduler.remove_task(task) {
+                Some(task) => {
+                    let ok = self.scheduler.set_priority(&task, prio);
+                    self.scheduler.put_prev_task(task, false);
+                    ok
+                }
+                None => self.scheduler.set_priority(task, prio),
+            }
+        } else {
+            self.scheduler.set_priority(task, prio)
+        };
+        if ok {
+            task.set_prio(prio, false);
+        }
+        ok
     }
 
     #[cfg(feature = "preempt")]
@@ -181,7 +210,6 @@ impl AxRunQueue {
         self.switch_to(prev, next);
     }
 
-    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
     fn switch_to(&mut self, prev_task: CurrentTask, next_task: AxTaskRef) {
         trace!(
             "context switch: {} -> {}",
@@ -204,45 +232,17 @@ impl AxRunQueue {
             assert!(Arc::strong_count(prev_task.as_task_ref()) > 1);
             assert!(Arc::strong_count(&next_task) >= 1);
 
-            let next_page_table = next_task.pagetable.lock();
-            let root_paddr = next_page_table.root_paddr();
-
-            // Drop the `next_page_table` here, so that it will not be dropped after context switch.
-            drop(next_page_table);
+            // The page table guard is a temporary, so it is released here rather
+            // than after the context switch.
+            #[cfg(feature = "paging")]
+            let root_paddr = next_task.pagetable.lock().root_paddr();
+            #[cfg(not(feature = "paging"))]
+            let root_paddr = ruxhal::arch::read_page_table_root();
 
             CurrentTask::set_current(prev_task, next_task);
-
             (*prev_ctx_ptr).switch_to(&*next_ctx_ptr, root_paddr);
         }
     }
-
-    #[cfg(target_arch = "x86_64")]
-    fn switch_to(&mut self, prev_task: CurrentTask, next_task: AxTaskRef) {
-        trace!(
-            "context switch: {} -> {}",
-            prev_task.id_name(),
-            next_task.id_name()
-        );
-        #[cfg(feature = "preempt")]
-        next_task.set_preempt_pending(false);
-        next_task.set_state(TaskState::Running);
-        if prev_task.ptr_eq(&next_task) {
-            return;
-        }
-
-        unsafe {
-            let prev_ctx_ptr = prev_task.ctx_mut_ptr();
-            let next_ctx_ptr = next_task.ctx_mut_ptr();
-
-            // The strong reference count of `prev_task` will be decremented by 1,
-            // but won't be dropped until `gc_entry()` is called.
-            assert!(Arc::strong_count(prev_task.as_task_ref()) > 1);
-            assert!(Arc::strong_count(&next_task) >= 1);
-
-            CurrentTask::set_current(prev_task, next_task);
-            (*prev_ctx_ptr).switch_to(&*next_ctx_ptr);
-        }
-    }
 }
 
 #[cfg(feature = "fs")]
@@ -8,17 +8,106 @@
  */
 
 //! Signal module for RuxOS.
+//!
+//! Signal numbers follow Linux: `1..=31` are standard signals and `32..=64`
+//! are realtime signals. Signal sets use the Linux layout, where signal
+//! `signum` is represented by bit `signum - 1`.
+//!
+//! Signals sent to a process are kept in the process-wide pending set in
+//! [`Signal`], which is shared by all threads of the process. Signals sent to
+//! a thread are kept in the thread's own [`ThreadSignal`], together with its
+//! blocked mask. A pending signal is delivered to a thread only if it is not
+//! blocked by that thread.
+//!
+//! Signals are delivered before returning from a trap, or by a syscall they
+//! interrupt, with the trap frame of the syscall. To run a handler, a signal
+//! frame holding the `siginfo_t` and the `ucontext_t` of the interrupted
+//! context is built on the current stack, or on the alternate signal stack of
+//! the thread. When the handler returns,
+//! either to the trampoline or by `rt_sigreturn`, the interrupted context is
+//! restored from the frame.
 
-use crate::current;
-#[cfg(feature = "irq")]
-use core::sync::atomic::AtomicI64;
+use crate::{current, AxTaskRef};
+use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
+use axerrno::{LinuxError, LinuxResult};
 use core::{
     ffi::{c_int, c_uint, c_ulong},
+    mem::MaybeUninit,
     time::Duration,
 };
 
-use log::warn;
-use ruxhal::trap::TrapHandler;
+use axio::PollWaiters;
+use ruxhal::arch::{
+    call_signal_handler, resume_signal_handler, signal_return_trampoline, TrapFrame, UContext,
+};
+use ruxhal::trap::{SignalStack, TrapHandler};
+
+/// Number of signals supported, same as `_NSIG - 1` in Linux.
+pub const NSIG: usize = 64;
+
+/// Kill signal, can not be caught, blocked or ignored.
+pub const SIGKILL: usize = 9;
+/// Timer signal from `alarm` or `ITIMER_REAL`.
+pub const SIGALRM: usize = 14;
+/// Child stopped or terminated.
+pub const SIGCHLD: usize = 17;
+/// Continue if stopped.
+pub const SIGCONT: usize = 18;
+/// Stop process, can not be caught, blocked or ignored.
+pub const SIGSTOP: usize = 19;
+/// Virtual alarm clock from `ITIMER_VIRTUAL`.
+pub const SIGVTALRM: usize = 26;
+/// Profiling timer expired from `ITIMER_PROF`.
+pub const SIGPROF: usize = 27;
+/// The first realtime signal.
+pub const SIGRTMIN: usize = 32;
+
+/// Signal sent by `kill`.
+pub const SI_USER: c_int = 0;
+/// Signal sent by the kernel.
+pub const SI_KERNEL: c_int = 0x80;
+/// Signal sent by `sigqueue`.
+pub const SI_QUEUE: c_int = -1;
+/// Signal sent by timer expiration.
+pub const SI_TIMER: c_int = -2;
+/// Signal sent by `tkill` or `tgkill`.
+pub const SI_TKILL: c_int = -6;
+
+/// Pass `siginfo_t` and `ucontext_t` to the handler.
+pub const SA_SIGINFO: c_ulong = 4;
+/// Run the handler on the alternate signal stack.
+pub const SA_ONSTACK: c_ulong = 0x0800_0000;
+/// Restart syscalls interrupted by the handler.
+pub const SA_RESTART: c_ulong = 0x1000_0000;
+/// Do not block the signal while its handler is running.
+pub const SA_NODEFER: c_ulong = 0x4000_0000;
+/// Reset the action to `SIG_DFL` once the handler is called.
+pub const SA_RESETHAND: c_ulong = 0x8000_0000;
+/// The handler returns to `sa_restorer`.
+pub const SA_RESTORER: c_ulong = 0x0400_0000;
+
+/// The thread is running on the alternate signal stack.
+pub const SS_ONSTACK: i32 = 1;
+/// The alternate signal stack is disabled.
+pub const SS_DISABLE: i32 = 2;
+/// Minimum size of an alternate signal stack.
+pub const MINSIGSTKSZ: usize = 2048;
+
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+/// Signals that can not be caught, blocked or ignored.
+const UNBLOCKABLE: u64 = sigbit(SIGKILL) | sigbit(SIGSTOP);
+
+/// Get the bit of `signum` in a signal set.
+pub const fn sigbit(signum: usize) -> u64 {
+    1 << (signum - 1)
+}
+
+/// Whether `signum` is a valid signal number.
+pub const fn is_valid_signal(signum: usize) -> bool {
+    signum >= 1 && signum <= NSIG
+}
 
 /// sigaction in kernel
 #[allow(non_camel_case_types)]
@@ -38,25 +127,249 @@ pub struct rx_sigaction {
 impl rx_sigaction {
     const fn new() -> Self {
         rx_sigaction {
-            sa_handler: Some(default_handler),
+            sa_handler: None,
             sa_flags: 0,
             sa_restorer: None,
             sa_mask: [0, 0],
         }
     }
+
+    /// Signals blocked while the handler is running.
+    pub fn mask(&self) -> u64 {
+        self.sa_mask[0] as u64 | (self.sa_mask[1] as u64) << 32
+    }
+
+    fn handler_addr(&self) -> usize {
+        self.sa_handler.map_or(SIG_DFL, |handler| handler as usize)
+    }
+}
+
+/// Information carried by a pending signal, a subset of `siginfo_t`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SigInfo {
+    /// Signal number.
+    pub signo: c_int,
+    /// Signal code, `SI_USER`, `SI_TKILL`, etc.
+    pub code: c_int,
+    /// Process ID of the sender.
+    pub pid: c_int,
+    /// Value sent along with the signal, by `sigqueue` for example.
+    pub value: usize,
+}
+
+impl SigInfo {
+    /// Create a new `SigInfo` sent by the current process.
+    pub fn new(signo: usize, code: c_int) -> Self {
+        Self {
+            signo: signo as c_int,
+            code,
+            pid: current().process_id().as_u64() as c_int,
+            value: 0,
+        }
+    }
+}
+
+/// `siginfo_t` passed to signal handlers.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct siginfo_t {
+    si_signo: c_int,
+    si_errno: c_int,
+    si_code: c_int,
+    _pad: c_int,
+    si_pid: c_int,
+    si_uid: c_uint,
+    si_value: usize,
+    _rest: [u64; 12],
+}
+
+impl From<SigInfo> for siginfo_t {
+    fn from(info: SigInfo) -> Self {
+        Self {
+            si_signo: info.signo,
+            si_errno: 0,
+            si_code: info.code,
+            _pad: 0,
+            si_pid: info.pid,
+            si_uid: 0,
+            si_value: info.value,
+            _rest: [0; 12],
+        }
+    }
+}
+
+/// Frame built on the stack to run a signal handler.
+#[repr(C)]
+struct SignalFrame {
+    info: siginfo_t,
+    uc: UContext,
+    /// Stack pointer saved by `call_signal_handler`, used to return from the
+    /// handler by `rt_sigreturn`.
+    kernel_sp: usize,
+    /// Frame of the outer handler if handlers are nested.
+    prev: usize,
 }
 
-/// Signal struct
+/// A set of pending signals.
+///
+/// Standard signals are not queued: sending one which is already pending has
+/// no effect. Every instance of a realtime signal is queued with its own
+/// [`SigInfo`], and they are delivered in the order they were sent.
+#[derive(Debug, Default)]
+pub struct SigPending {
+    set: u64,
+    queue: VecDeque<SigInfo>,
+}
+
+impl SigPending {
+    /// Create an empty pending set.
+    pub const fn new() -> Self {
+        Self {
+            set: 0,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Signals in this pending set.
+    pub fn set(&self) -> u64 {
+        self.set
+    }
+
+    /// Add a signal to this pending set.
+    pub fn push(&mut self, info: SigInfo) {
+        let signum = info.signo as usize;
+        if signum < SIGRTMIN && self.set & sigbit(signum) != 0 {
+            return;
+        }
+        self.set |= sigbit(signum);
+        self.queue.push_back(info);
+    }
+
+    /// Remove the lowest signal which is not in `blocked`.
+    pub fn pop(&mut self, blocked: u64) -> Option<SigInfo> {
+        let deliverable = self.set & !blocked;
+        if deliverable == 0 {
+            return None;
+        }
+        let signum = deliverable.trailing_zeros() as usize + 1;
+        let index = self
+            .queue
+            .iter()
+            .position(|info| info.signo as usize == signum)?;
+        let info = self.queue.remove(index);
+        if !self.queue.iter().any(|info| info.signo as usize == signum) {
+            self.set &= !sigbit(signum);
+        }
+        info
+    }
+
+    /// Discard all instances of `signum`.
+    pub fn discard(&mut self, signum: usize) {
+        self.set &= !sigbit(signum);
+        self.queue.retain(|info| info.signo as usize != signum);
+    }
+}
+
+/// Signal state of a single thread.
+#[derive(Debug)]
+pub struct ThreadSignal {
+    /// Signals blocked by this thread.
+    pub blocked: u64,
+    /// Signals sent to this thread only.
+    pub pending: SigPending,
+    /// Alternate stack for handlers with `SA_ONSTACK`.
+    pub altstack: SignalStack,
+    /// Frame of the innermost running handler, 0 if there is none.
+    frame: usize,
+    /// Trap frame of the syscall being handled, null if there is none.
+    syscall_frame: *mut TrapFrame,
+    /// `None` if no handler has run since the last [`Signal::clear_interrupted`],
+    /// otherwise whether all of them have `SA_RESTART`.
+    restart: Option<bool>,
+}
+
+impl ThreadSignal {
+    /// Create the signal state of a new thread, which inherits the blocked
+    /// mask from its creator and has no pending signal.
+    pub const fn new(blocked: u64) -> Self {
+        Self {
+            blocked,
+            pending: SigPending::new(),
+            altstack: SignalStack {
+                sp: 0,
+                flags: SS_DISABLE,
+                size: 0,
+            },
+            frame: 0,
+            syscall_frame: core::ptr::null_mut(),
+            restart: None,
+        }
+    }
+
+    /// Create the signal state of the main thread of a forked process, which
+    /// also inherits the alternate stack and the running handlers.
+    pub fn fork(&self) -> Self {
+        Self {
+            altstack: self.altstack,
+            frame: self.frame,
+            ..Self::new(self.blocked)
+        }
+    }
+
+    /// Whether `sp` is on the alternate signal stack.
+    fn on_altstack(&self, sp: usize) -> bool {
+        self.altstack.flags & SS_DISABLE == 0
+            && sp > self.altstack.sp
+            && sp <= self.altstack.sp + self.altstack.size
+    }
+}
+
+impl Default for ThreadSignal {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Default action taken when a signal is delivered with `SIG_DFL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultAction {
+    /// Terminate the process.
+    Terminate,
+    /// Ignore the signal.
+    Ignore,
+    /// Terminate the process and dump core.
+    CoreDump,
+    /// Stop the process.
+    Stop,
+    /// Continue the process if it is stopped.
+    Continue,
+}
+
+/// Get the default action of `signum`, see `signal(7)`.
+pub fn default_action(signum: usize) -> DefaultAction {
+    match signum {
+        // SIGQUIT, SIGILL, SIGTRAP, SIGABRT, SIGBUS, SIGFPE, SIGSEGV, SIGXCPU, SIGXFSZ, SIGSYS
+        3 | 4 | 5 | 6 | 7 | 8 | 11 | 24 | 25 | 31 => DefaultAction::CoreDump,
+        // SIGCHLD, SIGURG, SIGWINCH
+        SIGCHLD | 23 | 28 => DefaultAction::Ignore,
+        SIGCONT => DefaultAction::Continue,
+        // SIGSTOP, SIGTSTP, SIGTTIN, SIGTTOU
+        SIGSTOP | 20 | 21 | 22 => DefaultAction::Stop,
+        _ => DefaultAction::Terminate,
+    }
+}
+
+/// Signal struct, shared by all threads of a process.
 pub struct Signal {
-    #[cfg(feature = "irq")]
-    signal: AtomicI64,
-    sigaction: [rx_sigaction; 32],
+    sigaction: [rx_sigaction; NSIG],
+    pending: SigPending,
     timer_value: [Duration; 3],
     timer_interval: [Duration; 3],
-}
-
-unsafe extern "C" fn default_handler(signum: c_int) {
-    warn!("default_handler, signum: {signum}");
+    /// Threads blocked in interruptible waits, see [`Signal::interruptible`].
+    waiters: Vec<AxTaskRef>,
+    /// Notified whenever a signal is sent to the process or one of its
+    /// threads, such as by `signalfd`.
+    poll_waiters: Arc<PollWaiters>,
 }
 
 #[cfg(feature = "signal")]
@@ -65,17 +378,26 @@ struct SignalHandler;
 #[cfg(feature = "signal")]
 #[crate_interface::impl_interface]
 impl TrapHandler for SignalHandler {
-    fn handle_signal() {
-        let signal = Signal::signal(-1, true).unwrap();
-        for signum in 0..32 {
-            if signal & (1 << signum) != 0
-            /* TODO: && support mask */
-            {
-                Signal::signal(signum as i8, false);
-                Signal::sigaction(signum as u8, None, None);
-                Signal::signal_handle(signum as u8);
-            }
+    fn handle_signal(tf: &mut TrapFrame) {
+        let Some(curr) = crate::current_may_uninit() else {
+            return;
+        };
+        if curr.is_idle() {
+            return;
+        }
+        // the interrupted code may hold locks that the handler needs
+        #[cfg(feature = "preempt")]
+        if !curr.can_preempt(0) {
+            return;
         }
+        Signal::handle_pending(tf);
+    }
+
+    fn set_syscall_frame(tf: *mut TrapFrame) -> *mut TrapFrame {
+        let Some(curr) = crate::current_may_uninit() else {
+            return core::ptr::null_mut();
+        };
+        core::mem::replace(&mut curr.sig_thread.lock().syscall_frame, tf)
     }
 }
 
@@ -89,86 +411,421 @@ impl Signal {
     ///crate new Signal struct
     pub fn new() -> Self {
         Self {
-            #[cfg(feature = "irq")]
-            signal: AtomicI64::new(0),
-            sigaction: [rx_sigaction::new(); 32],
+            sigaction: [rx_sigaction::new(); NSIG],
+            pending: SigPending::new(),
             // Default::default() is not const
             timer_value: [Duration::from_nanos(0); 3],
             timer_interval: [Duration::from_nanos(0); 3],
+            waiters: Vec::new(),
+            poll_waiters: Arc::new(PollWaiters::new()),
         }
     }
-    /// Set signal
-    /// signum: signal number, if signum < 0, just return current signal
-    /// on: true: enable signal, false: disable signal
-    #[cfg(feature = "irq")]
-    pub fn signal(signum: i8, on: bool) -> Option<u32> {
-        use core::sync::atomic::Ordering;
-        if signum >= 32 {
-            return None;
+
+    /// Create the signal struct of a forked process, which inherits the signal
+    /// actions, but neither the pending signals nor the timers.
+    pub fn fork(&self) -> Self {
+        Self {
+            sigaction: self.sigaction,
+            ..Self::new()
         }
+    }
+
+    /// Send a signal to the process of `task`.
+    ///
+    /// Returns `false` if the signal number is invalid.
+    pub fn send_process(task: &AxTaskRef, info: SigInfo) -> bool {
+        let signum = info.signo as usize;
+        if !is_valid_signal(signum) {
+            return false;
+        }
+        let signal_if = task.signal_if.lock();
+        let ignored = signal_if.is_ignored(signum);
+        let waiters = signal_if.waiters.clone();
+        let poll_waiters = signal_if.poll_waiters.clone();
+        drop(signal_if);
+        let blocked_by =
+            |thread: &AxTaskRef| thread.sig_thread.lock().blocked & sigbit(signum) != 0;
+        // an ignored signal stays pending while it is blocked, so that it can
+        // still be taken by `sigwait` or `signalfd`
+        if ignored && !blocked_by(task) && !waiters.iter().any(blocked_by) {
+            return true;
+        }
+        task.signal_if.lock().pending.push(info);
+        poll_waiters.notify();
+        // wake up one of the threads which can handle it
+        if let Some(waiter) = waiters.into_iter().find(|waiter| !blocked_by(waiter)) {
+            crate::run_queue::RUN_QUEUE
+                .lock()
+                .unblock_task(waiter, true);
+        }
+        true
+    }
+
+    /// Send a signal to `task` only.
+    ///
+    /// Returns `false` if the signal number is invalid.
+    pub fn send_thread(task: &AxTaskRef, info: SigInfo) -> bool {
+        let signum = info.signo as usize;
+        if !is_valid_signal(signum) {
+            return false;
+        }
+        let signal_if = task.signal_if.lock();
+        let ignored = signal_if.is_ignored(signum);
+        let waiting = signal_if
+            .waiters
+            .iter()
+            .any(|waiter| Arc::ptr_eq(waiter, task));
+        let poll_waiters = signal_if.poll_waiters.clone();
+        drop(signal_if);
+        let mut sig_thread = task.sig_thread.lock();
+        let blocked = sig_thread.blocked & sigbit(signum) != 0;
+        if ignored && !blocked {
+            return true;
+        }
+        sig_thread.pending.push(info);
+        drop(sig_thread);
+        poll_waiters.notify();
+        if waiting && !blocked {
+            crate::run_queue::RUN_QUEUE
+                .lock()
+                .unblock_task(task.clone(), true);
+        }
+        true
+    }
+
+    /// Send a signal to the current process.
+    pub fn signal(signum: usize, code: c_int) -> bool {
+        Self::send_process(current().as_task_ref(), SigInfo::new(signum, code))
+    }
+
+    /// Get the blocked mask of the current thread, and replace it with
+    /// `new_mask` if given. `SIGKILL` and `SIGSTOP` can never be blocked.
+    pub fn sigmask(new_mask: Option<u64>) -> u64 {
         let binding = current();
-        let current_signal_if = binding.signal_if.lock();
-        let mut old = current_signal_if.signal.load(Ordering::Acquire);
-        if signum >= 0 {
-            loop {
-                let new = if on {
-                    old | (1 << signum)
-                } else {
-                    old & !(1 << signum)
-                };
-
-                match current_signal_if.signal.compare_exchange_weak(
-                    old,
-                    new,
-                    Ordering::AcqRel,
-                    Ordering::Acquire,
-                ) {
-                    Ok(_) => break,
-                    Err(x) => old = x,
-                }
-            }
+        let mut sig_thread = binding.sig_thread.lock();
+        let old = sig_thread.blocked;
+        if let Some(mask) = new_mask {
+            sig_thread.blocked = mask & !UNBLOCKABLE;
         }
-        Some(old.try_into().unwrap())
+        old
     }
+
+    /// Signals pending for the current thread, both thread-directed and
+    /// process-wide ones.
+    pub fn sigpending() -> u64 {
+        let binding = current();
+        let thread_pending = binding.sig_thread.lock().pending.set();
+        let process_pending = binding.signal_if.lock().pending.set();
+        thread_pending | process_pending
+    }
+
     /// Set signal action
     /// signum: signal number
-    /// sigaction: signal action, if sigaction == None, call the handler
+    /// sigaction: new signal action
+    /// oldact: where to store the old signal action
+    ///
+    /// Returns `false` if the signal number is invalid, or the action of
+    /// `SIGKILL` and `SIGSTOP` is to be changed.
     pub fn sigaction(
-        signum: u8,
+        signum: usize,
         sigaction: Option<*const rx_sigaction>,
         oldact: Option<*mut rx_sigaction>,
-    ) {
+    ) -> bool {
+        if !is_valid_signal(signum) {
+            return false;
+        }
+        if sigaction.is_some() && sigbit(signum) & UNBLOCKABLE != 0 {
+            return false;
+        }
         let binding = current();
         let mut current_signal_if = binding.signal_if.lock();
-        if signum >= current_signal_if.sigaction.len() as u8 {
-            return;
-        }
         if let Some(oldact) = oldact {
             unsafe {
-                *oldact = current_signal_if.sigaction[signum as usize];
+                *oldact = current_signal_if.sigaction[signum - 1];
             }
         }
 
         if let Some(s) = sigaction {
             unsafe {
-                current_signal_if.sigaction[signum as usize] = *s;
+                current_signal_if.sigaction[signum - 1] = *s;
+            }
+            // setting a signal to be ignored discards its pending instances
+            if current_signal_if.is_ignored(signum) {
+                current_signal_if.pending.discard(signum);
+                drop(current_signal_if);
+                binding.sig_thread.lock().pending.discard(signum);
             }
         }
+        true
+    }
+
+    /// Deliver all signals pending for the current thread and not blocked
+    /// by it. Thread-directed signals are delivered before process-wide ones.
+    ///
+    /// `tf` is the interrupted context, which is saved to the signal frames
+    /// and restored after the handlers return.
+    pub fn handle_pending(tf: &mut TrapFrame) {
+        while let Some(info) = Self::dequeue() {
+            Self::signal_handle(info, tf);
+        }
     }
-    /// Handle signal for the current process
-    /// signum: Signal number to handle
-    /// When the registered sa_handler for the specified signal is None, logs an error message
-    pub fn signal_handle(signum: u8) {
+
+    /// Take one deliverable signal of the current thread out of the pending sets.
+    fn dequeue() -> Option<SigInfo> {
+        let blocked = current().sig_thread.lock().blocked;
+        Self::dequeue_masked(!blocked)
+    }
+
+    /// Take the lowest signal in `mask` out of the pending sets of the current
+    /// thread, whether it is blocked or not, as `signalfd` reads signals.
+    pub fn dequeue_masked(mask: u64) -> Option<SigInfo> {
         let binding = current();
-        let current_signal_if = binding.signal_if.lock();
-        if let Some(handler) = current_signal_if.sigaction[signum as usize].sa_handler {
-            unsafe {
-                handler(signum as c_int);
+        if let Some(info) = binding.sig_thread.lock().pending.pop(!mask) {
+            return Some(info);
+        }
+        binding.signal_if.lock().pending.pop(!mask)
+    }
+
+    /// Waiters of the current process, notified whenever a signal is sent to
+    /// the process or one of its threads.
+    pub fn poll_waiters() -> Arc<PollWaiters> {
+        current().signal_if.lock().poll_waiters.clone()
+    }
+
+    /// Handle signal for the current thread
+    /// info: the signal to handle
+    /// tf: the interrupted context
+    fn signal_handle(info: SigInfo, tf: &mut TrapFrame) {
+        let signum = info.signo as usize;
+        let binding = current();
+        let action = {
+            let mut signal_if = binding.signal_if.lock();
+            let action = signal_if.sigaction[signum - 1];
+            if action.handler_addr() > SIG_IGN && action.sa_flags & SA_RESETHAND != 0 {
+                signal_if.sigaction[signum - 1] = rx_sigaction::new();
+            }
+            action
+        };
+        match action.handler_addr() {
+            // ignored signals kept pending while blocked are dropped here
+            SIG_IGN => {}
+            SIG_DFL => Self::default_handle(signum),
+            _ => {
+                Self::run_handler(info, &action, tf);
+                let mut sig_thread = binding.sig_thread.lock();
+                let restart = sig_thread.restart.unwrap_or(true);
+                sig_thread.restart = Some(restart && action.sa_flags & SA_RESTART != 0);
             }
+        }
+    }
+
+    /// Run the handler of `action` with a signal frame.
+    ///
+    /// While the handler is running, the signal itself (unless `SA_NODEFER`)
+    /// and the signals in `sa_mask` are blocked.
+    fn run_handler(info: SigInfo, action: &rx_sigaction, tf: &mut TrapFrame) {
+        let signum = info.signo as usize;
+        let binding = current();
+        let mut sig_thread = binding.sig_thread.lock();
+        let old_mask = sig_thread.blocked;
+        let mut stack = sig_thread.altstack;
+        let on_altstack = sig_thread.on_altstack(tf.sp());
+        let use_altstack =
+            action.sa_flags & SA_ONSTACK != 0 && stack.flags & SS_DISABLE == 0 && !on_altstack;
+        if on_altstack || use_altstack {
+            stack.flags |= SS_ONSTACK;
+        }
+
+        let mut local_frame = MaybeUninit::<SignalFrame>::zeroed();
+        let (frame_ptr, handler_sp) = if use_altstack {
+            let top = stack.sp + stack.size;
+            let frame = (top - core::mem::size_of::<SignalFrame>()) & !0xf;
+            unsafe { core::ptr::write_bytes(frame as *mut SignalFrame, 0, 1) };
+            (frame as *mut SignalFrame, frame)
+        } else {
+            // run the handler on the current stack, below this frame
+            (local_frame.as_mut_ptr(), 0)
+        };
+        let frame = unsafe { &mut *frame_ptr };
+        frame.info = info.into();
+        frame.uc.save(tf, old_mask, stack);
+        frame.prev = sig_thread.frame;
+        sig_thread.frame = frame_ptr as usize;
+        let mut mask = old_mask | action.mask();
+        if action.sa_flags & SA_NODEFER == 0 {
+            mask |= sigbit(signum);
+        }
+        sig_thread.blocked = mask & !UNBLOCKABLE;
+        drop(sig_thread);
+
+        let restorer = match action.sa_restorer {
+            Some(restorer) if action.sa_flags & SA_RESTORER != 0 => restorer as usize,
+            _ => signal_return_trampoline as usize,
+        };
+        let args = [
+            signum,
+            &frame.info as *const _ as usize,
+            &frame.uc as *const _ as usize,
+        ];
+        let irqs_enabled = ruxhal::arch::irqs_enabled();
+        if tf.irqs_enabled() {
+            ruxhal::arch::enable_irqs();
+        }
+        unsafe {
+            call_signal_handler(
+                &mut frame.kernel_sp,
+                action.handler_addr(),
+                &args,
+                handler_sp,
+                restorer,
+            );
+        }
+        if irqs_enabled {
+            ruxhal::arch::enable_irqs();
         } else {
-            log::error!("no sigaction !");
+            ruxhal::arch::disable_irqs();
+        }
+
+        // the handler may have modified the context
+        let frame = unsafe { &*frame_ptr };
+        let mask = frame.uc.restore(tf);
+        let mut sig_thread = binding.sig_thread.lock();
+        sig_thread.frame = frame.prev;
+        sig_thread.blocked = mask & !UNBLOCKABLE;
+    }
+
+    /// Return from the innermost signal handler of the current thread, as
+    /// `rt_sigreturn` does.
+    ///
+    /// Returns `false` if no handler is running.
+    pub fn sigreturn() -> bool {
+        let frame = current().sig_thread.lock().frame as *const SignalFrame;
+        if frame.is_null() {
+            return false;
+        }
+        unsafe { resume_signal_handler(&(*frame).kernel_sp) }
+    }
+
+    /// Get the alternate signal stack of the current thread, and replace it
+    /// with `new_stack` if given.
+    pub fn sigaltstack(new_stack: Option<SignalStack>) -> LinuxResult<SignalStack> {
+        let binding = current();
+        let mut sig_thread = binding.sig_thread.lock();
+        // the kernel runs on the same stack as the caller
+        let sp = &new_stack as *const _ as usize;
+        let mut old = sig_thread.altstack;
+        if sig_thread.on_altstack(sp) {
+            old.flags |= SS_ONSTACK;
         }
+        if let Some(mut stack) = new_stack {
+            if old.flags & SS_ONSTACK != 0 {
+                return Err(LinuxError::EPERM);
+            }
+            match stack.flags {
+                0 | SS_ONSTACK if stack.size < MINSIGSTKSZ => return Err(LinuxError::ENOMEM),
+                0 | SS_ONSTACK => stack.flags = 0,
+                SS_DISABLE => {
+                    stack = SignalStack {
+                        sp: 0,
+                        flags: SS_DISABLE,
+                        size: 0,
+                    }
+                }
+                _ => return Err(LinuxError::EINVAL),
+            }
+            sig_thread.altstack = stack;
+        }
+        Ok(old)
     }
+
+    /// Whether the current thread has a pending signal which is neither
+    /// blocked nor ignored.
+    pub fn has_pending() -> bool {
+        let binding = current();
+        let sig_thread = binding.sig_thread.lock();
+        let signal_if = binding.signal_if.lock();
+        let pending = sig_thread.pending.set() | signal_if.pending.set();
+        pending & !sig_thread.blocked & !signal_if.ignored_set() != 0
+    }
+
+    /// Whether the current thread is interrupted by signals, that is, a signal
+    /// is pending or a handler has run since the last
+    /// [`Signal::clear_interrupted`].
+    pub fn interrupted() -> bool {
+        current().sig_thread.lock().restart.is_some() || Self::has_pending()
+    }
+
+    /// Forget the handlers that have run, called at the start of a syscall
+    /// which can be interrupted.
+    pub fn clear_interrupted() {
+        current().sig_thread.lock().restart = None;
+    }
+
+    /// Handle the signals interrupting a syscall.
+    ///
+    /// They are delivered with the trap frame of the syscall, so handlers see
+    /// the context which invoked it. If the syscall is not invoked by a trap,
+    /// they are left pending until the next trap returns.
+    ///
+    /// Returns whether the syscall can be restarted, that is, all handlers
+    /// that have run have `SA_RESTART`, and no signal is left pending.
+    pub fn handle_interrupt() -> bool {
+        let binding = current();
+        let tf = binding.sig_thread.lock().syscall_frame;
+        if !tf.is_null() {
+            // the trap frame stays on the stack until the syscall returns
+            Self::handle_pending(unsafe { &mut *tf });
+        }
+        let restart = binding.sig_thread.lock().restart.take().unwrap_or(true);
+        restart && !Self::has_pending()
+    }
+
+    /// Run `f`, during which the current thread can be woken up from
+    /// blocking by a signal sent to it.
+    pub fn interruptible<T>(f: impl FnOnce() -> T) -> T {
+        let binding = current();
+        let task = binding.as_task_ref();
+        binding.signal_if.lock().waiters.push(task.clone());
+        let ret = f();
+        binding
+            .signal_if
+            .lock()
+            .waiters
+            .retain(|waiter| !Arc::ptr_eq(waiter, task));
+        ret
+    }
+
+    /// Take the default action of `signum`.
+    fn default_handle(signum: usize) {
+        match default_action(signum) {
+            DefaultAction::Ignore | DefaultAction::Continue => {}
+            DefaultAction::Stop => {
+                log::warn!("signal {signum}: stopping a process is not supported, ignored");
+            }
+            DefaultAction::Terminate | DefaultAction::CoreDump => {
+                info!("task {} terminated by signal {signum}", current().id_name());
+                current().set_term_signal(signum as i32);
+                crate::exit(128 + signum as i32);
+            }
+        }
+    }
+
+    /// Whether `signum` would be ignored if it was delivered now.
+    fn is_ignored(&self, signum: usize) -> bool {
+        match self.sigaction[signum - 1].handler_addr() {
+            SIG_IGN => true,
+            SIG_DFL => default_action(signum) == DefaultAction::Ignore,
+            _ => false,
+        }
+    }
+
+    /// Signals that would be ignored if they were delivered now.
+    fn ignored_set(&self) -> u64 {
+        (1..=NSIG)
+            .filter(|&signum| self.is_ignored(signum))
+            .fold(0, |set, signum| set | sigbit(signum))
+    }
+
     /// Set timer
     /// which: timer type
     /// new_value: new timer value
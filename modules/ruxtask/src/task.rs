@@ -18,15 +18,14 @@ use alloc::{
 };
 use core::mem::ManuallyDrop;
 use core::ops::Deref;
-use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicU8, Ordering};
+use core::sync::atomic::{
+    AtomicBool, AtomicI32, AtomicIsize, AtomicU64, AtomicU8, AtomicUsize, Ordering,
+};
 use core::{alloc::Layout, cell::UnsafeCell, fmt, ptr::NonNull};
 #[cfg(feature = "paging")]
 use ruxhal::paging::PageTable;
 use spinlock::SpinNoIrq;
 
-#[cfg(feature = "preempt")]
-use core::sync::atomic::AtomicUsize;
-
 #[cfg(feature = "tls")]
 use ruxhal::tls::TlsArea;
 
@@ -34,12 +33,12 @@ use memory_addr::{align_up_4k, VirtAddr, PAGE_SIZE_4K};
 use ruxhal::arch::TaskContext;
 
 use crate::current;
+#[cfg(feature = "signal")]
+use crate::signal::{Signal, ThreadSignal};
 #[cfg(not(feature = "musl"))]
 use crate::tsd::{DestrFunction, KEYS, TSD};
 #[cfg(feature = "paging")]
 use crate::vma::MmapStruct;
-#[cfg(feature = "signal")]
-use crate::Signal;
 use crate::{AxRunQueue, AxTask, AxTaskRef, WaitQueue};
 
 /// A unique identifier for a thread.
@@ -81,8 +80,21 @@ pub struct TaskInner {
     preempt_disable_count: AtomicUsize,
 
     exit_code: AtomicI32,
+    /// The signal terminating the task, or 0 if it exited by itself.
+    term_signal: AtomicI32,
     wait_for_exit: WaitQueue,
 
+    /// The priority set by [`set_priority`](crate::set_priority).
+    normal_prio: AtomicIsize,
+    /// The priority used by the scheduler, which may be boosted by priority
+    /// inheritance.
+    prio: AtomicIsize,
+    /// The head of the robust futex list.
+    robust_list: AtomicUsize,
+    /// Timer ticks spent running, by all the threads if it is a process.
+    #[cfg(feature = "irq")]
+    cpu_ticks: AtomicU64,
+
     stack_map_addr: SpinNoIrq<VirtAddr>,
     kstack: SpinNoIrq<Arc<Option<TaskStack>>>,
     ctx: UnsafeCell<TaskContext>,
@@ -96,6 +108,9 @@ pub struct TaskInner {
     #[cfg(feature = "signal")]
     /// The signal to be sent to the task.
     pub signal_if: Arc<SpinNoIrq<Signal>>,
+    #[cfg(feature = "signal")]
+    /// The blocked mask and the signals sent to this thread only.
+    pub sig_thread: SpinNoIrq<ThreadSignal>,
 
     // set tid
     #[cfg(feature = "musl")]
@@ -154,6 +169,44 @@ impl TaskInner {
         &self.tl
     }
 
+    /// Gets the priority set by [`set_priority`](crate::set_priority).
+    pub fn normal_priority(&self) -> isize {
+        self.normal_prio.load(Ordering::Acquire)
+    }
+
+    /// Gets the priority used by the scheduler, which may be boosted above
+    /// the normal priority by priority inheritance.
+    pub fn priority(&self) -> isize {
+        self.prio.load(Ordering::Acquire)
+    }
+
+    /// Gets the head of the robust futex list set by `set_robust_list`.
+    pub fn robust_list(&self) -> usize {
+        self.robust_list.load(Ordering::Acquire)
+    }
+
+    /// Sets the head of the robust futex list.
+    pub fn set_robust_list(&self, head: usize) {
+        self.robust_list.store(head, Ordering::Release)
+    }
+
+    /// Gets the CPU time spent running by the task, or by all its threads if
+    /// it is a process.
+    ///
+    /// It is counted in timer ticks, so it is always zero if `irq` is not
+    /// enabled.
+    pub fn cpu_time(&self) -> core::time::Duration {
+        #[cfg(feature = "irq")]
+        {
+            let ticks = self.cpu_ticks.load(Ordering::Relaxed);
+            core::time::Duration::from_nanos(
+                ticks * ruxhal::time::NANOS_PER_SEC / ruxconfig::TICKS_PER_SEC as u64,
+            )
+        }
+        #[cfg(not(feature = "irq"))]
+        core::time::Duration::ZERO
+    }
+
     /// Gets the name of the task.
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -177,6 +230,20 @@ impl TaskInner {
         self.exit_code.load(Ordering::Acquire)
     }
 
+    /// Get the status of the exited task reported by `wait4`, which tells
+    /// either the exit code or the signal terminating it.
+    pub fn wait_status(&self) -> i32 {
+        match self.term_signal.load(Ordering::Acquire) {
+            0 => (self.exit_code() & 0xff) << 8,
+            signum => signum & 0x7f,
+        }
+    }
+
+    /// Record the signal terminating the task, before it exits.
+    pub(crate) fn set_term_signal(&self, signum: i32) {
+        self.term_signal.store(signum, Ordering::Release);
+    }
+
     /// Get process task
     pub fn process_task(&self) -> Arc<AxTask> {
         if let Some(process_task) = self.process_task.upgrade() {
@@ -220,6 +287,13 @@ pub static PROCESS_MAP: SpinNoIrq<BTreeMap<u64, Arc<AxTask>>> = SpinNoIrq::new(B
 
 // private methods
 impl TaskInner {
+    pub(crate) fn set_prio(&self, prio: isize, normal: bool) {
+        if normal {
+            self.normal_prio.store(prio, Ordering::Release);
+        }
+        self.prio.store(prio, Ordering::Release);
+    }
+
     // clone a thread
     fn new_common(id: TaskId, name: String) -> Self {
         debug!(
@@ -244,6 +318,12 @@ impl TaskInner {
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            term_signal: AtomicI32::new(0),
+            normal_prio: AtomicIsize::new(0),
+            prio: AtomicIsize::new(0),
+            robust_list: AtomicUsize::new(0),
+            #[cfg(feature = "irq")]
+            cpu_ticks: AtomicU64::new(0),
             wait_for_exit: WaitQueue::new(),
             stack_map_addr: SpinNoIrq::new(VirtAddr::from(0)), // should be set later
             kstack: SpinNoIrq::new(Arc::new(None)),
@@ -256,6 +336,8 @@ impl TaskInner {
             set_tid: AtomicU64::new(0),
             #[cfg(feature = "signal")]
             signal_if: current().signal_if.clone(),
+            #[cfg(feature = "signal")]
+            sig_thread: SpinNoIrq::new(ThreadSignal::new(current().sig_thread.lock().blocked)),
             #[cfg(feature = "musl")]
             tl: AtomicU64::new(0),
             #[cfg(feature = "paging")]
@@ -293,6 +375,12 @@ impl TaskInner {
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            term_signal: AtomicI32::new(0),
+            normal_prio: AtomicIsize::new(0),
+            prio: AtomicIsize::new(0),
+            robust_list: AtomicUsize::new(0),
+            #[cfg(feature = "irq")]
+            cpu_ticks: AtomicU64::new(0),
             wait_for_exit: WaitQueue::new(),
             stack_map_addr: SpinNoIrq::new(VirtAddr::from(0)),
             kstack: SpinNoIrq::new(Arc::new(None)),
@@ -302,6 +390,8 @@ impl TaskInner {
             set_tid,
             #[cfg(feature = "signal")]
             signal_if: current().signal_if.clone(),
+            #[cfg(feature = "signal")]
+            sig_thread: SpinNoIrq::new(ThreadSignal::new(current().sig_thread.lock().blocked)),
             // clear child tid
             tl,
             #[cfg(feature = "paging")]
@@ -387,12 +477,9 @@ impl TaskInner {
         Arc::new(AxTask::new(t))
     }
 
-    #[cfg(all(
-        any(target_arch = "aarch64", target_arch = "riscv64"),
-        feature = "paging",
-        feature = "fs"
-    ))]
-    /// only support for aarch64
+    #[cfg(all(feature = "paging", feature = "fs"))]
+    /// Fork the current process, the child shares its memory pages with the
+    /// parent in Copy-on-Write mode.
     pub fn fork() -> AxTaskRef {
         use crate::alloc::string::ToString;
         use page_table::PageSize;
@@ -410,8 +497,8 @@ impl TaskInner {
         debug!("fork: current_stack_top={current_stack_top:#x}, stack_size={stack_size:#x}");
 
         #[cfg(feature = "paging")]
-        // TODO: clone parent page table, and mark all unshared pages to read-only
         let mut cloned_page_table = PageTable::try_new().expect("failed to create page table");
+        // pages in memory are shared by the parent and the child, see `MmapStruct::clone`
         let cloned_mm = current().mm.as_ref().clone();
 
         // clone the global shared pages (as system memory)
@@ -452,43 +539,36 @@ impl TaskInner {
             )
             .expect("failed to map stack region when forking");
 
-        // clone parent pages in memory, and mark all unshared pages to read-only
-        for (vaddr, page_info) in cloned_mm.mem_map.lock().iter() {
-            let paddr = page_info.paddr;
-            cloned_page_table
-                .map((*vaddr).into(), paddr, PageSize::Size4K, MappingFlags::READ)
-                .expect("failed to map when forking");
-        }
-
-        // mark the parent process's page table to read-only.
-        for (vaddr, _) in current_task.mm.mem_map.lock().iter() {
+        // clone parent pages in memory. Pages of shared mappings keep their flags, and
+        // private pages are marked to read-only in both processes to be copied on write.
+        {
+            let vma_map = current_task.mm.vma_map.lock();
             let mut page_table = current_task.pagetable.lock();
-            let vaddr = VirtAddr::from(*vaddr);
-            let (_, mapping_flag, _) = page_table
-                .query(vaddr)
-                .expect("Inconsistent page table with mem_map");
-            if mapping_flag.contains(MappingFlags::EXECUTE) {
-                page_table
-                    .update(
-                        vaddr,
-                        None,
-                        Some(MappingFlags::READ | MappingFlags::EXECUTE),
-                    )
-                    .expect("failed to update mapping when forking");
-
+            for (&vaddr, page_info) in cloned_mm.mem_map.lock().iter() {
+                let is_shared = vma_map
+                    .range(..=vaddr)
+                    .next_back()
+                    .is_some_and(|(_, vma)| vaddr < vma.end_addr && vma.is_shared());
+                let vaddr = VirtAddr::from(vaddr);
+                let (_, mapping_flag, _) = page_table
+                    .query(vaddr)
+                    .expect("Inconsistent page table with mem_map");
+                let child_flag = if is_shared {
+                    mapping_flag
+                } else {
+                    let cow_flag = mapping_flag - MappingFlags::WRITE;
+                    if mapping_flag.contains(MappingFlags::WRITE) {
+                        page_table
+                            .update(vaddr, None, Some(cow_flag))
+                            .expect("failed to update mapping when forking");
+                        flush_tlb(Some(vaddr));
+                    }
+                    cow_flag
+                };
                 cloned_page_table
-                    .update(
-                        vaddr,
-                        None,
-                        Some(MappingFlags::READ | MappingFlags::EXECUTE),
-                    )
-                    .expect("failed to update mapping when forking");
-            } else {
-                page_table
-                    .update(vaddr, None, Some(MappingFlags::READ))
-                    .expect("failed to update mapping when forking");
+                    .map(vaddr, page_info.paddr, PageSize::Size4K, child_flag)
+                    .expect("failed to map when forking");
             }
-            flush_tlb(Some(vaddr));
         }
 
         let new_pid = TaskId::new();
@@ -511,6 +591,12 @@ impl TaskInner {
                 current_task.preempt_disable_count.load(Ordering::Acquire),
             ),
             exit_code: AtomicI32::new(0),
+            term_signal: AtomicI32::new(0),
+            normal_prio: AtomicIsize::new(0),
+            prio: AtomicIsize::new(0),
+            robust_list: AtomicUsize::new(0),
+            #[cfg(feature = "irq")]
+            cpu_ticks: AtomicU64::new(0),
             wait_for_exit: WaitQueue::new(),
             stack_map_addr: SpinNoIrq::new(*current().stack_map_addr.lock()),
             kstack: SpinNoIrq::new(Arc::new(Some(new_stack))),
@@ -522,7 +608,11 @@ impl TaskInner {
             #[cfg(feature = "musl")]
             set_tid: AtomicU64::new(0),
             #[cfg(feature = "signal")]
-            signal_if: Arc::new(spinlock::SpinNoIrq::new(Signal::new())),
+            signal_if: Arc::new(spinlock::SpinNoIrq::new(
+                current_task.signal_if.lock().fork(),
+            )),
+            #[cfg(feature = "signal")]
+            sig_thread: SpinNoIrq::new(current_task.sig_thread.lock().fork()),
             #[cfg(feature = "musl")]
             tl: AtomicU64::new(0),
             #[cfg(feature = "paging")]
@@ -590,6 +680,12 @@ impl TaskInner {
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            term_signal: AtomicI32::new(0),
+            normal_prio: AtomicIsize::new(0),
+            prio: AtomicIsize::new(0),
+            robust_list: AtomicUsize::new(0),
+            #[cfg(feature = "irq")]
+            cpu_ticks: AtomicU64::new(0),
             wait_for_exit: WaitQueue::new(),
             stack_map_addr: SpinNoIrq::new(VirtAddr::from(0)), // set in set_stack_top
             kstack: SpinNoIrq::new(Arc::new(None)),
@@ -602,6 +698,8 @@ impl TaskInner {
             set_tid: AtomicU64::new(0),
             #[cfg(feature = "signal")]
             signal_if: Arc::new(spinlock::SpinNoIrq::new(Signal::new())),
+            #[cfg(feature = "signal")]
+            sig_thread: SpinNoIrq::new(ThreadSignal::new(0)),
             #[cfg(feature = "musl")]
             tl: AtomicU64::new(0),
             #[cfg(feature = "paging")]
@@ -662,6 +760,12 @@ impl TaskInner {
             #[cfg(feature = "preempt")]
             preempt_disable_count: AtomicUsize::new(0),
             exit_code: AtomicI32::new(0),
+            term_signal: AtomicI32::new(0),
+            normal_prio: AtomicIsize::new(0),
+            prio: AtomicIsize::new(0),
+            robust_list: AtomicUsize::new(0),
+            #[cfg(feature = "irq")]
+            cpu_ticks: AtomicU64::new(0),
             wait_for_exit: WaitQueue::new(),
             stack_map_addr: SpinNoIrq::new(idle_kstack.end()),
             kstack: SpinNoIrq::new(Arc::new(Some(idle_kstack))),
@@ -674,6 +778,8 @@ impl TaskInner {
             set_tid: AtomicU64::new(0),
             #[cfg(feature = "signal")]
             signal_if: task_ref.signal_if.clone(),
+            #[cfg(feature = "signal")]
+            sig_thread: SpinNoIrq::new(ThreadSignal::new(0)),
             #[cfg(feature = "musl")]
             tl: AtomicU64::new(0),
             #[cfg(feature = "paging")]
@@ -735,6 +841,15 @@ impl TaskInner {
         self.is_idle
     }
 
+    /// Charges a timer tick to the process of the task.
+    #[cfg(feature = "irq")]
+    #[inline]
+    pub(crate) fn add_cpu_tick(&self) {
+        self.process_task()
+            .cpu_ticks
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     #[inline]
     pub(crate) fn in_wait_queue(&self) -> bool {
         self.in_wait_queue.load(Ordering::Acquire)
@@ -113,6 +113,58 @@ fn test_wait_queue() {
     assert!(!current().in_wait_queue());
 }
 
+#[test]
+fn test_wait_queue_requeue() {
+    let _lock = SERIAL.lock();
+    INIT.call_once(ruxtask::init_scheduler);
+
+    const NUM_TASKS: usize = 10;
+
+    static WQ1: WaitQueue = WaitQueue::new();
+    static WQ2: WaitQueue = WaitQueue::new();
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+    static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+    for _ in 0..NUM_TASKS {
+        ruxtask::spawn(move || {
+            STARTED.fetch_add(1, Ordering::Relaxed);
+            WQ1.wait();
+            assert!(!current().in_wait_queue());
+            FINISHED.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    while STARTED.load(Ordering::Relaxed) < NUM_TASKS {
+        ruxtask::yield_now();
+    }
+
+    // nothing happens if the condition fails
+    assert_eq!(
+        WQ1.requeue_task_if(&WQ2, 1, usize::MAX, || Err(()), |_, _| true, |_, _| {}),
+        Err(())
+    );
+    let res = WQ1.requeue_task_if(
+        &WQ2,
+        1,
+        NUM_TASKS - 3,
+        || Ok::<_, ()>(()),
+        |_, _| true,
+        |_, _| {},
+    );
+    assert_eq!(res, Ok((1, NUM_TASKS - 3)));
+    while FINISHED.load(Ordering::Relaxed) < 1 {
+        ruxtask::yield_now();
+    }
+
+    WQ2.notify_all(true);
+    while FINISHED.load(Ordering::Relaxed) < NUM_TASKS - 2 {
+        ruxtask::yield_now();
+    }
+    WQ1.notify_all(true);
+    while FINISHED.load(Ordering::Relaxed) < NUM_TASKS {
+        ruxtask::yield_now();
+    }
+}
+
 #[test]
 fn test_task_join() {
     let _lock = SERIAL.lock();
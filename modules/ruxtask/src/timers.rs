@@ -7,7 +7,8 @@
  *   See the Mulan PSL v2 for more details.
  */
 
-use alloc::sync::Arc;
+use alloc::{boxed::Box, sync::Arc};
+use core::sync::atomic::{AtomicUsize, Ordering};
 use lazy_init::LazyInit;
 use ruxhal::time::current_time;
 use spinlock::SpinNoIrq;
@@ -16,28 +17,54 @@ use timer_list::{TimeValue, TimerEvent, TimerList};
 use crate::{AxTaskRef, RUN_QUEUE};
 
 // TODO: per-CPU
-static TIMER_LIST: LazyInit<SpinNoIrq<TimerList<TaskWakeupEvent>>> = LazyInit::new();
+static TIMER_LIST: LazyInit<SpinNoIrq<TimerList<TaskTimerEvent>>> = LazyInit::new();
 
-struct TaskWakeupEvent(AxTaskRef);
+static NEXT_TIMER_ID: AtomicUsize = AtomicUsize::new(1);
 
-impl TimerEvent for TaskWakeupEvent {
-    fn callback(self, _now: TimeValue) {
-        let mut rq = RUN_QUEUE.lock();
-        self.0.set_in_timer_list(false);
-        rq.unblock_task(self.0, true);
+enum TaskTimerEvent {
+    /// Wake up a task sleeping with a timeout.
+    Wakeup(AxTaskRef),
+    /// Call a function, identified by the ID returned from [`set_timer`].
+    Callback(usize, Box<dyn FnOnce(TimeValue) + Send>),
+}
+
+impl TimerEvent for TaskTimerEvent {
+    fn callback(self, now: TimeValue) {
+        match self {
+            Self::Wakeup(task) => {
+                let mut rq = RUN_QUEUE.lock();
+                task.set_in_timer_list(false);
+                rq.unblock_task(task, true);
+            }
+            Self::Callback(_, f) => f(now),
+        }
     }
 }
 
 pub fn set_alarm_wakeup(deadline: TimeValue, task: AxTaskRef) {
     let mut timers = TIMER_LIST.lock();
     task.set_in_timer_list(true);
-    timers.set(deadline, TaskWakeupEvent(task));
+    timers.set(deadline, TaskTimerEvent::Wakeup(task));
 }
 
 pub fn cancel_alarm(task: &AxTaskRef) {
     let mut timers = TIMER_LIST.lock();
     task.set_in_timer_list(false);
-    timers.cancel(|t| Arc::ptr_eq(&t.0, task));
+    timers.cancel(|t| matches!(t, TaskTimerEvent::Wakeup(t) if Arc::ptr_eq(t, task)));
+}
+
+pub fn set_timer(deadline: TimeValue, f: Box<dyn FnOnce(TimeValue) + Send>) -> usize {
+    let id = NEXT_TIMER_ID.fetch_add(1, Ordering::Relaxed);
+    TIMER_LIST
+        .lock()
+        .set(deadline, TaskTimerEvent::Callback(id, f));
+    id
+}
+
+pub fn cancel_timer(id: usize) {
+    TIMER_LIST
+        .lock()
+        .cancel(|t| matches!(t, TaskTimerEvent::Callback(t, _) if *t == id));
 }
 
 pub fn check_events() {
@@ -61,7 +61,8 @@ fn open_swap_file(filename: &str) -> Arc<File> {
     use axfs_vfs::VfsNodePerm;
     use ruxfdtable::OpenFlags;
 
-    let opt = OpenFlags::O_RDWR | OpenFlags::O_APPEND | OpenFlags::O_CREAT;
+    // the swapped pages bypass the page cache, which is evicted before swapping
+    let opt = OpenFlags::O_RDWR | OpenFlags::O_APPEND | OpenFlags::O_CREAT | OpenFlags::O_DIRECT;
     let path = absolute_path(filename).unwrap();
     ruxfs::fops::open_file_like(&path, opt, VfsNodePerm::default_file())
         .expect("create swap file failed")
@@ -90,6 +91,25 @@ pub struct PageInfo {
     pub mapping_file: Option<FileInfo>,
 }
 
+impl PageInfo {
+    /// Create a new `PageInfo` referring to the same physical page, and
+    /// increase the reference count of the page.
+    pub fn share(&self) -> Self {
+        global_allocator().page_ref_inc(phys_to_virt(self.paddr).as_usize());
+        Self {
+            paddr: self.paddr,
+            #[cfg(feature = "fs")]
+            mapping_file: self.mapping_file.clone(),
+        }
+    }
+
+    /// Get the number of `PageInfo`s (possibly in different processes)
+    /// referring to the physical page.
+    pub fn ref_count(&self) -> usize {
+        global_allocator().page_ref_count(phys_to_virt(self.paddr).as_usize())
+    }
+}
+
 /// Data structure for swaping out a page in a file.
 #[derive(Debug, Clone)]
 pub struct SwapInfo {
@@ -114,11 +134,19 @@ pub struct MmapStruct {
 }
 
 /// clone data structure for MmapStruct (when forking).
+///
+/// Pages in memory are shared with the cloned one, instead of being copied.
 impl Clone for MmapStruct {
     fn clone(&self) -> Self {
+        let mem_map = self
+            .mem_map
+            .lock()
+            .iter()
+            .map(|(&vaddr, page_info)| (vaddr, Arc::new(page_info.share())))
+            .collect();
         Self {
             vma_map: SpinNoIrq::new(self.vma_map.lock().clone()),
-            mem_map: SpinNoIrq::new(self.mem_map.lock().clone()),
+            mem_map: SpinNoIrq::new(mem_map),
             swaped_map: SpinNoIrq::new(self.swaped_map.lock().clone()),
         }
     }
@@ -135,8 +163,8 @@ impl Drop for SwapInfo {
 // release memory of a page in memory
 impl Drop for PageInfo {
     fn drop(&mut self) {
-        // use `global_allocator()` to dealloc pages.
-        global_allocator().dealloc_pages(phys_to_virt(self.paddr).as_usize(), 1);
+        // use `global_allocator()` to dealloc pages, shared pages are released after the last reference is dropped.
+        global_allocator().page_ref_dec(phys_to_virt(self.paddr).as_usize());
     }
 }
 
@@ -161,6 +189,9 @@ pub struct Vma {
     pub from_process: TaskId,
 }
 
+/// `MAP_SHARED` flag of mmap.
+const MAP_SHARED: u32 = 0x01;
+
 impl MmapStruct {
     /// Create a new `MmapStruct` instance.
     pub const fn new() -> Self {
@@ -207,6 +238,12 @@ impl Vma {
         }
     }
 
+    /// Whether the mapping is shared between processes, pages of shared
+    /// mappings are not copied on write after forking.
+    pub fn is_shared(&self) -> bool {
+        self.flags & MAP_SHARED != 0
+    }
+
     /// Clone a new `Vma` instance.
     pub fn clone_from(vma: &Vma, start_addr: usize, end_addr: usize) -> Self {
         Vma {
@@ -217,7 +254,7 @@ impl Vma {
             file: vma.file.clone(),
             offset: vma.offset,
             prot: vma.prot,
-            flags: vma.prot,
+            flags: vma.flags,
             from_process: current().id(),
         }
     }
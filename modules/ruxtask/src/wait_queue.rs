@@ -99,10 +99,14 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
 
     /// If `condition` returns [`Ok`], blocks the current task and put it into the wait queue,
     /// until other task notifies it.
-    pub fn wait_meta_if<F, R>(&self, meta: Meta, mut condition: F) -> Result<(), R>
+    ///
+    /// Returns whether the task was woken up without being notified, such as
+    /// by a signal.
+    pub fn wait_meta_if<F, R>(&self, meta: Meta, mut condition: F) -> Result<bool, R>
     where
         F: FnMut() -> Result<(), R>,
     {
+        let curr = crate::current();
         let mut rq = RUN_QUEUE.lock();
         let mut wq = self.queue.lock();
         condition()?;
@@ -112,9 +116,10 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
             wq.push_back((task, meta));
             drop(wq);
         });
-        self.cancel_events(crate::current());
+        let interrupted = curr.in_wait_queue(); // still in the wait queue, not notified
+        self.cancel_events(curr);
 
-        Ok(())
+        Ok(interrupted)
     }
 
     /// Blocks the current task and put it into the wait queue, until other tasks
@@ -269,6 +274,135 @@ impl<Meta> WaitQueueWithMetadata<Meta> {
         len_before - wq.len()
     }
 
+    /// Wakes up the task with the minimum `key` among the tasks that `filter`
+    /// returns true, e.g. the one with the highest priority.
+    ///
+    /// `update` is called with the awaken task, or [`None`] if there is no such
+    /// task, and the number of remaining tasks that `filter` returns true. It
+    /// is called atomically with the wake-up, just as the `condition` of
+    /// [`wait_meta_if`](Self::wait_meta_if).
+    ///
+    /// If `resched` is true, the current task will be preempted when the
+    /// preemption is enabled.
+    pub fn notify_one_by_key_if<F, K, T, U>(
+        &self,
+        resched: bool,
+        mut filter: F,
+        mut key: K,
+        update: U,
+    ) -> bool
+    where
+        F: FnMut(&AxTaskRef, &Meta) -> bool,
+        K: FnMut(&AxTaskRef, &Meta) -> T,
+        T: Ord,
+        U: FnOnce(Option<&AxTaskRef>, usize),
+    {
+        let mut rq = RUN_QUEUE.lock();
+        let mut wq = self.queue.lock();
+        let mut count = 0;
+        let mut selected: Option<(usize, T)> = None;
+        for (i, (task, meta)) in wq.iter().enumerate() {
+            if !filter(task, meta) {
+                continue;
+            }
+            count += 1;
+            let k = key(task, meta);
+            if selected.as_ref().is_none_or(|(_, min)| k < *min) {
+                selected = Some((i, k));
+            }
+        }
+
+        match selected {
+            Some((i, _)) => {
+                let (task, _) = wq.remove(i).unwrap();
+                update(Some(&task), count - 1);
+                task.set_in_wait_queue(false);
+                rq.unblock_task(task, resched);
+                true
+            }
+            None => {
+                update(None, 0);
+                false
+            }
+        }
+    }
+
+    /// Wakes up at most `max_wake` tasks that `filter` returns true, then moves
+    /// at most `max_requeue` of the remaining ones to the `target` queue,
+    /// after `requeue` updates their metadata. Tasks are not rescheduled.
+    ///
+    /// Nothing is done if `condition` returns [`Err`], which is checked
+    /// atomically with the operation, just as [`wait_meta_if`](Self::wait_meta_if).
+    ///
+    /// Returns the numbers of tasks awaken and moved.
+    pub fn requeue_task_if<C, F, U, R>(
+        &self,
+        target: &Self,
+        max_wake: usize,
+        max_requeue: usize,
+        condition: C,
+        mut filter: F,
+        mut requeue: U,
+    ) -> Result<(usize, usize), R>
+    where
+        C: FnOnce() -> Result<(), R>,
+        F: FnMut(&AxTaskRef, &Meta) -> bool,
+        U: FnMut(&AxTaskRef, &mut Meta),
+    {
+        let mut rq = RUN_QUEUE.lock();
+        let mut wq = self.queue.lock();
+        condition()?;
+
+        let mut woken = 0;
+        wq.retain(|(task, meta)| {
+            if woken < max_wake && filter(task, meta) {
+                woken += 1;
+                task.set_in_wait_queue(false);
+                rq.unblock_task(task.clone(), false);
+                false
+            } else {
+                true
+            }
+        });
+
+        let mut requeued = 0;
+        if core::ptr::eq(self, target) {
+            // only the metadata needs to be updated
+            for (task, meta) in wq.iter_mut() {
+                if requeued >= max_requeue {
+                    break;
+                }
+                if filter(task, meta) {
+                    requeue(task, meta);
+                    requeued += 1;
+                }
+            }
+        } else {
+            let mut target_wq = target.queue.lock();
+            let mut i = 0;
+            while i < wq.len() && requeued < max_requeue {
+                let (task, meta) = &mut wq[i];
+                if filter(task, meta) {
+                    requeue(task, meta);
+                    target_wq.push_back(wq.remove(i).unwrap());
+                    requeued += 1;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        Ok((woken, requeued))
+    }
+
+    /// Removes the current task from the wait queue, after it has been moved
+    /// here by [`requeue_task_if`](Self::requeue_task_if) and then woken up by
+    /// a timer or a signal, which only cancels the wait on the original queue.
+    pub fn remove_current(&self) {
+        let curr = crate::current();
+        let _guard = kernel_guard::IrqSave::new();
+        self.queue.lock().retain(|(t, _)| !curr.ptr_eq(t));
+    }
+
     pub(crate) fn notify_one_locked(&self, resched: bool, rq: &mut AxRunQueue) -> bool {
         if let Some((task, _)) = self.queue.lock().pop_front() {
             task.set_in_wait_queue(false);
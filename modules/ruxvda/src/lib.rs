@@ -17,20 +17,26 @@ extern crate log;
 
 pub mod fs;
 
-use alloc::string::String;
+use alloc::format;
 use alloc::sync::Arc;
+use alloc::vec::Vec;
 use log::*;
-use ruxdriver::{prelude::*, AxDeviceContainer};
+use ruxdriver::prelude::*;
 use ruxfs::MountPoint;
 
 /// Initializes filesystems by block devices.
-pub fn init_vdafs(mut vda_devs: AxDeviceContainer<AxBlockDevice>) -> MountPoint {
+///
+/// Each device is mounted on `/vda<n>`, with `n` starting from 1.
+pub fn init_vdafs(vda_devs: Vec<AxBlockDevice>) -> Vec<MountPoint> {
     info!("Initialize VDA filesystem...");
 
-    let vda = vda_devs.take_one().expect("No VDA device found!");
-    info!("  use VDA device 0: {:?}", vda.device_name());
-
-    let vda_fs = self::fs::VdaFileSystem::new(vda);
-
-    MountPoint::new(String::from("/vda1"), Arc::new(vda_fs))
+    vda_devs
+        .into_iter()
+        .enumerate()
+        .map(|(i, vda)| {
+            info!("  use VDA device {i}: {:?}", vda.device_name());
+            let vda_fs = self::fs::VdaFileSystem::new(vda);
+            MountPoint::new(format!("/vda{}", i + 1), Arc::new(vda_fs)).with_source("none", "vda")
+        })
+        .collect()
 }
@@ -37,7 +37,7 @@ else
 endif
 
 qemu_args-y := -m 2G -smp $(SMP) $(qemu_args-$(ARCH)) \
-  -append ";$(ARGS);$(ENVS)"
+  -append "$(BOOTARGS);$(ARGS);$(ENVS)"
 
 qemu_args-$(CONSOLE) += \
   -global virtio-mmio.force-legacy=false \
@@ -111,6 +111,7 @@
 #define AT_FDCWD      (-100)
 #define AT_EMPTY_PATH 0x1000
 #define AT_REMOVEDIR 0x200
+#define AT_SYMLINK_FOLLOW 0x400
 
 #define SYNC_FILE_RANGE_WAIT_BEFORE 1
 #define SYNC_FILE_RANGE_WRITE       2
@@ -61,6 +61,11 @@ uint16_t ntohs(uint16_t);
 #define IPPROTO_MPTCP    262
 #define IPPROTO_MAX      263
 
+#define IP_TOS     1
+#define IP_TTL     2
+#define IP_HDRINCL 3
+#define IP_OPTIONS 4
+
 #define IPV6_ADDRFORM             1
 #define IPV6_2292PKTINFO          2
 #define IPV6_2292HOPOPTS          3
@@ -16,6 +16,7 @@
 
 #define CLOCK_REALTIME  0
 #define CLOCK_MONOTONIC 1
+#define CLOCK_BOOTTIME  7
 #define CLOCKS_PER_SEC  1000000L
 
 #define __tm_gmtoff tm_gmtoff
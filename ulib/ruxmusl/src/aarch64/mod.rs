@@ -25,6 +25,10 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETCWD => {
                 ruxos_posix_api::sys_getcwd(args[0] as *mut core::ffi::c_char, args[1]) as _
             }
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD2 => {
+                ruxos_posix_api::sys_eventfd2(args[0] as core::ffi::c_uint, args[1] as c_int) as _
+            }
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE1 => ruxos_posix_api::sys_epoll_create1(args[0] as c_int) as _,
             #[cfg(feature = "epoll")]
@@ -75,6 +79,20 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::SYMLINKAT => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                args[1] as c_int,
+                args[2] as *const core::ffi::c_char,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::LINKAT => ruxos_posix_api::sys_linkat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as *const core::ffi::c_char,
+                args[4] as c_int,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::FCHMODAT => ruxos_posix_api::sys_fchmodat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -101,6 +119,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as c_int,
             ) as _,
             #[cfg(feature = "fs")]
+            SyscallId::FCHMOD => {
+                ruxos_posix_api::sys_fchmod(args[0] as c_int, args[1] as ctypes::mode_t) as _
+            }
+            #[cfg(feature = "fs")]
+            SyscallId::FCHOWN => ruxos_posix_api::sys_fchown(
+                args[0] as c_int,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+            #[cfg(feature = "fs")]
+            SyscallId::UTIMENSAT => ruxos_posix_api::sys_utimensat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const ctypes::timespec,
+                args[3] as c_int,
+            ) as _,
+            #[cfg(feature = "fs")]
             SyscallId::RENAMEAT => ruxos_posix_api::sys_renameat(
                 args[0] as c_int,
                 args[1] as *const core::ffi::c_char,
@@ -206,6 +241,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3] as *const ctypes::sigset_t,
                 args[4] as ctypes::size_t,
             ) as _,
+            #[cfg(all(feature = "fd", feature = "signal"))]
+            SyscallId::SIGNALFD4 => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const usize,
+                args[2],
+                args[3] as c_int,
+            ) as _,
             #[cfg(feature = "fs")]
             SyscallId::READLINKAT => ruxos_posix_api::sys_readlinkat(
                 args[0] as c_int,
@@ -225,6 +267,8 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_fstat(args[0] as c_int, args[1] as *mut core::ffi::c_void) as _
             }
             #[cfg(feature = "fs")]
+            SyscallId::SYNC => ruxos_posix_api::sys_sync() as _,
+            #[cfg(feature = "fs")]
             SyscallId::FSYNC => ruxos_posix_api::sys_fsync(args[0] as c_int) as _,
             SyscallId::GETEUID => ruxos_posix_api::sys_geteuid() as _,
             SyscallId::GETEGID => ruxos_posix_api::sys_getegid() as _,
@@ -232,6 +276,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::GETTID => ruxos_posix_api::sys_gettid() as _,
             #[cfg(feature = "fs")]
             SyscallId::FDATASYNC => ruxos_posix_api::sys_fdatasync(args[0] as c_int) as _,
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_CREATE => {
+                ruxos_posix_api::sys_timerfd_create(args[0] as ctypes::clockid_t, args[1] as c_int)
+                    as _
+            }
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_SETTIME => ruxos_posix_api::sys_timerfd_settime(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as *const ctypes::itimerspec,
+                args[3] as *mut ctypes::itimerspec,
+            ) as _,
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_GETTIME => ruxos_posix_api::sys_timerfd_gettime(
+                args[0] as c_int,
+                args[1] as *mut ctypes::itimerspec,
+            ) as _,
             SyscallId::CAP_GET => ruxos_posix_api::sys_cap_get(args[0], args[1]) as _,
             #[allow(unreachable_code)]
             #[cfg(not(feature = "multitask"))]
@@ -254,6 +315,16 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as _,
                 args[5] as _,
             ) as _,
+            #[cfg(feature = "multitask")]
+            SyscallId::SET_ROBUST_LIST => {
+                ruxos_posix_api::sys_set_robust_list(args[0], args[1]) as _
+            }
+            #[cfg(feature = "multitask")]
+            SyscallId::GET_ROBUST_LIST => ruxos_posix_api::sys_get_robust_list(
+                args[0] as c_int,
+                args[1] as *mut usize,
+                args[2] as *mut usize,
+            ) as _,
             SyscallId::NANO_SLEEP => ruxos_posix_api::sys_nanosleep(
                 args[0] as *const ctypes::timespec,
                 args[1] as *mut ctypes::timespec,
@@ -285,17 +356,19 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::TKILL => ruxos_posix_api::sys_tkill(args[0] as pid_t, args[1] as c_int) as _,
             #[cfg(feature = "signal")]
             SyscallId::SIGALTSTACK => ruxos_posix_api::sys_sigaltstack(
-                args[0] as *const core::ffi::c_void,
-                args[1] as *mut core::ffi::c_void,
+                args[0] as *const ctypes::stack_t,
+                args[1] as *mut ctypes::stack_t,
             ) as _,
             #[cfg(feature = "signal")]
             SyscallId::RT_SIGACTION => ruxos_posix_api::sys_rt_sigaction(
                 args[0] as c_int,
-                args[1] as *const ctypes::sigaction,
-                args[2] as *mut ctypes::sigaction,
+                args[1] as *const ruxos_posix_api::KSigaction,
+                args[2] as *mut ruxos_posix_api::KSigaction,
                 args[3] as ctypes::size_t,
             ) as _,
             #[cfg(feature = "signal")]
+            SyscallId::RT_SIGRETURN => ruxos_posix_api::sys_rt_sigreturn() as _,
+            #[cfg(feature = "signal")]
             SyscallId::RT_SIGPROCMASK => ruxos_posix_api::sys_rt_sigprocmask(
                 args[0] as c_int,
                 args[1] as *const usize,
@@ -18,6 +18,8 @@ pub enum SyscallId {
     INVALID = 999,
     #[cfg(feature = "fs")]
     GETCWD = 17,
+    #[cfg(feature = "fd")]
+    EVENTFD2 = 19,
     #[cfg(feature = "epoll")]
     EPOLL_CREATE1 = 20,
     #[cfg(feature = "epoll")]
@@ -37,14 +39,28 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     UNLINKAT = 35,
     #[cfg(feature = "fs")]
+    SYMLINKAT = 36,
+    #[cfg(feature = "fs")]
+    LINKAT = 37,
+    #[cfg(feature = "fs")]
     RENAMEAT = 38,
     #[cfg(feature = "fs")]
+    UMOUNT2 = 39,
+    #[cfg(feature = "fs")]
+    MOUNT = 40,
+    #[cfg(feature = "fs")]
     FACCESSAT = 48,
     #[cfg(feature = "fs")]
     CHDIR = 49,
     #[cfg(feature = "fs")]
+    FCHMOD = 52,
+    #[cfg(feature = "fs")]
+    FCHMODAT = 53,
+    #[cfg(feature = "fs")]
     FCHOWNAT = 54,
     #[cfg(feature = "fs")]
+    FCHOWN = 55,
+    #[cfg(feature = "fs")]
     OPENAT = 56,
     #[cfg(feature = "fd")]
     CLOSE = 57,
@@ -64,6 +80,8 @@ pub enum SyscallId {
     PSELECT6 = 72,
     #[cfg(feature = "poll")]
     PPOLL = 73,
+    #[cfg(all(feature = "fd", feature = "signal"))]
+    SIGNALFD4 = 74,
     #[cfg(feature = "fs")]
     READLINKAT = 78,
     #[cfg(feature = "fs")]
@@ -71,9 +89,19 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     FSTAT = 80,
     #[cfg(feature = "fs")]
+    SYNC = 81,
+    #[cfg(feature = "fs")]
     FSYNC = 82,
     #[cfg(feature = "fs")]
     FDATASYNC = 83,
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_CREATE = 85,
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_SETTIME = 86,
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_GETTIME = 87,
+    #[cfg(feature = "fs")]
+    UTIMENSAT = 88,
     EXIT = 93,
     #[cfg(feature = "multitask")]
     EXIT_GROUP = 94,
@@ -81,6 +109,10 @@ pub enum SyscallId {
     SET_TID_ADDRESS = 96,
     #[cfg(feature = "multitask")]
     FUTEX = 98,
+    #[cfg(feature = "multitask")]
+    SET_ROBUST_LIST = 99,
+    #[cfg(feature = "multitask")]
+    GET_ROBUST_LIST = 100,
     NANO_SLEEP = 101,
     CLOCK_SETTIME = 112,
     CLOCK_GETTIME = 113,
@@ -94,6 +126,8 @@ pub enum SyscallId {
     RT_SIGACTION = 134,
     #[cfg(feature = "signal")]
     RT_SIGPROCMASK = 135,
+    #[cfg(feature = "signal")]
+    RT_SIGRETURN = 139,
     GETPGID = 155,
     UNAME = 160,
     GETRLIMIT = 163,
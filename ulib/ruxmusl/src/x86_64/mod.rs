@@ -51,10 +51,8 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_fstat(args[0] as c_int, args[1] as *mut c_void) as _
             }
 
-            // TODO: lstat is identical to stat(), except that if pathname is a symbolic link,
-            // then it returns information about the link itself, not the file that the link refers to.
             #[cfg(feature = "fs")]
-            SyscallId::LSTAT => ruxos_posix_api::sys_stat(
+            SyscallId::LSTAT => ruxos_posix_api::sys_lstat(
                 args[0] as *const core::ffi::c_char,
                 args[1] as *mut c_void,
             ) as _,
@@ -98,8 +96,8 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "signal")]
             SyscallId::RT_SIGACTION => ruxos_posix_api::sys_rt_sigaction(
                 args[0] as c_int,
-                args[1] as *const ctypes::sigaction,
-                args[2] as *mut ctypes::sigaction,
+                args[1] as *const ruxos_posix_api::KSigaction,
+                args[2] as *mut ruxos_posix_api::KSigaction,
                 args[3] as ctypes::size_t,
             ) as _,
 
@@ -111,6 +109,9 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3],
             ) as _,
 
+            #[cfg(feature = "signal")]
+            SyscallId::RT_SIGRETURN => ruxos_posix_api::sys_rt_sigreturn() as _,
+
             #[cfg(feature = "fd")]
             SyscallId::IOCTL => ruxos_posix_api::sys_ioctl(args[0] as c_int, args[1], args[2]) as _,
 
@@ -308,6 +309,17 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[5] as *mut c_void,
             ) as _,
 
+            // vfork is implemented as fork, the child gets its own copy-on-write address space.
+            #[cfg(feature = "multitask")]
+            SyscallId::FORK | SyscallId::VFORK => ruxos_posix_api::sys_clone(
+                ctypes::SIGCHLD as c_int,
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+                core::ptr::null_mut(),
+            ) as _,
+
             #[cfg(feature = "fs")]
             #[allow(unreachable_code)]
             SyscallId::EXECVE => {
@@ -322,6 +334,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             #[cfg(feature = "multitask")]
             SyscallId::EXIT => ruxos_posix_api::sys_pthread_exit(args[0] as *mut c_void) as _,
 
+            #[cfg(feature = "multitask")]
+            SyscallId::WAIT4 => ruxos_posix_api::sys_wait4(
+                args[0] as ctypes::pid_t,
+                args[1] as *mut c_int,
+                args[2] as c_int,
+                args[3] as *mut ctypes::rusage,
+            ) as _,
+
             #[cfg(feature = "signal")]
             SyscallId::KILL => ruxos_posix_api::sys_kill(args[0] as pid_t, args[1] as c_int) as _,
 
@@ -370,11 +390,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_rmdir(args[0] as *const core::ffi::c_char) as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::LINK => ruxos_posix_api::sys_link(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::UNLINK => {
                 ruxos_posix_api::sys_unlink(args[0] as *const core::ffi::c_char) as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::SYMLINK => ruxos_posix_api::sys_symlink(
+                args[0] as *const core::ffi::c_char,
+                args[1] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::READLINK => ruxos_posix_api::sys_readlinkat(
                 ctypes::AT_FDCWD as c_int,
@@ -383,6 +415,43 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2],
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::CHMOD => ruxos_posix_api::sys_fchmodat(
+                ctypes::AT_FDCWD as c_int,
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::mode_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMOD => {
+                ruxos_posix_api::sys_fchmod(args[0] as c_int, args[1] as ctypes::mode_t) as _
+            }
+
+            #[cfg(feature = "fs")]
+            SyscallId::CHOWN => ruxos_posix_api::sys_fchownat(
+                ctypes::AT_FDCWD as c_int,
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+                0,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::FCHOWN => ruxos_posix_api::sys_fchown(
+                args[0] as c_int,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::LCHOWN => ruxos_posix_api::sys_fchownat(
+                ctypes::AT_FDCWD as c_int,
+                args[0] as *const core::ffi::c_char,
+                args[1] as ctypes::uid_t,
+                args[2] as ctypes::gid_t,
+                ctypes::AT_SYMLINK_NOFOLLOW as c_int,
+            ) as _,
+
             SyscallId::UMASK => ruxos_posix_api::sys_umask(args[0] as ctypes::mode_t) as _,
 
             SyscallId::GETTIMEOFDAY => ruxos_posix_api::sys_gettimeofday(
@@ -416,10 +485,10 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
             SyscallId::CAPGET => ruxos_posix_api::sys_cap_get(args[0], args[1]) as _,
 
             #[cfg(feature = "signal")]
-            SyscallId::SIGALTSTACK => {
-                ruxos_posix_api::sys_sigaltstack(args[0] as *const c_void, args[1] as *mut c_void)
-                    as _
-            }
+            SyscallId::SIGALTSTACK => ruxos_posix_api::sys_sigaltstack(
+                args[0] as *const ctypes::stack_t,
+                args[1] as *mut ctypes::stack_t,
+            ) as _,
 
             SyscallId::PRCTL => ruxos_posix_api::sys_prctl(
                 args[0] as c_int,
@@ -433,6 +502,23 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 ruxos_posix_api::sys_arch_prctl(args[0] as c_int, args[1] as c_ulong) as _
             }
 
+            #[cfg(feature = "fs")]
+            SyscallId::SYNC => ruxos_posix_api::sys_sync() as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::MOUNT => ruxos_posix_api::sys_mount(
+                args[0] as *const c_char,
+                args[1] as *const c_char,
+                args[2] as *const c_char,
+                args[3] as c_ulong,
+                args[4] as *const c_void,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::UMOUNT2 => {
+                ruxos_posix_api::sys_umount2(args[0] as *const c_char, args[1] as c_int) as _
+            }
+
             #[cfg(feature = "multitask")]
             SyscallId::GETTID => ruxos_posix_api::sys_gettid() as _,
 
@@ -446,6 +532,18 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[5] as _,
             ) as _,
 
+            #[cfg(feature = "multitask")]
+            SyscallId::SET_ROBUST_LIST => {
+                ruxos_posix_api::sys_set_robust_list(args[0], args[1]) as _
+            }
+
+            #[cfg(feature = "multitask")]
+            SyscallId::GET_ROBUST_LIST => ruxos_posix_api::sys_get_robust_list(
+                args[0] as c_int,
+                args[1] as *mut usize,
+                args[2] as *mut usize,
+            ) as _,
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE => ruxos_posix_api::sys_epoll_create1(args[0] as c_int) as _,
 
@@ -507,6 +605,15 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[2] as ctypes::mode_t,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::FCHOWNAT => ruxos_posix_api::sys_fchownat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::uid_t,
+                args[3] as ctypes::gid_t,
+                args[4] as c_int,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::NEWFSTATAT => ruxos_posix_api::sys_newfstatat(
                 args[0] as c_int,
@@ -530,6 +637,22 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3] as *const core::ffi::c_char,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::LINKAT => ruxos_posix_api::sys_linkat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as c_int,
+                args[3] as *const core::ffi::c_char,
+                args[4] as c_int,
+            ) as _,
+
+            #[cfg(feature = "fs")]
+            SyscallId::SYMLINKAT => ruxos_posix_api::sys_symlinkat(
+                args[0] as *const core::ffi::c_char,
+                args[1] as c_int,
+                args[2] as *const core::ffi::c_char,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::READLINKAT => ruxos_posix_api::sys_readlinkat(
                 args[0] as c_int,
@@ -538,6 +661,13 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[3],
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::FCHMODAT => ruxos_posix_api::sys_fchmodat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as ctypes::mode_t,
+            ) as _,
+
             #[cfg(feature = "fs")]
             SyscallId::FACCESSAT => ruxos_posix_api::sys_faccessat(
                 args[0] as c_int,
@@ -565,6 +695,14 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[4] as ctypes::size_t,
             ) as _,
 
+            #[cfg(feature = "fs")]
+            SyscallId::UTIMENSAT => ruxos_posix_api::sys_utimensat(
+                args[0] as c_int,
+                args[1] as *const core::ffi::c_char,
+                args[2] as *const ctypes::timespec,
+                args[3] as c_int,
+            ) as _,
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_PWAIT => ruxos_posix_api::sys_epoll_pwait(
                 args[0] as c_int,
@@ -575,6 +713,50 @@ pub fn syscall(syscall_id: SyscallId, args: [usize; 6]) -> isize {
                 args[5] as *const ctypes::size_t,
             ) as _,
 
+            #[cfg(all(feature = "fd", feature = "signal"))]
+            SyscallId::SIGNALFD => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const usize,
+                args[2],
+                0,
+            ) as _,
+
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_CREATE => {
+                ruxos_posix_api::sys_timerfd_create(args[0] as ctypes::clockid_t, args[1] as c_int)
+                    as _
+            }
+
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD => ruxos_posix_api::sys_eventfd(args[0] as core::ffi::c_uint) as _,
+
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_SETTIME => ruxos_posix_api::sys_timerfd_settime(
+                args[0] as c_int,
+                args[1] as c_int,
+                args[2] as *const ctypes::itimerspec,
+                args[3] as *mut ctypes::itimerspec,
+            ) as _,
+
+            #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+            SyscallId::TIMERFD_GETTIME => ruxos_posix_api::sys_timerfd_gettime(
+                args[0] as c_int,
+                args[1] as *mut ctypes::itimerspec,
+            ) as _,
+
+            #[cfg(all(feature = "fd", feature = "signal"))]
+            SyscallId::SIGNALFD4 => ruxos_posix_api::sys_signalfd4(
+                args[0] as c_int,
+                args[1] as *const usize,
+                args[2],
+                args[3] as c_int,
+            ) as _,
+
+            #[cfg(feature = "fd")]
+            SyscallId::EVENTFD2 => {
+                ruxos_posix_api::sys_eventfd2(args[0] as core::ffi::c_uint, args[1] as c_int) as _
+            }
+
             #[cfg(feature = "epoll")]
             SyscallId::EPOLL_CREATE1 => ruxos_posix_api::sys_epoll_create1(args[0] as c_int) as _,
 
@@ -56,6 +56,9 @@ pub enum SyscallId {
     #[cfg(feature = "signal")]
     RT_SIGPROCMASK = 14,
 
+    #[cfg(feature = "signal")]
+    RT_SIGRETURN = 15,
+
     #[cfg(feature = "fd")]
     IOCTL = 16,
 
@@ -145,11 +148,20 @@ pub enum SyscallId {
     #[cfg(feature = "multitask")]
     CLONE = 56,
 
+    #[cfg(feature = "multitask")]
+    FORK = 57,
+
+    #[cfg(feature = "multitask")]
+    VFORK = 58,
+
     #[cfg(feature = "fs")]
     EXECVE = 59,
 
     EXIT = 60,
 
+    #[cfg(feature = "multitask")]
+    WAIT4 = 61,
+
     #[cfg(feature = "signal")]
     KILL = 62,
 
@@ -182,12 +194,33 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     RMDIR = 84,
 
+    #[cfg(feature = "fs")]
+    LINK = 86,
+
     #[cfg(feature = "fs")]
     UNLINK = 87,
 
+    #[cfg(feature = "fs")]
+    SYMLINK = 88,
+
     #[cfg(feature = "fs")]
     READLINK = 89,
 
+    #[cfg(feature = "fs")]
+    CHMOD = 90,
+
+    #[cfg(feature = "fs")]
+    FCHMOD = 91,
+
+    #[cfg(feature = "fs")]
+    CHOWN = 92,
+
+    #[cfg(feature = "fs")]
+    FCHOWN = 93,
+
+    #[cfg(feature = "fs")]
+    LCHOWN = 94,
+
     UMASK = 95,
 
     GETTIMEOFDAY = 96,
@@ -219,6 +252,15 @@ pub enum SyscallId {
 
     ARCH_PRCTL = 158,
 
+    #[cfg(feature = "fs")]
+    SYNC = 162,
+
+    #[cfg(feature = "fs")]
+    MOUNT = 165,
+
+    #[cfg(feature = "fs")]
+    UMOUNT2 = 166,
+
     #[cfg(feature = "multitask")]
     GETTID = 186,
 
@@ -252,6 +294,9 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     MKDIRAT = 258,
 
+    #[cfg(feature = "fs")]
+    FCHOWNAT = 260,
+
     #[cfg(feature = "fs")]
     NEWFSTATAT = 262,
 
@@ -261,9 +306,18 @@ pub enum SyscallId {
     #[cfg(feature = "fs")]
     RENAMEAT = 264,
 
+    #[cfg(feature = "fs")]
+    LINKAT = 265,
+
+    #[cfg(feature = "fs")]
+    SYMLINKAT = 266,
+
     #[cfg(feature = "fs")]
     READLINKAT = 267,
 
+    #[cfg(feature = "fs")]
+    FCHMODAT = 268,
+
     #[cfg(feature = "fs")]
     FACCESSAT = 269,
 
@@ -273,9 +327,39 @@ pub enum SyscallId {
     #[cfg(feature = "poll")]
     PPOLL = 271,
 
+    #[cfg(feature = "multitask")]
+    SET_ROBUST_LIST = 273,
+
+    #[cfg(feature = "multitask")]
+    GET_ROBUST_LIST = 274,
+
+    #[cfg(feature = "fs")]
+    UTIMENSAT = 280,
+
     #[cfg(feature = "epoll")]
     EPOLL_PWAIT = 281,
 
+    #[cfg(all(feature = "fd", feature = "signal"))]
+    SIGNALFD = 282,
+
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_CREATE = 283,
+
+    #[cfg(feature = "fd")]
+    EVENTFD = 284,
+
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_SETTIME = 286,
+
+    #[cfg(all(feature = "fd", feature = "multitask", feature = "irq"))]
+    TIMERFD_GETTIME = 287,
+
+    #[cfg(all(feature = "fd", feature = "signal"))]
+    SIGNALFD4 = 289,
+
+    #[cfg(feature = "fd")]
+    EVENTFD2 = 290,
+
     #[cfg(feature = "epoll")]
     EPOLL_CREATE1 = 291,
 